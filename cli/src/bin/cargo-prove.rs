@@ -1,8 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use sp1_cli::commands::{
-    build::BuildCmd, build_toolchain::BuildToolchainCmd, install_toolchain::InstallToolchainCmd,
-    new::NewCmd, prove::ProveCmd,
+    build::BuildCmd, build_toolchain::BuildToolchainCmd, debug::DebugCmd,
+    install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd,
 };
 
 const VERSION_MESSAGE: &str = concat!(
@@ -35,6 +35,7 @@ pub enum ProveCliCommands {
     New(NewCmd),
     Build(BuildCmd),
     Prove(ProveCmd),
+    Debug(DebugCmd),
     BuildToolchain(BuildToolchainCmd),
     InstallToolchain(InstallToolchainCmd),
 }
@@ -46,6 +47,7 @@ fn main() -> Result<()> {
         ProveCliCommands::New(cmd) => cmd.run(),
         ProveCliCommands::Build(cmd) => cmd.run(),
         ProveCliCommands::Prove(cmd) => cmd.run(),
+        ProveCliCommands::Debug(cmd) => cmd.run(),
         ProveCliCommands::BuildToolchain(cmd) => cmd.run(),
         ProveCliCommands::InstallToolchain(cmd) => cmd.run(),
     }
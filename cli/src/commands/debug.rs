@@ -0,0 +1,237 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use sp1_core::runtime::{Debugger, GdbServer, Register, StopReason};
+use std::io::{self, BufRead, Read, Write};
+use std::{fs::File, net::TcpListener, ops::ControlFlow};
+
+use crate::{
+    build::{build_program, BuildArgs},
+    commands::prove::Input,
+};
+
+const HELP: &str = "\
+commands:
+  break <addr|symbol>    stop before executing the instruction at the address (b)
+  delete <addr|symbol>   remove a breakpoint (d)
+  watch <addr>           stop whenever the word at the address changes (w)
+  unwatch <addr>         remove a watchpoint
+  step [n]               execute n instructions, 1 by default (s)
+  continue               execute until a breakpoint or watchpoint is hit (c)
+  registers              print the registers (r)
+  memory <addr> [n]      print n words of memory, 1 by default (x)
+  info                   print the breakpoints and watchpoints (i)
+  quit                   exit the debugger (q)";
+
+#[derive(Parser)]
+#[command(
+    name = "debug",
+    about = "Build a program and step through its execution"
+)]
+pub struct DebugCmd {
+    #[clap(long, value_parser)]
+    input: Option<Input>,
+
+    /// Stop at this address or function before executing the program.
+    #[clap(long = "break", value_parser)]
+    breakpoints: Vec<String>,
+
+    /// Wait for GDB to connect on this address (e.g. `:1234`) instead of starting the prompt.
+    #[clap(long, value_parser)]
+    gdb: Option<String>,
+
+    #[clap(flatten)]
+    build_args: BuildArgs,
+}
+
+impl DebugCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf_path = build_program(&self.build_args)?;
+
+        let mut elf = Vec::new();
+        File::open(elf_path.as_path().as_str())
+            .expect("failed to open input file")
+            .read_to_end(&mut elf)
+            .expect("failed to read from input file");
+
+        let stdin = Input::to_stdin(&self.input, false)?;
+        let mut debugger = Debugger::new(&elf, &stdin)?;
+        for location in self.breakpoints.iter() {
+            let pc = resolve(&debugger, location)?;
+            debugger.add_breakpoint(pc);
+        }
+
+        if let Some(addr) = &self.gdb {
+            let addr = match addr.strip_prefix(':') {
+                Some(port) => format!("127.0.0.1:{}", port),
+                None => addr.clone(),
+            };
+            let listener = TcpListener::bind(&addr)?;
+            println!("waiting for gdb on {}", addr);
+            let (mut stream, _) = listener.accept()?;
+            GdbServer::new(debugger).serve(&mut stream)?;
+            return Ok(());
+        }
+
+        println!("{}", HELP);
+        print_location(&debugger);
+
+        let mut lines = io::stdin().lock().lines();
+        loop {
+            print!("(sp1) ");
+            io::stdout().flush()?;
+            let Some(line) = lines.next() else {
+                return Ok(());
+            };
+            let line = line?;
+            let args = line.split_whitespace().collect::<Vec<_>>();
+            let Some((command, args)) = args.split_first() else {
+                continue;
+            };
+            match run_command(&mut debugger, command, args) {
+                Ok(ControlFlow::Continue(())) => {}
+                Ok(ControlFlow::Break(())) => return Ok(()),
+                Err(err) => println!("error: {}", err),
+            }
+        }
+    }
+}
+
+/// Run a single debugger command, breaking if the debugger should exit.
+fn run_command(debugger: &mut Debugger, command: &str, args: &[&str]) -> Result<ControlFlow<()>> {
+    match command {
+        "b" | "break" => {
+            let pc = resolve(debugger, arg(args, 0)?)?;
+            debugger.add_breakpoint(pc);
+            println!("breakpoint at {}", describe(debugger, pc));
+        }
+        "d" | "delete" => {
+            let pc = resolve(debugger, arg(args, 0)?)?;
+            if !debugger.remove_breakpoint(pc) {
+                return Err(anyhow!("no breakpoint at 0x{:08x}", pc));
+            }
+        }
+        "w" | "watch" => {
+            let addr = parse_number(arg(args, 0)?)?;
+            debugger.add_watchpoint(addr);
+            println!("watching 0x{:08x}", addr - addr % 4);
+        }
+        "unwatch" => {
+            let addr = parse_number(arg(args, 0)?)?;
+            if !debugger.remove_watchpoint(addr) {
+                return Err(anyhow!("0x{:08x} is not watched", addr));
+            }
+        }
+        "s" | "step" => {
+            let n = args.first().map(|n| parse_number(n)).transpose()?;
+            for _ in 0..n.unwrap_or(1) {
+                let reason = debugger.step();
+                if reason != StopReason::Step {
+                    print_stop(debugger, &reason);
+                    return Ok(ControlFlow::Continue(()));
+                }
+            }
+            print_location(debugger);
+        }
+        "c" | "continue" => {
+            let reason = debugger.resume();
+            print_stop(debugger, &reason);
+        }
+        "r" | "registers" => {
+            for (i, value) in debugger.runtime.registers().iter().enumerate() {
+                print!(
+                    "{:<4}0x{:08x}",
+                    format!("{:?}", Register::from_u32(i as u32)),
+                    value
+                );
+                print!("{}", if i % 4 == 3 { "\n" } else { "    " });
+            }
+        }
+        "x" | "memory" => {
+            let addr = parse_number(arg(args, 0)?)?;
+            let n = args.get(1).map(|n| parse_number(n)).transpose()?;
+            let addr = addr - addr % 4;
+            for i in 0..n.unwrap_or(1) {
+                let addr = addr + i * 4;
+                println!("0x{:08x}: 0x{:08x}", addr, debugger.runtime.word(addr));
+            }
+        }
+        "i" | "info" => {
+            for pc in debugger.breakpoints() {
+                println!("breakpoint at {}", describe(debugger, pc));
+            }
+            for addr in debugger.watchpoints() {
+                println!("watchpoint at 0x{:08x}", addr);
+            }
+            print_location(debugger);
+        }
+        "q" | "quit" => return Ok(ControlFlow::Break(())),
+        "h" | "help" => println!("{}", HELP),
+        _ => return Err(anyhow!("unknown command {}, try help", command)),
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+fn arg<'a>(args: &[&'a str], i: usize) -> Result<&'a str> {
+    args.get(i)
+        .copied()
+        .ok_or_else(|| anyhow!("missing argument"))
+}
+
+/// Parse a decimal or 0x-prefixed hexadecimal number.
+fn parse_number(s: &str) -> Result<u32> {
+    let n = match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16)?,
+        None => s.parse()?,
+    };
+    Ok(n)
+}
+
+/// Resolve an address or the name of a function to an address.
+fn resolve(debugger: &Debugger, location: &str) -> Result<u32> {
+    parse_number(location).or_else(|_| {
+        debugger
+            .symbol_address(location)
+            .ok_or_else(|| anyhow!("no function named {}", location))
+    })
+}
+
+/// Describe an address together with the function it is in.
+fn describe(debugger: &Debugger, pc: u32) -> String {
+    match debugger.symbolize(pc) {
+        Some((name, 0)) => format!("0x{:08x} <{}>", pc, name),
+        Some((name, offset)) => format!("0x{:08x} <{}+{}>", pc, name, offset),
+        None => format!("0x{:08x}", pc),
+    }
+}
+
+fn print_location(debugger: &Debugger) {
+    match debugger.instruction() {
+        Some(instruction) => println!(
+            "clk={} {}: {:?}",
+            debugger.runtime.state.global_clk,
+            describe(debugger, debugger.pc()),
+            instruction
+        ),
+        None => println!("program has finished"),
+    }
+}
+
+fn print_stop(debugger: &Debugger, reason: &StopReason) {
+    match reason {
+        StopReason::Step => {}
+        StopReason::Breakpoint(pc) => println!("hit breakpoint at {}", describe(debugger, *pc)),
+        StopReason::Watchpoint { addr, old, new } => println!(
+            "watchpoint 0x{:08x} changed from 0x{:08x} to 0x{:08x}",
+            addr, old, new
+        ),
+        StopReason::Finished(outcome) => {
+            println!("program finished with exit code {}", outcome.exit_code);
+            return;
+        }
+        StopReason::Error(err) => {
+            println!("program failed: {}", err);
+            return;
+        }
+    }
+    print_location(debugger);
+}
@@ -1,20 +1,31 @@
 use anstyle::*;
 use anyhow::Result;
 use clap::Parser;
+use inferno::flamegraph;
 use sp1_core::{
+    runtime::{ExecutionLimits, Profiler},
     utils::{self},
     SP1Prover, SP1Stdin,
 };
 use std::time::Instant;
-use std::{env, fs::File, io::Read, path::PathBuf, str::FromStr};
+use std::{
+    env,
+    fs::{self, File},
+    io::Read,
+    path::PathBuf,
+    str::FromStr,
+};
 
 use crate::{
     build::{build_program, BuildArgs},
     util::{elapsed, write_status},
 };
 
+/// Input files larger than this are streamed rather than read into memory.
+const STREAM_INPUT_THRESHOLD: u64 = 64 << 20;
+
 #[derive(Debug, Clone)]
-enum Input {
+pub(crate) enum Input {
     FilePath(PathBuf),
     HexBytes(Vec<u8>),
 }
@@ -55,6 +66,32 @@ impl FromStr for Input {
     }
 }
 
+impl Input {
+    /// Builds the program's input from the `--input` argument, if any.
+    ///
+    /// Input files are read into the buffer of the stdin, unless `stream` is set or they are
+    /// larger than `STREAM_INPUT_THRESHOLD`, in which case they are memory-mapped and streamed.
+    /// Streamed input is not saved with a proof.
+    pub(crate) fn to_stdin(input: &Option<Input>, stream: bool) -> Result<SP1Stdin> {
+        let mut stdin = SP1Stdin::new();
+        if let Some(ref input) = input {
+            match input {
+                Input::FilePath(ref path) => {
+                    if stream || fs::metadata(path)?.len() > STREAM_INPUT_THRESHOLD {
+                        stdin.write_file(path)?;
+                    } else {
+                        stdin.write_slice(&fs::read(path)?);
+                    }
+                }
+                Input::HexBytes(ref bytes) => {
+                    stdin.write_slice(bytes);
+                }
+            }
+        }
+        Ok(stdin)
+    }
+}
+
 #[derive(Parser)]
 #[command(name = "prove", about = "(default) Build and prove a program")]
 pub struct ProveCmd {
@@ -64,12 +101,28 @@ pub struct ProveCmd {
     #[clap(long, action)]
     output: Option<PathBuf>,
 
+    /// Stream the input file instead of reading it into memory. Streamed input is not saved with
+    /// the proof, so it must be supplied again to verify it. Input files larger than 64 MiB are
+    /// always streamed.
+    #[clap(long, action)]
+    stream_input: bool,
+
+    /// Trace the prover and write a profile of the cycles spent in each function of the program
+    /// to profile.folded, flamegraph.svg and profile.pb.gz.
     #[clap(long, action)]
     profile: bool,
 
     #[clap(long, action)]
     verbose: bool,
 
+    /// Execute the program and print an estimate of the cost of proving it, without proving it.
+    #[clap(long, action)]
+    estimate: bool,
+
+    /// Stop execution with an error after this many cycles.
+    #[clap(long, value_parser)]
+    max_cycles: Option<u64>,
+
     #[clap(flatten)]
     build_args: BuildArgs,
 }
@@ -92,28 +145,41 @@ impl ProveCmd {
             utils::setup_tracer();
         }
 
+        let mut limits = ExecutionLimits::from_env()?;
+        if let Some(max_cycles) = self.max_cycles {
+            limits.max_cycles = Some(max_cycles);
+        }
+
         let mut elf = Vec::new();
         File::open(elf_path.as_path().as_str())
             .expect("failed to open input file")
             .read_to_end(&mut elf)
             .expect("failed to read from input file");
 
-        let mut stdin = SP1Stdin::new();
-        if let Some(ref input) = self.input {
-            match input {
-                Input::FilePath(ref path) => {
-                    let mut file = File::open(path).expect("failed to open input file");
-                    let mut bytes = Vec::new();
-                    file.read_to_end(&mut bytes)?;
-                    stdin.write_slice(&bytes);
-                }
-                Input::HexBytes(ref bytes) => {
-                    stdin.write_slice(bytes);
-                }
-            }
+        if self.profile {
+            let stdin = Input::to_stdin(&self.input, self.stream_input)?;
+            let profiler = SP1Prover::profile_with_limits(&elf, stdin, limits)?;
+            write_profile(&profiler)?;
+        }
+
+        if self.estimate {
+            let stdin = Input::to_stdin(&self.input, self.stream_input)?;
+            let report = SP1Prover::estimate_cost_with_limits(&elf, stdin, limits)?;
+            println!("{}", report);
+            return Ok(());
+        }
+
+        let stdin = Input::to_stdin(&self.input, self.stream_input)?;
+        if self.output.is_some() && !stdin.sources.is_empty() {
+            let yellow = AnsiColor::Yellow.on_default().effects(Effects::BOLD);
+            write_status(
+                &yellow,
+                "Warning",
+                "the streamed input is not saved with the proof",
+            );
         }
         let start_time = Instant::now();
-        let proof = SP1Prover::prove(&elf, stdin).unwrap();
+        let proof = SP1Prover::prove_with_limits(&elf, stdin, limits).unwrap();
 
         if let Some(ref path) = self.output {
             proof
@@ -132,3 +198,36 @@ impl ProveCmd {
         Ok(())
     }
 }
+
+/// Write the profile of the program as folded stacks, a flamegraph and a pprof profile, and print
+/// the functions the most cycles are spent in.
+fn write_profile(profiler: &Profiler) -> Result<()> {
+    let mut folded = Vec::new();
+    profiler.write_folded(&mut folded)?;
+    fs::write("profile.folded", &folded)?;
+
+    let lines = std::str::from_utf8(&folded)?.lines();
+    let mut options = flamegraph::Options::default();
+    options.title = "Cycles".to_string();
+    options.count_name = "cycles".to_string();
+    flamegraph::from_lines(&mut options, lines, File::create("flamegraph.svg")?)?;
+
+    profiler.write_pprof(File::create("profile.pb.gz")?)?;
+
+    let total = profiler.total_cycles();
+    println!("{:>12} {:>12}  function", "self", "total");
+    for (name, own, cumulative) in profiler.functions().into_iter().take(20) {
+        println!("{:>12} {:>12}  {}", own, cumulative, name);
+    }
+    let green = AnsiColor::Green.on_default().effects(Effects::BOLD);
+    write_status(
+        &green,
+        "Profiled",
+        format!(
+            "{} cycles to profile.folded, flamegraph.svg and profile.pb.gz",
+            total
+        )
+        .as_str(),
+    );
+    Ok(())
+}
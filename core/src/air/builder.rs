@@ -7,10 +7,12 @@ use super::word::Word;
 use crate::cpu::columns::InstructionCols;
 use crate::cpu::columns::OpcodeSelectorCols;
 use crate::lookup::InteractionKind;
+use crate::NUM_COMMITTED_WORDS;
 use crate::{bytes::ByteOpcode, memory::MemoryCols};
 use p3_field::{AbstractField, Field};
 
 use p3_uni_stark::StarkGenericConfig;
+use std::array;
 use std::iter::once;
 
 /// A Builder with the ability to encode the existance of interactions with other AIRs by sending
@@ -468,6 +470,19 @@ pub trait MultiTableAirBuilder: PermutationAirBuilder {
     fn cumulative_sum(&self) -> Self::Sum;
 }
 
+/// A builder with access to the public inputs of the shard being proven, which the verifier
+/// supplies itself instead of reading them from the proof.
+pub trait PublicInputBuilder: AirBuilder {
+    /// One in the last shard of the execution and zero in every other shard.
+    fn is_last_shard(&self) -> Self::Expr;
+
+    /// The exit code of the execution, as a word of bytes.
+    fn exit_code(&self) -> Word<Self::Expr>;
+
+    /// The words of the public values committed by the program, as words of bytes.
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS];
+}
+
 /// A trait which contains all helper methods for building an AIR.
 pub trait SP1AirBuilder:
     BaseAirBuilder
@@ -503,3 +518,61 @@ impl<'a, Challenge: Field> EmptyMessageBuilder for VerifierConstraintFolder<'a,
 impl<F: Field> EmptyMessageBuilder for SymbolicAirBuilder<F> {}
 
 impl<'a, F: Field> EmptyMessageBuilder for p3_uni_stark::DebugConstraintBuilder<'a, F> {}
+
+// A chip proven on its own with the uni-stark prover is not part of a sharded execution, so its
+// public inputs are all zero.
+impl<'a, SC: StarkGenericConfig> PublicInputBuilder for ProverConstraintFolder<'a, SC> {
+    fn is_last_shard(&self) -> Self::Expr {
+        Self::Expr::zero()
+    }
+
+    fn exit_code(&self) -> Word<Self::Expr> {
+        Word(array::from_fn(|_| Self::Expr::zero()))
+    }
+
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS] {
+        array::from_fn(|_| Word(array::from_fn(|_| Self::Expr::zero())))
+    }
+}
+
+impl<'a, Challenge: Field> PublicInputBuilder for VerifierConstraintFolder<'a, Challenge> {
+    fn is_last_shard(&self) -> Self::Expr {
+        Self::Expr::zero()
+    }
+
+    fn exit_code(&self) -> Word<Self::Expr> {
+        Word(array::from_fn(|_| Self::Expr::zero()))
+    }
+
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS] {
+        array::from_fn(|_| Word(array::from_fn(|_| Self::Expr::zero())))
+    }
+}
+
+impl<F: Field> PublicInputBuilder for SymbolicAirBuilder<F> {
+    fn is_last_shard(&self) -> Self::Expr {
+        Self::Expr::zero()
+    }
+
+    fn exit_code(&self) -> Word<Self::Expr> {
+        Word(array::from_fn(|_| Self::Expr::zero()))
+    }
+
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS] {
+        array::from_fn(|_| Word(array::from_fn(|_| Self::Expr::zero())))
+    }
+}
+
+impl<'a, F: Field> PublicInputBuilder for p3_uni_stark::DebugConstraintBuilder<'a, F> {
+    fn is_last_shard(&self) -> Self::Expr {
+        Self::Expr::zero()
+    }
+
+    fn exit_code(&self) -> Word<Self::Expr> {
+        Word(array::from_fn(|_| Self::Expr::zero()))
+    }
+
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS] {
+        array::from_fn(|_| Word(array::from_fn(|_| Self::Expr::zero())))
+    }
+}
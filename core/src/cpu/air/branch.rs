@@ -59,12 +59,13 @@ impl CpuChip {
                 local.branching,
             );
 
-            // When we are not branching, assert that local.pc + 4 <==> next.pc.
+            // When we are not branching, assert that local.pc + size <==> next.pc, where size is
+            // 2 for compressed instructions and 4 otherwise.
             builder
                 .when(local.not_branching)
                 .when_transition()
                 .when(next.is_real)
-                .assert_eq(local.pc + AB::Expr::from_canonical_u8(4), next.pc);
+                .assert_eq(local.pc + local.instruction.size::<AB>(), next.pc);
         }
 
         // Evaluate branching value constraints.
@@ -8,16 +8,17 @@ use p3_air::BaseAir;
 use p3_field::AbstractField;
 use p3_matrix::MatrixRowSlices;
 
-use crate::air::{SP1AirBuilder, WordAirBuilder};
+use crate::air::{PublicInputBuilder, SP1AirBuilder, WordAirBuilder};
 use crate::cpu::columns::OpcodeSelectorCols;
 use crate::cpu::columns::{CpuCols, NUM_CPU_COLS};
 use crate::cpu::CpuChip;
 use crate::memory::MemoryCols;
-use crate::runtime::{AccessPosition, Opcode};
+use crate::operations::IsZeroOperation;
+use crate::runtime::{AccessPosition, Opcode, SyscallCode};
 
 impl<AB> Air<AB> for CpuChip
 where
-    AB: SP1AirBuilder,
+    AB: SP1AirBuilder + PublicInputBuilder,
 {
     #[inline(never)]
     fn eval(&self, builder: &mut AB) {
@@ -139,8 +140,22 @@ where
         );
 
         // ECALL instructions.
-        // TODO:  Need to handle HALT ecall
-        // For all non branch or jump instructions, verify that next.pc == pc + 4
+        self.ecall_eval(builder, local);
+
+        // The real rows come before the padding rows, and the last of them is marked.
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .assert_one(local.is_real);
+        builder.when_transition().assert_eq(
+            local.is_last_real,
+            local.is_real * (AB::Expr::one() - next.is_real),
+        );
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last_real, local.is_real);
+
+        // For all non branch or jump instructions, verify that next.pc == pc + size
         // builder
         //     .when_not(is_branch_instruction + local.selectors.is_jal + local.selectors.is_jalr)
         //     .assert_eq(local.pc + AB::Expr::from_canonical_u8(4), next.pc);
@@ -175,12 +190,12 @@ impl CpuChip {
         // Get the jump specific columns
         let jump_columns = local.opcode_specific_columns.jump();
 
-        // Verify that the local.pc + 4 is saved in op_a for both jump instructions.
+        // Verify that the local.pc + size is saved in op_a for both jump instructions.
         builder
             .when(local.selectors.is_jal + local.selectors.is_jalr)
             .assert_eq(
                 local.op_a_val().reduce::<AB>(),
-                local.pc + AB::F::from_canonical_u8(4),
+                local.pc + local.instruction.size::<AB>(),
             );
 
         // Verify that the word form of local.pc is correct for JAL instructions.
@@ -234,6 +249,96 @@ impl CpuChip {
     }
 }
 
+impl CpuChip {
+    /// Constraints related to the ECALL opcode.
+    ///
+    /// Only the `HALT` and `COMMIT` syscalls are constrained here. The exit code `HALT` leaves in
+    /// `a0` must be the exit code in the public values, and the execution must end with it, so that
+    /// the prover cannot leave out the end of the execution. The word `COMMIT` leaves in `a0` must
+    /// be the committed word in the public values at the index read from `a1`.
+    pub(crate) fn ecall_eval<AB: SP1AirBuilder + PublicInputBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &CpuCols<AB::Var>,
+    ) {
+        // Get the ecall specific columns.
+        let ecall_columns = local.opcode_specific_columns.ecall();
+
+        // Check whether the syscall id read from `t0` is the one of `HALT`.
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            local.op_b_val().reduce::<AB>()
+                - AB::Expr::from_canonical_u32(SyscallCode::HALT as u32),
+            ecall_columns.is_halt,
+            local.selectors.is_ecall.into(),
+        );
+        let is_halt = local.selectors.is_ecall * ecall_columns.is_halt.result;
+
+        // `HALT` leaves the exit code in `a0`, which must match the public exit code.
+        let exit_code = builder.exit_code();
+        builder
+            .when(is_halt.clone())
+            .assert_word_eq(local.op_a_val(), local.op_a_access.prev_value);
+        builder
+            .when(is_halt)
+            .assert_word_eq(local.op_a_val().map(|x| x.into()), exit_code);
+
+        // Check whether the syscall id read from `t0` is the one of `COMMIT`.
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            local.op_b_val().reduce::<AB>()
+                - AB::Expr::from_canonical_u32(SyscallCode::COMMIT as u32),
+            ecall_columns.is_commit,
+            local.selectors.is_ecall.into(),
+        );
+        let is_commit = local.selectors.is_ecall * ecall_columns.is_commit.result;
+
+        // For `COMMIT`, exactly one index is set, and it is the one read from `a1` into `op_c`.
+        let mut num_indices = AB::Expr::zero();
+        let mut index = AB::Expr::zero();
+        for (i, is_index) in ecall_columns.commit_index.iter().enumerate() {
+            builder
+                .when(local.selectors.is_ecall)
+                .assert_bool(*is_index);
+            num_indices += (*is_index).into();
+            index += *is_index * AB::Expr::from_canonical_usize(i);
+        }
+        builder
+            .when(local.selectors.is_ecall)
+            .assert_eq(num_indices, ecall_columns.is_commit.result);
+        builder
+            .when(is_commit.clone())
+            .assert_zero(local.selectors.imm_c);
+        builder
+            .when(is_commit.clone())
+            .assert_eq(local.op_c_val().reduce::<AB>(), index);
+
+        // `COMMIT` leaves the committed word in `a0`, which must match the public values.
+        builder
+            .when(is_commit)
+            .assert_word_eq(local.op_a_val(), local.op_a_access.prev_value);
+        let committed_words = builder.committed_words();
+        for (is_index, word) in ecall_columns.commit_index.iter().zip(committed_words) {
+            builder
+                .when(local.selectors.is_ecall * *is_index)
+                .assert_word_eq(local.op_a_val().map(|x| x.into()), word);
+        }
+
+        // The last shard is not empty, and its last real row is a `HALT`.
+        let is_last_shard = builder.is_last_shard();
+        builder
+            .when_first_row()
+            .when(is_last_shard.clone())
+            .assert_one(local.is_real);
+        builder
+            .when(local.is_last_real * is_last_shard.clone())
+            .assert_one(local.selectors.is_ecall);
+        builder
+            .when(local.is_last_real * is_last_shard)
+            .assert_one(ecall_columns.is_halt.result);
+    }
+}
+
 impl<F> BaseAir<F> for CpuChip {
     fn width(&self) -> usize {
         NUM_CPU_COLS
@@ -0,0 +1,21 @@
+use sp1_derive::AlignedBorrow;
+use std::mem::size_of;
+
+use crate::operations::IsZeroOperation;
+use crate::NUM_COMMITTED_WORDS;
+
+pub const NUM_ECALL_COLS: usize = size_of::<EcallCols<u8>>();
+
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct EcallCols<T> {
+    /// Whether the syscall id in `op_b` is the one of `HALT`.
+    pub is_halt: IsZeroOperation<T>,
+
+    /// Whether the syscall id in `op_b` is the one of `COMMIT`.
+    pub is_commit: IsZeroOperation<T>,
+
+    /// For `COMMIT`, a one at the index of the committed word, which is read into `op_c`, and
+    /// zeros everywhere else.
+    pub commit_index: [T; NUM_COMMITTED_WORDS],
+}
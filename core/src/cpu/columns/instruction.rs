@@ -1,4 +1,5 @@
-use p3_field::PrimeField;
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, PrimeField};
 use sp1_derive::AlignedBorrow;
 use std::mem::size_of;
 use std::{iter::once, vec::IntoIter};
@@ -22,6 +23,9 @@ pub struct InstructionCols<T> {
 
     /// The third operand for this instruction.
     pub op_c: Word<T>,
+
+    /// Whether the instruction is a 16-bit compressed instruction.
+    pub is_compressed: T,
 }
 
 impl<F: PrimeField> InstructionCols<F> {
@@ -30,6 +34,15 @@ impl<F: PrimeField> InstructionCols<F> {
         self.op_a = instruction.op_a.into();
         self.op_b = instruction.op_b.into();
         self.op_c = instruction.op_c.into();
+        self.is_compressed = F::from_bool(instruction.compressed);
+    }
+}
+
+impl<V: Copy> InstructionCols<V> {
+    /// The size of the instruction in bytes, which is 2 for compressed instructions and 4
+    /// otherwise.
+    pub fn size<AB: AirBuilder<Var = V>>(&self) -> AB::Expr {
+        AB::Expr::from_canonical_u8(4) - AB::Expr::two() * self.is_compressed
     }
 }
 
@@ -42,6 +55,7 @@ impl<T> IntoIterator for InstructionCols<T> {
             .chain(self.op_a)
             .chain(self.op_b)
             .chain(self.op_c)
+            .chain(once(self.is_compressed))
             .collect::<Vec<_>>()
             .into_iter()
     }
@@ -1,4 +1,4 @@
-use crate::cpu::columns::{AuipcCols, BranchCols, JumpCols, MemoryColumns};
+use crate::cpu::columns::{AuipcCols, BranchCols, EcallCols, JumpCols, MemoryColumns};
 use std::fmt::{Debug, Formatter};
 use std::mem::{size_of, transmute};
 
@@ -12,6 +12,7 @@ pub union OpcodeSpecificCols<T: Copy> {
     branch: BranchCols<T>,
     jump: JumpCols<T>,
     auipc: AuipcCols<T>,
+    ecall: EcallCols<T>,
 }
 
 impl<T: Copy + Default> Default for OpcodeSpecificCols<T> {
@@ -56,4 +57,10 @@ impl<T: Copy> OpcodeSpecificCols<T> {
     pub fn auipc_mut(&mut self) -> &mut AuipcCols<T> {
         unsafe { &mut self.auipc }
     }
+    pub fn ecall(&self) -> &EcallCols<T> {
+        unsafe { &self.ecall }
+    }
+    pub fn ecall_mut(&mut self) -> &mut EcallCols<T> {
+        unsafe { &mut self.ecall }
+    }
 }
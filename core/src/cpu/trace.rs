@@ -8,7 +8,7 @@ use crate::cpu::memory::MemoryRecordEnum;
 use crate::disassembler::WORD_SIZE;
 use crate::field::event::FieldEvent;
 use crate::memory::MemoryCols;
-use crate::runtime::{ExecutionRecord, Opcode};
+use crate::runtime::{ExecutionRecord, Opcode, SyscallCode};
 use hashbrown::HashMap;
 use p3_field::PrimeField;
 use p3_matrix::dense::RowMajorMatrix;
@@ -61,6 +61,11 @@ impl<F: PrimeField> MachineAir<F> for CpuChip {
         output.add_byte_lookup_events(new_blu_events);
         output.add_field_events(&new_field_events);
 
+        // Mark the last real row, which comes right before the padding rows.
+        if let Some(last_row) = rows.len().checked_sub(NUM_CPU_COLS) {
+            rows[last_row + CPU_COL_MAP.is_last_real] = F::one();
+        }
+
         // Convert the trace to a row major matrix.
         let mut trace = RowMajorMatrix::new(rows, NUM_CPU_COLS);
 
@@ -152,11 +157,12 @@ impl CpuChip {
                 .populate(record, &mut new_field_events)
         }
 
-        // Populate memory, branch, jump, and auipc specific fields.
+        // Populate memory, branch, jump, auipc, and ecall specific fields.
         self.populate_memory(cols, event, &mut new_alu_events, &mut new_blu_events);
         self.populate_branch(cols, event, &mut new_alu_events);
         self.populate_jump(cols, event, &mut new_alu_events);
         self.populate_auipc(cols, event, &mut new_alu_events);
+        self.populate_ecall(cols, event);
 
         // Assert that the instruction is not a no-op.
         cols.is_real = F::one();
@@ -459,14 +465,45 @@ impl CpuChip {
         }
     }
 
+    /// Populates columns related to ECALL.
+    fn populate_ecall<F: PrimeField>(&self, cols: &mut CpuCols<F>, event: CpuEvent) {
+        if matches!(event.instruction.opcode, Opcode::ECALL) {
+            let ecall_columns = cols.opcode_specific_columns.ecall_mut();
+
+            // The syscall id is reduced into the field in the same way as in the constraints.
+            let syscall_id = F::from_wrapped_u32(event.b);
+            ecall_columns.is_halt.populate_from_field_element(
+                syscall_id - F::from_canonical_u32(SyscallCode::HALT as u32),
+            );
+            let is_commit = ecall_columns.is_commit.populate_from_field_element(
+                syscall_id - F::from_canonical_u32(SyscallCode::COMMIT as u32),
+            );
+            if is_commit == 1 {
+                if let Some(is_index) = ecall_columns.commit_index.get_mut(event.c as usize) {
+                    *is_index = F::one();
+                }
+            }
+        }
+    }
+
     fn pad_to_power_of_two<F: PrimeField>(values: &mut Vec<F>) {
         let len: usize = values.len();
         let n_real_rows = values.len() / NUM_CPU_COLS;
-        let last_row = &values[len - NUM_CPU_COLS..];
-        let pc = last_row[CPU_COL_MAP.pc];
-        let clk = last_row[CPU_COL_MAP.clk];
 
-        values.resize(n_real_rows.next_power_of_two() * NUM_CPU_COLS, F::zero());
+        // A shard which only holds the events of other chips has no CPU events, in which case the
+        // trace is made of padding rows only.
+        let (pc, clk, n_rows) = if n_real_rows == 0 {
+            (F::zero(), F::zero(), 8)
+        } else {
+            let last_row = &values[len - NUM_CPU_COLS..];
+            (
+                last_row[CPU_COL_MAP.pc],
+                last_row[CPU_COL_MAP.clk],
+                n_real_rows.next_power_of_two(),
+            )
+        };
+
+        values.resize(n_rows * NUM_CPU_COLS, F::zero());
 
         // Interpret values as a slice of arrays of length `NUM_CPU_COLS`
         let rows = unsafe {
@@ -516,6 +553,7 @@ mod tests {
                 op_c: 2,
                 imm_b: false,
                 imm_c: false,
+                compressed: false,
             },
             a: 1,
             a_record: None,
@@ -536,7 +574,7 @@ mod tests {
     fn generate_trace_simple_program() {
         let program = simple_program();
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         let chip = CpuChip::default();
         let trace: RowMajorMatrix<BabyBear> =
             chip.generate_trace(&runtime.record, &mut ExecutionRecord::default());
@@ -553,7 +591,7 @@ mod tests {
 
         let program = simple_program();
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         let chip = CpuChip::default();
         let trace: RowMajorMatrix<BabyBear> =
             chip.generate_trace(&runtime.record, &mut ExecutionRecord::default());
@@ -0,0 +1,249 @@
+//! Expansion of RV32C compressed instructions into their 32-bit RV32I equivalents.
+//!
+//! Reference: The RISC-V Instruction Set Manual, Volume I, Chapter 16 "C" Standard Extension.
+
+/// Returns whether the 16-bit parcel is the start of a compressed instruction. 32-bit
+/// instructions have their lowest two bits set.
+pub fn is_compressed(parcel: u16) -> bool {
+    parcel & 0b11 != 0b11
+}
+
+/// Expand a 16-bit compressed instruction into the equivalent 32-bit instruction.
+///
+/// Returns `None` for reserved encodings and for instructions of extensions that are not
+/// supported by the VM (floating point and RV64/RV128 only instructions).
+pub fn decompress(inst: u16) -> Option<u32> {
+    let inst = inst as u32;
+    let quadrant = inst & 0b11;
+    let funct3 = bits(inst, 15, 13);
+
+    // The full 5-bit register fields and the 3-bit fields which address x8-x15. The field in
+    // bits 4-2 is also the destination of C.ADDI4SPN and C.LW.
+    let rd = bits(inst, 11, 7);
+    let rs2 = bits(inst, 6, 2);
+    let rs1_prime = bits(inst, 9, 7) + 8;
+    let rs2_prime = bits(inst, 4, 2) + 8;
+
+    match (quadrant, funct3) {
+        // C.ADDI4SPN
+        (0b00, 0b000) => {
+            let imm = bits(inst, 12, 11) << 4
+                | bits(inst, 10, 7) << 6
+                | bits(inst, 6, 6) << 2
+                | bits(inst, 5, 5) << 3;
+            (imm != 0).then(|| i_type(OP_IMM, rs2_prime, 0b000, 2, imm))
+        }
+        // C.LW
+        (0b00, 0b010) => {
+            let imm = bits(inst, 12, 10) << 3 | bits(inst, 6, 6) << 2 | bits(inst, 5, 5) << 6;
+            Some(i_type(LOAD, rs2_prime, 0b010, rs1_prime, imm))
+        }
+        // C.SW
+        (0b00, 0b110) => {
+            let imm = bits(inst, 12, 10) << 3 | bits(inst, 6, 6) << 2 | bits(inst, 5, 5) << 6;
+            Some(s_type(0b010, rs1_prime, rs2_prime, imm))
+        }
+        // C.ADDI (C.NOP when rd is x0)
+        (0b01, 0b000) => Some(i_type(OP_IMM, rd, 0b000, rd, imm6(inst))),
+        // C.JAL
+        (0b01, 0b001) => Some(j_type(1, jump_offset(inst))),
+        // C.LI
+        (0b01, 0b010) => Some(i_type(OP_IMM, rd, 0b000, 0, imm6(inst))),
+        // C.ADDI16SP
+        (0b01, 0b011) if rd == 2 => {
+            let imm = sign_extend(
+                bits(inst, 12, 12) << 9
+                    | bits(inst, 6, 6) << 4
+                    | bits(inst, 5, 5) << 6
+                    | bits(inst, 4, 3) << 7
+                    | bits(inst, 2, 2) << 5,
+                10,
+            );
+            (imm != 0).then(|| i_type(OP_IMM, 2, 0b000, 2, imm))
+        }
+        // C.LUI
+        (0b01, 0b011) => {
+            let imm = sign_extend(bits(inst, 12, 12) << 17 | bits(inst, 6, 2) << 12, 18);
+            (imm != 0).then_some((imm & 0xffff_f000) | rd << 7 | LUI)
+        }
+        // C.SRLI, C.SRAI, C.ANDI, C.SUB, C.XOR, C.OR and C.AND
+        (0b01, 0b100) => match bits(inst, 11, 10) {
+            // Shift amounts of 32 or more are reserved on RV32.
+            0b00 if bits(inst, 12, 12) == 0 => {
+                Some(i_type(OP_IMM, rs1_prime, 0b101, rs1_prime, rs2))
+            }
+            0b01 if bits(inst, 12, 12) == 0 => Some(i_type(
+                OP_IMM,
+                rs1_prime,
+                0b101,
+                rs1_prime,
+                0b0100000 << 5 | rs2,
+            )),
+            0b10 => Some(i_type(OP_IMM, rs1_prime, 0b111, rs1_prime, imm6(inst))),
+            0b11 if bits(inst, 12, 12) == 0 => {
+                let (funct7, funct3) = match bits(inst, 6, 5) {
+                    0b00 => (0b0100000, 0b000),
+                    0b01 => (0b0000000, 0b100),
+                    0b10 => (0b0000000, 0b110),
+                    _ => (0b0000000, 0b111),
+                };
+                Some(r_type(funct7, rs1_prime, rs1_prime, funct3, rs2_prime))
+            }
+            _ => None,
+        },
+        // C.J
+        (0b01, 0b101) => Some(j_type(0, jump_offset(inst))),
+        // C.BEQZ and C.BNEZ
+        (0b01, 0b110) | (0b01, 0b111) => {
+            let offset = sign_extend(
+                bits(inst, 12, 12) << 8
+                    | bits(inst, 11, 10) << 3
+                    | bits(inst, 6, 5) << 6
+                    | bits(inst, 4, 3) << 1
+                    | bits(inst, 2, 2) << 5,
+                9,
+            );
+            Some(b_type(funct3 & 0b001, rs1_prime, offset))
+        }
+        // C.SLLI
+        (0b10, 0b000) if bits(inst, 12, 12) == 0 => Some(i_type(OP_IMM, rd, 0b001, rd, rs2)),
+        // C.LWSP
+        (0b10, 0b010) if rd != 0 => {
+            let imm = bits(inst, 12, 12) << 5 | bits(inst, 6, 4) << 2 | bits(inst, 3, 2) << 6;
+            Some(i_type(LOAD, rd, 0b010, 2, imm))
+        }
+        // C.JR, C.MV, C.EBREAK, C.JALR and C.ADD
+        (0b10, 0b100) => match (bits(inst, 12, 12), rd, rs2) {
+            (0, 0, 0) => None,
+            (0, _, 0) => Some(i_type(JALR, 0, 0b000, rd, 0)),
+            (0, _, _) => Some(r_type(0, rd, 0, 0b000, rs2)),
+            (1, 0, 0) => Some(EBREAK),
+            (1, _, 0) => Some(i_type(JALR, 1, 0b000, rd, 0)),
+            _ => Some(r_type(0, rd, rd, 0b000, rs2)),
+        },
+        // C.SWSP
+        (0b10, 0b110) => {
+            let imm = bits(inst, 12, 9) << 2 | bits(inst, 8, 7) << 6;
+            Some(s_type(0b010, 2, rs2, imm))
+        }
+        _ => None,
+    }
+}
+
+const LOAD: u32 = 0b0000011;
+const OP_IMM: u32 = 0b0010011;
+const STORE: u32 = 0b0100011;
+const OP: u32 = 0b0110011;
+const LUI: u32 = 0b0110111;
+const BRANCH: u32 = 0b1100011;
+const JALR: u32 = 0b1100111;
+const JAL: u32 = 0b1101111;
+const EBREAK: u32 = 0x00100073;
+
+/// Extract the bits `hi..=lo` of `value`.
+fn bits(value: u32, hi: u32, lo: u32) -> u32 {
+    (value >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+/// Sign extend the lowest `width` bits of `value`.
+fn sign_extend(value: u32, width: u32) -> u32 {
+    (((value << (32 - width)) as i32) >> (32 - width)) as u32
+}
+
+/// The sign extended 6-bit immediate of C.ADDI, C.LI and C.ANDI.
+fn imm6(inst: u32) -> u32 {
+    sign_extend(bits(inst, 12, 12) << 5 | bits(inst, 6, 2), 6)
+}
+
+/// The sign extended 12-bit offset of C.J and C.JAL.
+fn jump_offset(inst: u32) -> u32 {
+    sign_extend(
+        bits(inst, 12, 12) << 11
+            | bits(inst, 11, 11) << 4
+            | bits(inst, 10, 9) << 8
+            | bits(inst, 8, 8) << 10
+            | bits(inst, 7, 7) << 6
+            | bits(inst, 6, 6) << 7
+            | bits(inst, 5, 3) << 1
+            | bits(inst, 2, 2) << 5,
+        12,
+    )
+}
+
+fn r_type(funct7: u32, rd: u32, rs1: u32, funct3: u32, rs2: u32) -> u32 {
+    funct7 << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | OP
+}
+
+fn i_type(opcode: u32, rd: u32, funct3: u32, rs1: u32, imm: u32) -> u32 {
+    (imm & 0xfff) << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | opcode
+}
+
+fn s_type(funct3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    bits(imm, 11, 5) << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | bits(imm, 4, 0) << 7 | STORE
+}
+
+fn b_type(funct3: u32, rs1: u32, offset: u32) -> u32 {
+    bits(offset, 12, 12) << 31
+        | bits(offset, 10, 5) << 25
+        | rs1 << 15
+        | funct3 << 12
+        | bits(offset, 4, 1) << 8
+        | bits(offset, 11, 11) << 7
+        | BRANCH
+}
+
+fn j_type(rd: u32, offset: u32) -> u32 {
+    bits(offset, 20, 20) << 31
+        | bits(offset, 10, 1) << 21
+        | bits(offset, 11, 11) << 20
+        | bits(offset, 19, 12) << 12
+        | rd << 7
+        | JAL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decompress, is_compressed};
+
+    #[test]
+    fn test_decompress() {
+        // Pairs of compressed instructions and their expansions, as encoded by llvm-mc.
+        let cases = [
+            (0x4505, 0x00100513), // c.li a0, 1
+            (0x852e, 0x00b00533), // c.mv a0, a1
+            (0x4188, 0x0005a503), // c.lw a0, 0(a1)
+            (0x8082, 0x00008067), // c.jr ra
+            (0x7139, 0xfc010113), // c.addi16sp sp, -64
+            (0xde06, 0x02112e23), // c.swsp ra, 60(sp)
+            (0x50f2, 0x03c12083), // c.lwsp ra, 60(sp)
+            (0xdd65, 0xfe050ce3), // c.beqz a0, -8
+            (0xa00d, 0x0220006f), // c.j 34
+            (0x3f71, 0xf9dff0ef), // c.jal -100
+            (0x868d, 0x4036d693), // c.srai a3, 3
+            (0x9b6d, 0xffb77713), // c.andi a4, -5
+            (0x8f81, 0x408787b3), // c.sub a5, s0
+            (0x0808, 0x01010513), // c.addi4spn a0, sp, 16
+            (0x7585, 0xfffe15b7), // c.lui a1, 0xfffe1
+            (0x049e, 0x00749493), // c.slli s1, 7
+            (0x9002, 0x00100073), // c.ebreak
+            (0x9282, 0x000280e7), // c.jalr t0
+            (0x9636, 0x00d60633), // c.add a2, a3
+            (0xdf7c, 0x06f72e23), // c.sw a5, 124(a4)
+        ];
+        for (compressed, expanded) in cases {
+            assert!(is_compressed(compressed));
+            assert_eq!(decompress(compressed), Some(expanded), "{:04x}", compressed);
+        }
+    }
+
+    #[test]
+    fn test_decompress_invalid() {
+        // The all-zero parcel is defined to be illegal.
+        assert_eq!(decompress(0x0000), None);
+        // c.fld is not supported.
+        assert_eq!(decompress(0x2000), None);
+        // c.slli with a shift amount of 32 is reserved on RV32.
+        assert_eq!(decompress(0x1082), None);
+        assert!(!is_compressed(0x0513));
+    }
+}
@@ -1,9 +1,13 @@
-use elf::abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD};
+use addr2line::Context;
+use core::fmt::{Display, Formatter};
+use elf::abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD, STT_FUNC};
 use elf::endian::LittleEndian;
 use elf::file::Class;
 use elf::ElfBytes;
+use gimli::{Dwarf, EndianRcSlice, RunTimeEndian, SectionId};
 use std::cmp::min;
 use std::collections::BTreeMap;
+use std::rc::Rc;
 
 /// The maximum size of the memory in bytes.
 pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
@@ -11,7 +15,13 @@ pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
 /// The size of a word in bytes.
 pub const WORD_SIZE: usize = 4;
 
-/// A RV32IM ELF file.
+/// The number of registers, which occupy the lowest addresses of memory.
+pub const NUM_REGISTERS: u32 = 32;
+
+/// The maximum number of program headers in an ELF file.
+pub const MAXIMUM_PROGRAM_HEADERS: usize = 256;
+
+/// A RV32IM ELF file, optionally with compressed instructions.
 #[derive(Debug, Clone)]
 pub struct Elf {
     /// The instructions of the program encoded as 32-bits.
@@ -46,88 +56,90 @@ impl Elf {
     /// Parse the ELF file into a vector of 32-bit encoded instructions and the first memory address.
     ///
     /// Reference: https://en.wikipedia.org/wiki/Executable_and_Linkable_Format
-    pub fn decode(input: &[u8]) -> Self {
+    pub fn decode(input: &[u8]) -> Result<Self, ElfError> {
         let mut image: BTreeMap<u32, u32> = BTreeMap::new();
         // Parse the ELF file assuming that it is little-endian..
-        let elf = ElfBytes::<LittleEndian>::minimal_parse(input).expect("failed to parse elf");
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(input)
+            .map_err(|e| ElfError::Parse(e.to_string()))?;
 
         // Some sanity checks to make sure that the ELF file is valid.
         if elf.ehdr.class != Class::ELF32 {
-            panic!("must be a 32-bit elf");
+            return Err(ElfError::Not32Bit);
         } else if elf.ehdr.e_machine != EM_RISCV {
-            panic!("must be a riscv machine");
+            return Err(ElfError::NotRiscv(elf.ehdr.e_machine));
         } else if elf.ehdr.e_type != ET_EXEC {
-            panic!("must be executable");
+            return Err(ElfError::NotExecutable(elf.ehdr.e_type));
         }
 
         // Get the entrypoint of the ELF file as an u32.
-        let entry: u32 = elf
-            .ehdr
-            .e_entry
-            .try_into()
-            .expect("e_entry was larger than 32 bits");
-
-        // Make sure the entrypoint is valid.
-        if entry == MAXIMUM_MEMORY_SIZE || entry % WORD_SIZE as u32 != 0 {
-            panic!("invalid entrypoint");
+        let entry = to_u32("e_entry", elf.ehdr.e_entry)?;
+
+        // Make sure the entrypoint is valid. Compressed instructions only need to be aligned to
+        // two bytes.
+        if entry == MAXIMUM_MEMORY_SIZE || entry % 2 != 0 {
+            return Err(ElfError::InvalidEntrypoint(entry));
         }
 
         // Get the segments of the ELF file.
-        let segments = elf.segments().expect("failed to get segments");
-        if segments.len() > 256 {
-            panic!("too many program headers");
+        let segments = elf.segments().ok_or(ElfError::MissingProgramHeaders)?;
+        if segments.len() > MAXIMUM_PROGRAM_HEADERS {
+            return Err(ElfError::TooManyProgramHeaders(segments.len()));
         }
 
         let mut instructions: Vec<u32> = Vec::new();
         let mut base_address = u32::MAX;
 
+        // The address ranges of the loaded segments, and whether each one is executable.
+        let mut ranges: Vec<(u32, u32, bool)> = Vec::new();
+
         // Only read segments that are executable instructions that are also PT_LOAD.
         for segment in segments.iter().filter(|x| x.p_type == PT_LOAD) {
             // Get the file size of the segment as an u32.
-            let file_size: u32 = segment
-                .p_filesz
-                .try_into()
-                .expect("filesize was larger than 32 bits");
+            let file_size = to_u32("p_filesz", segment.p_filesz)?;
             if file_size == MAXIMUM_MEMORY_SIZE {
-                panic!("invalid segment file_size");
+                return Err(ElfError::InvalidSegmentSize(file_size));
             }
 
             // Get the memory size of the segment as an u32.
-            let mem_size: u32 = segment
-                .p_memsz
-                .try_into()
-                .expect("mem_size was larger than 32 bits");
+            let mem_size = to_u32("p_memsz", segment.p_memsz)?;
             if mem_size == MAXIMUM_MEMORY_SIZE {
-                panic!("Invalid segment mem_size");
+                return Err(ElfError::InvalidSegmentSize(mem_size));
             }
 
             // Get the virtual address of the segment as an u32.
-            let vaddr: u32 = segment
-                .p_vaddr
-                .try_into()
-                .expect("vaddr was larger than 32 bits");
+            let vaddr = to_u32("p_vaddr", segment.p_vaddr)?;
             if vaddr % WORD_SIZE as u32 != 0 {
-                panic!("vaddr {vaddr:08x} is unaligned");
+                return Err(ElfError::UnalignedSegment(vaddr));
+            }
+
+            // Make sure the segment fits in memory and does not overwrite the registers.
+            let end = vaddr
+                .checked_add(mem_size)
+                .filter(|end| *end < MAXIMUM_MEMORY_SIZE)
+                .ok_or(ElfError::SegmentOutOfMemory(vaddr))?;
+            if mem_size > 0 && vaddr < NUM_REGISTERS {
+                return Err(ElfError::SegmentOverlapsRegisters(vaddr));
+            }
+            let executable = (segment.p_flags & PF_X) != 0;
+            if mem_size > 0 {
+                ranges.push((vaddr, end, executable));
             }
 
             // If the virtual address is less than the first memory address, then update the first
             // memory address.
-            if (segment.p_flags & PF_X) != 0 && base_address > vaddr {
+            if executable && base_address > vaddr {
                 base_address = vaddr;
             }
 
-            // Get the offset to the segment.
-            let offset: u32 = segment
-                .p_offset
-                .try_into()
-                .expect("offset was larger than 32 bits");
+            // Get the offset to the segment, and make sure the segment lies within the file.
+            let offset = to_u32("p_offset", segment.p_offset)?;
+            if offset as usize + file_size as usize > input.len() {
+                return Err(ElfError::SegmentOutOfFile(vaddr));
+            }
 
             // Read the segment and decode each word as an instruction.
             for i in (0..mem_size).step_by(WORD_SIZE) {
-                let addr = vaddr.checked_add(i).expect("invalid segment vaddr");
-                if addr == MAXIMUM_MEMORY_SIZE {
-                    panic!("address [0x{addr:08x}] exceeds maximum address for guest programs [0x{MAXIMUM_MEMORY_SIZE:08x}]");
-                }
+                let addr = vaddr + i;
 
                 // If we are reading past the end of the file, then break.
                 if i >= file_size {
@@ -139,17 +151,279 @@ impl Elf {
                 let mut word = 0;
                 let len = min(file_size - i, WORD_SIZE as u32);
                 for j in 0..len {
-                    let offset = (offset + i + j) as usize;
-                    let byte = input.get(offset).expect("invalid segment offset");
-                    word |= (*byte as u32) << (j * 8);
+                    let byte = input[offset as usize + (i + j) as usize];
+                    word |= (byte as u32) << (j * 8);
                 }
                 image.insert(addr, word);
-                if (segment.p_flags & PF_X) != 0 {
+                if executable {
                     instructions.push(word);
                 }
             }
         }
 
-        Elf::new(instructions, entry, base_address, image)
+        // Make sure no two segments are loaded at the same address.
+        ranges.sort_unstable();
+        for pair in ranges.windows(2) {
+            if pair[1].0 < pair[0].1 {
+                return Err(ElfError::OverlappingSegments(pair[0].0, pair[1].0));
+            }
+        }
+
+        // Make sure the entrypoint points into an executable segment.
+        if !ranges
+            .iter()
+            .any(|(start, end, executable)| *executable && (*start..*end).contains(&entry))
+        {
+            return Err(ElfError::EntrypointNotExecutable(entry));
+        }
+
+        Ok(Elf::new(instructions, entry, base_address, image))
+    }
+}
+
+/// A function symbol of an ELF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// The demangled name of the function.
+    pub name: String,
+
+    /// The address of the first instruction of the function.
+    pub address: u32,
+
+    /// The size of the function in bytes.
+    pub size: u32,
+}
+
+impl Elf {
+    /// Parse the function symbols of the ELF file, sorted by address.
+    pub fn symbols(input: &[u8]) -> Result<Vec<Symbol>, ElfError> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(input)
+            .map_err(|e| ElfError::Parse(e.to_string()))?;
+        let Some((symbol_table, string_table)) = elf
+            .symbol_table()
+            .map_err(|e| ElfError::Parse(e.to_string()))?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut symbols = Vec::new();
+        for symbol in symbol_table
+            .iter()
+            .filter(|symbol| symbol.st_symtype() == STT_FUNC && symbol.st_value != 0)
+        {
+            let name = string_table
+                .get(symbol.st_name as usize)
+                .map_err(|e| ElfError::Parse(e.to_string()))?;
+            symbols.push(Symbol {
+                name: format!("{:#}", rustc_demangle::demangle(name)),
+                address: to_u32("st_value", symbol.st_value)?,
+                size: to_u32("st_size", symbol.st_size)?,
+            });
+        }
+        symbols.sort_by_key(|symbol| symbol.address);
+        Ok(symbols)
+    }
+
+    /// Parse the DWARF debug information of the ELF file, or `None` if it has none.
+    pub fn debug_info(input: &[u8]) -> Result<Option<DebugInfo>, ElfError> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(input)
+            .map_err(|e| ElfError::Parse(e.to_string()))?;
+        let has_debug_info = elf
+            .section_header_by_name(SectionId::DebugInfo.name())
+            .map_err(|e| ElfError::Parse(e.to_string()))?
+            .is_some();
+        if !has_debug_info {
+            return Ok(None);
+        }
+
+        let dwarf = Dwarf::load(|id| -> Result<_, ElfError> {
+            let data = match elf
+                .section_header_by_name(id.name())
+                .map_err(|e| ElfError::Parse(e.to_string()))?
+            {
+                Some(header) => {
+                    let (data, compression) = elf
+                        .section_data(&header)
+                        .map_err(|e| ElfError::Parse(e.to_string()))?;
+                    if compression.is_some() {
+                        return Err(ElfError::InvalidDebugInfo(format!(
+                            "{} is compressed",
+                            id.name()
+                        )));
+                    }
+                    data
+                }
+                None => &[],
+            };
+            Ok(EndianRcSlice::new(Rc::from(data), RunTimeEndian::Little))
+        })?;
+        let context =
+            Context::from_dwarf(dwarf).map_err(|e| ElfError::InvalidDebugInfo(e.to_string()))?;
+        Ok(Some(DebugInfo { context }))
+    }
+}
+
+/// The DWARF debug information of an ELF file, which tells which functions were inlined where.
+pub struct DebugInfo {
+    context: Context<EndianRcSlice<RunTimeEndian>>,
+}
+
+impl DebugInfo {
+    /// The demangled names of the functions inlined at `pc`, from the outermost to the innermost,
+    /// excluding the function they were inlined into.
+    pub fn inlined_functions(&self, pc: u32) -> Result<Vec<String>, ElfError> {
+        let mut frames = self
+            .context
+            .find_frames(pc as u64)
+            .skip_all_loads()
+            .map_err(|e| ElfError::InvalidDebugInfo(e.to_string()))?;
+        let mut names = Vec::new();
+        while let Some(frame) = frames
+            .next()
+            .map_err(|e| ElfError::InvalidDebugInfo(e.to_string()))?
+        {
+            let name = match frame.function {
+                Some(function) => {
+                    let name = function
+                        .raw_name()
+                        .map_err(|e| ElfError::InvalidDebugInfo(e.to_string()))?;
+                    format!("{:#}", rustc_demangle::demangle(&name))
+                }
+                None => "[unknown]".to_string(),
+            };
+            names.push(name);
+        }
+        // The frames start with the innermost inlined function and end with the function the code
+        // belongs to.
+        names.pop();
+        names.reverse();
+        Ok(names)
+    }
+}
+
+/// Find the index of the symbol containing `pc` in symbols sorted by address.
+pub fn find_symbol(symbols: &[Symbol], pc: u32) -> Option<usize> {
+    let idx = symbols
+        .partition_point(|symbol| symbol.address <= pc)
+        .checked_sub(1)?;
+    let symbol = &symbols[idx];
+    (pc - symbol.address < symbol.size.max(1)).then_some(idx)
+}
+
+/// Convert a field of the ELF file to an u32, failing if it does not fit.
+fn to_u32(field: &'static str, value: u64) -> Result<u32, ElfError> {
+    value
+        .try_into()
+        .map_err(|_| ElfError::ValueTooLarge(field, value))
+}
+
+/// An error that occurred while loading an ELF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElfError {
+    /// The file could not be parsed as an ELF file.
+    Parse(String),
+
+    /// The ELF file is not a 32-bit ELF file.
+    Not32Bit,
+
+    /// The ELF file targets a machine other than RISC-V.
+    NotRiscv(u16),
+
+    /// The ELF file is not an executable.
+    NotExecutable(u16),
+
+    /// A field of the ELF file does not fit in 32 bits.
+    ValueTooLarge(&'static str, u64),
+
+    /// The entrypoint is not a valid halfword-aligned address.
+    InvalidEntrypoint(u32),
+
+    /// The entrypoint does not point into an executable segment.
+    EntrypointNotExecutable(u32),
+
+    /// The ELF file has no program headers.
+    MissingProgramHeaders,
+
+    /// The ELF file has more than `MAXIMUM_PROGRAM_HEADERS` program headers.
+    TooManyProgramHeaders(usize),
+
+    /// A segment has an invalid size.
+    InvalidSegmentSize(u32),
+
+    /// The segment at the given address is not word-aligned.
+    UnalignedSegment(u32),
+
+    /// The segment at the given address extends past the end of memory.
+    SegmentOutOfMemory(u32),
+
+    /// The segment at the given address extends past the end of the file.
+    SegmentOutOfFile(u32),
+
+    /// The segment at the given address overlaps the addresses reserved for registers.
+    SegmentOverlapsRegisters(u32),
+
+    /// The segments at the given addresses overlap.
+    OverlappingSegments(u32, u32),
+
+    /// The word at the given address in an executable segment is not a valid instruction.
+    InvalidInstruction(u32, u32),
+
+    /// The DWARF debug information could not be parsed.
+    InvalidDebugInfo(String),
+}
+
+impl Display for ElfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ElfError::Parse(e) => write!(f, "failed to parse elf: {}", e),
+            ElfError::Not32Bit => write!(f, "must be a 32-bit elf"),
+            ElfError::NotRiscv(machine) => {
+                write!(f, "must be a riscv machine, found machine {}", machine)
+            }
+            ElfError::NotExecutable(e_type) => {
+                write!(f, "must be executable, found type {}", e_type)
+            }
+            ElfError::ValueTooLarge(field, value) => {
+                write!(f, "{} 0x{:x} was larger than 32 bits", field, value)
+            }
+            ElfError::InvalidEntrypoint(entry) => write!(f, "invalid entrypoint 0x{:08x}", entry),
+            ElfError::EntrypointNotExecutable(entry) => {
+                write!(
+                    f,
+                    "entrypoint 0x{:08x} is not in an executable segment",
+                    entry
+                )
+            }
+            ElfError::MissingProgramHeaders => write!(f, "missing program headers"),
+            ElfError::TooManyProgramHeaders(count) => {
+                write!(f, "too many program headers: {}", count)
+            }
+            ElfError::InvalidSegmentSize(size) => write!(f, "invalid segment size 0x{:08x}", size),
+            ElfError::UnalignedSegment(vaddr) => write!(f, "vaddr 0x{:08x} is unaligned", vaddr),
+            ElfError::SegmentOutOfMemory(vaddr) => write!(
+                f,
+                "segment at 0x{:08x} exceeds maximum address for guest programs [0x{:08x}]",
+                vaddr, MAXIMUM_MEMORY_SIZE
+            ),
+            ElfError::SegmentOutOfFile(vaddr) => {
+                write!(
+                    f,
+                    "segment at 0x{:08x} extends past the end of the file",
+                    vaddr
+                )
+            }
+            ElfError::SegmentOverlapsRegisters(vaddr) => {
+                write!(f, "segment at 0x{:08x} overlaps the registers", vaddr)
+            }
+            ElfError::OverlappingSegments(a, b) => {
+                write!(f, "segments at 0x{:08x} and 0x{:08x} overlap", a, b)
+            }
+            ElfError::InvalidInstruction(addr, word) => {
+                write!(f, "invalid instruction 0x{:08x} at 0x{:08x}", word, addr)
+            }
+            ElfError::InvalidDebugInfo(e) => write!(f, "invalid debug info: {}", e),
+        }
     }
 }
+
+impl std::error::Error for ElfError {}
@@ -3,6 +3,7 @@ use rrs_lib::instruction_formats::{
 };
 use rrs_lib::{process_instruction, InstructionProcessor};
 
+use super::{decompress, is_compressed, ElfError};
 use crate::runtime::{Instruction, Opcode, Register};
 
 impl Instruction {
@@ -322,13 +323,15 @@ impl InstructionProcessor for InstructionTranspiler {
     }
 
     fn process_ecall(&mut self) -> Self::InstructionResult {
+        // The syscall writes its result to `a0`, and reads its number from `t0` and its second
+        // argument from `a1`.
         Instruction::new(
             Opcode::ECALL,
             Register::X10 as u32,
             Register::X5 as u32,
-            0,
+            Register::X11 as u32,
+            false,
             false,
-            true,
         )
     }
 
@@ -405,13 +408,49 @@ impl InstructionProcessor for InstructionTranspiler {
     }
 }
 
-/// Transpile the instructions from the 32-bit encoded instructions.
-pub fn transpile(instructions_u32: &[u32]) -> Vec<Instruction> {
+/// Transpile the instructions from the encoded instructions, which start at `pc_base`.
+///
+/// The instructions are read as a stream of 16-bit parcels, so that both 32-bit and compressed
+/// 16-bit instructions are supported.
+pub fn transpile(instructions_u32: &[u32], pc_base: u32) -> Result<Vec<Instruction>, ElfError> {
+    let parcels = instructions_u32
+        .iter()
+        .flat_map(|word| [*word as u16, (*word >> 16) as u16])
+        .collect::<Vec<_>>();
+
     let mut instructions = Vec::new();
     let mut transpiler = InstructionTranspiler;
-    for instruction_u32 in instructions_u32 {
-        let instruction = process_instruction(&mut transpiler, *instruction_u32).unwrap();
+    let mut i = 0;
+    while i < parcels.len() {
+        let pc = pc_base + (i * 2) as u32;
+        let parcel = parcels[i];
+        let instruction = if !is_compressed(parcel) {
+            // A 32-bit instruction is made up of this parcel and the next.
+            let high = *parcels
+                .get(i + 1)
+                .ok_or(ElfError::InvalidInstruction(pc, parcel as u32))?;
+            let instruction_u32 = parcel as u32 | (high as u32) << 16;
+            i += 2;
+            process_instruction(&mut transpiler, instruction_u32)
+                .ok_or(ElfError::InvalidInstruction(pc, instruction_u32))?
+        } else if parcel == 0 {
+            // The all-zero parcel is the defined illegal instruction, which is also used as padding.
+            i += 1;
+            Instruction {
+                compressed: true,
+                ..Instruction::unimp()
+            }
+        } else {
+            i += 1;
+            let instruction = decompress(parcel)
+                .and_then(|instruction_u32| process_instruction(&mut transpiler, instruction_u32))
+                .ok_or(ElfError::InvalidInstruction(pc, parcel as u32))?;
+            Instruction {
+                compressed: true,
+                ..instruction
+            }
+        };
         instructions.push(instruction);
     }
-    instructions
+    Ok(instructions)
 }
@@ -1,6 +1,8 @@
+mod compressed;
 mod elf;
 mod instruction;
 
+pub use compressed::*;
 pub use elf::*;
 pub use instruction::*;
 
@@ -19,20 +21,28 @@ impl Program {
     }
 
     /// Disassemble a RV32IM ELF to a program that be executed by the VM.
+    ///
+    /// Panics if the ELF is invalid, use `Program::try_from_elf` to handle untrusted ELFs.
     pub fn from(input: &[u8]) -> Self {
+        Self::try_from_elf(input).unwrap_or_else(|e| panic!("invalid elf: {}", e))
+    }
+
+    /// Disassemble a RV32IM ELF to a program that be executed by the VM, failing if the ELF is
+    /// malformed or cannot be loaded into the VM's memory.
+    pub fn try_from_elf(input: &[u8]) -> Result<Self, ElfError> {
         // Decode the bytes as an ELF.
-        let elf = Elf::decode(input);
+        let elf = Elf::decode(input)?;
 
         // Transpile the RV32IM instructions.
-        let instructions = transpile(&elf.instructions);
+        let instructions = transpile(&elf.instructions, elf.pc_base)?;
 
         // Return the program.
-        Program {
+        Ok(Program {
             instructions,
             pc_start: elf.pc_start,
             pc_base: elf.pc_base,
             memory_image: elf.memory_image,
-        }
+        })
     }
 
     /// Disassemble a RV32IM ELF to a program that be executed by the VM from a file path.
@@ -45,3 +55,104 @@ impl Program {
         Program::from(&elf_code)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{transpile, ElfError};
+    use crate::runtime::{Opcode, Program};
+    use crate::utils::tests::FIBONACCI_ELF;
+
+    /// Overwrite the little-endian u32 at `offset` of the fibonacci ELF.
+    fn patched_elf(offset: usize, value: u32) -> Vec<u8> {
+        let mut elf = FIBONACCI_ELF.to_vec();
+        elf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        elf
+    }
+
+    #[test]
+    fn test_try_from_elf() {
+        let program = Program::try_from_elf(FIBONACCI_ELF).unwrap();
+        assert_eq!(program.pc_start, 0x201188);
+        assert_eq!(program.pc_base, 0x200800);
+    }
+
+    #[test]
+    fn test_try_from_elf_invalid() {
+        assert!(Program::try_from_elf(&FIBONACCI_ELF[..16]).is_err());
+        assert!(Program::try_from_elf(&FIBONACCI_ELF[..0x1000]).is_err());
+
+        // The machine is stored at offset 18.
+        let mut elf = FIBONACCI_ELF.to_vec();
+        elf[18] = 0x3e;
+        assert_eq!(
+            Program::try_from_elf(&elf).unwrap_err(),
+            ElfError::NotRiscv(0x3e)
+        );
+
+        // The entrypoint is stored at offset 24, point it at the read-only segment.
+        let elf = patched_elf(24, 0x10000);
+        assert_eq!(
+            Program::try_from_elf(&elf).unwrap_err(),
+            ElfError::EntrypointNotExecutable(0x10000)
+        );
+
+        // The program headers start at offset 52 and are 32 bytes each, with the vaddr at offset 8.
+        // Load the first segment over the registers.
+        let elf = patched_elf(52 + 32 + 8, 0);
+        assert_eq!(
+            Program::try_from_elf(&elf).unwrap_err(),
+            ElfError::SegmentOverlapsRegisters(0)
+        );
+
+        // Load the read-only data over the code.
+        let elf = patched_elf(52 + 3 * 32 + 8, 0x200800);
+        assert_eq!(
+            Program::try_from_elf(&elf).unwrap_err(),
+            ElfError::OverlappingSegments(0x200800, 0x200800)
+        );
+
+        // Make the code segment unaligned.
+        let elf = patched_elf(52 + 2 * 32 + 8, 0x200802);
+        assert_eq!(
+            Program::try_from_elf(&elf).unwrap_err(),
+            ElfError::UnalignedSegment(0x200802)
+        );
+    }
+
+    #[test]
+    fn test_transpile_compressed() {
+        // c.li a0, 1; c.mv a0, a1; addi a0, x0, 1; c.jr ra; padding.
+        let instructions = transpile(&[0x852e4505, 0x00100513, 0x00008082], 0x1000).unwrap();
+        let opcodes = instructions.iter().map(|i| i.opcode).collect::<Vec<_>>();
+        let compressed = instructions
+            .iter()
+            .map(|i| i.compressed)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            opcodes,
+            vec![
+                Opcode::ADD,
+                Opcode::ADD,
+                Opcode::ADD,
+                Opcode::JALR,
+                Opcode::UNIMP
+            ]
+        );
+        assert_eq!(compressed, vec![true, true, false, true, true]);
+
+        // c.li a0, 1; addi a0, x0, 1 split across two words; padding.
+        let instructions = transpile(&[0x05134505, 0x00000010], 0x1000).unwrap();
+        let compressed = instructions
+            .iter()
+            .map(|i| i.compressed)
+            .collect::<Vec<_>>();
+        assert_eq!(compressed, vec![true, false, true]);
+        assert_eq!(instructions[1].op_c, 1);
+
+        // A 32-bit instruction cut off at the end of the code.
+        assert_eq!(
+            transpile(&[0x05134505], 0x1000).unwrap_err(),
+            ElfError::InvalidInstruction(0x1002, 0x0513)
+        );
+    }
+}
@@ -1,11 +1,50 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+use memmap2::Mmap;
+use p3_field::AbstractField;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
+use crate::syscall::Hint;
 use crate::utils::Buffer;
 
 /// Standard input for the prover.
+///
+/// The input is split into a public part, which is visible to the verifier and the part of which
+/// the program reads is committed to in the public values of the proof, and a private part, which
+/// is only known to the prover and never leaves it. The prover can also answer hints the program
+/// asks for while it executes.
+///
+/// Public input which is too large to hold in memory can be streamed from `InputSource`s, which
+/// the program reads after the public input written to the buffer. Sources are not serialized
+/// with a proof, so they must be written again to the stdin of a deserialized proof to verify it.
 #[derive(Serialize, Deserialize)]
 pub struct SP1Stdin {
     pub buffer: Buffer,
+    #[serde(skip)]
+    pub private_buffer: Buffer,
+    #[serde(skip)]
+    pub sources: Vec<InputSource>,
+    #[serde(skip)]
+    pub files: BTreeMap<String, Arc<[u8]>>,
+    #[serde(skip)]
+    pub hints: HashMap<String, Hint>,
+}
+
+/// Public input which is read lazily as the program consumes it, so that it never has to be held
+/// in memory in full.
+#[derive(Clone)]
+pub enum InputSource {
+    /// A memory-mapped file.
+    Mmap(Arc<Mmap>),
+
+    /// A function opening a reader over the input from its start. It is called when the program
+    /// starts reading the input, and again to check the input of a proof.
+    Reader(Arc<dyn Fn() -> io::Result<Box<dyn Read + Send>> + Send + Sync>),
 }
 
 /// Standard output for the prover.
@@ -14,18 +53,54 @@ pub struct SP1Stdout {
     pub buffer: Buffer,
 }
 
+/// The number of words of the public values which the program commits to with the `COMMIT`
+/// syscall: the eight words of the digest of its input, the length of its input and the eight
+/// words of the digest of its output.
+pub const NUM_COMMITTED_WORDS: usize = 17;
+
+/// The public values of an execution, which every shard proof observes before sampling any
+/// challenge.
+///
+/// They consist of a digest of the public input the program read, a digest of the output it wrote
+/// and its exit code. The program computes the digests itself as it reads and writes, and commits
+/// to them with the `COMMIT` syscall before it halts. The CPU constrains the committed words and
+/// the exit code to be the ones of the public values, so a proof shows that the program read and
+/// wrote the bytes with these digests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicValues {
+    /// The SHA-256 digest of the public input read by the program.
+    pub input_digest: [u8; 32],
+
+    /// The number of bytes of public input read by the program.
+    pub input_len: u32,
+
+    /// The SHA-256 digest of the output written by the program.
+    pub output_digest: [u8; 32],
+
+    /// The exit code the program halted with.
+    pub exit_code: u32,
+}
+
 impl SP1Stdin {
     /// Create a new `SP1Stdin`.
     pub fn new() -> Self {
         Self {
             buffer: Buffer::new(),
+            private_buffer: Buffer::new(),
+            sources: Vec::new(),
+            files: BTreeMap::new(),
+            hints: HashMap::new(),
         }
     }
 
-    /// Create a `SP1Stdin` from a slice of bytes.
+    /// Create a `SP1Stdin` from a slice of bytes of public input.
     pub fn from(data: &[u8]) -> Self {
         Self {
             buffer: Buffer::from(data),
+            private_buffer: Buffer::new(),
+            sources: Vec::new(),
+            files: BTreeMap::new(),
+            hints: HashMap::new(),
         }
     }
 
@@ -39,15 +114,137 @@ impl SP1Stdin {
         self.buffer.read_slice(slice);
     }
 
-    /// Write a value to the buffer.
+    /// Write a public value to the buffer. Equivalent to `write_public`.
     pub fn write<T: Serialize>(&mut self, data: &T) {
-        self.buffer.write(data);
+        self.write_public(data);
     }
 
-    /// Write a slice of bytes to the buffer.
+    /// Write a slice of bytes of public input to the buffer. Equivalent to `write_public_slice`.
     pub fn write_slice(&mut self, slice: &[u8]) {
+        self.write_public_slice(slice);
+    }
+
+    /// Write a public value, which the program reads with `io::read_public`.
+    pub fn write_public<T: Serialize>(&mut self, data: &T) {
+        self.buffer.write(data);
+    }
+
+    /// Write a slice of bytes of public input, which the program reads with
+    /// `io::read_public_slice`.
+    pub fn write_public_slice(&mut self, slice: &[u8]) {
         self.buffer.write_slice(slice);
     }
+
+    /// Write a private value, which the program reads with `io::read_private`.
+    pub fn write_private<T: Serialize>(&mut self, data: &T) {
+        self.private_buffer.write(data);
+    }
+
+    /// Write a slice of bytes of private input, which the program reads with
+    /// `io::read_private_slice`.
+    pub fn write_private_slice(&mut self, slice: &[u8]) {
+        self.private_buffer.write_slice(slice);
+    }
+
+    /// Stream public input from the given source, after the public input written so far. The
+    /// program reads it with `io::read_public` like any other public input.
+    pub fn write_source(&mut self, source: InputSource) {
+        self.sources.push(source);
+    }
+
+    /// Stream public input from the file at the given path, which is memory-mapped rather than
+    /// loaded into memory.
+    pub fn write_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.write_source(InputSource::mmap(path)?);
+        Ok(())
+    }
+
+    /// Add a virtual file with the given name and contents, which the program opens with
+    /// `io::File::open(name)`.
+    ///
+    /// Like private input, the contents of virtual files are not part of the public values of the
+    /// proof, so the program must write them to its output if the verifier has to know them.
+    pub fn add_file<D: Into<Arc<[u8]>>>(&mut self, name: &str, data: D) {
+        self.files.insert(name.to_string(), data.into());
+    }
+
+    /// The SHA-256 digest of the first `len` bytes of public input, including the input streamed
+    /// from the sources, which the program read if `len` is the input length of its public values.
+    ///
+    /// Fails if there are fewer than `len` bytes of public input.
+    pub fn input_digest(&self, len: u32) -> io::Result<[u8; 32]> {
+        let mut hasher = Sha256::new();
+        let buffered = self.buffer.data.len().min(len as usize);
+        hasher.update(&self.buffer.data[..buffered]);
+        let mut remaining = (len as usize - buffered) as u64;
+        for source in self.sources.iter() {
+            if remaining == 0 {
+                break;
+            }
+            remaining -= io::copy(&mut source.open()?.take(remaining), &mut hasher)?;
+        }
+        if remaining > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the public input is shorter than the input read by the program",
+            ));
+        }
+        Ok(hasher.finalize().into())
+    }
+
+    /// Register a hint which the program invokes with `io::hint_query(name, input)`.
+    ///
+    /// The hint runs natively on the host, taking the input bytes sent by the program and
+    /// returning the answer the program reads back from a stream of its own, separate from the
+    /// public and private input. The answer is not part of the public values of the proof, so the
+    /// program must check it.
+    pub fn register_hint<F>(&mut self, name: &str, hint: F)
+    where
+        F: Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    {
+        self.hints.insert(name.to_string(), Arc::new(hint));
+    }
+}
+
+impl InputSource {
+    /// Memory-map the file at the given path. The file must not be modified while the program
+    /// or the verifier reads it.
+    pub fn mmap<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the file is only read, and must not be modified while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self::Mmap(Arc::new(mmap)))
+    }
+
+    /// Stream the input from the readers returned by `open`, which must all return the same
+    /// bytes.
+    pub fn reader<F, R>(open: F) -> Self
+    where
+        F: Fn() -> io::Result<R> + Send + Sync + 'static,
+        R: Read + Send + 'static,
+    {
+        Self::Reader(Arc::new(move || {
+            let reader = BufReader::new(open()?);
+            Ok(Box::new(reader) as Box<dyn Read + Send>)
+        }))
+    }
+
+    /// Open a reader over the input from its start.
+    pub fn open(&self) -> io::Result<Box<dyn Read + Send>> {
+        match self {
+            InputSource::Mmap(mmap) => Ok(Box::new(Cursor::new(MmapBytes(mmap.clone())))),
+            InputSource::Reader(open) => open(),
+        }
+    }
+}
+
+/// The bytes of a shared memory map, so that it can be read through a `Cursor`.
+struct MmapBytes(Arc<Mmap>);
+
+impl AsRef<[u8]> for MmapBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
 }
 
 impl SP1Stdout {
@@ -86,6 +283,58 @@ impl SP1Stdout {
     }
 }
 
+impl PublicValues {
+    /// Create the public values of an execution which read the given input, wrote the given output
+    /// and halted with the given exit code.
+    pub fn new(input: &[u8], output: &[u8], exit_code: u32) -> Self {
+        Self {
+            input_digest: Sha256::digest(input).into(),
+            input_len: input.len() as u32,
+            output_digest: Sha256::digest(output).into(),
+            exit_code,
+        }
+    }
+
+    /// Create the public values from the words committed by the program and its exit code.
+    pub fn from_committed_words(words: &[u32; NUM_COMMITTED_WORDS], exit_code: u32) -> Self {
+        let digest = |words: &[u32]| -> [u8; 32] {
+            let bytes = words.iter().flat_map(|word| word.to_le_bytes());
+            bytes.collect::<Vec<_>>().try_into().unwrap()
+        };
+        Self {
+            input_digest: digest(&words[0..8]),
+            input_len: words[8],
+            output_digest: digest(&words[9..17]),
+            exit_code,
+        }
+    }
+
+    /// The words the program commits to, in the order of their indices.
+    pub fn committed_words(&self) -> [u32; NUM_COMMITTED_WORDS] {
+        let mut words = [0u32; NUM_COMMITTED_WORDS];
+        let digest_words = |digest: &[u8; 32]| {
+            digest
+                .chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+                .collect::<Vec<_>>()
+        };
+        words[0..8].copy_from_slice(&digest_words(&self.input_digest));
+        words[8] = self.input_len;
+        words[9..17].copy_from_slice(&digest_words(&self.output_digest));
+        words
+    }
+
+    /// The public values as field elements, one for each byte, to be observed by the challenger.
+    pub fn to_field_elements<F: AbstractField>(&self) -> Vec<F> {
+        self.committed_words()
+            .iter()
+            .chain(std::iter::once(&self.exit_code))
+            .flat_map(|word| word.to_le_bytes())
+            .map(F::from_canonical_u8)
+            .collect()
+    }
+}
+
 pub mod proof_serde {
     use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 
@@ -154,3 +403,47 @@ pub mod proof_serde {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        stark::ProgramVerificationError, utils::tests::FIBONACCI_IO_ELF, SP1Prover, SP1Stdin,
+        SP1Stdout, SP1Verifier,
+    };
+
+    #[test]
+    fn test_verify_with_output() {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&3u32);
+        let proof = SP1Prover::prove(FIBONACCI_IO_ELF, stdin).unwrap();
+        let mut stdout = SP1Verifier::verify_with_output(FIBONACCI_IO_ELF, &proof).unwrap();
+        assert_eq!(stdout.read::<u32>(), 1);
+        assert_eq!(stdout.read::<u32>(), 2);
+    }
+
+    #[test]
+    fn test_tampered_io_fails() {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&3u32);
+        let mut proof = SP1Prover::prove(FIBONACCI_IO_ELF, stdin).unwrap();
+
+        // Swapping the output must invalidate the proof.
+        let mut stdout = SP1Stdout::new();
+        stdout.write(&1u32);
+        stdout.write(&3u32);
+        let honest_stdout = std::mem::replace(&mut proof.stdout, stdout);
+        assert!(matches!(
+            SP1Verifier::verify(FIBONACCI_IO_ELF, &proof),
+            Err(ProgramVerificationError::IoDigestMismatch)
+        ));
+
+        // So must swapping the input.
+        proof.stdout = honest_stdout;
+        proof.stdin = SP1Stdin::new();
+        proof.stdin.write(&4u32);
+        assert!(matches!(
+            SP1Verifier::verify(FIBONACCI_IO_ELF, &proof),
+            Err(ProgramVerificationError::IoDigestMismatch)
+        ));
+    }
+}
@@ -31,16 +31,18 @@ pub mod utils;
 
 pub use io::*;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use disassembler::Elf;
 use p3_commit::Pcs;
 use p3_matrix::dense::RowMajorMatrix;
-use runtime::{Program, Runtime};
+use runtime::{ExecutionLimits, ExecutionReport, Profiler, Program, Runtime, ShardingConfig};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use stark::{CostReport, RiscvStark, StarkGenericConfig};
 use stark::{OpeningProof, ProgramVerificationError, Proof, ShardMainData};
-use stark::{RiscvStark, StarkGenericConfig};
 use std::fs;
-use utils::{prove_core, BabyBearBlake3, StarkUtils};
+use utils::{prove_core, prove_core_streaming, BabyBearBlake3, StarkUtils};
 
 /// A prover that can prove RISCV ELFs.
 pub struct SP1Prover;
@@ -55,37 +57,113 @@ pub struct SP1ProofWithIO<SC: StarkGenericConfig + Serialize + DeserializeOwned>
     pub proof: Proof<SC>,
     pub stdin: SP1Stdin,
     pub stdout: SP1Stdout,
+    pub exit_code: u32,
 }
 
 impl SP1Prover {
-    /// Executes the elf with the given inputs and returns the output.
-    pub fn execute(elf: &[u8], stdin: SP1Stdin) -> Result<SP1Stdout> {
-        let program = Program::from(elf);
-        let mut runtime = Runtime::new(program);
-        runtime.write_stdin_slice(&stdin.buffer.data);
-        runtime.run();
-        Ok(SP1Stdout::from(&runtime.state.output_stream))
+    /// Executes the elf with the given inputs and returns the output and a report of the
+    /// execution.
+    ///
+    /// Fails if the program exits with a non-zero exit code.
+    pub fn execute(elf: &[u8], stdin: SP1Stdin) -> Result<(SP1Stdout, ExecutionReport)> {
+        Self::execute_with_limits(elf, stdin, ExecutionLimits::from_env()?)
+    }
+
+    /// Executes the elf with the given inputs and returns the output and a report of the
+    /// execution, stopping with an error if the program exceeds the given cycle or memory limits.
+    ///
+    /// Fails if the program exits with a non-zero exit code.
+    pub fn execute_with_limits(
+        elf: &[u8],
+        stdin: SP1Stdin,
+        limits: ExecutionLimits,
+    ) -> Result<(SP1Stdout, ExecutionReport)> {
+        let runtime = Self::run(elf, &stdin, limits, false)?;
+        let stdout = SP1Stdout::from(&runtime.state.output_stream);
+        Ok((stdout, runtime.report()))
+    }
+
+    /// Executes the elf with the given inputs and profiles the cycles spent in each function of
+    /// the program, whatever its exit code.
+    pub fn profile(elf: &[u8], stdin: SP1Stdin) -> Result<Profiler> {
+        Self::profile_with_limits(elf, stdin, ExecutionLimits::from_env()?)
+    }
+
+    /// Executes the elf with the given inputs and profiles the cycles spent in each function of
+    /// the program, stopping with an error if the program exceeds the given limits.
+    pub fn profile_with_limits(
+        elf: &[u8],
+        stdin: SP1Stdin,
+        limits: ExecutionLimits,
+    ) -> Result<Profiler> {
+        let mut runtime = Self::runtime(elf, &stdin, limits)?;
+        let debug_info = Elf::debug_info(elf).unwrap_or_else(|e| {
+            log::warn!("ignoring the debug info of the elf: {}", e);
+            None
+        });
+        runtime.profiler = Some(Profiler::new(Elf::symbols(elf)?, debug_info));
+        tracing::info_span!("runtime.run(...)").in_scope(|| runtime.run())?;
+        Ok(runtime.profiler.take().unwrap())
+    }
+
+    /// Executes the elf with the given inputs and estimates the cost of proving the execution,
+    /// without proving it.
+    ///
+    /// Fails if the program exits with a non-zero exit code.
+    pub fn estimate_cost(elf: &[u8], stdin: SP1Stdin) -> Result<CostReport> {
+        Self::estimate_cost_with_limits(elf, stdin, ExecutionLimits::from_env()?)
+    }
+
+    /// Executes the elf with the given inputs and estimates the cost of proving the execution,
+    /// stopping with an error if the program exceeds the given limits.
+    ///
+    /// Fails if the program exits with a non-zero exit code.
+    pub fn estimate_cost_with_limits(
+        elf: &[u8],
+        stdin: SP1Stdin,
+        limits: ExecutionLimits,
+    ) -> Result<CostReport> {
+        let runtime = Self::run(elf, &stdin, limits, false)?;
+        let machine = RiscvStark::new(BabyBearBlake3::new());
+        Ok(machine.estimate_cost(&runtime.record, &ShardingConfig::default()))
     }
 
     /// Generate a proof for the execution of the ELF with the given public inputs.
+    ///
+    /// Fails if the program exits with a non-zero exit code.
     pub fn prove(elf: &[u8], stdin: SP1Stdin) -> Result<SP1ProofWithIO<BabyBearBlake3>> {
-        let program = Program::from(elf);
-        let mut runtime = Runtime::new(program);
-        runtime.write_stdin_slice(&stdin.buffer.data);
-        tracing::info_span!("runtime.run(...)").in_scope(|| {
-            runtime.run();
-        });
-        let config = BabyBearBlake3::new();
-        let stdout = SP1Stdout::from(&runtime.state.output_stream);
-        let proof = prove_core(config, runtime);
-        Ok(SP1ProofWithIO {
-            proof,
-            stdin,
-            stdout,
-        })
+        Self::prove_with_config(elf, stdin, BabyBearBlake3::new())
+    }
+
+    /// Generate a proof for the execution of the ELF with the given public inputs, stopping with
+    /// an error if the program exceeds the given limits.
+    ///
+    /// Fails if the program exits with a non-zero exit code.
+    pub fn prove_with_limits(
+        elf: &[u8],
+        stdin: SP1Stdin,
+        limits: ExecutionLimits,
+    ) -> Result<SP1ProofWithIO<BabyBearBlake3>> {
+        let runtime = Self::run(elf, &stdin, limits, false)?;
+        Ok(Self::prove_runtime(runtime, stdin, BabyBearBlake3::new()))
+    }
+
+    /// Generate a proof for the execution of the ELF with the given public inputs, even if the
+    /// program exits with a non-zero exit code (for example, because it panicked).
+    ///
+    /// The exit code is part of the public values of the proof, so it can be checked with
+    /// `SP1Verifier::verify_with_exit_code`.
+    pub fn prove_allow_nonzero_exit(
+        elf: &[u8],
+        stdin: SP1Stdin,
+    ) -> Result<SP1ProofWithIO<BabyBearBlake3>> {
+        let runtime = Self::run(elf, &stdin, ExecutionLimits::from_env()?, true)?;
+        Ok(Self::prove_runtime(runtime, stdin, BabyBearBlake3::new()))
     }
 
     /// Generate a proof for the execution of the ELF with the given public inputs and a custom config.
+    ///
+    /// Fails if the program exits with a non-zero exit code.
     pub fn prove_with_config<SC: StarkGenericConfig>(
         elf: &[u8],
         stdin: SP1Stdin,
@@ -100,41 +178,149 @@ impl SP1Prover {
         ShardMainData<SC>: Serialize + DeserializeOwned,
         <SC as StarkGenericConfig>::Val: p3_field::PrimeField32,
     {
-        let program = Program::from(elf);
+        let runtime = Self::run(elf, &stdin, ExecutionLimits::from_env()?, false)?;
+        Ok(Self::prove_runtime(runtime, stdin, config))
+    }
+
+    /// Generate a proof for the execution of the ELF with the given public inputs, proving one
+    /// shard at a time so that the memory used does not grow with the number of cycles.
+    ///
+    /// The program is executed twice, so its input sources and hints must give the same answers
+    /// every time they are read. Fails if the program exits with a non-zero exit code.
+    pub fn prove_streaming(elf: &[u8], stdin: SP1Stdin) -> Result<SP1ProofWithIO<BabyBearBlake3>> {
+        let program = Program::try_from_elf(elf)?;
+        let limits = ExecutionLimits::from_env()?;
+        let new_runtime = || {
+            let mut runtime = Runtime::new(program.clone());
+            runtime.limits = limits;
+            runtime.write_input(&stdin);
+            runtime
+        };
+        let (proof, runtime) = prove_core_streaming(BabyBearBlake3::new(), new_runtime, false)?;
+        Ok(SP1ProofWithIO {
+            proof,
+            stdout: SP1Stdout::from(&runtime.state.output_stream),
+            exit_code: runtime.state.exit_code,
+            stdin,
+        })
+    }
+
+    /// Executes the elf with the given inputs and limits, failing on a non-zero exit code unless
+    /// `allow_nonzero_exit` is set.
+    fn run(
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        limits: ExecutionLimits,
+        allow_nonzero_exit: bool,
+    ) -> Result<Runtime> {
+        let mut runtime = Self::runtime(elf, stdin, limits)?;
+        let outcome = tracing::info_span!("runtime.run(...)").in_scope(|| runtime.run())?;
+        if !allow_nonzero_exit && !outcome.is_success() {
+            bail!(
+                "program exited with non-zero exit code {}",
+                outcome.exit_code
+            );
+        }
+        Ok(runtime)
+    }
+
+    /// Creates a runtime for the elf with the given inputs and limits.
+    fn runtime(elf: &[u8], stdin: &SP1Stdin, limits: ExecutionLimits) -> Result<Runtime> {
+        let program = Program::try_from_elf(elf)?;
         let mut runtime = Runtime::new(program);
-        runtime.write_stdin_slice(&stdin.buffer.data);
-        runtime.run();
+        runtime.limits = limits;
+        runtime.write_input(stdin);
+        Ok(runtime)
+    }
+
+    /// Proves an execution that has already been run.
+    fn prove_runtime<SC: StarkGenericConfig>(
+        runtime: Runtime,
+        stdin: SP1Stdin,
+        config: SC,
+    ) -> SP1ProofWithIO<SC>
+    where
+        SC: StarkUtils + Send + Sync + Serialize + DeserializeOwned + Clone,
+        SC::Challenger: Clone,
+        OpeningProof<SC>: Send + Sync,
+        <SC::Pcs as Pcs<SC::Val, RowMajorMatrix<SC::Val>>>::Commitment: Send + Sync,
+        <SC::Pcs as Pcs<SC::Val, RowMajorMatrix<SC::Val>>>::ProverData: Send + Sync,
+        ShardMainData<SC>: Serialize + DeserializeOwned,
+        <SC as StarkGenericConfig>::Val: p3_field::PrimeField32,
+    {
         let stdout = SP1Stdout::from(&runtime.state.output_stream);
+        let exit_code = runtime.state.exit_code;
         let proof = prove_core(config, runtime);
-        Ok(SP1ProofWithIO {
+        SP1ProofWithIO {
             proof,
             stdin,
             stdout,
-        })
+            exit_code,
+        }
     }
 }
 
 impl SP1Verifier {
     /// Verify a proof generated by `SP1Prover`.
-    #[allow(unused_variables)]
+    ///
+    /// Proofs of executions that exited with a non-zero exit code are rejected.
     pub fn verify(
         elf: &[u8],
         proof: &SP1ProofWithIO<BabyBearBlake3>,
     ) -> Result<(), ProgramVerificationError> {
-        let config = BabyBearBlake3::new();
-        let mut challenger = config.challenger();
-        let machine = RiscvStark::new(config);
-        let (_, vk) = machine.setup(&Program::from(elf));
-        machine.verify(&vk, &proof.proof, &mut challenger)
+        Self::verify_with_config(elf, proof, BabyBearBlake3::new())
+    }
+
+    /// Verify a proof generated by `SP1Prover` and return the output attached to it.
+    pub fn verify_with_output(
+        elf: &[u8],
+        proof: &SP1ProofWithIO<BabyBearBlake3>,
+    ) -> Result<SP1Stdout, ProgramVerificationError> {
+        Self::verify(elf, proof)?;
+        Ok(SP1Stdout::from(&proof.stdout.buffer.data))
+    }
+
+    /// Verify a proof generated by `SP1Prover`, accepting any exit code, and return the exit code
+    /// of the proven execution.
+    pub fn verify_with_exit_code(
+        elf: &[u8],
+        proof: &SP1ProofWithIO<BabyBearBlake3>,
+    ) -> Result<u32, ProgramVerificationError> {
+        Self::verify_execution(elf, proof, BabyBearBlake3::new())?;
+        Ok(proof.exit_code)
     }
 
     /// Verify a proof generated by `SP1Prover` with a custom config.
-    #[allow(unused_variables)]
+    ///
+    /// Proofs of executions that exited with a non-zero exit code are rejected.
     pub fn verify_with_config<SC: StarkGenericConfig>(
         elf: &[u8],
         proof: &SP1ProofWithIO<SC>,
         config: SC,
     ) -> Result<(), ProgramVerificationError>
+    where
+        SC: StarkUtils + Send + Sync + Serialize + DeserializeOwned,
+        SC::Challenger: Clone,
+        OpeningProof<SC>: Send + Sync,
+        <SC::Pcs as Pcs<SC::Val, RowMajorMatrix<SC::Val>>>::Commitment: Send + Sync,
+        <SC::Pcs as Pcs<SC::Val, RowMajorMatrix<SC::Val>>>::ProverData: Send + Sync,
+        ShardMainData<SC>: Serialize + DeserializeOwned,
+        <SC as StarkGenericConfig>::Val: p3_field::PrimeField32,
+    {
+        Self::verify_execution(elf, proof, config)?;
+        if proof.exit_code != 0 {
+            return Err(ProgramVerificationError::NonZeroExitCode(proof.exit_code));
+        }
+        Ok(())
+    }
+
+    /// Verify the proof and that the digests of its attached input and output and its exit code
+    /// match its public values.
+    fn verify_execution<SC: StarkGenericConfig>(
+        elf: &[u8],
+        proof: &SP1ProofWithIO<SC>,
+        config: SC,
+    ) -> Result<(), ProgramVerificationError>
     where
         SC: StarkUtils + Send + Sync + Serialize + DeserializeOwned,
         SC::Challenger: Clone,
@@ -147,8 +333,10 @@ impl SP1Verifier {
         let mut challenger = config.challenger();
         let machine = RiscvStark::new(config);
 
-        let (_, vk) = machine.setup(&Program::from(elf));
-        machine.verify(&vk, &proof.proof, &mut challenger)
+        let program = Program::try_from_elf(elf).map_err(ProgramVerificationError::InvalidElf)?;
+        let (_, vk) = machine.setup(&program);
+        machine.verify(&vk, &proof.proof, &mut challenger)?;
+        proof.check_io_digests()
     }
 }
 
@@ -159,4 +347,28 @@ impl<SC: StarkGenericConfig + Serialize + DeserializeOwned> SP1ProofWithIO<SC> {
         fs::write(path, data).unwrap();
         Ok(())
     }
+
+    /// Checks that the stdin, stdout and exit code attached to the proof match the public values it
+    /// observes, which the AIR constrains to be the digests committed to by the program and its
+    /// exit code. Only the public input the program read is checked, so the stdin may hold more.
+    fn check_io_digests(&self) -> Result<(), ProgramVerificationError> {
+        let public_values = self
+            .proof
+            .public_values()
+            .ok_or(ProgramVerificationError::IoDigestMismatch)?;
+        let input_digest = self
+            .stdin
+            .input_digest(public_values.input_len)
+            .map_err(|_| ProgramVerificationError::IoDigestMismatch)?;
+        let expected = PublicValues {
+            input_digest,
+            input_len: public_values.input_len,
+            output_digest: Sha256::digest(&self.stdout.buffer.data).into(),
+            exit_code: self.exit_code,
+        };
+        if public_values != expected {
+            return Err(ProgramVerificationError::IoDigestMismatch);
+        }
+        Ok(())
+    }
 }
@@ -1,29 +1,42 @@
-use crate::air::{AirInteraction, MessageBuilder};
-use p3_air::{AirBuilder, PairCol, VirtualPairCol};
+use crate::air::{AirInteraction, MessageBuilder, PublicInputBuilder, Word};
+use p3_air::{AirBuilder, PairBuilder, PairCol, VirtualPairCol};
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_uni_stark::{SymbolicExpression, SymbolicVariable};
 
 use super::Interaction;
+use crate::NUM_COMMITTED_WORDS;
+use std::array;
 
 /// A builder for the lookup table interactions.
+///
+/// The symbolic variables of the preprocessed trace occupy the columns `0..preprocessed_width` and
+/// the ones of the main trace are shifted by `preprocessed_width`, so that an interaction can refer
+/// to both traces.
 pub struct InteractionBuilder<F: Field> {
+    preprocessed: RowMajorMatrix<SymbolicVariable<F>>,
     main: RowMajorMatrix<SymbolicVariable<F>>,
+    preprocessed_width: usize,
     sends: Vec<Interaction<F>>,
     receives: Vec<Interaction<F>>,
 }
 
 impl<F: Field> InteractionBuilder<F> {
-    /// Creates a new `InteractionBuilder` with the given width.
-    pub fn new(width: usize) -> Self {
-        let values = [false, true]
-            .into_iter()
-            .flat_map(|is_next| {
-                (0..width).map(move |column| SymbolicVariable::new(is_next, column))
-            })
-            .collect();
+    /// Creates a new `InteractionBuilder` with the given preprocessed and main widths.
+    pub fn new(preprocessed_width: usize, main_width: usize) -> Self {
+        let symbolic_matrix = |offset: usize, width: usize| {
+            let values = [false, true]
+                .into_iter()
+                .flat_map(|is_next| {
+                    (0..width).map(move |column| SymbolicVariable::new(is_next, offset + column))
+                })
+                .collect();
+            RowMajorMatrix::new(values, width)
+        };
         Self {
-            main: RowMajorMatrix::new(values, width),
+            preprocessed: symbolic_matrix(0, preprocessed_width),
+            main: symbolic_matrix(preprocessed_width, main_width),
+            preprocessed_width,
             sends: vec![],
             receives: vec![],
         }
@@ -64,15 +77,36 @@ impl<F: Field> AirBuilder for InteractionBuilder<F> {
     fn assert_zero<I: Into<Self::Expr>>(&mut self, _x: I) {}
 }
 
+impl<F: Field> PairBuilder for InteractionBuilder<F> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed.clone()
+    }
+}
+
+// The interactions of a chip must not depend on the public inputs, which differ between shards.
+impl<F: Field> PublicInputBuilder for InteractionBuilder<F> {
+    fn is_last_shard(&self) -> Self::Expr {
+        SymbolicExpression::Constant(F::zero())
+    }
+
+    fn exit_code(&self) -> Word<Self::Expr> {
+        Word::from(0).map(SymbolicExpression::Constant)
+    }
+
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS] {
+        array::from_fn(|_| Word::from(0).map(SymbolicExpression::Constant))
+    }
+}
+
 impl<F: Field> MessageBuilder<AirInteraction<SymbolicExpression<F>>> for InteractionBuilder<F> {
     fn send(&mut self, message: AirInteraction<SymbolicExpression<F>>) {
         let values = message
             .values
             .into_iter()
-            .map(|v| symbolic_to_virtual_pair(&v))
+            .map(|v| symbolic_to_virtual_pair(&v, self.preprocessed_width))
             .collect::<Vec<_>>();
 
-        let multiplicity = symbolic_to_virtual_pair(&message.multiplicity);
+        let multiplicity = symbolic_to_virtual_pair(&message.multiplicity, self.preprocessed_width);
 
         self.sends
             .push(Interaction::new(values, multiplicity, message.kind));
@@ -82,22 +116,25 @@ impl<F: Field> MessageBuilder<AirInteraction<SymbolicExpression<F>>> for Interac
         let values = message
             .values
             .into_iter()
-            .map(|v| symbolic_to_virtual_pair(&v))
+            .map(|v| symbolic_to_virtual_pair(&v, self.preprocessed_width))
             .collect::<Vec<_>>();
 
-        let multiplicity = symbolic_to_virtual_pair(&message.multiplicity);
+        let multiplicity = symbolic_to_virtual_pair(&message.multiplicity, self.preprocessed_width);
 
         self.receives
             .push(Interaction::new(values, multiplicity, message.kind));
     }
 }
 
-fn symbolic_to_virtual_pair<F: Field>(expression: &SymbolicExpression<F>) -> VirtualPairCol<F> {
+fn symbolic_to_virtual_pair<F: Field>(
+    expression: &SymbolicExpression<F>,
+    preprocessed_width: usize,
+) -> VirtualPairCol<F> {
     if expression.degree_multiple() > 1 {
         panic!("degree multiple is too high");
     }
 
-    let (column_weights, constant) = eval_symbolic_to_virtual_pair(expression);
+    let (column_weights, constant) = eval_symbolic_to_virtual_pair(expression, preprocessed_width);
 
     let column_weights = column_weights.into_iter().collect();
 
@@ -106,30 +143,36 @@ fn symbolic_to_virtual_pair<F: Field>(expression: &SymbolicExpression<F>) -> Vir
 
 fn eval_symbolic_to_virtual_pair<F: Field>(
     expression: &SymbolicExpression<F>,
+    preprocessed_width: usize,
 ) -> (Vec<(PairCol, F)>, F) {
     match expression {
         SymbolicExpression::Constant(c) => (vec![], *c),
         SymbolicExpression::Variable(v) if !v.is_next => {
-            (vec![(PairCol::Main(v.column), F::one())], F::zero())
+            let column = if v.column < preprocessed_width {
+                PairCol::Preprocessed(v.column)
+            } else {
+                PairCol::Main(v.column - preprocessed_width)
+            };
+            (vec![(column, F::one())], F::zero())
         }
         SymbolicExpression::Add { x, y, .. } => {
-            let (v_l, c_l) = eval_symbolic_to_virtual_pair(x);
-            let (v_r, c_r) = eval_symbolic_to_virtual_pair(y);
+            let (v_l, c_l) = eval_symbolic_to_virtual_pair(x, preprocessed_width);
+            let (v_r, c_r) = eval_symbolic_to_virtual_pair(y, preprocessed_width);
             ([v_l, v_r].concat(), c_l + c_r)
         }
         SymbolicExpression::Sub { x, y, .. } => {
-            let (v_l, c_l) = eval_symbolic_to_virtual_pair(x);
-            let (v_r, c_r) = eval_symbolic_to_virtual_pair(y);
+            let (v_l, c_l) = eval_symbolic_to_virtual_pair(x, preprocessed_width);
+            let (v_r, c_r) = eval_symbolic_to_virtual_pair(y, preprocessed_width);
             let neg_v_r = v_r.iter().map(|(c, w)| (*c, -*w)).collect();
             ([v_l, neg_v_r].concat(), c_l - c_r)
         }
         SymbolicExpression::Neg { x, .. } => {
-            let (v, c) = eval_symbolic_to_virtual_pair(x);
+            let (v, c) = eval_symbolic_to_virtual_pair(x, preprocessed_width);
             (v.iter().map(|(c, w)| (*c, -*w)).collect(), -c)
         }
         SymbolicExpression::Mul { x, y, .. } => {
-            let (v_l, c_l) = eval_symbolic_to_virtual_pair(x);
-            let (v_r, c_r) = eval_symbolic_to_virtual_pair(y);
+            let (v_l, c_l) = eval_symbolic_to_virtual_pair(x, preprocessed_width);
+            let (v_r, c_r) = eval_symbolic_to_virtual_pair(y, preprocessed_width);
 
             let mut v = vec![];
             v.extend(v_l.iter().map(|(c, w)| (*c, *w * c_r)));
@@ -180,7 +223,7 @@ mod tests {
 
         let z = x + y;
 
-        let (column_weights, constant) = super::eval_symbolic_to_virtual_pair(&z);
+        let (column_weights, constant) = super::eval_symbolic_to_virtual_pair(&z, 0);
         println!("column_weights: {:?}", column_weights);
         println!("constant: {:?}", constant);
 
@@ -235,7 +278,7 @@ mod tests {
     fn test_lookup_interactions() {
         let air = LookupTestAir {};
 
-        let mut builder = InteractionBuilder::<BabyBear>::new(NUM_COLS);
+        let mut builder = InteractionBuilder::<BabyBear>::new(0, NUM_COLS);
 
         air.eval(&mut builder);
 
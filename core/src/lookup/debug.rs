@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 use p3_baby_bear::BabyBear;
 use p3_field::AbstractField;
 use p3_field::{Field, PrimeField64};
-use p3_matrix::Matrix;
+use p3_matrix::{Matrix, MatrixRowSlices};
 
 use crate::air::MachineAir;
 use crate::runtime::ExecutionRecord;
@@ -55,6 +55,7 @@ pub fn debug_interactions<SC: StarkGenericConfig>(
     let mut key_to_count = BTreeMap::new();
 
     let trace = chip.generate_trace(record, &mut ExecutionRecord::default());
+    let preprocessed = chip.generate_preprocessed_trace(&record.program);
     let mut main = trace.clone();
     let height = trace.clone().height();
 
@@ -70,12 +71,18 @@ pub fn debug_interactions<SC: StarkGenericConfig>(
                 continue;
             }
             let is_send = m < nb_send_interactions;
-            let multiplicity_eval: SC::Val = interaction.multiplicity.apply(&[], main.row_mut(row));
+            let preprocessed_row: &[SC::Val] = match preprocessed {
+                Some(ref preprocessed) => preprocessed.row_slice(row),
+                None => &[],
+            };
+            let multiplicity_eval: SC::Val = interaction
+                .multiplicity
+                .apply(preprocessed_row, main.row_mut(row));
 
             if !multiplicity_eval.is_zero() {
                 let mut values = vec![];
                 for value in &interaction.values {
-                    let expr: SC::Val = value.apply(&[], main.row_mut(row));
+                    let expr: SC::Val = value.apply(preprocessed_row, main.row_mut(row));
                     values.push(expr);
                 }
                 let key = format!(
@@ -18,7 +18,6 @@ use sp1_derive::AlignedBorrow;
 pub enum MemoryChipKind {
     Init,
     Finalize,
-    Program,
 }
 
 pub struct MemoryGlobalChip {
@@ -42,7 +41,6 @@ impl<F: PrimeField> MachineAir<F> for MemoryGlobalChip {
         match self.kind {
             MemoryChipKind::Init => "MemoryInit".to_string(),
             MemoryChipKind::Finalize => "MemoryFinalize".to_string(),
-            MemoryChipKind::Program => "MemoryProgram".to_string(),
         }
     }
 
@@ -54,7 +52,6 @@ impl<F: PrimeField> MachineAir<F> for MemoryGlobalChip {
         let memory_record = match self.kind {
             MemoryChipKind::Init => &input.first_memory_record,
             MemoryChipKind::Finalize => &input.last_memory_record,
-            MemoryChipKind::Program => &input.program_memory_record,
         };
         let rows: Vec<[F; 8]> = (0..memory_record.len()) // TODO: change this back to par_iter
             .map(|i| {
@@ -114,7 +111,13 @@ where
             local.is_real * local.is_real * local.is_real,
         );
 
-        if self.kind == MemoryChipKind::Init || self.kind == MemoryChipKind::Program {
+        if self.kind == MemoryChipKind::Init {
+            // Memory outside of the program's memory image, which is initialized by the
+            // `MemoryProgram` chip, always starts out as zero.
+            for limb in local.value.0 {
+                builder.assert_zero(limb);
+            }
+
             let mut values = vec![AB::Expr::zero(), AB::Expr::zero(), local.addr.into()];
             values.extend(local.value.map(Into::into));
             builder.receive(AirInteraction::new(
@@ -158,7 +161,7 @@ mod tests {
     fn test_memory_generate_trace() {
         let program = simple_program();
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         let shard = runtime.record.clone();
 
         let chip: MemoryGlobalChip = MemoryGlobalChip::new(MemoryChipKind::Init);
@@ -184,7 +187,7 @@ mod tests {
 
         let program = simple_program();
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
 
         let chip = MemoryGlobalChip::new(MemoryChipKind::Init);
 
@@ -201,7 +204,7 @@ mod tests {
         setup_logger();
         let program = sha_extend_program();
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
 
         let machine = RiscvStark::new(BabyBearPoseidon2::new());
         debug_interactions_with_all_chips::<BabyBearPoseidon2>(
@@ -216,7 +219,7 @@ mod tests {
         setup_logger();
         let program = sha_extend_program();
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
 
         let machine = RiscvStark::new(BabyBearPoseidon2::new());
         debug_interactions_with_all_chips::<BabyBearPoseidon2>(
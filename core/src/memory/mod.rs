@@ -1,6 +1,8 @@
 mod columns;
 mod global;
+mod program;
 mod trace;
 
 pub use columns::*;
 pub use global::*;
+pub use program::*;
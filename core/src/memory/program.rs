@@ -0,0 +1,220 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use p3_air::{Air, AirBuilder, BaseAir, PairBuilder};
+use p3_field::AbstractField;
+use p3_field::PrimeField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use std::collections::HashMap;
+
+use sp1_derive::AlignedBorrow;
+
+use crate::air::{AirInteraction, MachineAir, PublicInputBuilder, SP1AirBuilder, Word};
+use crate::lookup::InteractionKind;
+use crate::runtime::{ExecutionRecord, Program};
+use crate::utils::pad_to_power_of_two;
+
+pub const NUM_MEMORY_PROGRAM_PREPROCESSED_COLS: usize =
+    size_of::<MemoryProgramPreprocessedCols<u8>>();
+pub const NUM_MEMORY_PROGRAM_MULT_COLS: usize = size_of::<MemoryProgramMultCols<u8>>();
+
+/// The column layout for the preprocessed part of the chip, which is fixed by the program's memory
+/// image.
+#[derive(AlignedBorrow, Clone, Copy, Default)]
+#[repr(C)]
+pub struct MemoryProgramPreprocessedCols<T> {
+    pub addr: T,
+    pub value: Word<T>,
+    pub is_real: T,
+}
+
+/// The column layout for the main trace of the chip, which depends on the shard.
+#[derive(AlignedBorrow, Clone, Copy, Default)]
+#[repr(C)]
+pub struct MemoryProgramMultCols<T> {
+    /// Whether the memory image is initialized in this shard, which is only the case in the last
+    /// shard.
+    pub multiplicity: T,
+}
+
+/// A chip that initializes the memory with the program's memory image.
+///
+/// The addresses and values of the memory image are committed to in the preprocessed trace, and
+/// every one of them is initialized exactly once, in the last shard, so that a proof can only
+/// initialize memory with the image of the program it was set up with.
+#[derive(Default)]
+pub struct MemoryProgramChip;
+
+impl MemoryProgramChip {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<F> BaseAir<F> for MemoryProgramChip {
+    fn width(&self) -> usize {
+        NUM_MEMORY_PROGRAM_MULT_COLS
+    }
+}
+
+impl<F: PrimeField> MachineAir<F> for MemoryProgramChip {
+    fn name(&self) -> String {
+        "MemoryProgram".to_string()
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        NUM_MEMORY_PROGRAM_PREPROCESSED_COLS
+    }
+
+    fn generate_preprocessed_trace(&self, program: &Program) -> Option<RowMajorMatrix<F>> {
+        if program.memory_image.is_empty() {
+            return None;
+        }
+
+        let rows = program
+            .memory_image
+            .iter()
+            .map(|(&addr, &word)| {
+                let mut row = [F::zero(); NUM_MEMORY_PROGRAM_PREPROCESSED_COLS];
+                let cols: &mut MemoryProgramPreprocessedCols<F> = row.as_mut_slice().borrow_mut();
+                cols.addr = F::from_canonical_u32(addr);
+                cols.value = word.into();
+                cols.is_real = F::one();
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let mut trace = RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_MEMORY_PROGRAM_PREPROCESSED_COLS,
+        );
+
+        pad_to_power_of_two::<NUM_MEMORY_PROGRAM_PREPROCESSED_COLS, F>(&mut trace.values);
+
+        Some(trace)
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        _output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        // The program memory records are only present in the last shard, where every address of
+        // the image is initialized. In every other shard, the table is still included (to match the
+        // preprocessed trace) but with zero multiplicities.
+        let used = input
+            .program_memory_record
+            .iter()
+            .map(|(addr, _, used)| (*addr, *used))
+            .collect::<HashMap<_, _>>();
+
+        // The rows are in the same order as the rows of the preprocessed trace.
+        let rows = input
+            .program
+            .memory_image
+            .keys()
+            .map(|addr| {
+                let mut row = [F::zero(); NUM_MEMORY_PROGRAM_MULT_COLS];
+                let cols: &mut MemoryProgramMultCols<F> = row.as_mut_slice().borrow_mut();
+                cols.multiplicity = F::from_canonical_u32(*used.get(addr).unwrap_or(&0));
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let mut trace = RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_MEMORY_PROGRAM_MULT_COLS,
+        );
+
+        pad_to_power_of_two::<NUM_MEMORY_PROGRAM_MULT_COLS, F>(&mut trace.values);
+
+        trace
+    }
+}
+
+impl<AB> Air<AB> for MemoryProgramChip
+where
+    AB: SP1AirBuilder + PairBuilder + PublicInputBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+
+        let prep_local: &MemoryProgramPreprocessedCols<AB::Var> =
+            preprocessed.row_slice(0).borrow();
+        let mult_local: &MemoryProgramMultCols<AB::Var> = main.row_slice(0).borrow();
+
+        // Dummy constraint of degree 3.
+        builder.assert_eq(
+            prep_local.is_real * prep_local.is_real * prep_local.is_real,
+            prep_local.is_real * prep_local.is_real * prep_local.is_real,
+        );
+
+        // Every row of the memory image is initialized in the last shard, and no row is initialized
+        // in any other shard, so the prover cannot skip the image.
+        let is_last_shard = builder.is_last_shard();
+        builder.assert_eq(mult_local.multiplicity, prep_local.is_real * is_last_shard);
+
+        let mut values = vec![AB::Expr::zero(), AB::Expr::zero(), prep_local.addr.into()];
+        values.extend(prep_local.value.map(Into::into));
+        builder.receive(AirInteraction::new(
+            values,
+            mult_local.multiplicity.into(),
+            InteractionKind::Memory,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_matrix::{dense::RowMajorMatrix, Matrix};
+
+    use crate::air::MachineAir;
+    use crate::memory::MemoryProgramChip;
+    use crate::runtime::tests::ssz_withdrawals_program;
+    use crate::runtime::{ExecutionRecord, Runtime};
+
+    #[test]
+    fn test_memory_program_generate_trace() {
+        let program = ssz_withdrawals_program();
+        let mut runtime = Runtime::new(program);
+        runtime.run().unwrap();
+
+        let chip = MemoryProgramChip::new();
+        let preprocessed: RowMajorMatrix<BabyBear> =
+            chip.generate_preprocessed_trace(&runtime.program).unwrap();
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&runtime.record, &mut ExecutionRecord::default());
+        assert_eq!(preprocessed.height(), trace.height());
+    }
+
+    #[test]
+    fn test_memory_program_initializes_whole_image() {
+        let program = ssz_withdrawals_program();
+        let mut runtime = Runtime::new(program);
+        runtime.run().unwrap();
+
+        // Every address of the image is initialized exactly once, and the addresses the program
+        // never accessed are finalized with the value they were initialized with.
+        let record = &runtime.record;
+        assert_eq!(
+            record.program_memory_record.len(),
+            runtime.program.memory_image.len()
+        );
+        assert!(record
+            .program_memory_record
+            .iter()
+            .all(|(_, _, multiplicity)| *multiplicity == 1));
+        for (addr, value) in runtime.program.memory_image.iter() {
+            let (_, last, _) = record
+                .last_memory_record
+                .iter()
+                .find(|(last_addr, _, _)| last_addr == addr)
+                .unwrap();
+            if last.shard == 0 && last.timestamp == 0 {
+                assert_eq!(last.value, *value);
+            }
+        }
+    }
+}
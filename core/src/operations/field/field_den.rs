@@ -1,4 +1,5 @@
 use super::params::Limbs;
+use super::params::NUM_LIMBS;
 use super::params::NUM_WITNESS_LIMBS;
 use super::util::{compute_root_quotient_and_shift, split_u16_limbs_to_u8_limbs};
 use super::util_air::eval_field_operation;
@@ -16,19 +17,19 @@ use std::fmt::Debug;
 /// `a / (1 + b)` if `sign`
 /// `a / -b` if `!sign`
 ///
-/// Right now the number of limbs is assumed to be a constant, although this could be macro-ed
-/// or made generic in the future.
+/// `N` is the number of limbs of the field and `W = 2 * N - 2` the number of limbs of the witness,
+/// as in `FieldOpCols`.
 #[derive(Debug, Clone, AlignedBorrow)]
 #[repr(C)]
-pub struct FieldDenCols<T> {
+pub struct FieldDenCols<T, const N: usize = NUM_LIMBS, const W: usize = NUM_WITNESS_LIMBS> {
     /// The result of `a den b`, where a, b are field elements
-    pub result: Limbs<T>,
-    pub(crate) carry: Limbs<T>,
-    pub(crate) witness_low: [T; NUM_WITNESS_LIMBS],
-    pub(crate) witness_high: [T; NUM_WITNESS_LIMBS],
+    pub result: Limbs<T, N>,
+    pub(crate) carry: Limbs<T, N>,
+    pub(crate) witness_low: [T; W],
+    pub(crate) witness_high: [T; W],
 }
 
-impl<F: PrimeField32> FieldDenCols<F> {
+impl<F: PrimeField32, const N: usize, const W: usize> FieldDenCols<F, N, W> {
     pub fn populate<P: FieldParameters>(
         &mut self,
         a: &BigUint,
@@ -54,11 +55,11 @@ impl<F: PrimeField32> FieldDenCols<F> {
         debug_assert!(carry < p);
         debug_assert_eq!(&carry * &p, &equation_lhs - &equation_rhs);
 
-        let p_a: Polynomial<F> = P::to_limbs_field::<F>(a).into();
-        let p_b: Polynomial<F> = P::to_limbs_field::<F>(b).into();
-        let p_p: Polynomial<F> = P::to_limbs_field::<F>(&p).into();
-        let p_result: Polynomial<F> = P::to_limbs_field::<F>(&result).into();
-        let p_carry: Polynomial<F> = P::to_limbs_field::<F>(&carry).into();
+        let p_a: Polynomial<F> = P::to_limbs_field::<F, N>(a).into();
+        let p_b: Polynomial<F> = P::to_limbs_field::<F, N>(b).into();
+        let p_p: Polynomial<F> = P::to_limbs_field::<F, N>(&p).into();
+        let p_result: Polynomial<F> = P::to_limbs_field::<F, N>(&result).into();
+        let p_carry: Polynomial<F> = P::to_limbs_field::<F, N>(&carry).into();
 
         // Compute the vanishing polynomial.
         let vanishing_poly = if sign {
@@ -84,13 +85,13 @@ impl<F: PrimeField32> FieldDenCols<F> {
     }
 }
 
-impl<V: Copy> FieldDenCols<V> {
+impl<V: Copy, const N: usize, const W: usize> FieldDenCols<V, N, W> {
     #[allow(unused_variables)]
     pub fn eval<AB: SP1AirBuilder<Var = V>, P: FieldParameters>(
         &self,
         builder: &mut AB,
-        a: &Limbs<AB::Var>,
-        b: &Limbs<AB::Var>,
+        a: &Limbs<AB::Var, N>,
+        b: &Limbs<AB::Var, N>,
         sign: bool,
     ) where
         V: Into<AB::Expr>,
@@ -205,8 +206,8 @@ mod tests {
                 .map(|(a, b)| {
                     let mut row = [F::zero(); NUM_TEST_COLS];
                     let cols: &mut TestCols<F> = row.as_mut_slice().borrow_mut();
-                    cols.a = P::to_limbs_field::<F>(a);
-                    cols.b = P::to_limbs_field::<F>(b);
+                    cols.a = P::to_limbs_field(a);
+                    cols.b = P::to_limbs_field(b);
                     cols.a_den_b.populate::<P>(a, b, self.sign);
                     row
                 })
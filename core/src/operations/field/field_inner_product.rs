@@ -1,4 +1,5 @@
 use super::params::Limbs;
+use super::params::NUM_LIMBS;
 use super::params::NUM_WITNESS_LIMBS;
 use super::util::{compute_root_quotient_and_shift, split_u16_limbs_to_u8_limbs};
 use super::util_air::eval_field_operation;
@@ -13,24 +14,30 @@ use sp1_derive::AlignedBorrow;
 use std::fmt::Debug;
 
 /// A set of columns to compute `FieldInnerProduct(Vec<a>, Vec<b>)` where a, b are field elements.
-/// Right now the number of limbs is assumed to be a constant, although this could be macro-ed
-/// or made generic in the future.
+///
+/// `N` is the number of limbs of the field and `W = 2 * N - 2` the number of limbs of the witness,
+/// as in `FieldOpCols`.
 #[derive(Debug, Clone, AlignedBorrow)]
 #[repr(C)]
-pub struct FieldInnerProductCols<T> {
+pub struct FieldInnerProductCols<T, const N: usize = NUM_LIMBS, const W: usize = NUM_WITNESS_LIMBS>
+{
     /// The result of `a inner product b`, where a, b are field elements
-    pub result: Limbs<T>,
-    pub(crate) carry: Limbs<T>,
-    pub(crate) witness_low: [T; NUM_WITNESS_LIMBS],
-    pub(crate) witness_high: [T; NUM_WITNESS_LIMBS],
+    pub result: Limbs<T, N>,
+    pub(crate) carry: Limbs<T, N>,
+    pub(crate) witness_low: [T; W],
+    pub(crate) witness_high: [T; W],
 }
 
-impl<F: PrimeField32> FieldInnerProductCols<F> {
+impl<F: PrimeField32, const N: usize, const W: usize> FieldInnerProductCols<F, N, W> {
     pub fn populate<P: FieldParameters>(&mut self, a: &[BigUint], b: &[BigUint]) -> BigUint {
-        let p_a_vec: Vec<Polynomial<F>> =
-            a.iter().map(|x| P::to_limbs_field::<F>(x).into()).collect();
-        let p_b_vec: Vec<Polynomial<F>> =
-            b.iter().map(|x| P::to_limbs_field::<F>(x).into()).collect();
+        let p_a_vec: Vec<Polynomial<F>> = a
+            .iter()
+            .map(|x| P::to_limbs_field::<F, N>(x).into())
+            .collect();
+        let p_b_vec: Vec<Polynomial<F>> = b
+            .iter()
+            .map(|x| P::to_limbs_field::<F, N>(x).into())
+            .collect();
 
         let modulus = &P::modulus();
         let inner_product = a
@@ -44,9 +51,9 @@ impl<F: PrimeField32> FieldInnerProductCols<F> {
         assert!(carry < &(2u32 * modulus));
         assert_eq!(carry * modulus, inner_product - result);
 
-        let p_modulus: Polynomial<F> = P::to_limbs_field::<F>(modulus).into();
-        let p_result: Polynomial<F> = P::to_limbs_field::<F>(result).into();
-        let p_carry: Polynomial<F> = P::to_limbs_field::<F>(carry).into();
+        let p_modulus: Polynomial<F> = P::to_limbs_field::<F, N>(modulus).into();
+        let p_result: Polynomial<F> = P::to_limbs_field::<F, N>(result).into();
+        let p_carry: Polynomial<F> = P::to_limbs_field::<F, N>(carry).into();
 
         // Compute the vanishing polynomial.
         let p_inner_product = p_a_vec
@@ -74,13 +81,13 @@ impl<F: PrimeField32> FieldInnerProductCols<F> {
     }
 }
 
-impl<V: Copy> FieldInnerProductCols<V> {
+impl<V: Copy, const N: usize, const W: usize> FieldInnerProductCols<V, N, W> {
     #[allow(unused_variables)]
     pub fn eval<AB: SP1AirBuilder<Var = V>, P: FieldParameters>(
         &self,
         builder: &mut AB,
-        a: &[Limbs<AB::Var>],
-        b: &[Limbs<AB::Var>],
+        a: &[Limbs<AB::Var, N>],
+        b: &[Limbs<AB::Var, N>],
     ) where
         V: Into<AB::Expr>,
     {
@@ -188,8 +195,8 @@ mod tests {
                 .map(|(a, b)| {
                     let mut row = [F::zero(); NUM_TEST_COLS];
                     let cols: &mut TestCols<F> = row.as_mut_slice().borrow_mut();
-                    cols.a[0] = P::to_limbs_field::<F>(&a[0]);
-                    cols.b[0] = P::to_limbs_field::<F>(&b[0]);
+                    cols.a[0] = P::to_limbs_field(&a[0]);
+                    cols.b[0] = P::to_limbs_field(&b[0]);
                     cols.a_ip_b.populate::<P>(a, b);
                     row
                 })
@@ -1,4 +1,5 @@
 use super::params::Limbs;
+use super::params::NUM_LIMBS;
 use super::params::NUM_WITNESS_LIMBS;
 use super::util::{compute_root_quotient_and_shift, split_u16_limbs_to_u8_limbs};
 use super::util_air::eval_field_operation;
@@ -21,19 +22,20 @@ pub enum FieldOperation {
 }
 
 /// A set of columns to compute `FieldOperation(a, b)` where a, b are field elements.
-/// Right now the number of limbs is assumed to be a constant, although this could be macro-ed
-/// or made generic in the future.
+///
+/// `N` is the number of limbs of the field and `W = 2 * N - 2` the number of limbs of the witness,
+/// which default to the ones of fields of at most 256 bits.
 #[derive(Debug, Clone, AlignedBorrow)]
 #[repr(C)]
-pub struct FieldOpCols<T> {
+pub struct FieldOpCols<T, const N: usize = NUM_LIMBS, const W: usize = NUM_WITNESS_LIMBS> {
     /// The result of `a op b`, where a, b are field elements
-    pub result: Limbs<T>,
-    pub(crate) carry: Limbs<T>,
-    pub(crate) witness_low: [T; NUM_WITNESS_LIMBS],
-    pub(crate) witness_high: [T; NUM_WITNESS_LIMBS],
+    pub result: Limbs<T, N>,
+    pub(crate) carry: Limbs<T, N>,
+    pub(crate) witness_low: [T; W],
+    pub(crate) witness_high: [T; W],
 }
 
-impl<F: PrimeField32> FieldOpCols<F> {
+impl<F: PrimeField32, const N: usize, const W: usize> FieldOpCols<F, N, W> {
     pub fn populate<P: FieldParameters>(
         &mut self,
         a: &BigUint,
@@ -60,7 +62,7 @@ impl<F: PrimeField32> FieldOpCols<F> {
             // Note that this reversal means we have to flip result, a correspondingly in
             // the `eval` function.
             self.populate::<P>(&result, b, FieldOperation::Add);
-            self.result = P::to_limbs_field::<F>(&result);
+            self.result = P::to_limbs_field::<F, N>(&result);
             return result;
         }
 
@@ -77,12 +79,12 @@ impl<F: PrimeField32> FieldOpCols<F> {
             // Note that this reversal means we have to flip result, a correspondingly in the `eval`
             // function.
             self.populate::<P>(&result, b, FieldOperation::Mul);
-            self.result = P::to_limbs_field::<F>(&result);
+            self.result = P::to_limbs_field::<F, N>(&result);
             return result;
         }
 
-        let p_a: Polynomial<F> = P::to_limbs_field::<F>(a).into();
-        let p_b: Polynomial<F> = P::to_limbs_field::<F>(b).into();
+        let p_a: Polynomial<F> = P::to_limbs_field::<F, N>(a).into();
+        let p_b: Polynomial<F> = P::to_limbs_field::<F, N>(b).into();
 
         // Compute field addition in the integers.
         let modulus = &P::modulus();
@@ -100,9 +102,9 @@ impl<F: PrimeField32> FieldOpCols<F> {
         }
 
         // Make little endian polynomial limbs.
-        let p_modulus: Polynomial<F> = P::to_limbs_field::<F>(modulus).into();
-        let p_result: Polynomial<F> = P::to_limbs_field::<F>(&result).into();
-        let p_carry: Polynomial<F> = P::to_limbs_field::<F>(&carry).into();
+        let p_modulus: Polynomial<F> = P::to_limbs_field::<F, N>(modulus).into();
+        let p_result: Polynomial<F> = P::to_limbs_field::<F, N>(&result).into();
+        let p_carry: Polynomial<F> = P::to_limbs_field::<F, N>(&carry).into();
 
         // Compute the vanishing polynomial.
         let p_op = match op {
@@ -129,7 +131,7 @@ impl<F: PrimeField32> FieldOpCols<F> {
     }
 }
 
-impl<V: Copy> FieldOpCols<V> {
+impl<V: Copy, const N: usize, const W: usize> FieldOpCols<V, N, W> {
     #[allow(unused_variables)]
     pub fn eval<
         AB: SP1AirBuilder<Var = V>,
@@ -249,8 +251,8 @@ mod tests {
                 .map(|(a, b)| {
                     let mut row = [F::zero(); NUM_TEST_COLS];
                     let cols: &mut TestCols<F> = row.as_mut_slice().borrow_mut();
-                    cols.a = P::to_limbs_field::<F>(a);
-                    cols.b = P::to_limbs_field::<F>(b);
+                    cols.a = P::to_limbs_field(a);
+                    cols.b = P::to_limbs_field(b);
                     cols.a_op_b.populate::<P>(a, b, self.operation);
                     row
                 })
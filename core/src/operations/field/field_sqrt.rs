@@ -1,5 +1,7 @@
 use super::field_op::FieldOpCols;
 use super::params::Limbs;
+use super::params::NUM_LIMBS;
+use super::params::NUM_WITNESS_LIMBS;
 use crate::air::SP1AirBuilder;
 use crate::utils::ec::field::FieldParameters;
 use core::mem::size_of;
@@ -9,18 +11,18 @@ use sp1_derive::AlignedBorrow;
 use std::fmt::Debug;
 
 /// A set of columns to compute the square root in the ed25519 curve. `T` is the field in which each
-/// limb lives.
+/// limb lives, and `N` and `W` are the numbers of limbs as in `FieldOpCols`.
 #[derive(Debug, Clone, AlignedBorrow)]
 #[repr(C)]
-pub struct FieldSqrtCols<T> {
+pub struct FieldSqrtCols<T, const N: usize = NUM_LIMBS, const W: usize = NUM_WITNESS_LIMBS> {
     /// The multiplication operation to verify that the sqrt and the input match.
     ///
     /// In order to save space, we actually store the sqrt of the input in `multiplication.result`
     /// since we'll receive the input again in the `eval` function.
-    pub multiplication: FieldOpCols<T>,
+    pub multiplication: FieldOpCols<T, N, W>,
 }
 
-impl<F: PrimeField32> FieldSqrtCols<F> {
+impl<F: PrimeField32, const N: usize, const W: usize> FieldSqrtCols<F, N, W> {
     /// Populates the trace.
     ///
     /// `P` is the parameter of the field that each limb lives in.
@@ -41,18 +43,18 @@ impl<F: PrimeField32> FieldSqrtCols<F> {
 
         // This is a hack to save a column in FieldSqrtCols. We will receive the value a again in the
         // eval function, so we'll overwrite it with the sqrt.
-        self.multiplication.result = P::to_limbs_field::<F>(&sqrt);
+        self.multiplication.result = P::to_limbs_field::<F, N>(&sqrt);
 
         sqrt
     }
 }
 
-impl<V: Copy> FieldSqrtCols<V> {
+impl<V: Copy, const N: usize, const W: usize> FieldSqrtCols<V, N, W> {
     /// Calculates the square root of `a`.
     pub fn eval<AB: SP1AirBuilder<Var = V>, P: FieldParameters>(
         &self,
         builder: &mut AB,
-        a: &Limbs<AB::Var>,
+        a: &Limbs<AB::Var, N>,
     ) where
         V: Into<AB::Expr>,
     {
@@ -64,7 +66,7 @@ impl<V: Copy> FieldSqrtCols<V> {
         multiplication.result = *a;
 
         // Compute sqrt * sqrt. We pass in P since we want its BaseField to be the mod.
-        multiplication.eval::<AB, P, Limbs<V>, Limbs<V>>(
+        multiplication.eval::<AB, P, Limbs<V, N>, Limbs<V, N>>(
             builder,
             &sqrt,
             &sqrt,
@@ -147,7 +149,7 @@ mod tests {
                 .map(|a| {
                     let mut row = [F::zero(); NUM_TEST_COLS];
                     let cols: &mut TestCols<F> = row.as_mut_slice().borrow_mut();
-                    cols.a = P::to_limbs_field::<F>(a);
+                    cols.a = P::to_limbs_field(a);
                     cols.sqrt.populate::<P>(a, ed25519_sqrt);
                     row
                 })
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ExecutionError, ExecutionRecord, ExecutionState, Program, Runtime};
+
+/// A snapshot of the state of an execution, from which it can be resumed with `Runtime::resume`.
+///
+/// A checkpoint holds the state needed to continue executing the program, but not the events
+/// emitted so far, so a resumed execution only records the events emitted after the checkpoint.
+/// The cycle tracker and profiler also start over when an execution is resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionCheckpoint {
+    /// The state of the execution.
+    pub state: ExecutionState,
+
+    /// The digest of the program being executed, to check that the execution is resumed with the
+    /// same program.
+    program_digest: [u8; 32],
+
+    /// The length of the public input supplied to the program before execution started.
+    input_len: usize,
+
+    /// The number of bytes of streamed input read so far.
+    streamed_input_read: u64,
+
+    /// The buffered output to stdout and stderr which has not been printed yet.
+    io_buf: HashMap<u32, String>,
+}
+
+impl ExecutionCheckpoint {
+    /// Save the checkpoint to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), bincode::Error> {
+        let writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(writer, self)
+    }
+
+    /// Load a checkpoint saved with `save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, bincode::Error> {
+        let reader = BufReader::new(File::open(path)?);
+        bincode::deserialize_from(reader)
+    }
+}
+
+/// An error that occurred while taking a checkpoint or resuming from one.
+#[derive(Debug)]
+pub enum CheckpointError {
+    /// The program is executing an unconstrained block, whose changes to the state are discarded
+    /// when it ends.
+    Unconstrained,
+
+    /// The checkpoint was taken while executing a different program.
+    ProgramMismatch,
+
+    /// The streamed input which was read before the checkpoint was taken could not be read again.
+    Input(io::Error),
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Unconstrained => {
+                write!(f, "cannot checkpoint inside an unconstrained block")
+            }
+            CheckpointError::ProgramMismatch => write!(
+                f,
+                "the checkpoint was taken while executing a different program"
+            ),
+            CheckpointError::Input(err) => {
+                write!(f, "failed to read the streamed input again: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckpointError::Input(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Runtime {
+    /// Take a checkpoint of the execution, from which it can be resumed.
+    ///
+    /// Fails if the program is executing an unconstrained block.
+    pub fn checkpoint(&self) -> Result<ExecutionCheckpoint, CheckpointError> {
+        if self.unconstrained {
+            return Err(CheckpointError::Unconstrained);
+        }
+        Ok(ExecutionCheckpoint {
+            state: self.state.clone(),
+            program_digest: self.program_digest,
+            input_len: self.input_len,
+            streamed_input_read: self.streamed_input.bytes_read(),
+            io_buf: self.io_buf.clone(),
+        })
+    }
+
+    /// Resume the execution from a checkpoint, instead of calling `initialize`. The input sources,
+    /// virtual files and hints must be written to the runtime again before resuming, while the
+    /// rest of the input is restored from the checkpoint.
+    ///
+    /// Fails if the checkpoint was taken while executing a different program, or if the streamed
+    /// input which was read before the checkpoint can no longer be read.
+    pub fn resume(&mut self, checkpoint: ExecutionCheckpoint) -> Result<(), CheckpointError> {
+        if checkpoint.program_digest != self.program_digest {
+            return Err(CheckpointError::ProgramMismatch);
+        }
+        self.state = checkpoint.state;
+        self.input_len = checkpoint.input_len;
+        self.io_buf = checkpoint.io_buf;
+
+        // Skip over the streamed input which was already read.
+        let read = io::copy(
+            &mut (&mut self.streamed_input).take(checkpoint.streamed_input_read),
+            &mut io::sink(),
+        )
+        .map_err(CheckpointError::Input)?;
+        if read != checkpoint.streamed_input_read {
+            return Err(CheckpointError::Input(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the streamed input is shorter than when the checkpoint was taken",
+            )));
+        }
+
+        self.max_syscall_cycles = self.max_syscall_cycles();
+        Ok(())
+    }
+
+    /// Execute the program until the current shard ends or the program is done, so that a
+    /// checkpoint can be taken at the start of the next shard.
+    pub fn run_shard(&mut self) -> Result<(), ExecutionError> {
+        let shard = self.state.current_shard;
+        while !self.is_done() && self.state.current_shard == shard {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Take the events recorded so far, leaving an empty record to record the next events in, so
+    /// that the events of each shard can be dropped once they are no longer needed.
+    pub fn take_record(&mut self) -> ExecutionRecord {
+        let record = ExecutionRecord {
+            program: self.program.clone(),
+            ..Default::default()
+        };
+        std::mem::replace(&mut self.record, record)
+    }
+}
+
+/// The digest of a program, which identifies the program a checkpoint was taken while executing.
+pub(crate) fn program_digest(program: &Program) -> [u8; 32] {
+    let bytes = bincode::serialize(program).expect("serialization failed");
+    blake3::hash(&bytes).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CheckpointError;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime};
+    use crate::utils::tests::FIBONACCI_ELF;
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let program = Program::from(FIBONACCI_ELF);
+        let mut expected = Runtime::new(program.clone());
+        expected.shard_size = 256;
+        expected.run().unwrap();
+
+        // Take a checkpoint at the start of each shard, and resume from the one of the third
+        // shard, going through a file.
+        let mut runtime = Runtime::new(program.clone());
+        runtime.shard_size = 256;
+        runtime.initialize();
+        let mut checkpoints = Vec::new();
+        while !runtime.is_done() {
+            checkpoints.push(runtime.checkpoint().unwrap());
+            runtime.run_shard().unwrap();
+        }
+        runtime.finalize().unwrap();
+        assert_eq!(runtime.state.memory, expected.state.memory);
+        assert!(checkpoints.len() > 3);
+        assert_eq!(checkpoints[2].state.current_shard, 3);
+        assert_eq!(checkpoints[2].state.clk, 0);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        checkpoints[2].save(file.path()).unwrap();
+        let checkpoint = super::ExecutionCheckpoint::load(file.path()).unwrap();
+
+        let mut resumed = Runtime::new(program);
+        resumed.shard_size = 256;
+        resumed.resume(checkpoint).unwrap();
+        resumed.run_shard().unwrap();
+        assert_eq!(resumed.state.current_shard, 4);
+        assert_eq!(resumed.state.pc, checkpoints[3].state.pc);
+        assert_eq!(
+            resumed.record.cpu_events.len() as u32,
+            checkpoints[3].state.global_clk - checkpoints[2].state.global_clk
+        );
+        while !resumed.is_done() {
+            resumed.step().unwrap();
+        }
+        resumed.finalize().unwrap();
+        assert_eq!(resumed.state.global_clk, expected.state.global_clk);
+        assert_eq!(resumed.state.memory, expected.state.memory);
+        assert_eq!(resumed.record.public_values, expected.record.public_values);
+    }
+
+    #[test]
+    fn test_checkpoint_errors() {
+        // A checkpoint cannot be taken inside an unconstrained block.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 110, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        runtime.initialize();
+        runtime.step().unwrap();
+        runtime.step().unwrap();
+        assert!(matches!(
+            runtime.checkpoint(),
+            Err(CheckpointError::Unconstrained)
+        ));
+
+        // A checkpoint cannot be resumed with a different program.
+        let mut runtime = Runtime::new(Program::from(FIBONACCI_ELF));
+        runtime.initialize();
+        let checkpoint = runtime.checkpoint().unwrap();
+        let instructions = vec![Instruction::new(Opcode::ADD, 29, 0, 5, false, true)];
+        let mut other = Runtime::new(Program::new(instructions, 0, 0));
+        assert!(matches!(
+            other.resume(checkpoint),
+            Err(CheckpointError::ProgramMismatch)
+        ));
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{ExecutionError, ExecutionOutcome, Instruction, Program, Runtime};
+use crate::disassembler::{find_symbol, Elf, ElfError, Symbol};
+use crate::SP1Stdin;
+
+/// Why the debugger stopped executing the program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// A single instruction was executed.
+    Step,
+
+    /// The program reached a breakpoint at the given address.
+    Breakpoint(u32),
+
+    /// The word at a watched address changed.
+    Watchpoint { addr: u32, old: u32, new: u32 },
+
+    /// The program finished executing.
+    Finished(ExecutionOutcome),
+
+    /// The program failed with an error.
+    Error(ExecutionError),
+}
+
+/// An interactive debugger which steps through a program on a `Runtime`, stopping at breakpoints
+/// and when watched memory changes. No proofs are generated.
+pub struct Debugger {
+    /// The runtime executing the program, which can be used to inspect registers and memory.
+    pub runtime: Runtime,
+
+    /// The function symbols of the program, sorted by address.
+    symbols: Vec<Symbol>,
+
+    /// The addresses of the breakpoints.
+    breakpoints: BTreeSet<u32>,
+
+    /// The watched addresses and the last value of the word at each of them.
+    watchpoints: BTreeMap<u32, u32>,
+
+    /// The reason the program stopped for good, once it finished or failed.
+    stopped: Option<StopReason>,
+}
+
+impl Debugger {
+    /// Create a debugger for the given ELF, with the given inputs written to the program.
+    pub fn new(elf: &[u8], stdin: &SP1Stdin) -> Result<Self, ElfError> {
+        let program = Program::try_from_elf(elf)?;
+        let symbols = Elf::symbols(elf)?;
+        let mut runtime = Runtime::new(program);
+        runtime.write_input(stdin);
+        runtime.initialize();
+        Ok(Self {
+            runtime,
+            symbols,
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeMap::new(),
+            stopped: None,
+        })
+    }
+
+    /// The function symbols of the program, sorted by address.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Look up the address of the function with the given name.
+    pub fn symbol_address(&self, name: &str) -> Option<u32> {
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.name == name)
+            .map(|symbol| symbol.address)
+    }
+
+    /// Find the function containing `pc` and the offset of `pc` within it.
+    pub fn symbolize(&self, pc: u32) -> Option<(&str, u32)> {
+        let symbol = &self.symbols[find_symbol(&self.symbols, pc)?];
+        Some((symbol.name.as_str(), pc - symbol.address))
+    }
+
+    /// The address of the next instruction to execute.
+    pub fn pc(&self) -> u32 {
+        self.runtime.state.pc
+    }
+
+    /// The next instruction to execute, if the program has not finished.
+    pub fn instruction(&self) -> Option<Instruction> {
+        self.runtime
+            .instruction_index(self.pc())
+            .map(|idx| self.runtime.program.instructions[idx])
+    }
+
+    /// The addresses of the breakpoints.
+    pub fn breakpoints(&self) -> impl Iterator<Item = u32> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    /// Stop before executing the instruction at `pc`.
+    pub fn add_breakpoint(&mut self, pc: u32) {
+        self.breakpoints.insert(pc);
+    }
+
+    /// Remove the breakpoint at `pc`, returning whether there was one.
+    pub fn remove_breakpoint(&mut self, pc: u32) -> bool {
+        self.breakpoints.remove(&pc)
+    }
+
+    /// The watched addresses.
+    pub fn watchpoints(&self) -> impl Iterator<Item = u32> + '_ {
+        self.watchpoints.keys().copied()
+    }
+
+    /// Stop whenever the word at `addr` changes.
+    pub fn add_watchpoint(&mut self, addr: u32) {
+        let addr = addr - addr % 4;
+        let value = self.runtime.word(addr);
+        self.watchpoints.insert(addr, value);
+    }
+
+    /// Stop watching the word at `addr`, returning whether it was watched.
+    pub fn remove_watchpoint(&mut self, addr: u32) -> bool {
+        self.watchpoints.remove(&(addr - addr % 4)).is_some()
+    }
+
+    /// Execute a single instruction.
+    pub fn step(&mut self) -> StopReason {
+        if let Some(reason) = &self.stopped {
+            return reason.clone();
+        }
+
+        if let Err(err) = self.runtime.step() {
+            return self.stop(StopReason::Error(err));
+        }
+        if self.runtime.is_done() {
+            return match self.runtime.finalize() {
+                Ok(outcome) => self.stop(StopReason::Finished(outcome)),
+                Err(err) => self.stop(StopReason::Error(err)),
+            };
+        }
+
+        // Report the first watched word that changed, but remember the new value of all of them.
+        let mut reason = None;
+        for (addr, value) in self.watchpoints.iter_mut() {
+            let new = self.runtime.word(*addr);
+            if new != *value && reason.is_none() {
+                reason = Some(StopReason::Watchpoint {
+                    addr: *addr,
+                    old: *value,
+                    new,
+                });
+            }
+            *value = new;
+        }
+        if let Some(reason) = reason {
+            return reason;
+        }
+
+        if self.breakpoints.contains(&self.pc()) {
+            return StopReason::Breakpoint(self.pc());
+        }
+        StopReason::Step
+    }
+
+    /// Execute instructions until a breakpoint or watchpoint is hit, or the program stops.
+    pub fn resume(&mut self) -> StopReason {
+        loop {
+            match self.step() {
+                StopReason::Step => continue,
+                reason => return reason,
+            }
+        }
+    }
+
+    fn stop(&mut self, reason: StopReason) -> StopReason {
+        self.stopped = Some(reason.clone());
+        reason
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Debugger, StopReason};
+    use crate::runtime::{ExecutionOutcome, Register};
+    use crate::utils::tests::FIBONACCI_ELF;
+    use crate::SP1Stdin;
+
+    #[test]
+    fn test_debugger() {
+        let mut debugger = Debugger::new(FIBONACCI_ELF, &SP1Stdin::new()).unwrap();
+        let main = debugger.symbol_address("main").unwrap();
+        assert_eq!(debugger.symbolize(main + 4), Some(("main", 4)));
+
+        // Run to the start of main.
+        debugger.add_breakpoint(main);
+        assert_eq!(debugger.resume(), StopReason::Breakpoint(main));
+        assert_eq!(debugger.pc(), main);
+        assert!(debugger.instruction().is_some());
+
+        // Watch the word just below the stack pointer, which main's prologue writes to.
+        let sp = debugger.runtime.register(Register::X2);
+        debugger.add_watchpoint(sp - 4);
+        assert!(matches!(
+            debugger.resume(),
+            StopReason::Watchpoint { addr, .. } if addr == sp - 4
+        ));
+
+        debugger.remove_breakpoint(main);
+        debugger.remove_watchpoint(sp - 4);
+        assert_eq!(
+            debugger.resume(),
+            StopReason::Finished(ExecutionOutcome { exit_code: 0 })
+        );
+        assert!(matches!(debugger.step(), StopReason::Finished(_)));
+    }
+}
@@ -0,0 +1,178 @@
+use core::fmt::{Display, Formatter};
+
+use super::{Instruction, SyscallCode};
+
+/// An error that occurred while executing a program, together with where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionError {
+    /// The program counter of the instruction that failed.
+    pub pc: u32,
+
+    /// The shard in which the instruction failed.
+    pub shard: u32,
+
+    /// The clock cycle within the shard at which the instruction failed.
+    pub clk: u32,
+
+    /// The instruction that failed, or `None` if there is no instruction at the program counter.
+    pub instruction: Option<Instruction>,
+
+    /// What went wrong.
+    pub kind: ExecutionErrorKind,
+}
+
+/// The kind of an `ExecutionError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionErrorKind {
+    /// The program invoked a syscall with a number that does not correspond to any syscall.
+    InvalidSyscall(u32),
+
+    /// The program invoked a syscall that the runtime does not support.
+    UnsupportedSyscall(SyscallCode),
+
+    /// The program executed an `UNIMP` instruction.
+    Unimplemented,
+
+    /// The program executed an `EBREAK` instruction.
+    Breakpoint,
+
+    /// The program read past the end of the input of the given file descriptor.
+    InputExhausted(u32),
+
+    /// The program read from or wrote to a file descriptor that does not support it.
+    InvalidFileDescriptor(u32),
+
+    /// The program accessed memory at an address that is not aligned to the access size.
+    UnalignedMemoryAccess(u32),
+
+    /// The program accessed memory at an address which holds the registers or which does not fit
+    /// in a field element.
+    InvalidMemoryAccess(u32),
+
+    /// The program jumped to the given address, which is in the middle of an instruction.
+    InvalidPc(u32),
+
+    /// The program executed for more than the given maximum number of cycles.
+    CycleLimitExceeded(u64),
+
+    /// The program used more than the given maximum number of memory words.
+    MemoryLimitExceeded(usize),
+
+    /// The program invoked a hint which the host did not register.
+    UnknownHint(String),
+
+    /// The streamed public input could not be read.
+    InputReadFailed(String),
+
+    /// The program asked to read the given number of bytes into a word, which holds at most four.
+    InvalidReadLength(u32),
+
+    /// The program wrote bytes that are not valid UTF-8 to the given file descriptor.
+    InvalidUtf8(u32),
+
+    /// The program entered an unconstrained block while already inside one.
+    NestedUnconstrained,
+
+    /// The program passed a compressed point that does not decompress to a point on the curve.
+    InvalidCompressedPoint,
+
+    /// The syscall with the given number advanced the clock by a different number of cycles
+    /// than it declared.
+    SyscallCycleMismatch(u32),
+
+    /// The program passed a buffer with the given address and length to a syscall which is
+    /// longer than `MAX_SYSCALL_BUFFER_LEN` or wraps around the end of memory.
+    InvalidBuffer(u32, u32),
+
+    /// The program committed to a word of the public values at the given index, which is not
+    /// less than `NUM_COMMITTED_WORDS`.
+    InvalidCommitIndex(u32),
+
+    /// The program read public input inside an unconstrained block, where the read would escape
+    /// the digest of the input the program commits to.
+    UnconstrainedPublicInput,
+}
+
+impl Display for ExecutionErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ExecutionErrorKind::InvalidSyscall(id) => write!(f, "invalid syscall number {}", id),
+            ExecutionErrorKind::UnsupportedSyscall(syscall) => {
+                write!(f, "unsupported syscall {:?}", syscall)
+            }
+            ExecutionErrorKind::Unimplemented => write!(f, "UNIMP instruction encountered"),
+            ExecutionErrorKind::Breakpoint => write!(f, "EBREAK instruction encountered"),
+            ExecutionErrorKind::InputExhausted(fd) => write!(
+                f,
+                "not enough input was passed in on fd {}, use --input to pass in more",
+                fd
+            ),
+            ExecutionErrorKind::InvalidFileDescriptor(fd) => {
+                write!(f, "invalid file descriptor {}", fd)
+            }
+            ExecutionErrorKind::UnalignedMemoryAccess(addr) => {
+                write!(f, "unaligned memory access at address 0x{:x}", addr)
+            }
+            ExecutionErrorKind::InvalidMemoryAccess(addr) => {
+                write!(f, "invalid memory access at address 0x{:x}", addr)
+            }
+            ExecutionErrorKind::InvalidPc(pc) => {
+                write!(
+                    f,
+                    "jump to 0x{:x}, which is not the start of an instruction",
+                    pc
+                )
+            }
+            ExecutionErrorKind::CycleLimitExceeded(max_cycles) => {
+                write!(f, "exceeded the limit of {} cycles", max_cycles)
+            }
+            ExecutionErrorKind::MemoryLimitExceeded(max_memory) => {
+                write!(f, "exceeded the limit of {} memory words", max_memory)
+            }
+            ExecutionErrorKind::UnknownHint(name) => write!(f, "unknown hint {:?}", name),
+            ExecutionErrorKind::InputReadFailed(err) => {
+                write!(f, "failed to read the public input: {}", err)
+            }
+            ExecutionErrorKind::InvalidReadLength(len) => {
+                write!(f, "cannot read {} bytes into a word", len)
+            }
+            ExecutionErrorKind::InvalidUtf8(fd) => {
+                write!(f, "invalid UTF-8 written to fd {}", fd)
+            }
+            ExecutionErrorKind::NestedUnconstrained => {
+                write!(f, "unconstrained block is already active")
+            }
+            ExecutionErrorKind::InvalidCompressedPoint => {
+                write!(f, "compressed point is not on the curve")
+            }
+            ExecutionErrorKind::SyscallCycleMismatch(id) => {
+                write!(f, "syscall {} used an unexpected number of cycles", id)
+            }
+            ExecutionErrorKind::InvalidBuffer(addr, len) => {
+                write!(f, "invalid buffer of {} bytes at address 0x{:x}", len, addr)
+            }
+            ExecutionErrorKind::InvalidCommitIndex(index) => {
+                write!(f, "invalid index {} of a committed word", index)
+            }
+            ExecutionErrorKind::UnconstrainedPublicInput => {
+                write!(f, "public input read inside an unconstrained block")
+            }
+        }
+    }
+}
+
+impl Display for ExecutionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} at pc=0x{:x} (shard={}, clk={}",
+            self.kind, self.pc, self.shard, self.clk
+        )?;
+        if let Some(instruction) = self.instruction {
+            write!(f, ", instruction={:?}", instruction)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl std::error::Error for ExecutionError {}
@@ -0,0 +1,337 @@
+use std::io::{self, Read, Write};
+
+use super::{Debugger, ExecutionErrorKind, StopReason};
+
+/// The maximum size of a packet, advertised to GDB in the reply to `qSupported`.
+const PACKET_SIZE: usize = 0x1000;
+
+/// The ABI names of the registers, in the order GDB numbers them.
+const REGISTER_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "fp", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+/// The register number GDB uses for the program counter.
+const PC_REGISTER: usize = 32;
+
+/// A server for the GDB remote serial protocol, so that `riscv32-gdb` can debug a program running
+/// on the same `Runtime` as the prover.
+///
+/// Registers and memory are read-only, since changing them would make the execution diverge
+/// from the one that is proven.
+///
+/// Reference: https://sourceware.org/gdb/current/onlinedocs/gdb.html/Remote-Protocol.html
+pub struct GdbServer {
+    debugger: Debugger,
+
+    /// The reason the program last stopped.
+    last_stop: StopReason,
+}
+
+impl GdbServer {
+    pub fn new(debugger: Debugger) -> Self {
+        Self {
+            debugger,
+            last_stop: StopReason::Step,
+        }
+    }
+
+    /// Serve a single GDB session over `stream` until GDB detaches, kills the program or closes
+    /// the connection.
+    pub fn serve<S: Read + Write>(&mut self, stream: &mut S) -> io::Result<()> {
+        loop {
+            let Some(packet) = read_packet(stream)? else {
+                return Ok(());
+            };
+            match packet.as_str() {
+                "k" => return Ok(()),
+                "D" => return write_packet(stream, "OK"),
+                _ => {
+                    let reply = self.handle(&packet);
+                    write_packet(stream, &reply)?;
+                }
+            }
+        }
+    }
+
+    /// Handle a single packet, returning the reply.
+    fn handle(&mut self, packet: &str) -> String {
+        let Some(command) = packet.chars().next() else {
+            return String::new();
+        };
+        let args = &packet[command.len_utf8()..];
+        match command {
+            '?' => self.stop_reply(),
+            'g' => {
+                let mut registers = self.debugger.runtime.registers().to_vec();
+                registers.push(self.debugger.pc());
+                registers.into_iter().map(hex_le).collect()
+            }
+            'p' => match usize::from_str_radix(args, 16) {
+                Ok(PC_REGISTER) => hex_le(self.debugger.pc()),
+                Ok(n) if n < PC_REGISTER => hex_le(self.debugger.runtime.registers()[n]),
+                _ => "E01".to_string(),
+            },
+            'm' => match parse_pair(args) {
+                Some((addr, len)) => (0..len.min(PACKET_SIZE as u32 / 2))
+                    .map(|i| format!("{:02x}", self.debugger.runtime.byte(addr.wrapping_add(i))))
+                    .collect(),
+                None => "E01".to_string(),
+            },
+            'Z' | 'z' => self.update_breakpoint(command == 'Z', args),
+            's' => {
+                self.last_stop = self.debugger.step();
+                self.stop_reply()
+            }
+            'c' => {
+                self.last_stop = self.debugger.resume();
+                self.stop_reply()
+            }
+            'H' => "OK".to_string(),
+            // Writing to registers or memory is not supported.
+            'G' | 'P' | 'M' | 'X' => "E01".to_string(),
+            _ => self.handle_query(packet),
+        }
+    }
+
+    /// Handle a general query packet, returning an empty reply for unsupported queries.
+    fn handle_query(&self, packet: &str) -> String {
+        if packet.starts_with("qSupported") {
+            format!(
+                "PacketSize={:x};qXfer:features:read+;swbreak+;hwbreak+",
+                PACKET_SIZE
+            )
+        } else if let Some(args) = packet.strip_prefix("qXfer:features:read:target.xml:") {
+            match parse_pair(args) {
+                Some((offset, len)) => {
+                    let xml = target_xml();
+                    let start = (offset as usize).min(xml.len());
+                    let end = (start + len as usize).min(xml.len());
+                    let prefix = if end == xml.len() { "l" } else { "m" };
+                    format!("{}{}", prefix, &xml[start..end])
+                }
+                None => "E01".to_string(),
+            }
+        } else {
+            match packet {
+                "qAttached" => "1".to_string(),
+                "qC" => "QC1".to_string(),
+                "qfThreadInfo" => "m1".to_string(),
+                "qsThreadInfo" => "l".to_string(),
+                _ => String::new(),
+            }
+        }
+    }
+
+    /// Insert or remove a breakpoint or write watchpoint, given the arguments `type,addr,kind`.
+    fn update_breakpoint(&mut self, insert: bool, args: &str) -> String {
+        let mut parts = args.splitn(2, ',');
+        let kind = parts.next().unwrap_or_default();
+        let Some((addr, len)) = parts.next().and_then(parse_pair) else {
+            return "E01".to_string();
+        };
+        match kind {
+            // Software and hardware breakpoints are the same thing on the runtime.
+            "0" | "1" => {
+                if insert {
+                    self.debugger.add_breakpoint(addr);
+                } else {
+                    self.debugger.remove_breakpoint(addr);
+                }
+            }
+            // Write watchpoints on every word the watched range touches.
+            "2" => {
+                for word in (addr - addr % 4..addr.saturating_add(len.max(1))).step_by(4) {
+                    if insert {
+                        self.debugger.add_watchpoint(word);
+                    } else {
+                        self.debugger.remove_watchpoint(word);
+                    }
+                }
+            }
+            _ => return String::new(),
+        }
+        "OK".to_string()
+    }
+
+    /// The reply describing why the program last stopped.
+    fn stop_reply(&self) -> String {
+        match &self.last_stop {
+            StopReason::Step => "S05".to_string(),
+            StopReason::Breakpoint(_) => "T05swbreak:;".to_string(),
+            StopReason::Watchpoint { addr, .. } => format!("T05watch:{:x};", addr),
+            StopReason::Finished(outcome) => format!("W{:02x}", outcome.exit_code & 0xff),
+            // Report an EBREAK as a trap and any other failure as an illegal instruction.
+            StopReason::Error(err) if err.kind == ExecutionErrorKind::Breakpoint => {
+                "S05".to_string()
+            }
+            StopReason::Error(_) => "S04".to_string(),
+        }
+    }
+}
+
+/// Read the next packet, acknowledging it. Returns `None` once the stream is closed.
+fn read_packet<S: Read + Write>(stream: &mut S) -> io::Result<Option<String>> {
+    let mut byte = [0u8];
+    loop {
+        // Skip acknowledgements and interrupts until the start of a packet.
+        loop {
+            if stream.read(&mut byte)? == 0 {
+                return Ok(None);
+            }
+            if byte[0] == b'$' {
+                break;
+            }
+        }
+
+        let mut data = Vec::new();
+        loop {
+            if stream.read(&mut byte)? == 0 {
+                return Ok(None);
+            }
+            if byte[0] == b'#' {
+                break;
+            }
+            data.push(byte[0]);
+        }
+        let mut checksum = [0u8; 2];
+        stream.read_exact(&mut checksum)?;
+
+        let expected = std::str::from_utf8(&checksum)
+            .ok()
+            .and_then(|checksum| u8::from_str_radix(checksum, 16).ok());
+        if expected == Some(checksum_of(&data)) {
+            stream.write_all(b"+")?;
+            return Ok(Some(String::from_utf8_lossy(&data).into_owned()));
+        }
+        // Ask GDB to send the packet again.
+        stream.write_all(b"-")?;
+    }
+}
+
+/// Write a packet with the given data.
+fn write_packet<S: Write>(stream: &mut S, data: &str) -> io::Result<()> {
+    write!(stream, "${}#{:02x}", data, checksum_of(data.as_bytes()))?;
+    stream.flush()
+}
+
+fn checksum_of(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// Encode a value as hex in target (little-endian) byte order.
+fn hex_le(value: u32) -> String {
+    hex::encode(value.to_le_bytes())
+}
+
+/// Parse arguments of the form `addr,len` in hex.
+fn parse_pair(args: &str) -> Option<(u32, u32)> {
+    let (a, b) = args.split_once(',')?;
+    Some((
+        u32::from_str_radix(a, 16).ok()?,
+        u32::from_str_radix(b, 16).ok()?,
+    ))
+}
+
+/// The target description, which tells GDB the machine only has the RV32 integer registers.
+fn target_xml() -> String {
+    let registers = REGISTER_NAMES
+        .iter()
+        .map(|name| format!(r#"<reg name="{}" bitsize="32" type="int"/>"#, name))
+        .collect::<String>();
+    format!(
+        concat!(
+            r#"<?xml version="1.0"?><!DOCTYPE target SYSTEM "gdb-target.dtd">"#,
+            r#"<target version="1.0"><architecture>riscv:rv32</architecture>"#,
+            r#"<feature name="org.gnu.gdb.riscv.cpu">{}"#,
+            r#"<reg name="pc" bitsize="32" type="code_ptr"/></feature></target>"#
+        ),
+        registers
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor, Read, Write};
+
+    use super::{checksum_of, GdbServer};
+    use crate::runtime::Debugger;
+    use crate::utils::tests::FIBONACCI_ELF;
+    use crate::SP1Stdin;
+
+    /// A stream which replays the packets GDB would send and records the replies.
+    struct MockStream {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for MockStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for MockStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Run a session with the given packets and return the data of the replies.
+    fn session(packets: &[String]) -> Vec<String> {
+        let input = packets
+            .iter()
+            .map(|packet| format!("${}#{:02x}", packet, checksum_of(packet.as_bytes())))
+            .collect::<String>();
+        let mut stream = MockStream {
+            input: Cursor::new(input.into_bytes()),
+            output: Vec::new(),
+        };
+        let debugger = Debugger::new(FIBONACCI_ELF, &SP1Stdin::new()).unwrap();
+        GdbServer::new(debugger).serve(&mut stream).unwrap();
+
+        let output = String::from_utf8(stream.output).unwrap();
+        output
+            .split('$')
+            .skip(1)
+            .map(|reply| reply.split('#').next().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_gdb_session() {
+        let debugger = Debugger::new(FIBONACCI_ELF, &SP1Stdin::new()).unwrap();
+        let main = debugger.symbol_address("main").unwrap();
+        let entry = debugger.pc();
+
+        let replies = session(&[
+            "qSupported:swbreak+".to_string(),
+            "?".to_string(),
+            "p20".to_string(),
+            format!("Z0,{:x},4", main),
+            "c".to_string(),
+            "g".to_string(),
+            format!("m{:x},4", main),
+            format!("z0,{:x},4", main),
+            "c".to_string(),
+            "k".to_string(),
+        ]);
+        assert!(replies[0].starts_with("PacketSize="));
+        assert_eq!(replies[1], "S05");
+        assert_eq!(replies[2], hex::encode(entry.to_le_bytes()));
+        assert_eq!(replies[3], "OK");
+        assert_eq!(replies[4], "T05swbreak:;");
+        // 32 registers and the pc, each 8 hex digits.
+        assert_eq!(replies[5].len(), 33 * 8);
+        assert!(replies[5].ends_with(&hex::encode(main.to_le_bytes())));
+        assert_eq!(replies[6].len(), 8);
+        assert_eq!(replies[7], "OK");
+        assert_eq!(replies[8], "W00");
+        assert_eq!(replies.len(), 9);
+    }
+}
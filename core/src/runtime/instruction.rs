@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use super::Opcode;
 
 /// An instruction specifies an operation to execute and the operands.
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Instruction {
     pub opcode: Opcode,
     pub op_a: u32,
@@ -13,6 +13,8 @@ pub struct Instruction {
     pub op_c: u32,
     pub imm_b: bool,
     pub imm_c: bool,
+    /// Whether the instruction was decoded from a 16-bit compressed instruction.
+    pub compressed: bool,
 }
 
 impl Instruction {
@@ -24,6 +26,16 @@ impl Instruction {
             op_c,
             imm_b,
             imm_c,
+            compressed: false,
+        }
+    }
+
+    /// Returns the size of the encoded instruction in bytes.
+    pub fn size(&self) -> u32 {
+        if self.compressed {
+            2
+        } else {
+            4
         }
     }
 
@@ -1,8 +1,52 @@
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::io::Read;
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::sync::Arc;
 
-use super::Runtime;
+use super::{ExecutionErrorKind, Runtime};
+use crate::{InputSource, SP1Stdin};
+
+/// The public input streamed from `InputSource`s, which are opened one after the other as the
+/// program reads them.
+#[derive(Default)]
+pub(crate) struct StreamedInput {
+    /// The sources which have not been opened yet.
+    sources: VecDeque<InputSource>,
+
+    /// The reader over the source currently being read.
+    reader: Option<Box<dyn Read + Send>>,
+
+    /// The number of bytes read from the sources so far.
+    bytes_read: u64,
+}
+
+impl StreamedInput {
+    /// The number of bytes read from the sources so far.
+    pub(crate) fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+}
+
+impl Read for StreamedInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.reader.is_none() {
+                match self.sources.pop_front() {
+                    Some(source) => self.reader = Some(source.open()?),
+                    None => return Ok(0),
+                }
+            }
+            match self.reader.as_mut().unwrap().read(buf)? {
+                0 if !buf.is_empty() => self.reader = None,
+                n => {
+                    self.bytes_read += n as u64;
+                    return Ok(n);
+                }
+            }
+        }
+    }
+}
 
 impl Read for Runtime {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
@@ -12,6 +56,37 @@ impl Read for Runtime {
 }
 
 impl Runtime {
+    /// Write the public and private input, input sources, virtual files and hints of `stdin` to
+    /// the program.
+    pub fn write_input(&mut self, stdin: &SP1Stdin) {
+        self.write_stdin_slice(&stdin.buffer.data);
+        self.write_private_stdin_slice(&stdin.private_buffer.data);
+        for source in stdin.sources.iter() {
+            self.write_stdin_source(source.clone());
+        }
+        for (name, data) in stdin.files.iter() {
+            self.add_file(name, data.clone());
+        }
+        for (name, hint) in stdin.hints.iter() {
+            self.register_hint(name, hint.clone());
+        }
+    }
+
+    /// Stream public input from the given source, after all the public input written before
+    /// execution starts.
+    pub fn write_stdin_source(&mut self, source: InputSource) {
+        self.streamed_input.sources.push_back(source);
+    }
+
+    /// Add a virtual file with the given name and contents, which the program can open, replacing
+    /// the file with the same name if there is one.
+    pub fn add_file(&mut self, name: &str, data: Arc<[u8]>) {
+        match self.files.iter_mut().find(|(file, _)| file == name) {
+            Some((_, contents)) => *contents = data,
+            None => self.files.push((name.to_string(), data)),
+        }
+    }
+
     pub fn write_stdin<T: Serialize>(&mut self, input: &T) {
         let mut buf = Vec::new();
         bincode::serialize_into(&mut buf, input).expect("serialization failed");
@@ -22,6 +97,39 @@ impl Runtime {
         self.state.input_stream.extend(input);
     }
 
+    pub fn write_private_stdin<T: Serialize>(&mut self, input: &T) {
+        let mut buf = Vec::new();
+        bincode::serialize_into(&mut buf, input).expect("serialization failed");
+        self.state.private_input_stream.extend(buf);
+    }
+
+    pub fn write_private_stdin_slice(&mut self, input: &[u8]) {
+        self.state.private_input_stream.extend(input);
+    }
+
+    /// Read the next byte of public input, or `None` if the input is exhausted.
+    ///
+    /// The program first reads the input written before execution started, then the input
+    /// streamed from the sources.
+    pub(crate) fn read_input_byte(&mut self) -> Result<Option<u8>, ExecutionErrorKind> {
+        let ptr = self.state.input_stream_ptr;
+        if ptr >= self.input_len {
+            let mut byte = [0u8];
+            let n = self
+                .streamed_input
+                .read(&mut byte)
+                .map_err(|err| ExecutionErrorKind::InputReadFailed(err.to_string()))?;
+            if n == 1 {
+                return Ok(Some(byte[0]));
+            }
+        }
+        let byte = self.state.input_stream.get(ptr).copied();
+        if byte.is_some() {
+            self.state.input_stream_ptr += 1;
+        }
+        Ok(byte)
+    }
+
     pub fn read_stdout<T: DeserializeOwned>(&mut self) -> T {
         let result = bincode::deserialize_from::<_, T>(self);
         result.unwrap()
@@ -40,10 +148,12 @@ impl Runtime {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::runtime::Program;
+    use crate::runtime::{Instruction, Opcode, Program, Register};
     use crate::utils::tests::IO_ELF;
     use crate::utils::{self, prove_core, BabyBearBlake3};
+    use crate::PublicValues;
     use serde::Deserialize;
+    use std::io::Cursor;
 
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
     struct MyPointUnaligned {
@@ -75,7 +185,7 @@ pub mod tests {
         let points = points();
         runtime.write_stdin(&points.0);
         runtime.write_stdin(&points.1);
-        runtime.run();
+        runtime.run().unwrap();
         let added_point = runtime.read_stdout::<MyPointUnaligned>();
         assert_eq!(
             added_point,
@@ -95,8 +205,72 @@ pub mod tests {
         let points = points();
         runtime.write_stdin(&points.0);
         runtime.write_stdin(&points.1);
-        runtime.run();
+        runtime.run().unwrap();
         let config = BabyBearBlake3::new();
         prove_core(config, runtime);
     }
+
+    #[test]
+    fn test_streamed_input() {
+        // Read two words of public input into x29 and x30.
+        let mut instructions = Vec::new();
+        for rd in [29, 30] {
+            instructions.extend([
+                Instruction::new(Opcode::ADD, 5, 0, 101, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+                Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+                Instruction::new(Opcode::ADD, rd, 10, 0, false, true),
+            ]);
+        }
+        let streamed = [42u32.to_le_bytes(), 99u32.to_le_bytes()].concat();
+        let mut stdin = SP1Stdin::new();
+        stdin.write_slice(&7u32.to_le_bytes());
+        let source = streamed.clone();
+        stdin.write_source(InputSource::reader(move || Ok(Cursor::new(source.clone()))));
+
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        runtime.write_input(&stdin);
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X29), 7);
+        assert_eq!(runtime.register(Register::X30), 42);
+        // The streamed input is not copied into the input stream.
+        assert_eq!(runtime.state.input_stream.len(), 4);
+
+        // The digest of the input read by the program is taken over the streamed input too.
+        let input = [&7u32.to_le_bytes()[..], &streamed].concat();
+        assert_eq!(
+            stdin.input_digest(8).unwrap(),
+            PublicValues::new(&input[..8], &[], 0).input_digest
+        );
+        assert_eq!(
+            stdin.input_digest(12).unwrap(),
+            PublicValues::new(&input, &[], 0).input_digest
+        );
+        assert_eq!(
+            stdin.input_digest(13).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_streamed_input_read_failed() {
+        // Read a word of public input, which is streamed from a source that cannot be opened.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 101, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut stdin = SP1Stdin::new();
+        stdin.write_source(InputSource::reader(|| {
+            Err::<Cursor<Vec<u8>>, _>(io::Error::new(io::ErrorKind::NotFound, "missing"))
+        }));
+
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        runtime.write_input(&stdin);
+        let err = runtime.run().unwrap_err();
+        assert!(matches!(err.kind, ExecutionErrorKind::InputReadFailed(_)));
+        assert_eq!(err.instruction.unwrap().opcode, Opcode::ECALL);
+    }
 }
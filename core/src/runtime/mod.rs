@@ -1,22 +1,38 @@
+mod checkpoint;
+mod debugger;
+mod error;
+mod gdb;
 mod instruction;
 mod io;
 mod opcode;
+mod profiler;
 mod program;
 mod record;
 mod register;
+mod report;
 mod state;
 mod syscall;
 
 use crate::cpu::{MemoryReadRecord, MemoryRecord, MemoryWriteRecord};
+use crate::syscall::Hint;
 use crate::utils::env;
+use crate::PublicValues;
 use crate::{alu::AluEvent, cpu::CpuEvent};
+pub use checkpoint::*;
+pub use debugger::*;
+pub use error::*;
+pub use gdb::*;
 use hashbrown::hash_map::Entry;
 pub use instruction::*;
-use nohash_hasher::BuildNoHashHasher;
+use io::StreamedInput;
 pub use opcode::*;
+use p3_baby_bear::BabyBear;
+use p3_field::PrimeField64;
+pub use profiler::*;
 pub use program::*;
 pub use record::*;
 pub use register::*;
+pub use report::*;
 pub use state::*;
 use std::collections::HashMap;
 use std::fs::File;
@@ -61,8 +77,8 @@ pub struct Runtime {
     /// The maximum size of each shard.
     pub shard_size: u32,
 
-    /// A counter for the number of cycles that have been executed in certain functions.
-    pub cycle_tracker: HashMap<String, (u32, u32)>,
+    /// The spans of the program delimited by `cycle-tracker-start:` and `cycle-tracker-end:`.
+    pub cycle_tracker: CycleTracker,
 
     /// A buffer for stdout and stderr IO.
     pub io_buf: HashMap<u32, String>,
@@ -70,22 +86,52 @@ pub struct Runtime {
     /// A buffer for writing trace events to a file.
     pub trace_buf: Option<BufWriter<File>>,
 
-    /// Whether the runtime should fail on panic or not.
-    pub fail_on_panic: bool,
+    /// A profiler attributing the cycles of the execution to functions, if profiling is enabled.
+    pub profiler: Option<Profiler>,
 
     /// Whether the runtime is in constrained mode or not.
     /// In unconstrained mode, any events, clock, register, or memory changes are reset after leaving
-    /// the unconstrained block. The only thing preserved is writes to the input stream.
+    /// the unconstrained block. The only thing preserved is writes to the hint stream.
     pub unconstrained: bool,
 
     pub(crate) unconstrained_state: ForkState,
 
     pub syscall_map: HashMap<SyscallCode, Rc<dyn Syscall>>,
+
+    /// The syscalls registered by the application, keyed by their number in `USER_SYSCALLS`.
+    user_syscall_map: HashMap<u32, Rc<dyn Syscall>>,
+
+    /// The hints registered by the host, keyed by name.
+    pub(crate) hints: HashMap<String, Hint>,
+
+    /// The names and contents of the virtual files supplied by the host, in the order of their
+    /// file descriptors.
+    pub(crate) files: Vec<(String, Arc<[u8]>)>,
+
+    /// The limits on the cycles and memory the program may use, which are unlimited by default.
+    pub limits: ExecutionLimits,
+
+    /// The index of the instruction at each halfword of the program's code.
+    pc_index: Vec<Option<u32>>,
+
+    /// The length of the public input supplied to the program before execution started.
+    input_len: usize,
+
+    /// The public input streamed from input sources after the public input in the input stream.
+    streamed_input: StreamedInput,
+
+    /// The maximum number of cycles a syscall can take, cached when execution starts.
+    max_syscall_cycles: u32,
+
+    /// The digest of the program, checked when resuming from a checkpoint.
+    program_digest: [u8; 32],
 }
 
 impl Runtime {
     // Create a new runtime
     pub fn new(program: Program) -> Self {
+        let pc_index = program.pc_index();
+        let program_digest = checkpoint::program_digest(&program);
         let program_arc = Arc::new(program);
         let record = ExecutionRecord {
             program: program_arc.clone(),
@@ -105,13 +151,22 @@ impl Runtime {
             program: program_arc,
             cpu_record: CpuRecord::default(),
             shard_size: env::shard_size() as u32 * 4,
-            cycle_tracker: HashMap::new(),
+            cycle_tracker: CycleTracker::default(),
             io_buf: HashMap::new(),
             trace_buf,
-            fail_on_panic: true,
+            profiler: None,
             unconstrained: false,
             unconstrained_state: ForkState::default(),
             syscall_map: default_syscall_map(),
+            user_syscall_map: HashMap::new(),
+            hints: HashMap::new(),
+            files: Vec::new(),
+            limits: ExecutionLimits::default(),
+            pc_index,
+            input_len: 0,
+            streamed_input: StreamedInput::default(),
+            max_syscall_cycles: 0,
+            program_digest,
         }
     }
 
@@ -163,24 +218,6 @@ impl Runtime {
         addr - addr % 4
     }
 
-    #[inline]
-    #[cfg(debug_assertions)]
-    fn validate_memory_access(&self, addr: u32, position: AccessPosition) {
-        use p3_baby_bear::BabyBear;
-        use p3_field::AbstractField;
-
-        if position == AccessPosition::Memory {
-            assert_eq!(addr % 4, 0, "addr is not aligned");
-            let _ = BabyBear::from_canonical_u32(addr);
-            assert!(addr > 40); // Assert that the address is > the max register.
-        } else {
-            let _ = Register::from_u32(addr);
-        }
-    }
-
-    #[cfg(not(debug_assertions))]
-    fn validate_memory_access(&self, _addr: u32, _position: AccessPosition) {}
-
     pub fn mr(&mut self, addr: u32, shard: u32, clk: u32) -> MemoryReadRecord {
         // Get the memory entry.
         let memory_entry = self.state.memory.entry(addr);
@@ -230,8 +267,6 @@ impl Runtime {
 
     /// Read from memory, assuming that all addresses are aligned.
     pub fn mr_cpu(&mut self, addr: u32, position: AccessPosition) -> u32 {
-        self.validate_memory_access(addr, position);
-
         let record = self.mr(
             addr,
             self.current_shard(),
@@ -251,8 +286,6 @@ impl Runtime {
 
     /// Write to memory.
     pub fn mw_cpu(&mut self, addr: u32, value: u32, position: AccessPosition) {
-        self.validate_memory_access(addr, position);
-
         let record = self.mw(
             addr,
             value,
@@ -398,26 +431,40 @@ impl Runtime {
         self.emit_alu(self.state.clk, instruction.opcode, a, b, c);
     }
 
-    /// Fetch the input operand values for a load instruction.
+    /// Fetch the input operand values for a load instruction of `size` bytes, checking the
+    /// address before reading from memory.
     #[inline(always)]
-    fn load_rr(&mut self, instruction: Instruction) -> (Register, u32, u32, u32, u32) {
+    fn load_rr(
+        &mut self,
+        instruction: Instruction,
+        size: u32,
+    ) -> Result<(Register, u32, u32, u32, u32), ExecutionError> {
         let (rd, rs1, imm) = instruction.i_type();
         let (b, c) = (self.rr(rs1, AccessPosition::B), imm);
         let addr = b.wrapping_add(c);
+        self.check_alignment(instruction, addr, size)?;
+        check_word_address(self.align(addr)).map_err(|kind| self.error(instruction, kind))?;
         let memory_value = self.mr_cpu(self.align(addr), AccessPosition::Memory);
-        (rd, b, c, addr, memory_value)
+        Ok((rd, b, c, addr, memory_value))
     }
 
-    /// Fetch the input operand values for a store instruction.
+    /// Fetch the input operand values for a store instruction of `size` bytes, checking the
+    /// address before reading from memory.
     #[inline(always)]
-    fn store_rr(&mut self, instruction: Instruction) -> (u32, u32, u32, u32, u32) {
+    fn store_rr(
+        &mut self,
+        instruction: Instruction,
+        size: u32,
+    ) -> Result<(u32, u32, u32, u32, u32), ExecutionError> {
         let (rs1, rs2, imm) = instruction.s_type();
         let c = imm;
         let b = self.rr(rs2, AccessPosition::B);
         let a = self.rr(rs1, AccessPosition::A);
         let addr = b.wrapping_add(c);
+        self.check_alignment(instruction, addr, size)?;
+        check_word_address(self.align(addr)).map_err(|kind| self.error(instruction, kind))?;
         let memory_value = self.word(self.align(addr));
-        (a, b, c, addr, memory_value)
+        Ok((a, b, c, addr, memory_value))
     }
 
     /// Fetch the input operand values for a branch instruction.
@@ -430,29 +477,131 @@ impl Runtime {
         (a, b, c)
     }
 
-    /// Fetch the instruction at the current program counter.
+    /// Fetch the instruction at the current program counter, failing if the program counter is
+    /// in the middle of an instruction.
     #[inline(always)]
-    fn fetch(&self) -> Instruction {
-        let idx = ((self.state.pc - self.program.pc_base) / 4) as usize;
-        self.program.instructions[idx]
+    fn fetch(&self) -> Result<Instruction, ExecutionError> {
+        let idx = self.instruction_index(self.state.pc).ok_or_else(|| {
+            self.error_without_instruction(ExecutionErrorKind::InvalidPc(self.state.pc))
+        })?;
+        Ok(self.program.instructions[idx])
+    }
+
+    /// Returns the index of the instruction starting at `pc`, if there is one.
+    pub(crate) fn instruction_index(&self, pc: u32) -> Option<usize> {
+        let offset = pc.wrapping_sub(self.program.pc_base);
+        if offset % 2 != 0 {
+            return None;
+        }
+        let idx = self
+            .pc_index
+            .get((offset / 2) as usize)
+            .copied()
+            .flatten()?;
+        Some(idx as usize)
+    }
+
+    /// Returns whether `pc` lies within the program's code.
+    pub fn is_code(&self, pc: u32) -> bool {
+        (pc.wrapping_sub(self.program.pc_base) / 2) < self.pc_index.len() as u32
+    }
+
+    /// Register a syscall which the program can invoke with the number `code`, returning the
+    /// syscall previously registered with this number, if any. Syscalls must be registered
+    /// before execution starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` is not in the range reserved for user syscalls, `USER_SYSCALLS`.
+    pub fn register_syscall(
+        &mut self,
+        code: u32,
+        syscall: Rc<dyn Syscall>,
+    ) -> Option<Rc<dyn Syscall>> {
+        assert!(
+            USER_SYSCALLS.contains(&code),
+            "user syscall number {:#x} is not in the reserved range {:#x?}",
+            code,
+            USER_SYSCALLS
+        );
+        self.user_syscall_map.insert(code, syscall)
+    }
+
+    /// Register a hint which the program can invoke by name, returning the hint previously
+    /// registered with this name, if any.
+    ///
+    /// The hint runs natively on the host: it receives the bytes sent by the program and its
+    /// answer is appended to the hint stream, from which the program reads it back. The answer is
+    /// not part of the public values of the proof, so the program must check it.
+    pub fn register_hint(&mut self, name: &str, hint: Hint) -> Option<Hint> {
+        self.hints.insert(name.to_string(), hint)
     }
 
-    fn get_syscall(&mut self, code: SyscallCode) -> Option<&Rc<dyn Syscall>> {
-        self.syscall_map.get(&code)
+    /// Look up the implementation of the syscall with the given number.
+    fn get_syscall(&self, id: u32) -> Result<Rc<dyn Syscall>, ExecutionErrorKind> {
+        if USER_SYSCALLS.contains(&id) {
+            return self
+                .user_syscall_map
+                .get(&id)
+                .cloned()
+                .ok_or(ExecutionErrorKind::InvalidSyscall(id));
+        }
+        let code = SyscallCode::from_u32(id).ok_or(ExecutionErrorKind::InvalidSyscall(id))?;
+        self.syscall_map
+            .get(&code)
+            .cloned()
+            .ok_or(ExecutionErrorKind::UnsupportedSyscall(code))
     }
 
     fn max_syscall_cycles(&self) -> u32 {
         self.syscall_map
             .values()
+            .chain(self.user_syscall_map.values())
             .map(|syscall| syscall.num_extra_cycles())
             .max()
             .unwrap_or(0)
     }
 
+    /// Create an error of the given kind for the instruction at the current program counter.
+    fn error(&self, instruction: Instruction, kind: ExecutionErrorKind) -> ExecutionError {
+        ExecutionError {
+            pc: self.state.pc,
+            shard: self.current_shard(),
+            clk: self.state.clk,
+            instruction: Some(instruction),
+            kind,
+        }
+    }
+
+    /// Create an error of the given kind at the current program counter which is not caused by
+    /// executing an instruction.
+    fn error_without_instruction(&self, kind: ExecutionErrorKind) -> ExecutionError {
+        ExecutionError {
+            pc: self.state.pc,
+            shard: self.current_shard(),
+            clk: self.state.clk,
+            instruction: None,
+            kind,
+        }
+    }
+
+    /// Check that `addr` is aligned to `size` bytes.
+    fn check_alignment(
+        &self,
+        instruction: Instruction,
+        addr: u32,
+        size: u32,
+    ) -> Result<(), ExecutionError> {
+        if addr % size != 0 {
+            return Err(self.error(instruction, ExecutionErrorKind::UnalignedMemoryAccess(addr)));
+        }
+        Ok(())
+    }
+
     /// Execute the given instruction over the current state of the runtime.
-    fn execute(&mut self, instruction: Instruction) {
+    fn execute(&mut self, instruction: Instruction) -> Result<(), ExecutionError> {
         let pc = self.state.pc;
-        let mut next_pc = self.state.pc.wrapping_add(4);
+        let mut next_pc = self.state.pc.wrapping_add(instruction.size());
 
         let rd: Register;
         let (a, b, c): (u32, u32, u32);
@@ -515,15 +664,14 @@ impl Runtime {
 
             // Load instructions.
             Opcode::LB => {
-                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction);
+                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction, 1)?;
                 let value = (memory_read_value).to_le_bytes()[(addr % 4) as usize];
                 a = ((value as i8) as i32) as u32;
                 memory_store_value = Some(memory_read_value);
                 self.rw(rd, a);
             }
             Opcode::LH => {
-                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction);
-                assert_eq!(addr % 2, 0, "addr is not aligned");
+                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction, 2)?;
                 let value = match (addr >> 1) % 2 {
                     0 => memory_read_value & 0x0000FFFF,
                     1 => (memory_read_value & 0xFFFF0000) >> 16,
@@ -534,22 +682,20 @@ impl Runtime {
                 self.rw(rd, a);
             }
             Opcode::LW => {
-                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction);
-                assert_eq!(addr % 4, 0, "addr is not aligned");
+                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction, 4)?;
                 a = memory_read_value;
                 memory_store_value = Some(memory_read_value);
                 self.rw(rd, a);
             }
             Opcode::LBU => {
-                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction);
+                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction, 1)?;
                 let value = (memory_read_value).to_le_bytes()[(addr % 4) as usize];
                 a = value as u32;
                 memory_store_value = Some(memory_read_value);
                 self.rw(rd, a);
             }
             Opcode::LHU => {
-                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction);
-                assert_eq!(addr % 2, 0, "addr is not aligned");
+                (rd, b, c, addr, memory_read_value) = self.load_rr(instruction, 2)?;
                 let value = match (addr >> 1) % 2 {
                     0 => memory_read_value & 0x0000FFFF,
                     1 => (memory_read_value & 0xFFFF0000) >> 16,
@@ -562,7 +708,7 @@ impl Runtime {
 
             // Store instructions.
             Opcode::SB => {
-                (a, b, c, addr, memory_read_value) = self.store_rr(instruction);
+                (a, b, c, addr, memory_read_value) = self.store_rr(instruction, 1)?;
                 let value = match addr % 4 {
                     0 => (a & 0x000000FF) + (memory_read_value & 0xFFFFFF00),
                     1 => ((a & 0x000000FF) << 8) + (memory_read_value & 0xFFFF00FF),
@@ -574,8 +720,7 @@ impl Runtime {
                 self.mw_cpu(self.align(addr), value, AccessPosition::Memory);
             }
             Opcode::SH => {
-                (a, b, c, addr, memory_read_value) = self.store_rr(instruction);
-                assert_eq!(addr % 2, 0, "addr is not aligned");
+                (a, b, c, addr, memory_read_value) = self.store_rr(instruction, 2)?;
                 let value = match (addr >> 1) % 2 {
                     0 => (a & 0x0000FFFF) + (memory_read_value & 0xFFFF0000),
                     1 => ((a & 0x0000FFFF) << 16) + (memory_read_value & 0x0000FFFF),
@@ -585,8 +730,7 @@ impl Runtime {
                 self.mw_cpu(self.align(addr), value, AccessPosition::Memory);
             }
             Opcode::SW => {
-                (a, b, c, addr, _) = self.store_rr(instruction);
-                assert_eq!(addr % 4, 0, "addr is not aligned");
+                (a, b, c, addr, _) = self.store_rr(instruction, 4)?;
                 let value = a;
                 memory_store_value = Some(value);
                 self.mw_cpu(self.align(addr), value, AccessPosition::Memory);
@@ -634,14 +778,14 @@ impl Runtime {
             Opcode::JAL => {
                 let (rd, imm) = instruction.j_type();
                 (b, c) = (imm, 0);
-                a = self.state.pc + 4;
+                a = self.state.pc + instruction.size();
                 self.rw(rd, a);
                 next_pc = self.state.pc.wrapping_add(imm);
             }
             Opcode::JALR => {
                 let (rd, rs1, imm) = instruction.i_type();
                 (b, c) = (self.rr(rs1, AccessPosition::B), imm);
-                a = self.state.pc + 4;
+                a = self.state.pc + instruction.size();
                 self.rw(rd, a);
                 next_pc = b.wrapping_add(c);
             }
@@ -659,30 +803,37 @@ impl Runtime {
                 let t0 = Register::X5;
                 let a0 = Register::X10;
                 let syscall_id = self.register(t0);
-                let syscall = SyscallCode::from_u32(syscall_id);
-
                 let init_clk = self.state.clk;
-                let syscall_impl = self.get_syscall(syscall).cloned();
+                let syscall_impl = self
+                    .get_syscall(syscall_id)
+                    .map_err(|kind| self.error(instruction, kind))?;
                 let mut precompile_rt = SyscallContext::new(self);
-
-                if let Some(syscall_impl) = syscall_impl {
-                    a = syscall_impl.execute(&mut precompile_rt);
-                    next_pc = precompile_rt.next_pc;
-                    self.state.clk = precompile_rt.clk;
-                    assert_eq!(init_clk + syscall_impl.num_extra_cycles(), self.state.clk);
-                } else {
-                    panic!("Unsupported syscall: {:?}", syscall);
+                let result = syscall_impl.execute(&mut precompile_rt);
+                let (syscall_next_pc, syscall_clk) = (precompile_rt.next_pc, precompile_rt.clk);
+                a = result.map_err(|kind| self.error(instruction, kind))?;
+                next_pc = syscall_next_pc;
+                self.state.clk = syscall_clk;
+                if init_clk + syscall_impl.num_extra_cycles() != self.state.clk {
+                    return Err(self.error(
+                        instruction,
+                        ExecutionErrorKind::SyscallCycleMismatch(syscall_id),
+                    ));
                 }
 
                 // We have to do this AFTER the precompile execution because the CPU event
                 // gets emitted at the end of this loop with the incremented clock.
                 // TODO: fix this.
+                c = if instruction.imm_c {
+                    instruction.op_c
+                } else {
+                    self.rr(Register::from_u32(instruction.op_c), AccessPosition::C)
+                };
                 self.rw(a0, a);
-                (b, c) = (self.rr(t0, AccessPosition::B), 0);
+                b = self.rr(t0, AccessPosition::B);
             }
 
             Opcode::EBREAK => {
-                todo!()
+                return Err(self.error(instruction, ExecutionErrorKind::Breakpoint));
             }
 
             // Multiply instructions.
@@ -745,10 +896,15 @@ impl Runtime {
 
             Opcode::UNIMP => {
                 // See https://github.com/riscv-non-isa/riscv-asm-manual/blob/master/riscv-asm.md#instruction-aliases
-                panic!("UNIMP encountered, we should never get here.");
+                return Err(self.error(instruction, ExecutionErrorKind::Unimplemented));
             }
         }
 
+        // Make sure the program does not continue in the middle of an instruction.
+        if self.is_code(next_pc) && self.instruction_index(next_pc).is_none() {
+            return Err(self.error(instruction, ExecutionErrorKind::InvalidPc(next_pc)));
+        }
+
         // Update the program counter.
         self.state.pc = next_pc;
 
@@ -764,10 +920,27 @@ impl Runtime {
             memory_store_value,
             self.cpu_record,
         );
+
+        Ok(())
+    }
+
+    /// Execute the program, returning the outcome of the execution once it halts or the error that
+    /// stopped it.
+    pub fn run(&mut self) -> Result<ExecutionOutcome, ExecutionError> {
+        self.initialize();
+        while !self.is_done() {
+            self.step()?;
+        }
+        self.finalize()
     }
 
-    /// Execute the program.
-    pub fn run(&mut self) {
+    /// Prepare the runtime to execute the program by loading its memory image. This must be called
+    /// once before stepping through the program, after the input has been written.
+    pub fn initialize(&mut self) {
+        // The public input stream only contains the input supplied to the program before execution
+        // starts, later writes to it are hints from the program itself.
+        self.input_len = self.state.input_stream.len();
+
         tracing::info_span!("load memory").in_scope(|| {
             // First load the memory image into the memory table.
             for (addr, value) in self.program.memory_image.iter() {
@@ -775,65 +948,111 @@ impl Runtime {
             }
         });
 
-        let max_syscall_cycles = self.max_syscall_cycles();
-
+        self.max_syscall_cycles = self.max_syscall_cycles();
         self.state.clk += 1;
-        while self.state.pc.wrapping_sub(self.program.pc_base)
-            < (self.program.instructions.len() * 4) as u32
-        {
-            // Fetch the instruction at the current program counter.
-            let instruction = self.fetch();
-
-            if let Some(ref mut buf) = self.trace_buf {
-                if !self.unconstrained {
-                    buf.write_all(&u32::to_be_bytes(self.state.pc)).unwrap();
-                }
+    }
+
+    /// Whether the program has finished executing, i.e. the program counter has left the code.
+    pub fn is_done(&self) -> bool {
+        !self.is_code(self.state.pc)
+    }
+
+    /// Execute the instruction at the current program counter.
+    pub fn step(&mut self) -> Result<(), ExecutionError> {
+        // Fetch the instruction at the current program counter.
+        let instruction = self.fetch()?;
+
+        if let Some(ref mut buf) = self.trace_buf {
+            if !self.unconstrained {
+                buf.write_all(&u32::to_be_bytes(self.state.pc)).unwrap();
             }
+        }
 
-            let width = 12;
-            log::trace!(
-                "clk={} [pc=0x{:x?}] {:<width$?} |         x0={:<width$} x1={:<width$} x2={:<width$} x3={:<width$} x4={:<width$} x5={:<width$} x6={:<width$} x7={:<width$} x8={:<width$} x9={:<width$} x10={:<width$} x11={:<width$} x12={:<width$} x13={:<width$} x14={:<width$} x15={:<width$} x16={:<width$} x17={:<width$} x18={:<width$}",
-                self.state.global_clk,
-                self.state.pc,
-                instruction,
-                self.register(Register::X0),
-                self.register(Register::X1),
-                self.register(Register::X2),
-                self.register(Register::X3),
-                self.register(Register::X4),
-                self.register(Register::X5),
-                self.register(Register::X6),
-                self.register(Register::X7),
-                self.register(Register::X8),
-                self.register(Register::X9),
-                self.register(Register::X10),
-                self.register(Register::X11),
-                self.register(Register::X12),
-                self.register(Register::X13),
-                self.register(Register::X14),
-                self.register(Register::X15),
-                self.register(Register::X16),
-                self.register(Register::X17),
-                self.register(Register::X18),
-            );
+        if let Some(ref mut profiler) = self.profiler {
+            if !self.unconstrained {
+                profiler.record(self.state.pc, &instruction);
+            }
+        }
 
-            // Execute the instruction.
-            self.execute(instruction);
+        let width = 12;
+        log::trace!(
+            "clk={} [pc=0x{:x?}] {:<width$?} |         x0={:<width$} x1={:<width$} x2={:<width$} x3={:<width$} x4={:<width$} x5={:<width$} x6={:<width$} x7={:<width$} x8={:<width$} x9={:<width$} x10={:<width$} x11={:<width$} x12={:<width$} x13={:<width$} x14={:<width$} x15={:<width$} x16={:<width$} x17={:<width$} x18={:<width$}",
+            self.state.global_clk,
+            self.state.pc,
+            instruction,
+            self.register(Register::X0),
+            self.register(Register::X1),
+            self.register(Register::X2),
+            self.register(Register::X3),
+            self.register(Register::X4),
+            self.register(Register::X5),
+            self.register(Register::X6),
+            self.register(Register::X7),
+            self.register(Register::X8),
+            self.register(Register::X9),
+            self.register(Register::X10),
+            self.register(Register::X11),
+            self.register(Register::X12),
+            self.register(Register::X13),
+            self.register(Register::X14),
+            self.register(Register::X15),
+            self.register(Register::X16),
+            self.register(Register::X17),
+            self.register(Register::X18),
+        );
 
-            // Increment the clock.
-            self.state.global_clk += 1;
-            self.state.clk += 4;
+        // Stop before executing an instruction past the cycle limit.
+        if let Some(max_cycles) = self.limits.max_cycles {
+            if self.state.global_clk as u64 >= max_cycles {
+                return Err(self.error(
+                    instruction,
+                    ExecutionErrorKind::CycleLimitExceeded(max_cycles),
+                ));
+            }
+        }
 
-            // If there's not enough cycles left for another instruction, move to the next shard.
-            // We multiply by 4 because clk is incremented by 4 for each normal instruction.
-            if !self.unconstrained && max_syscall_cycles + self.state.clk >= self.shard_size * 4 {
-                self.state.current_shard += 1;
-                self.state.clk = 0;
+        // Execute the instruction.
+        let pc = self.state.pc;
+        self.execute(instruction)?;
+
+        // Stop if the instruction took the program over the memory limit.
+        if let Some(max_memory) = self.limits.max_memory {
+            if self.state.memory.len() > max_memory {
+                return Err(ExecutionError {
+                    pc,
+                    ..self.error(
+                        instruction,
+                        ExecutionErrorKind::MemoryLimitExceeded(max_memory),
+                    )
+                });
             }
         }
+
+        // Increment the clock.
+        self.state.global_clk += 1;
+        self.state.clk += 4;
+
+        // If there's not enough cycles left for another instruction, move to the next shard.
+        // We multiply by 4 because clk is incremented by 4 for each normal instruction.
+        if !self.unconstrained && self.max_syscall_cycles + self.state.clk >= self.shard_size * 4 {
+            self.state.current_shard += 1;
+            self.state.clk = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Finish the execution of the program once it is done, recording its public values and
+    /// setting up the global tables of the execution record.
+    pub fn finalize(&mut self) -> Result<ExecutionOutcome, ExecutionError> {
         if let Some(ref mut buf) = self.trace_buf {
             buf.flush().unwrap();
         }
+        if self.cycle_tracker.depth() > 0 {
+            log::warn!("the program halted inside a cycle tracker span");
+            let events = self.record.event_counts();
+            self.cycle_tracker.end_all(self.state.global_clk, &events);
+        }
         // Flush remaining stdout/stderr
         for (fd, buf) in self.io_buf.iter() {
             if !buf.is_empty() {
@@ -849,34 +1068,38 @@ impl Runtime {
             }
         }
 
+        // The public values are the words the program committed to and its exit code.
+        self.record.public_values =
+            PublicValues::from_committed_words(&self.state.committed_words, self.state.exit_code);
+
         // Call postprocess to set up all variables needed for global accounts, like memory
         // argument or any other deferred tables.
         tracing::info_span!("postprocess").in_scope(|| self.postprocess());
+
+        Ok(ExecutionOutcome {
+            exit_code: self.state.exit_code,
+        })
     }
 
-    fn postprocess(&mut self) {
-        let mut program_memory_used = HashMap::with_hasher(BuildNoHashHasher::<u32>::default());
-        for (key, value) in &self.program.memory_image {
-            // By default we assume that the program_memory is used.
-            program_memory_used.insert(*key, (*value, 1));
+    /// A report of the execution so far, with the spans of the cycle tracker.
+    pub fn report(&self) -> ExecutionReport {
+        ExecutionReport {
+            total_cycles: self.state.global_clk as u64,
+            spans: self.cycle_tracker.spans().to_vec(),
         }
+    }
 
+    fn postprocess(&mut self) {
         let mut first_memory_record = Vec::new();
         let mut last_memory_record = Vec::new();
 
         let memory_keys = self.state.memory.keys().cloned().collect::<Vec<u32>>();
         for addr in memory_keys {
             let (value, shard, timestamp) = *self.state.memory.get(&addr).unwrap();
-            if shard == 0 && timestamp == 0 {
-                // This means that we never accessed this memory location throughout our entire program.
-                // The only way this can happen is if this was in the program memory image.
-                // We mark this (addr, value) as not used in the `program_memory_used` map.
-                program_memory_used.insert(addr, (value, 0));
-                continue;
-            }
             // If the memory addr was accessed, we only add it to "first_memory_record" if it was
             // not in the program_memory_image, otherwise we'll add to the memory argument from
-            // the program_memory_image table.
+            // the program_memory_image table. An address of the image that was never accessed is
+            // finalized with the value it was initialized with.
             if !self.program.memory_image.contains_key(&addr) {
                 first_memory_record.push((
                     addr,
@@ -900,9 +1123,12 @@ impl Runtime {
             ));
         }
 
-        let mut program_memory_record = program_memory_used
+        // Every address of the memory image is initialized, whether it was accessed or not.
+        let program_memory_record = self
+            .program
+            .memory_image
             .iter()
-            .map(|(&addr, &(value, used))| {
+            .map(|(&addr, &value)| {
                 (
                     addr,
                     MemoryRecord {
@@ -910,11 +1136,10 @@ impl Runtime {
                         shard: 0,
                         timestamp: 0,
                     },
-                    used,
+                    1,
                 )
             })
             .collect::<Vec<(u32, MemoryRecord, u32)>>();
-        program_memory_record.sort_by_key(|&(addr, _, _)| addr);
 
         self.record.first_memory_record = first_memory_record;
         self.record.last_memory_record = last_memory_record;
@@ -922,15 +1147,50 @@ impl Runtime {
     }
 }
 
+/// Check that the program may access the word at `addr`: it must be aligned, lie past the
+/// registers and fit in a field element, so that the memory argument can represent it.
+pub(crate) fn check_word_address(addr: u32) -> Result<(), ExecutionErrorKind> {
+    if addr % 4 != 0 {
+        return Err(ExecutionErrorKind::UnalignedMemoryAccess(addr));
+    }
+    if addr <= 40 || addr as u64 >= BabyBear::ORDER_U64 {
+        return Err(ExecutionErrorKind::InvalidMemoryAccess(addr));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 pub mod tests {
 
     use crate::{
         runtime::Register,
+        syscall::{FD_FILES_START, FILE_NOT_FOUND},
         utils::tests::{FIBONACCI_ELF, SSZ_WITHDRAWALS_ELF},
+        NUM_COMMITTED_WORDS,
+    };
+
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use super::{
+        ExecutionErrorKind, ExecutionLimits, Instruction, Opcode, Program, Runtime, Syscall,
+        SyscallContext, MAX_SYSCALL_BUFFER_LEN, USER_SYSCALLS,
     };
 
-    use super::{Instruction, Opcode, Program, Runtime};
+    /// Append a `HALT` with exit code zero to a program, which must end with one to be proven.
+    /// Programs at address zero are moved, since `HALT` jumps there.
+    pub fn halting(mut program: Program) -> Program {
+        program.instructions.extend([
+            Instruction::new(Opcode::ADD, 5, 0, 100, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ]);
+        if program.pc_base == 0 {
+            program.pc_base += 4;
+            program.pc_start += 4;
+        }
+        program
+    }
 
     pub fn simple_program() -> Program {
         let instructions = vec![
@@ -941,6 +1201,27 @@ pub mod tests {
         Program::new(instructions, 0, 0)
     }
 
+    pub fn compressed_program() -> Program {
+        let compressed = |instruction: Instruction| Instruction {
+            compressed: true,
+            ..instruction
+        };
+        // main:
+        //     c.li x29, 5
+        //     addi x30, x0, 37
+        //     c.jal 4
+        //     c.unimp
+        //     c.add x31, x30, x29
+        let instructions = vec![
+            compressed(Instruction::new(Opcode::ADD, 29, 0, 5, false, true)),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+            compressed(Instruction::new(Opcode::JAL, 1, 4, 0, true, true)),
+            compressed(Instruction::new(Opcode::UNIMP, 0, 0, 0, true, true)),
+            compressed(Instruction::new(Opcode::ADD, 31, 30, 29, false, false)),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
     pub fn fibonacci_program() -> Program {
         Program::from(FIBONACCI_ELF)
     }
@@ -952,6 +1233,7 @@ pub mod tests {
     pub fn ecall_lwa_program() -> Program {
         let instructions = vec![
             Instruction::new(Opcode::ADD, 5, 0, 101, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
             Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
         ];
         Program::new(instructions, 0, 0)
@@ -961,8 +1243,421 @@ pub mod tests {
     fn test_simple_program_run() {
         let program = simple_program();
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X31), 42);
+    }
+
+    #[test]
+    fn test_ecall_lwa_public_and_private() {
+        // Read a word from the public input into x29 and from the private input into x30.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 101, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 29, 10, 0, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 5, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 30, 10, 0, false, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program);
+        runtime.write_stdin_slice(&7u32.to_le_bytes());
+        runtime.write_private_stdin_slice(&42u32.to_le_bytes());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X29), 7);
+        assert_eq!(runtime.register(Register::X30), 42);
+    }
+
+    /// A user syscall which returns its argument plus one.
+    struct SyscallIncrement;
+
+    impl Syscall for SyscallIncrement {
+        fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+            Ok(ctx.register_unsafe(Register::X10) + 1)
+        }
+    }
+
+    #[test]
+    fn test_user_syscall() {
+        let code = USER_SYSCALLS.start + 1;
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, code, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 41, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions.clone(), 0, 0));
+        assert!(runtime
+            .register_syscall(code, Rc::new(SyscallIncrement))
+            .is_none());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X10), 42);
+
+        // Invoking a user syscall which was not registered is an error.
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InvalidSyscall(code));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_user_syscall_reserved() {
+        let mut runtime = Runtime::new(simple_program());
+        runtime.register_syscall(101, Rc::new(SyscallIncrement));
+    }
+
+    #[test]
+    fn test_hint() {
+        // Invoke the hint named "sort" on 4 bytes and read its answer back as a word into x29.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 113, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x100, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 0x104, false, true),
+            Instruction::new(Opcode::ADD, 13, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 28, 10, 0, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 101, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 6, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 29, 10, 0, false, true),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        program
+            .memory_image
+            .insert(0x100, u32::from_le_bytes(*b"sort"));
+        program
+            .memory_image
+            .insert(0x104, u32::from_le_bytes([3, 1, 2, 0]));
+
+        // The answer is read from its own stream, even though the public input is not exhausted.
+        let mut runtime = Runtime::new(program.clone());
+        runtime.write_stdin_slice(&[9, 9, 9, 9]);
+        runtime.register_hint(
+            "sort",
+            Arc::new(|input: &[u8]| {
+                let mut output = input.to_vec();
+                output.sort();
+                output
+            }),
+        );
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X28), 4);
+        assert_eq!(
+            runtime.register(Register::X29),
+            u32::from_le_bytes([0, 1, 2, 3])
+        );
+        assert_eq!(runtime.state.input_stream_ptr, 0);
+
+        let mut runtime = Runtime::new(program);
+        let err = runtime.run().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExecutionErrorKind::UnknownHint("sort".to_string())
+        );
+    }
+
+    #[test]
+    fn test_hint_invalid_buffer() {
+        // The length of the name of the hint runs past the end of memory.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 113, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x100, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0xffff_ff80, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExecutionErrorKind::InvalidBuffer(0x100, 0xffff_ff80)
+        );
+    }
+
+    #[test]
+    fn test_virtual_file() {
+        // Open the file named "cfg", then read its length into x29 and the two bytes at offset 4
+        // into x30.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 114, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x100, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 3, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 28, 10, 0, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 115, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 29, 10, 0, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 116, false, true),
+            Instruction::new(Opcode::ADD, 10, 28, 0, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 2, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 30, 10, 0, false, true),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        program
+            .memory_image
+            .insert(0x100, u32::from_le_bytes(*b"cfg\0"));
+
+        let mut runtime = Runtime::new(program.clone());
+        runtime.add_file("cfg", b"key=value".to_vec().into());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X28), FD_FILES_START);
+        assert_eq!(runtime.register(Register::X29), 9);
+        assert_eq!(
+            runtime.register(Register::X30),
+            u32::from_le_bytes([b'v', b'a', 0, 0])
+        );
+
+        // Opening a missing file returns an error code, and reading from it fails.
+        let mut runtime = Runtime::new(program);
+        let err = runtime.run().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExecutionErrorKind::InvalidFileDescriptor(FILE_NOT_FOUND)
+        );
+
+        // A name longer than the maximum buffer length is rejected before it is read.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 114, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x100, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, MAX_SYSCALL_BUFFER_LEN + 1, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExecutionErrorKind::InvalidBuffer(0x100, MAX_SYSCALL_BUFFER_LEN + 1)
+        );
+    }
+
+    #[test]
+    fn test_commit() {
+        // Commit to 42 as the word at index 8 of the public values, then to 7 at the invalid index
+        // right after the last one.
+        let mut instructions = Vec::new();
+        for (word, index) in [(42, 8), (7, NUM_COMMITTED_WORDS as u32)] {
+            instructions.extend([
+                Instruction::new(Opcode::ADD, 5, 0, 127, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, word, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, index, false, true),
+                Instruction::new(Opcode::ECALL, 10, 5, 11, false, false),
+            ]);
+        }
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExecutionErrorKind::InvalidCommitIndex(NUM_COMMITTED_WORDS as u32)
+        );
+        assert_eq!(runtime.state.committed_words[8], 42);
+    }
+
+    #[test]
+    fn test_write_invalid_fd() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 999, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 7, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InvalidFileDescriptor(7));
+    }
+
+    #[test]
+    fn test_halt_nonzero_exit_code() {
+        // The program starts at a non-zero base so that jumping to pc 0 on halt ends execution.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 100, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 7, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 31, 0, 42, false, true),
+        ];
+        let program = Program::new(instructions, 4, 4);
+        let mut runtime = Runtime::new(program);
+        let outcome = runtime.run().unwrap();
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.exit_code, 7);
+        assert_eq!(runtime.register(Register::X31), 0);
+        assert_eq!(runtime.record.public_values.exit_code, 7);
+    }
+
+    #[test]
+    fn test_execution_errors() {
+        // An invalid syscall number.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 42, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InvalidSyscall(42));
+        assert_eq!(err.pc, 4);
+        assert_eq!(err.instruction.unwrap().opcode, Opcode::ECALL);
+
+        // Reading a word from an empty input.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 101, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InputExhausted(3));
+        assert_eq!(err.pc, 12);
+
+        // An unaligned word load.
+        let instructions = vec![Instruction::new(Opcode::LW, 29, 0, 0x27654321, false, true)];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExecutionErrorKind::UnalignedMemoryAccess(0x27654321)
+        );
+
+        // A breakpoint.
+        let instructions = vec![Instruction::new(Opcode::EBREAK, 0, 0, 0, false, false)];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::Breakpoint);
+
+        // Reading more bytes than fit in a word.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 101, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 5, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InvalidReadLength(5));
+
+        // Entering an unconstrained block twice.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 110, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::NestedUnconstrained);
+
+        // Reading public input inside an unconstrained block.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 110, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, 101, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        runtime.write_stdin_slice(&7u32.to_le_bytes());
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::UnconstrainedPublicInput);
+
+        // Doubling a point at an unaligned address.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 108, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x1001, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::UnalignedMemoryAccess(0x1001));
+
+        // Loading a word from the addresses of the registers.
+        let instructions = vec![Instruction::new(Opcode::LW, 29, 0, 8, false, true)];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InvalidMemoryAccess(8));
+
+        // Storing a word at an address which does not fit in a field element.
+        let instructions = vec![Instruction::new(Opcode::SW, 29, 0, 0x78000004, false, true)];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExecutionErrorKind::InvalidMemoryAccess(0x78000004)
+        );
+
+        // Permuting a keccak state which runs past the end of memory.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 106, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x77fffff0, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExecutionErrorKind::InvalidMemoryAccess(0x780000b4)
+        );
+    }
+
+    #[test]
+    fn test_compressed() {
+        let compressed = |instruction: Instruction| Instruction {
+            compressed: true,
+            ..instruction
+        };
+        let program = compressed_program();
+        assert_eq!(program.pcs().collect::<Vec<_>>(), vec![0, 2, 6, 8, 10]);
+        let mut runtime = Runtime::new(program);
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X1), 8);
         assert_eq!(runtime.register(Register::X31), 42);
+        assert_eq!(runtime.state.pc, 12);
+
+        // Jumping into the middle of the 32-bit instruction is an error.
+        let instructions = vec![
+            compressed(Instruction::new(Opcode::JAL, 0, 4, 0, true, true)),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InvalidPc(4));
+    }
+
+    #[test]
+    fn test_execution_limits() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+            Instruction::new(Opcode::ADD, 31, 30, 29, false, false),
+        ];
+
+        // Exactly enough cycles to finish.
+        let mut runtime = Runtime::new(Program::new(instructions.clone(), 0, 0));
+        runtime.limits = ExecutionLimits {
+            max_cycles: Some(3),
+            max_memory: None,
+        };
+        runtime.run().unwrap();
+
+        // One cycle short.
+        let mut runtime = Runtime::new(Program::new(instructions.clone(), 0, 0));
+        runtime.limits = ExecutionLimits {
+            max_cycles: Some(2),
+            max_memory: None,
+        };
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::CycleLimitExceeded(2));
+        assert_eq!(err.pc, 8);
+
+        // Each register written to takes up a word of memory.
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0));
+        runtime.limits = ExecutionLimits {
+            max_cycles: None,
+            max_memory: Some(2),
+        };
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::MemoryLimitExceeded(2));
+        assert_eq!(err.pc, 8);
     }
 
     #[test]
@@ -978,7 +1673,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 42);
     }
 
@@ -995,7 +1690,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 32);
     }
 
@@ -1012,7 +1707,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 32);
     }
 
@@ -1030,7 +1725,7 @@ pub mod tests {
 
         let mut runtime = Runtime::new(program);
 
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 37);
     }
 
@@ -1047,7 +1742,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 5);
     }
 
@@ -1064,7 +1759,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 1184);
     }
 
@@ -1081,7 +1776,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 1);
     }
 
@@ -1098,7 +1793,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 1);
     }
 
@@ -1115,7 +1810,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 0);
     }
 
@@ -1132,7 +1827,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 0);
     }
 
@@ -1149,7 +1844,7 @@ pub mod tests {
         let program = Program::new(instructions, 0, 0);
 
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 84);
     }
 
@@ -1165,7 +1860,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 5 - 1 + 4);
     }
 
@@ -1181,7 +1876,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 10);
     }
 
@@ -1197,7 +1892,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 47);
     }
 
@@ -1213,7 +1908,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 0);
     }
 
@@ -1227,7 +1922,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 80);
     }
 
@@ -1241,7 +1936,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 2);
     }
 
@@ -1255,7 +1950,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 2);
     }
 
@@ -1269,7 +1964,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 0);
     }
 
@@ -1283,7 +1978,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.register(Register::X31), 0);
     }
 
@@ -1302,7 +1997,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.registers()[Register::X5 as usize], 8);
         assert_eq!(runtime.registers()[Register::X11 as usize], 100);
         assert_eq!(runtime.state.pc, 108);
@@ -1316,7 +2011,7 @@ pub mod tests {
         ];
         let program = Program::new(instructions, 0, 0);
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         assert_eq!(runtime.registers()[Register::X12 as usize], expected);
     }
 
@@ -1534,7 +2229,7 @@ pub mod tests {
     fn test_simple_memory_program_run() {
         let program = simple_memory_program();
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
 
         // Assert SW & LW case
         assert_eq!(runtime.register(Register::X28), 0x12348765);
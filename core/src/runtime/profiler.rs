@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use super::{Instruction, Opcode, Register};
+use crate::disassembler::{find_symbol, DebugInfo, Symbol};
+
+/// The root of the call tree, which is not a function.
+const ROOT: usize = 0;
+
+/// A profiler which attributes the cycles of an execution to the call stacks they were spent in.
+///
+/// Program counters are resolved against the function symbols of the ELF. The call stack is
+/// inferred from the instructions: a `JAL` or `JALR` which links a return address is a call, a
+/// `JALR` through `ra` which does not is a return, and reaching another function without a call
+/// (e.g. a tail call) replaces the function on top of the stack. If the ELF has DWARF debug
+/// information, the functions inlined at a program counter are added on top of the stack.
+pub struct Profiler {
+    /// The function symbols of the program, sorted by address.
+    symbols: Vec<Symbol>,
+
+    /// The debug information used to find inlined functions, if the ELF has any.
+    debug_info: Option<DebugInfo>,
+
+    /// The names of the inlined functions, whose indices come after `symbols.len()`.
+    inlined_names: Vec<String>,
+
+    /// The index of each name in `inlined_names`.
+    inlined_indices: HashMap<String, usize>,
+
+    /// The inlined functions at each program counter, from the outermost to the innermost.
+    inlined: HashMap<u32, Vec<usize>>,
+
+    /// The nodes of the call tree as the parent node and the index of the function, where
+    /// `symbols.len()` stands for code outside of any known function.
+    nodes: Vec<(usize, usize)>,
+
+    /// The child of a node for each function called from it.
+    children: HashMap<(usize, usize), usize>,
+
+    /// The number of cycles spent in each node of the call tree, excluding its children.
+    cycles: Vec<u64>,
+
+    /// The node of the function currently executing, excluding the functions inlined into it.
+    current: usize,
+
+    /// The node the last cycle was recorded in, including inlined functions.
+    last: usize,
+
+    /// Whether the last instruction was a call.
+    calling: bool,
+}
+
+impl Profiler {
+    pub fn new(symbols: Vec<Symbol>, debug_info: Option<DebugInfo>) -> Self {
+        Self {
+            symbols,
+            debug_info,
+            inlined_names: Vec::new(),
+            inlined_indices: HashMap::new(),
+            inlined: HashMap::new(),
+            nodes: vec![(ROOT, usize::MAX)],
+            children: HashMap::new(),
+            cycles: vec![0],
+            current: ROOT,
+            last: ROOT,
+            calling: false,
+        }
+    }
+
+    /// Record a cycle spent executing the instruction at `pc`.
+    pub fn record(&mut self, pc: u32, instruction: &Instruction) {
+        let function = find_symbol(&self.symbols, pc).unwrap_or(self.symbols.len());
+        if self.calling {
+            // The caller is the innermost function the call was made from, which may be inlined.
+            self.current = self.child(self.last, function);
+            self.calling = false;
+        } else if self.nodes[self.current].1 != function {
+            let (parent, _) = self.nodes[self.current];
+            self.current = self.child(parent, function);
+        }
+        self.last = self.current;
+        for inlined in self.inlined(pc) {
+            self.last = self.child(self.last, inlined);
+        }
+        self.cycles[self.last] += 1;
+
+        let links = instruction.op_a != Register::X0 as u32;
+        match instruction.opcode {
+            Opcode::JAL | Opcode::JALR if links => self.calling = true,
+            Opcode::JALR if instruction.op_b == Register::X1 as u32 => {
+                // Return to the caller, skipping the functions inlined into it.
+                let mut parent = self.nodes[self.current].0;
+                while parent != ROOT && self.nodes[parent].1 > self.symbols.len() {
+                    parent = self.nodes[parent].0;
+                }
+                self.current = parent;
+            }
+            _ => {}
+        }
+    }
+
+    /// The total number of cycles recorded.
+    pub fn total_cycles(&self) -> u64 {
+        self.cycles.iter().sum()
+    }
+
+    /// The number of cycles spent in each function, excluding and including the functions it
+    /// called, sorted by the cycles spent in the function itself.
+    pub fn functions(&self) -> Vec<(String, u64, u64)> {
+        let mut functions = HashMap::<usize, (u64, u64)>::new();
+        for node in 1..self.nodes.len() {
+            functions.entry(self.nodes[node].1).or_default().0 += self.cycles[node];
+            // Count the cycles once for every function on the stack, even if it recurses.
+            let mut stack = self.stack(node);
+            stack.sort_unstable();
+            stack.dedup();
+            for function in stack {
+                functions.entry(function).or_default().1 += self.cycles[node];
+            }
+        }
+        let mut functions = functions
+            .into_iter()
+            .map(|(function, (own, total))| (self.name(function).to_string(), own, total))
+            .collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        functions
+    }
+
+    /// Write the profile as folded stacks, one `caller;callee cycles` line per call stack, which
+    /// can be rendered with flamegraph tools such as `inferno-flamegraph`.
+    pub fn write_folded<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for node in 1..self.nodes.len() {
+            if self.cycles[node] == 0 {
+                continue;
+            }
+            let mut stack = self.stack(node);
+            stack.reverse();
+            let names = stack
+                .into_iter()
+                .map(|function| self.name(function))
+                .collect::<Vec<_>>();
+            writeln!(writer, "{} {}", names.join(";"), self.cycles[node])?;
+        }
+        Ok(())
+    }
+
+    /// Write the profile as a gzipped pprof protobuf, which can be opened with `go tool pprof`.
+    ///
+    /// Reference: https://github.com/google/pprof/blob/main/proto/profile.proto
+    pub fn write_pprof<W: Write>(&self, writer: W) -> io::Result<()> {
+        // The string table starts with the empty string. Every function gets one location, with
+        // the same id as the function.
+        let mut strings = vec!["", "cycles", "count"];
+        let mut profile = Vec::new();
+
+        let mut sample_type = Vec::new();
+        encode_uint(&mut sample_type, 1, 1);
+        encode_uint(&mut sample_type, 2, 2);
+        encode_bytes(&mut profile, 1, &sample_type);
+
+        for node in 1..self.nodes.len() {
+            if self.cycles[node] == 0 {
+                continue;
+            }
+            let mut sample = Vec::new();
+            for function in self.stack(node) {
+                encode_uint(&mut sample, 1, function as u64 + 1);
+            }
+            encode_uint(&mut sample, 2, self.cycles[node]);
+            encode_bytes(&mut profile, 2, &sample);
+        }
+
+        for function in 0..=self.symbols.len() + self.inlined_names.len() {
+            let id = function as u64 + 1;
+            let mut line = Vec::new();
+            encode_uint(&mut line, 1, id);
+            let mut location = Vec::new();
+            encode_uint(&mut location, 1, id);
+            if let Some(symbol) = self.symbols.get(function) {
+                encode_uint(&mut location, 3, symbol.address as u64);
+            }
+            encode_bytes(&mut location, 4, &line);
+            encode_bytes(&mut profile, 4, &location);
+
+            let mut func = Vec::new();
+            encode_uint(&mut func, 1, id);
+            encode_uint(&mut func, 2, strings.len() as u64);
+            encode_bytes(&mut profile, 5, &func);
+            strings.push(self.name(function));
+        }
+
+        for string in strings {
+            encode_bytes(&mut profile, 6, string.as_bytes());
+        }
+
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        encoder.write_all(&profile)?;
+        encoder.finish()?.flush()
+    }
+
+    /// The functions inlined at `pc`, from the outermost to the innermost. The debug information is
+    /// only looked up the first time a program counter is executed.
+    fn inlined(&mut self, pc: u32) -> Vec<usize> {
+        if let Some(inlined) = self.inlined.get(&pc) {
+            return inlined.clone();
+        }
+        // Code without valid debug information is attributed to its symbol only.
+        let names = match &self.debug_info {
+            Some(debug_info) => debug_info.inlined_functions(pc).unwrap_or_default(),
+            None => Vec::new(),
+        };
+        let inlined = names
+            .into_iter()
+            .map(|name| {
+                let next = self.symbols.len() + 1 + self.inlined_names.len();
+                *self.inlined_indices.entry(name.clone()).or_insert_with(|| {
+                    self.inlined_names.push(name);
+                    next
+                })
+            })
+            .collect::<Vec<_>>();
+        self.inlined.insert(pc, inlined.clone());
+        inlined
+    }
+
+    /// The child of `parent` for calls to `function`, created if it does not exist yet.
+    fn child(&mut self, parent: usize, function: usize) -> usize {
+        let next = self.nodes.len();
+        let node = *self.children.entry((parent, function)).or_insert(next);
+        if node == next {
+            self.nodes.push((parent, function));
+            self.cycles.push(0);
+        }
+        node
+    }
+
+    /// The functions on the call stack of a node, innermost first.
+    fn stack(&self, mut node: usize) -> Vec<usize> {
+        let mut stack = Vec::new();
+        while node != ROOT {
+            let (parent, function) = self.nodes[node];
+            stack.push(function);
+            node = parent;
+        }
+        stack
+    }
+
+    fn name(&self, function: usize) -> &str {
+        match function.checked_sub(self.symbols.len() + 1) {
+            Some(inlined) => &self.inlined_names[inlined],
+            None => self
+                .symbols
+                .get(function)
+                .map_or("[unknown]", |symbol| symbol.name.as_str()),
+        }
+    }
+}
+
+/// Encode a protobuf varint.
+fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Encode an integer field of a protobuf message.
+fn encode_uint(buf: &mut Vec<u8>, field: u64, value: u64) {
+    encode_varint(buf, field << 3);
+    encode_varint(buf, value);
+}
+
+/// Encode a length-delimited field of a protobuf message, i.e. a string or a nested message.
+fn encode_bytes(buf: &mut Vec<u8>, field: u64, data: &[u8]) {
+    encode_varint(buf, field << 3 | 2);
+    encode_varint(buf, data.len() as u64);
+    buf.extend_from_slice(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_uint, Profiler};
+    use crate::disassembler::{Elf, Symbol};
+    use crate::runtime::{Instruction, Opcode, Program, Runtime};
+    use crate::utils::tests::FIBONACCI_ELF;
+
+    #[test]
+    fn test_profiler() {
+        let symbols = Elf::symbols(FIBONACCI_ELF).unwrap();
+        let mut runtime = Runtime::new(Program::from(FIBONACCI_ELF));
+        runtime.profiler = Some(Profiler::new(symbols, None));
+        runtime.run().unwrap();
+        let profiler = runtime.profiler.take().unwrap();
+        assert_eq!(profiler.total_cycles(), runtime.state.global_clk as u64);
+
+        let mut folded = Vec::new();
+        profiler.write_folded(&mut folded).unwrap();
+        let folded = String::from_utf8(folded).unwrap();
+        let stacks = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap())
+            .collect::<Vec<_>>();
+        assert!(stacks
+            .iter()
+            .any(|(stack, _)| stack.ends_with(";main") || *stack == "main"));
+        let cycles = stacks
+            .iter()
+            .map(|(_, cycles)| cycles.parse::<u64>().unwrap())
+            .sum::<u64>();
+        assert_eq!(cycles, profiler.total_cycles());
+
+        let functions = profiler.functions();
+        let (_, own, total) = functions.iter().find(|f| f.0 == "main").unwrap();
+        assert!(own <= total);
+
+        let mut pprof = Vec::new();
+        profiler.write_pprof(&mut pprof).unwrap();
+        assert_eq!(&pprof[..2], &[0x1f, 0x8b]);
+    }
+
+    #[test]
+    fn test_profiler_inlined_functions() {
+        let symbol = |name: &str, address| Symbol {
+            name: name.to_string(),
+            address,
+            size: 0x10,
+        };
+        let mut profiler = Profiler::new(vec![symbol("main", 0x1000), symbol("foo", 0x2000)], None);
+        // Pretend that `helper` was inlined into `main` at 0x1004 and 0x1008.
+        profiler.inlined_names.push("helper".to_string());
+        profiler.inlined_indices.insert("helper".to_string(), 3);
+        profiler.inlined.insert(0x1004, vec![3]);
+        profiler.inlined.insert(0x1008, vec![3]);
+
+        let add = Instruction::new(Opcode::ADD, 5, 0, 1, false, true);
+        let call = Instruction::new(Opcode::JAL, 1, 0x2000 - 0x1008, 0, true, true);
+        let ret = Instruction::new(Opcode::JALR, 0, 1, 0, false, true);
+        for (pc, instruction) in [
+            (0x1000, add),
+            (0x1004, add),
+            (0x1008, call),
+            (0x2000, add),
+            (0x2004, ret),
+            (0x100c, add),
+        ] {
+            profiler.record(pc, &instruction);
+        }
+
+        let mut folded = Vec::new();
+        profiler.write_folded(&mut folded).unwrap();
+        let mut folded = String::from_utf8(folded)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        folded.sort();
+        assert_eq!(folded, ["main 2", "main;helper 2", "main;helper;foo 2"]);
+
+        let functions = profiler.functions();
+        let helper = functions.iter().find(|f| f.0 == "helper").unwrap();
+        assert_eq!((helper.1, helper.2), (2, 4));
+    }
+
+    #[test]
+    fn test_encode_uint() {
+        let mut buf = Vec::new();
+        encode_uint(&mut buf, 1, 300);
+        assert_eq!(buf, vec![0x08, 0xac, 0x02]);
+    }
+}
@@ -18,3 +18,31 @@ pub struct Program {
     /// The initial memory image, useful for global constants.
     pub memory_image: BTreeMap<u32, u32>,
 }
+
+impl Program {
+    /// Returns the address of each instruction, in order.
+    pub fn pcs(&self) -> impl Iterator<Item = u32> + '_ {
+        self.instructions
+            .iter()
+            .scan(self.pc_base, |pc, instruction| {
+                let instruction_pc = *pc;
+                *pc += instruction.size();
+                Some(instruction_pc)
+            })
+    }
+
+    /// Returns the number of bytes taken up by the instructions.
+    pub fn code_size(&self) -> u32 {
+        self.instructions.iter().map(Instruction::size).sum()
+    }
+
+    /// Returns a table from each halfword of the code, starting at `pc_base`, to the index of the
+    /// instruction starting at that address if there is one.
+    pub fn pc_index(&self) -> Vec<Option<u32>> {
+        let mut index = vec![None; (self.code_size() / 2) as usize];
+        for (i, pc) in self.pcs().enumerate() {
+            index[((pc - self.pc_base) / 2) as usize] = Some(i as u32);
+        }
+        index
+    }
+}
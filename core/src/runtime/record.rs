@@ -10,13 +10,18 @@ use crate::bytes::{ByteLookupEvent, ByteOpcode};
 use crate::cpu::{CpuEvent, MemoryRecordEnum};
 use crate::field::event::FieldEvent;
 use crate::runtime::MemoryRecord;
-use crate::syscall::precompiles::blake3::Blake3CompressInnerEvent;
-use crate::syscall::precompiles::edwards::EdDecompressEvent;
+use crate::syscall::precompiles::blake3::{Blake3CompressInnerEvent, OPERATION_COUNT, ROUND_COUNT};
+use crate::syscall::precompiles::bls12_381::Bls12381DecompressEvent;
+use crate::syscall::precompiles::bn254::Bn254Fp2MulEvent;
+use crate::syscall::precompiles::edwards::{EdDecompressEvent, EdScalarMulEvent, NUM_SCALAR_BITS};
 use crate::syscall::precompiles::k256::K256DecompressEvent;
 use crate::syscall::precompiles::keccak256::KeccakPermuteEvent;
+use crate::syscall::precompiles::secp256r1::Secp256r1DecompressEvent;
 use crate::syscall::precompiles::sha256::{ShaCompressEvent, ShaExtendEvent};
 use crate::syscall::precompiles::{ECAddEvent, ECDoubleEvent};
 use crate::utils::env;
+use crate::PublicValues;
+use p3_keccak_air::NUM_ROUNDS as KECCAK_NUM_ROUNDS;
 use serde::{Deserialize, Serialize};
 
 /// A record of the execution of a program. Contains event data for everything that happened during
@@ -29,6 +34,9 @@ pub struct ExecutionRecord {
     /// The program.
     pub program: Arc<Program>,
 
+    /// The public values of the execution, shared by all shards.
+    pub public_values: PublicValues,
+
     /// A trace of the CPU events which get emitted during execution.
     pub cpu_events: Vec<CpuEvent>,
 
@@ -72,12 +80,32 @@ pub struct ExecutionRecord {
 
     pub ed_decompress_events: Vec<EdDecompressEvent>,
 
-    pub weierstrass_add_events: Vec<ECAddEvent>,
+    pub ed_scalar_mul_events: Vec<EdScalarMulEvent>,
+
+    pub secp256k1_add_events: Vec<ECAddEvent>,
+
+    pub secp256k1_double_events: Vec<ECDoubleEvent>,
+
+    pub bn254_add_events: Vec<ECAddEvent>,
+
+    pub bn254_double_events: Vec<ECDoubleEvent>,
 
-    pub weierstrass_double_events: Vec<ECDoubleEvent>,
+    pub bn254_fp2_mul_events: Vec<Bn254Fp2MulEvent>,
 
     pub k256_decompress_events: Vec<K256DecompressEvent>,
 
+    pub bls12381_add_events: Vec<ECAddEvent>,
+
+    pub bls12381_double_events: Vec<ECDoubleEvent>,
+
+    pub bls12381_decompress_events: Vec<Bls12381DecompressEvent>,
+
+    pub secp256r1_add_events: Vec<ECAddEvent>,
+
+    pub secp256r1_double_events: Vec<ECDoubleEvent>,
+
+    pub secp256r1_decompress_events: Vec<Secp256r1DecompressEvent>,
+
     pub blake3_compress_inner_events: Vec<Blake3CompressInnerEvent>,
 
     /// Information needed for global chips. This shouldn't really be here but for legacy reasons,
@@ -99,8 +127,24 @@ pub struct ShardingConfig {
     pub lt_len: usize,
     pub field_len: usize,
     pub keccak_len: usize,
-    pub weierstrass_add_len: usize,
-    pub weierstrass_double_len: usize,
+    pub secp256k1_add_len: usize,
+    pub secp256k1_double_len: usize,
+    pub bn254_add_len: usize,
+    pub bn254_double_len: usize,
+    pub bn254_fp2_mul_len: usize,
+    pub sha_extend_len: usize,
+    pub sha_compress_len: usize,
+    pub ed_add_len: usize,
+    pub ed_decompress_len: usize,
+    pub ed_scalar_mul_len: usize,
+    pub k256_decompress_len: usize,
+    pub bls12381_add_len: usize,
+    pub bls12381_double_len: usize,
+    pub bls12381_decompress_len: usize,
+    pub secp256r1_add_len: usize,
+    pub secp256r1_double_len: usize,
+    pub secp256r1_decompress_len: usize,
+    pub blake3_compress_inner_len: usize,
 }
 
 impl ShardingConfig {
@@ -112,6 +156,9 @@ impl ShardingConfig {
 impl Default for ShardingConfig {
     fn default() -> Self {
         let shard_size = env::shard_size();
+        // The number of events of a chip which fit in a shard, given the number of rows of its
+        // trace taken by each event.
+        let events_per_shard = |rows_per_event: usize| (shard_size / rows_per_event).max(1);
         Self {
             shard_size,
             add_len: shard_size,
@@ -123,9 +170,25 @@ impl Default for ShardingConfig {
             mul_len: shard_size,
             shift_right_len: shard_size,
             field_len: shard_size * 4,
-            keccak_len: shard_size,
-            weierstrass_add_len: shard_size,
-            weierstrass_double_len: shard_size,
+            keccak_len: events_per_shard(KECCAK_NUM_ROUNDS),
+            secp256k1_add_len: shard_size,
+            secp256k1_double_len: shard_size,
+            bn254_add_len: shard_size,
+            bn254_double_len: shard_size,
+            bn254_fp2_mul_len: shard_size,
+            sha_extend_len: events_per_shard(48),
+            sha_compress_len: events_per_shard(80),
+            ed_add_len: shard_size,
+            ed_decompress_len: shard_size,
+            ed_scalar_mul_len: events_per_shard(NUM_SCALAR_BITS),
+            k256_decompress_len: shard_size,
+            bls12381_add_len: shard_size,
+            bls12381_double_len: shard_size,
+            bls12381_decompress_len: shard_size,
+            secp256r1_add_len: shard_size,
+            secp256r1_double_len: shard_size,
+            secp256r1_decompress_len: shard_size,
+            blake3_compress_inner_len: events_per_shard(ROUND_COUNT * OPERATION_COUNT),
         }
     }
 }
@@ -147,9 +210,19 @@ pub struct ShardStats {
     pub nb_keccak_permute_events: usize,
     pub nb_ed_add_events: usize,
     pub nb_ed_decompress_events: usize,
-    pub nb_weierstrass_add_events: usize,
-    pub nb_weierstrass_double_events: usize,
+    pub nb_ed_scalar_mul_events: usize,
+    pub nb_secp256k1_add_events: usize,
+    pub nb_secp256k1_double_events: usize,
+    pub nb_bn254_add_events: usize,
+    pub nb_bn254_double_events: usize,
+    pub nb_bn254_fp2_mul_events: usize,
     pub nb_k256_decompress_events: usize,
+    pub nb_bls12381_add_events: usize,
+    pub nb_bls12381_double_events: usize,
+    pub nb_bls12381_decompress_events: usize,
+    pub nb_secp256r1_add_events: usize,
+    pub nb_secp256r1_double_events: usize,
+    pub nb_secp256r1_decompress_events: usize,
 }
 
 impl ExecutionRecord {
@@ -175,133 +248,196 @@ impl ExecutionRecord {
             shard.index = (index + 1) as u32;
             shard.cpu_events = self.cpu_events.split_off(start);
             shard.program = self.program.clone();
+            shard.public_values = self.public_values;
         }
 
         // Shard all the other events according to the configuration.
 
         // Shard the ADD events.
-        for (add_chunk, shard) in take(&mut self.add_events)
-            .chunks_mut(config.add_len)
-            .zip(shards.iter_mut())
-        {
-            shard.add_events.extend_from_slice(add_chunk);
-        }
+        let events = take(&mut self.add_events);
+        self.shard_events(&mut shards, &events, config.add_len, |shard| {
+            &mut shard.add_events
+        });
 
         // Shard the MUL events.
-        for (mul_chunk, shard) in take(&mut self.mul_events)
-            .chunks_mut(config.mul_len)
-            .zip(shards.iter_mut())
-        {
-            shard.mul_events.extend_from_slice(mul_chunk);
-        }
+        let events = take(&mut self.mul_events);
+        self.shard_events(&mut shards, &events, config.mul_len, |shard| {
+            &mut shard.mul_events
+        });
 
         // Shard the SUB events.
-        for (sub_chunk, shard) in take(&mut self.sub_events)
-            .chunks_mut(config.sub_len)
-            .zip(shards.iter_mut())
-        {
-            shard.sub_events.extend_from_slice(sub_chunk);
-        }
+        let events = take(&mut self.sub_events);
+        self.shard_events(&mut shards, &events, config.sub_len, |shard| {
+            &mut shard.sub_events
+        });
 
         // Shard the bitwise events.
-        for (bitwise_chunk, shard) in take(&mut self.bitwise_events)
-            .chunks_mut(config.bitwise_len)
-            .zip(shards.iter_mut())
-        {
-            shard.bitwise_events.extend_from_slice(bitwise_chunk);
-        }
+        let events = take(&mut self.bitwise_events);
+        self.shard_events(&mut shards, &events, config.bitwise_len, |shard| {
+            &mut shard.bitwise_events
+        });
 
         // Shard the shift left events.
-        for (shift_left_chunk, shard) in take(&mut self.shift_left_events)
-            .chunks_mut(config.shift_left_len)
-            .zip(shards.iter_mut())
-        {
-            shard.shift_left_events.extend_from_slice(shift_left_chunk);
-        }
+        let events = take(&mut self.shift_left_events);
+        self.shard_events(&mut shards, &events, config.shift_left_len, |shard| {
+            &mut shard.shift_left_events
+        });
 
         // Shard the shift right events.
-        for (shift_right_chunk, shard) in take(&mut self.shift_right_events)
-            .chunks_mut(config.shift_right_len)
-            .zip(shards.iter_mut())
-        {
-            shard
-                .shift_right_events
-                .extend_from_slice(shift_right_chunk);
-        }
+        let events = take(&mut self.shift_right_events);
+        self.shard_events(&mut shards, &events, config.shift_right_len, |shard| {
+            &mut shard.shift_right_events
+        });
 
         // Shard the divrem events.
-        for (divrem_chunk, shard) in take(&mut self.divrem_events)
-            .chunks_mut(config.divrem_len)
-            .zip(shards.iter_mut())
-        {
-            shard.divrem_events.extend_from_slice(divrem_chunk);
-        }
+        let events = take(&mut self.divrem_events);
+        self.shard_events(&mut shards, &events, config.divrem_len, |shard| {
+            &mut shard.divrem_events
+        });
 
         // Shard the LT events.
-        for (lt_chunk, shard) in take(&mut self.lt_events)
-            .chunks_mut(config.lt_len)
-            .zip(shards.iter_mut())
-        {
-            shard.lt_events.extend_from_slice(lt_chunk);
-        }
+        let events = take(&mut self.lt_events);
+        self.shard_events(&mut shards, &events, config.lt_len, |shard| {
+            &mut shard.lt_events
+        });
 
         // Shard the field events.
-        take(&mut self.field_events)
-            .into_iter()
-            .enumerate()
-            .for_each(|(i, event)| {
-                let shard = &mut shards[i / config.field_len];
-                shard.field_events.push(event);
-            });
+        let events = take(&mut self.field_events);
+        self.shard_events(&mut shards, &events, config.field_len, |shard| {
+            &mut shard.field_events
+        });
 
         // Keccak-256 permute events.
-        for (keccak_chunk, shard) in take(&mut self.keccak_permute_events)
-            .chunks_mut(config.keccak_len)
-            .zip(shards.iter_mut())
-        {
-            shard.keccak_permute_events.extend_from_slice(keccak_chunk);
-        }
+        let events = take(&mut self.keccak_permute_events);
+        self.shard_events(&mut shards, &events, config.keccak_len, |shard| {
+            &mut shard.keccak_permute_events
+        });
 
-        // Weierstrass curve add events.
-        for (weierstrass_add_chunk, shard) in take(&mut self.weierstrass_add_events)
-            .chunks_mut(config.weierstrass_add_len)
-            .zip(shards.iter_mut())
-        {
-            shard
-                .weierstrass_add_events
-                .extend_from_slice(weierstrass_add_chunk);
-        }
+        // Secp256k1 curve add events.
+        let events = take(&mut self.secp256k1_add_events);
+        self.shard_events(&mut shards, &events, config.secp256k1_add_len, |shard| {
+            &mut shard.secp256k1_add_events
+        });
 
-        // Weierstrass curve double events.
-        for (weierstrass_double_chunk, shard) in take(&mut self.weierstrass_double_events)
-            .chunks_mut(config.weierstrass_double_len)
-            .zip(shards.iter_mut())
-        {
-            shard
-                .weierstrass_double_events
-                .extend_from_slice(weierstrass_double_chunk);
-        }
+        // Secp256k1 curve double events.
+        let events = take(&mut self.secp256k1_double_events);
+        self.shard_events(&mut shards, &events, config.secp256k1_double_len, |shard| {
+            &mut shard.secp256k1_double_events
+        });
 
-        // Put the precompile events in the first shard.
-        let first = shards.first_mut().unwrap();
+        // Bn254 curve add events.
+        let events = take(&mut self.bn254_add_events);
+        self.shard_events(&mut shards, &events, config.bn254_add_len, |shard| {
+            &mut shard.bn254_add_events
+        });
+
+        // Bn254 curve double events.
+        let events = take(&mut self.bn254_double_events);
+        self.shard_events(&mut shards, &events, config.bn254_double_len, |shard| {
+            &mut shard.bn254_double_events
+        });
+
+        // Bn254 Fp2 multiplication events.
+        let events = take(&mut self.bn254_fp2_mul_events);
+        self.shard_events(&mut shards, &events, config.bn254_fp2_mul_len, |shard| {
+            &mut shard.bn254_fp2_mul_events
+        });
 
         // SHA-256 extend events.
-        first.sha_extend_events = std::mem::take(&mut self.sha_extend_events);
+        let events = take(&mut self.sha_extend_events);
+        self.shard_events(&mut shards, &events, config.sha_extend_len, |shard| {
+            &mut shard.sha_extend_events
+        });
 
         // SHA-256 compress events.
-        first.sha_compress_events = std::mem::take(&mut self.sha_compress_events);
+        let events = take(&mut self.sha_compress_events);
+        self.shard_events(&mut shards, &events, config.sha_compress_len, |shard| {
+            &mut shard.sha_compress_events
+        });
 
         // Edwards curve add events.
-        first.ed_add_events = std::mem::take(&mut self.ed_add_events);
+        let events = take(&mut self.ed_add_events);
+        self.shard_events(&mut shards, &events, config.ed_add_len, |shard| {
+            &mut shard.ed_add_events
+        });
 
         // Edwards curve decompress events.
-        first.ed_decompress_events = std::mem::take(&mut self.ed_decompress_events);
+        let events = take(&mut self.ed_decompress_events);
+        self.shard_events(&mut shards, &events, config.ed_decompress_len, |shard| {
+            &mut shard.ed_decompress_events
+        });
+
+        // Edwards curve scalar mul events.
+        let events = take(&mut self.ed_scalar_mul_events);
+        self.shard_events(&mut shards, &events, config.ed_scalar_mul_len, |shard| {
+            &mut shard.ed_scalar_mul_events
+        });
 
         // K256 curve decompress events.
-        first.k256_decompress_events = std::mem::take(&mut self.k256_decompress_events);
+        let events = take(&mut self.k256_decompress_events);
+        self.shard_events(&mut shards, &events, config.k256_decompress_len, |shard| {
+            &mut shard.k256_decompress_events
+        });
 
-        // Blake3 compress events .
-        first.blake3_compress_inner_events = std::mem::take(&mut self.blake3_compress_inner_events);
+        // Bls12381 curve add events.
+        let events = take(&mut self.bls12381_add_events);
+        self.shard_events(&mut shards, &events, config.bls12381_add_len, |shard| {
+            &mut shard.bls12381_add_events
+        });
+
+        // Bls12381 curve double events.
+        let events = take(&mut self.bls12381_double_events);
+        self.shard_events(&mut shards, &events, config.bls12381_double_len, |shard| {
+            &mut shard.bls12381_double_events
+        });
+
+        // Bls12381 curve decompress events.
+        let events = take(&mut self.bls12381_decompress_events);
+        self.shard_events(
+            &mut shards,
+            &events,
+            config.bls12381_decompress_len,
+            |shard| &mut shard.bls12381_decompress_events,
+        );
+
+        // Secp256r1 curve add events.
+        let events = take(&mut self.secp256r1_add_events);
+        self.shard_events(&mut shards, &events, config.secp256r1_add_len, |shard| {
+            &mut shard.secp256r1_add_events
+        });
+
+        // Secp256r1 curve double events.
+        let events = take(&mut self.secp256r1_double_events);
+        self.shard_events(&mut shards, &events, config.secp256r1_double_len, |shard| {
+            &mut shard.secp256r1_double_events
+        });
+
+        // Secp256r1 curve decompress events.
+        let events = take(&mut self.secp256r1_decompress_events);
+        self.shard_events(
+            &mut shards,
+            &events,
+            config.secp256r1_decompress_len,
+            |shard| &mut shard.secp256r1_decompress_events,
+        );
+
+        // Blake3 compress events.
+        let events = take(&mut self.blake3_compress_inner_events);
+        self.shard_events(
+            &mut shards,
+            &events,
+            config.blake3_compress_inner_len,
+            |shard| &mut shard.blake3_compress_inner_events,
+        );
+
+        // The last CPU events end with the `HALT`, which the CPU chip checks in the last shard, so
+        // they are moved there when shards without CPU events were added after them.
+        if num_shards > 0 && shards.len() > num_shards {
+            let cpu_events = take(&mut shards[num_shards - 1].cpu_events);
+            shards.last_mut().unwrap().cpu_events = cpu_events;
+        }
+
+        let first = shards.first_mut().unwrap();
 
         // Put all byte lookups in the first shard (as the table size is fixed)
         first.byte_lookups = std::mem::take(&mut self.byte_lookups);
@@ -322,6 +458,25 @@ impl ExecutionRecord {
         shards
     }
 
+    /// Adds the `i`-th chunk of `len` events to the `i`-th shard, adding shards without CPU events
+    /// when there are more chunks than shards so that no event is dropped.
+    fn shard_events<T: Clone>(
+        &self,
+        shards: &mut Vec<ExecutionRecord>,
+        events: &[T],
+        len: usize,
+        shard_events: impl Fn(&mut ExecutionRecord) -> &mut Vec<T>,
+    ) {
+        for (i, chunk) in events.chunks(len).enumerate() {
+            if i == shards.len() {
+                let mut shard = ExecutionRecord::new((i + 1) as u32, self.program.clone());
+                shard.public_values = self.public_values;
+                shards.push(shard);
+            }
+            shard_events(&mut shards[i]).extend_from_slice(chunk);
+        }
+    }
+
     pub fn add_mul_event(&mut self, mul_event: AluEvent) {
         self.mul_events.push(mul_event);
     }
@@ -452,12 +607,63 @@ impl ExecutionRecord {
             nb_keccak_permute_events: self.keccak_permute_events.len(),
             nb_ed_add_events: self.ed_add_events.len(),
             nb_ed_decompress_events: self.ed_decompress_events.len(),
-            nb_weierstrass_add_events: self.weierstrass_add_events.len(),
-            nb_weierstrass_double_events: self.weierstrass_double_events.len(),
+            nb_ed_scalar_mul_events: self.ed_scalar_mul_events.len(),
+            nb_secp256k1_add_events: self.secp256k1_add_events.len(),
+            nb_secp256k1_double_events: self.secp256k1_double_events.len(),
+            nb_bn254_add_events: self.bn254_add_events.len(),
+            nb_bn254_double_events: self.bn254_double_events.len(),
+            nb_bn254_fp2_mul_events: self.bn254_fp2_mul_events.len(),
             nb_k256_decompress_events: self.k256_decompress_events.len(),
+            nb_bls12381_add_events: self.bls12381_add_events.len(),
+            nb_bls12381_double_events: self.bls12381_double_events.len(),
+            nb_bls12381_decompress_events: self.bls12381_decompress_events.len(),
+            nb_secp256r1_add_events: self.secp256r1_add_events.len(),
+            nb_secp256r1_double_events: self.secp256r1_double_events.len(),
+            nb_secp256r1_decompress_events: self.secp256r1_decompress_events.len(),
         }
     }
 
+    /// The number of events emitted so far for each chip, keyed by the name of the chip.
+    pub fn event_counts(&self) -> BTreeMap<&'static str, usize> {
+        BTreeMap::from([
+            ("CPU", self.cpu_events.len()),
+            ("Add", self.add_events.len()),
+            ("Mul", self.mul_events.len()),
+            ("Sub", self.sub_events.len()),
+            ("Bitwise", self.bitwise_events.len()),
+            ("ShiftLeft", self.shift_left_events.len()),
+            ("ShiftRight", self.shift_right_events.len()),
+            ("DivRem", self.divrem_events.len()),
+            ("Lt", self.lt_events.len()),
+            ("FieldLTU", self.field_events.len()),
+            ("ShaExtend", self.sha_extend_events.len()),
+            ("ShaCompress", self.sha_compress_events.len()),
+            ("KeccakPermute", self.keccak_permute_events.len()),
+            ("EdAddAssign", self.ed_add_events.len()),
+            ("EdDecompress", self.ed_decompress_events.len()),
+            ("EdScalarMul", self.ed_scalar_mul_events.len()),
+            ("Secp256k1AddAssign", self.secp256k1_add_events.len()),
+            ("Secp256k1DoubleAssign", self.secp256k1_double_events.len()),
+            ("Bn254AddAssign", self.bn254_add_events.len()),
+            ("Bn254DoubleAssign", self.bn254_double_events.len()),
+            ("Bn254Fp2Mul", self.bn254_fp2_mul_events.len()),
+            ("K256Decompress", self.k256_decompress_events.len()),
+            ("Bls12381AddAssign", self.bls12381_add_events.len()),
+            ("Bls12381DoubleAssign", self.bls12381_double_events.len()),
+            ("Bls12381Decompress", self.bls12381_decompress_events.len()),
+            ("Secp256r1AddAssign", self.secp256r1_add_events.len()),
+            ("Secp256r1DoubleAssign", self.secp256r1_double_events.len()),
+            (
+                "Secp256r1Decompress",
+                self.secp256r1_decompress_events.len(),
+            ),
+            (
+                "Blake3CompressInner",
+                self.blake3_compress_inner_events.len(),
+            ),
+        ])
+    }
+
     /// Append the events from another execution record to this one, leaving the other one empty.
     pub fn append(&mut self, other: &mut ExecutionRecord) {
         assert_eq!(self.index, other.index, "Shard index mismatch");
@@ -481,12 +687,31 @@ impl ExecutionRecord {
         self.ed_add_events.append(&mut other.ed_add_events);
         self.ed_decompress_events
             .append(&mut other.ed_decompress_events);
-        self.weierstrass_add_events
-            .append(&mut other.weierstrass_add_events);
-        self.weierstrass_double_events
-            .append(&mut other.weierstrass_double_events);
+        self.ed_scalar_mul_events
+            .append(&mut other.ed_scalar_mul_events);
+        self.secp256k1_add_events
+            .append(&mut other.secp256k1_add_events);
+        self.secp256k1_double_events
+            .append(&mut other.secp256k1_double_events);
+        self.bn254_add_events.append(&mut other.bn254_add_events);
+        self.bn254_double_events
+            .append(&mut other.bn254_double_events);
+        self.bn254_fp2_mul_events
+            .append(&mut other.bn254_fp2_mul_events);
         self.k256_decompress_events
             .append(&mut other.k256_decompress_events);
+        self.bls12381_add_events
+            .append(&mut other.bls12381_add_events);
+        self.bls12381_double_events
+            .append(&mut other.bls12381_double_events);
+        self.bls12381_decompress_events
+            .append(&mut other.bls12381_decompress_events);
+        self.secp256r1_add_events
+            .append(&mut other.secp256r1_add_events);
+        self.secp256r1_double_events
+            .append(&mut other.secp256r1_double_events);
+        self.secp256r1_decompress_events
+            .append(&mut other.secp256r1_decompress_events);
         self.blake3_compress_inner_events
             .append(&mut other.blake3_compress_inner_events);
 
@@ -513,3 +738,43 @@ pub struct CpuRecord {
     pub c: Option<MemoryRecordEnum>,
     pub memory: Option<MemoryRecordEnum>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::tests::fibonacci_program;
+    use crate::runtime::Runtime;
+
+    #[test]
+    fn test_shard_keeps_all_events() {
+        let mut runtime = Runtime::new(fibonacci_program());
+        runtime.run().unwrap();
+        let record = runtime.record.clone();
+        let nb_cpu_events = record.cpu_events.len();
+        let nb_add_events = record.add_events.len();
+        assert!(nb_add_events > 1);
+
+        // All the CPU events fit in one shard but only one ADD event does, so shards without CPU
+        // events must be added for the other ADD events, and the CPU events move to the last one.
+        let config = ShardingConfig {
+            shard_size: nb_cpu_events,
+            add_len: 1,
+            ..Default::default()
+        };
+        let shards = record.shard(&config);
+        assert_eq!(shards.len(), nb_add_events);
+        assert_eq!(shards.last().unwrap().cpu_events.len(), nb_cpu_events);
+        assert!(shards[0].cpu_events.is_empty());
+        assert!(shards.iter().all(|shard| shard.add_events.len() == 1));
+        for (i, shard) in shards.iter().enumerate() {
+            assert_eq!(shard.index, (i + 1) as u32);
+            assert_eq!(shard.public_values, runtime.record.public_values);
+        }
+
+        // The memory records are still in the last shard.
+        assert_eq!(
+            shards.last().unwrap().last_memory_record.len(),
+            runtime.record.last_memory_record.len()
+        );
+    }
+}
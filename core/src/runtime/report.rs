@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A report of the execution of a program, which can be serialized to track the cost of a
+/// program over time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    /// The total number of cycles of the execution.
+    pub total_cycles: u64,
+
+    /// The outermost spans delimited by `cycle-tracker-start:` and `cycle-tracker-end:`.
+    pub spans: Vec<Span>,
+}
+
+/// A span of the program delimited by `cycle-tracker-start: name` and `cycle-tracker-end: name`.
+///
+/// All invocations of a span with the same name and parent are merged into one span, while
+/// recursive invocations are nested in each other.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// The name of the span.
+    pub name: String,
+
+    /// The number of spans this span is nested in.
+    pub depth: u32,
+
+    /// The global clock when the first invocation of the span started.
+    pub start: u32,
+
+    /// The global clock when the last invocation of the span ended.
+    pub end: u32,
+
+    /// The number of times the span was entered and exited.
+    pub invocations: u32,
+
+    /// The number of cycles spent in all invocations of the span.
+    pub cycles: u64,
+
+    /// The number of events emitted for each chip in all invocations of the span.
+    pub events: BTreeMap<String, usize>,
+
+    /// The spans nested in this span.
+    pub children: Vec<Span>,
+}
+
+/// A span which has been entered but not exited yet.
+#[derive(Debug, Clone)]
+struct OpenSpan {
+    /// The indices of the span and its parents in the span tree, outermost first.
+    path: Vec<usize>,
+
+    /// The global clock when the span was entered.
+    start: u32,
+
+    /// The number of events of each chip when the span was entered.
+    events: BTreeMap<&'static str, usize>,
+}
+
+/// Tracks the spans of a program as it executes, building a tree of spans.
+#[derive(Debug, Clone, Default)]
+pub struct CycleTracker {
+    spans: Vec<Span>,
+    open: Vec<OpenSpan>,
+}
+
+impl CycleTracker {
+    /// The outermost spans.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// The number of spans which are currently open.
+    pub fn depth(&self) -> u32 {
+        self.open.len() as u32
+    }
+
+    /// Enter the span with the given name, given the global clock and the current event counts.
+    pub fn start(&mut self, name: &str, clk: u32, events: BTreeMap<&'static str, usize>) {
+        let depth = self.depth();
+        let mut path = self
+            .open
+            .last()
+            .map(|open| open.path.clone())
+            .unwrap_or_default();
+        let siblings = if path.is_empty() {
+            &mut self.spans
+        } else {
+            &mut span_mut(&mut self.spans, &path).children
+        };
+        let idx = match siblings.iter().position(|span| span.name == name) {
+            Some(idx) => idx,
+            None => {
+                siblings.push(Span {
+                    name: name.to_string(),
+                    depth,
+                    start: clk,
+                    ..Default::default()
+                });
+                siblings.len() - 1
+            }
+        };
+        path.push(idx);
+        self.open.push(OpenSpan {
+            path,
+            start: clk,
+            events,
+        });
+    }
+
+    /// Exit the innermost open span with the given name, also exiting the spans nested in it
+    /// which were not exited. Returns the depth of the span and the cycles spent in it, or `None`
+    /// if no span with this name is open.
+    pub fn end(
+        &mut self,
+        name: &str,
+        clk: u32,
+        events: &BTreeMap<&'static str, usize>,
+    ) -> Option<(u32, u32)> {
+        let idx = self
+            .open
+            .iter()
+            .rposition(|open| span(&self.spans, &open.path).name == name)?;
+        self.close(idx, clk, events)
+    }
+
+    /// Exit all open spans, for example when the program halts.
+    pub fn end_all(&mut self, clk: u32, events: &BTreeMap<&'static str, usize>) {
+        self.close(0, clk, events);
+    }
+
+    /// Close the open spans from the given index, returning the depth and cycles of the
+    /// outermost one.
+    fn close(
+        &mut self,
+        idx: usize,
+        clk: u32,
+        events: &BTreeMap<&'static str, usize>,
+    ) -> Option<(u32, u32)> {
+        let mut closed = None;
+        while self.open.len() > idx {
+            let open = self.open.pop().unwrap();
+            let span = span_mut(&mut self.spans, &open.path);
+            let cycles = clk - open.start;
+            span.end = clk;
+            span.invocations += 1;
+            span.cycles += cycles as u64;
+            for (chip, count) in events.iter() {
+                let before = open.events.get(chip).copied().unwrap_or_default();
+                if *count > before {
+                    *span.events.entry(chip.to_string()).or_default() += count - before;
+                }
+            }
+            closed = Some((span.depth, cycles));
+        }
+        closed
+    }
+}
+
+/// The span at the given path of indices in the span tree.
+fn span<'a>(spans: &'a [Span], path: &[usize]) -> &'a Span {
+    let (first, rest) = path.split_first().expect("empty span path");
+    rest.iter()
+        .fold(&spans[*first], |span, idx| &span.children[*idx])
+}
+
+/// The span at the given path of indices in the span tree, mutably.
+fn span_mut<'a>(spans: &'a mut [Span], path: &[usize]) -> &'a mut Span {
+    let (first, rest) = path.split_first().expect("empty span path");
+    rest.iter()
+        .fold(&mut spans[*first], |span, idx| &mut span.children[*idx])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::CycleTracker;
+    use crate::runtime::{Program, Runtime};
+    use crate::utils::tests::CYCLE_TRACKER_ELF;
+
+    #[test]
+    fn test_cycle_tracker() {
+        let events = |n| BTreeMap::from([("CPU", n)]);
+        let mut tracker = CycleTracker::default();
+        tracker.start("outer", 0, events(0));
+        for i in 0..3 {
+            tracker.start("inner", 10 * i + 1, events(10 * i as usize + 1));
+            assert_eq!(
+                tracker.end("inner", 10 * i + 5, &events(10 * i as usize + 5)),
+                Some((1, 4))
+            );
+        }
+        // Recursive invocations are nested rather than merged.
+        tracker.start("outer", 40, events(40));
+        tracker.start("unterminated", 45, events(45));
+        assert_eq!(tracker.end("outer", 50, &events(50)), Some((1, 10)));
+        assert_eq!(tracker.end("missing", 50, &events(50)), None);
+        tracker.end_all(60, &events(60));
+        assert_eq!(tracker.depth(), 0);
+
+        let outer = &tracker.spans()[0];
+        assert_eq!((outer.invocations, outer.cycles), (1, 60));
+        assert_eq!(outer.events["CPU"], 60);
+        let inner = &outer.children[0];
+        assert_eq!((inner.name.as_str(), inner.depth), ("inner", 1));
+        assert_eq!((inner.start, inner.end), (1, 25));
+        assert_eq!((inner.invocations, inner.cycles), (3, 12));
+        assert_eq!(inner.events["CPU"], 12);
+        let nested = &outer.children[1];
+        assert_eq!((nested.name.as_str(), nested.depth), ("outer", 1));
+        assert_eq!(nested.children[0].name, "unterminated");
+        assert_eq!(nested.children[0].cycles, 5);
+    }
+
+    #[test]
+    fn test_cycle_tracker_program() {
+        let mut runtime = Runtime::new(Program::from(CYCLE_TRACKER_ELF));
+        runtime.run().unwrap();
+        let report = runtime.report();
+        assert_eq!(report.total_cycles, runtime.state.global_clk as u64);
+        let names = report
+            .spans
+            .iter()
+            .map(|span| span.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["f", "g"]);
+        for span in report.spans.iter() {
+            assert_eq!(span.invocations, 1);
+            assert!(span.cycles > 0);
+            assert_eq!(span.events["CPU"] as u64, span.cycles);
+        }
+    }
+}
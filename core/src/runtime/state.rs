@@ -1,10 +1,13 @@
 use hashbrown::HashMap;
 use nohash_hasher::BuildNoHashHasher;
+use serde::{Deserialize, Serialize};
 
 use super::{CpuRecord, ExecutionRecord};
+use crate::utils::env;
+use crate::NUM_COMMITTED_WORDS;
 
 /// Holds data describing the current state of a program's execution.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExecutionState {
     /// The global clock keeps track of how many instrutions have been executed through all shards.
     pub global_clk: u32,
@@ -29,11 +32,44 @@ pub struct ExecutionState {
     /// A ptr to the current position in the input stream incremented by LWA opcode.
     pub input_stream_ptr: usize,
 
+    /// A stream of private input values, which are not part of the public values of the proof.
+    pub private_input_stream: Vec<u8>,
+
+    /// A ptr to the current position in the private input stream incremented by LWA opcode.
+    pub private_input_stream_ptr: usize,
+
+    /// A stream of the answers of the hints queried by the program, which are not part of the
+    /// public values of the proof.
+    pub hint_stream: Vec<u8>,
+
+    /// A ptr to the current position in the hint stream incremented by LWA opcode.
+    pub hint_stream_ptr: usize,
+
     /// A stream of output values from the program (global to entire program).
     pub output_stream: Vec<u8>,
 
     /// A ptr to the current position in the output stream, incremented when reading from output_stream.
     pub output_stream_ptr: usize,
+
+    /// The words of the public values committed by the program with the `COMMIT` syscall.
+    pub committed_words: [u32; NUM_COMMITTED_WORDS],
+
+    /// The exit code the program halted with, or zero if it has not halted.
+    pub exit_code: u32,
+}
+
+/// The outcome of running a program to completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionOutcome {
+    /// The exit code the program halted with, or zero if it ran past its last instruction.
+    pub exit_code: u32,
+}
+
+impl ExecutionOutcome {
+    /// Whether the program exited successfully, i.e. with a zero exit code.
+    pub fn is_success(&self) -> bool {
+        self.exit_code == 0
+    }
 }
 
 impl ExecutionState {
@@ -47,8 +83,14 @@ impl ExecutionState {
             memory: HashMap::default(),
             input_stream: Vec::new(),
             input_stream_ptr: 0,
+            private_input_stream: Vec::new(),
+            private_input_stream_ptr: 0,
+            hint_stream: Vec::new(),
+            hint_stream_ptr: 0,
             output_stream: Vec::new(),
             output_stream_ptr: 0,
+            committed_words: [0; NUM_COMMITTED_WORDS],
+            exit_code: 0,
         }
     }
 }
@@ -74,3 +116,25 @@ pub(crate) struct ForkState {
     /// Full shard from original state
     pub(crate) record: ExecutionRecord,
 }
+
+/// Limits on the resources a program may use while executing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// The maximum number of cycles the program may execute for.
+    pub max_cycles: Option<u64>,
+
+    /// The maximum number of memory words, including registers, the program may use.
+    pub max_memory: Option<usize>,
+}
+
+impl ExecutionLimits {
+    /// Read the limits from the `MAX_CYCLES` and `MAX_MEMORY` environment variables.
+    ///
+    /// Fails if a variable is set to a value that is not a valid number.
+    pub fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            max_cycles: env::max_cycles()?,
+            max_memory: env::max_memory()?,
+        })
+    }
+}
@@ -1,24 +1,36 @@
 use std::collections::HashMap;
+use std::ops::Range;
 use std::rc::Rc;
 
-use crate::runtime::{Register, Runtime};
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::{check_word_address, ExecutionErrorKind, Register, Runtime};
 use crate::syscall::precompiles::blake3::Blake3CompressInnerChip;
+use crate::syscall::precompiles::bls12_381::{
+    Bls12381AddAssignChip, Bls12381DecompressChip, Bls12381DoubleAssignChip,
+};
+use crate::syscall::precompiles::bn254::Bn254Fp2MulChip;
 use crate::syscall::precompiles::edwards::EdAddAssignChip;
 use crate::syscall::precompiles::edwards::EdDecompressChip;
+use crate::syscall::precompiles::edwards::EdScalarMulChip;
 use crate::syscall::precompiles::k256::K256DecompressChip;
 use crate::syscall::precompiles::keccak256::KeccakPermuteChip;
+use crate::syscall::precompiles::secp256r1::Secp256r1DecompressChip;
 use crate::syscall::precompiles::sha256::{ShaCompressChip, ShaExtendChip};
 use crate::syscall::precompiles::weierstrass::WeierstrassAddAssignChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassDoubleAssignChip;
 use crate::syscall::{
-    SyscallEnterUnconstrained, SyscallExitUnconstrained, SyscallHalt, SyscallLWA, SyscallWrite,
+    SyscallCommit, SyscallEnterUnconstrained, SyscallExitUnconstrained, SyscallFileLen,
+    SyscallFileOpen, SyscallFileRead, SyscallHalt, SyscallHint, SyscallLWA, SyscallWrite,
 };
 use crate::utils::ec::edwards::ed25519::{Ed25519, Ed25519Parameters};
+use crate::utils::ec::weierstrass::bn254::Bn254;
 use crate::utils::ec::weierstrass::secp256k1::Secp256k1;
+use crate::utils::ec::weierstrass::secp256r1::Secp256r1;
 use crate::{cpu::MemoryReadRecord, cpu::MemoryWriteRecord, runtime::ExecutionRecord};
 
 /// A system call is invoked by the the `ecall` instruction with a specific value in register t0.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[allow(non_camel_case_types)]
 pub enum SyscallCode {
     /// Halts the program.
@@ -60,13 +72,65 @@ pub enum SyscallCode {
     /// Executes the `BLAKE3_COMPRESS_INNER` precompile.
     BLAKE3_COMPRESS_INNER = 112,
 
+    /// Invokes a hint registered by the host.
+    HINT = 113,
+
+    /// Opens a virtual file supplied by the host.
+    FILE_OPEN = 114,
+
+    /// Returns the length of a virtual file.
+    FILE_LEN = 115,
+
+    /// Reads a word from a virtual file.
+    FILE_READ = 116,
+
+    /// Executes the `BN254_ADD` precompile.
+    BN254_ADD = 117,
+
+    /// Executes the `BN254_DOUBLE` precompile.
+    BN254_DOUBLE = 118,
+
+    /// Executes the `BN254_FP2_MUL` precompile.
+    BN254_FP2_MUL = 119,
+
+    /// Executes the `BLS12381_ADD` precompile.
+    BLS12381_ADD = 120,
+
+    /// Executes the `BLS12381_DOUBLE` precompile.
+    BLS12381_DOUBLE = 121,
+
+    /// Executes the `BLS12381_DECOMPRESS` precompile.
+    BLS12381_DECOMPRESS = 122,
+
+    /// Executes the `SECP256R1_ADD` precompile.
+    SECP256R1_ADD = 123,
+
+    /// Executes the `SECP256R1_DOUBLE` precompile.
+    SECP256R1_DOUBLE = 124,
+
+    /// Executes the `SECP256R1_DECOMPRESS` precompile.
+    SECP256R1_DECOMPRESS = 125,
+
+    /// Executes the `ED_SCALAR_MUL` precompile.
+    ED_SCALAR_MUL = 126,
+
+    /// Commits to a word of the public values.
+    COMMIT = 127,
+
     WRITE = 999,
 }
 
+/// The syscall numbers reserved for the syscalls an application registers with
+/// `Runtime::register_syscall`. Built-in syscalls will never use a number in this range.
+pub const USER_SYSCALLS: Range<u32> = 0x10000..0x20000;
+
+/// The maximum number of bytes a syscall reads from a buffer passed by the program.
+pub const MAX_SYSCALL_BUFFER_LEN: u32 = 1 << 26;
+
 impl SyscallCode {
-    /// Create a syscall from a u32.
-    pub fn from_u32(value: u32) -> Self {
-        match value {
+    /// Create a syscall from a u32, or `None` if the value is not a valid syscall number.
+    pub fn from_u32(value: u32) -> Option<Self> {
+        let code = match value {
             100 => SyscallCode::HALT,
             101 => SyscallCode::LWA,
             102 => SyscallCode::SHA_EXTEND,
@@ -80,15 +144,32 @@ impl SyscallCode {
             110 => SyscallCode::ENTER_UNCONSTRAINED,
             111 => SyscallCode::EXIT_UNCONSTRAINED,
             112 => SyscallCode::BLAKE3_COMPRESS_INNER,
+            113 => SyscallCode::HINT,
+            114 => SyscallCode::FILE_OPEN,
+            115 => SyscallCode::FILE_LEN,
+            116 => SyscallCode::FILE_READ,
+            117 => SyscallCode::BN254_ADD,
+            118 => SyscallCode::BN254_DOUBLE,
+            119 => SyscallCode::BN254_FP2_MUL,
+            120 => SyscallCode::BLS12381_ADD,
+            121 => SyscallCode::BLS12381_DOUBLE,
+            122 => SyscallCode::BLS12381_DECOMPRESS,
+            123 => SyscallCode::SECP256R1_ADD,
+            124 => SyscallCode::SECP256R1_DOUBLE,
+            125 => SyscallCode::SECP256R1_DECOMPRESS,
+            126 => SyscallCode::ED_SCALAR_MUL,
+            127 => SyscallCode::COMMIT,
             999 => SyscallCode::WRITE,
-            _ => panic!("invalid syscall number: {}", value),
-        }
+            _ => return None,
+        };
+        Some(code)
     }
 }
 
 pub trait Syscall {
-    /// Execute the syscall and return the resulting value of register a0.
-    fn execute(&self, ctx: &mut SyscallContext) -> u32;
+    /// Execute the syscall and return the resulting value of register a0, or the reason the
+    /// syscall failed.
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind>;
 
     /// The number of extra cycles that the syscall takes to execute. Unless this syscall is complex
     /// and requires many cycles, this should be zero.
@@ -131,28 +212,42 @@ impl<'a> SyscallContext<'a> {
         (record, record.value)
     }
 
-    pub fn mr_slice(&mut self, addr: u32, len: usize) -> (Vec<MemoryReadRecord>, Vec<u32>) {
+    /// Read `len` words of memory starting at `addr`, failing before reading anything if one of
+    /// the addresses is not valid.
+    pub fn mr_slice(
+        &mut self,
+        addr: u32,
+        len: usize,
+    ) -> Result<(Vec<MemoryReadRecord>, Vec<u32>), ExecutionErrorKind> {
+        let addrs = word_addresses(addr, len)?;
         let mut records = Vec::new();
         let mut values = Vec::new();
-        for i in 0..len {
-            let (record, value) = self.mr(addr + i as u32 * 4);
+        for addr in addrs {
+            let (record, value) = self.mr(addr);
             records.push(record);
             values.push(value);
         }
-        (records, values)
+        Ok((records, values))
     }
 
     pub fn mw(&mut self, addr: u32, value: u32) -> MemoryWriteRecord {
         self.rt.mw(addr, value, self.current_shard, self.clk)
     }
 
-    pub fn mw_slice(&mut self, addr: u32, values: &[u32]) -> Vec<MemoryWriteRecord> {
+    /// Write `values` to memory starting at `addr`, failing before writing anything if one of
+    /// the addresses is not valid.
+    pub fn mw_slice(
+        &mut self,
+        addr: u32,
+        values: &[u32],
+    ) -> Result<Vec<MemoryWriteRecord>, ExecutionErrorKind> {
+        let addrs = word_addresses(addr, values.len())?;
         let mut records = Vec::new();
-        for i in 0..values.len() {
-            let record = self.mw(addr + i as u32 * 4, values[i]);
+        for (addr, value) in addrs.zip(values) {
+            let record = self.mw(addr, *value);
             records.push(record);
         }
-        records
+        Ok(records)
     }
 
     /// Get the current value of a register, but doesn't use a memory record.
@@ -169,6 +264,17 @@ impl<'a> SyscallContext<'a> {
         self.rt.word(addr)
     }
 
+    /// Get the `len` bytes of memory starting at `addr`, but doesn't use memory records.
+    ///
+    /// The buffer is chosen by the program, so it fails instead of allocating when the buffer is
+    /// longer than `MAX_SYSCALL_BUFFER_LEN` or wraps around the end of memory.
+    pub fn bytes_unsafe(&self, addr: u32, len: u32) -> Result<Vec<u8>, ExecutionErrorKind> {
+        if len > MAX_SYSCALL_BUFFER_LEN || addr.checked_add(len).is_none() {
+            return Err(ExecutionErrorKind::InvalidBuffer(addr, len));
+        }
+        Ok((0..len).map(|i| self.rt.byte(addr + i)).collect())
+    }
+
     pub fn slice_unsafe(&self, addr: u32, len: usize) -> Vec<u32> {
         let mut values = Vec::new();
         for i in 0..len {
@@ -182,6 +288,19 @@ impl<'a> SyscallContext<'a> {
     }
 }
 
+/// The addresses of the `len` words starting at `addr`, which must all be valid word addresses.
+fn word_addresses(addr: u32, len: usize) -> Result<impl Iterator<Item = u32>, ExecutionErrorKind> {
+    check_word_address(addr)?;
+    let end = (len as u32)
+        .checked_mul(4)
+        .and_then(|size| addr.checked_add(size))
+        .ok_or(ExecutionErrorKind::InvalidMemoryAccess(addr))?;
+    if len > 0 {
+        check_word_address(end - 4)?;
+    }
+    Ok((0..len as u32).map(move |i| addr + i * 4))
+}
+
 pub fn default_syscall_map() -> HashMap<SyscallCode, Rc<dyn Syscall>> {
     let mut syscall_map = HashMap::<SyscallCode, Rc<dyn Syscall>>::default();
     syscall_map.insert(SyscallCode::HALT, Rc::new(SyscallHalt {}));
@@ -196,6 +315,10 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Rc<dyn Syscall>> {
         SyscallCode::ED_DECOMPRESS,
         Rc::new(EdDecompressChip::<Ed25519Parameters>::new()),
     );
+    syscall_map.insert(
+        SyscallCode::ED_SCALAR_MUL,
+        Rc::new(EdScalarMulChip::<Ed25519>::new()),
+    );
     syscall_map.insert(
         SyscallCode::KECCAK_PERMUTE,
         Rc::new(KeccakPermuteChip::new()),
@@ -217,6 +340,39 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Rc<dyn Syscall>> {
         SyscallCode::BLAKE3_COMPRESS_INNER,
         Rc::new(Blake3CompressInnerChip::new()),
     );
+    syscall_map.insert(
+        SyscallCode::BN254_ADD,
+        Rc::new(WeierstrassAddAssignChip::<Bn254>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::BN254_DOUBLE,
+        Rc::new(WeierstrassDoubleAssignChip::<Bn254>::new()),
+    );
+    syscall_map.insert(SyscallCode::BN254_FP2_MUL, Rc::new(Bn254Fp2MulChip::new()));
+    syscall_map.insert(
+        SyscallCode::BLS12381_ADD,
+        Rc::new(Bls12381AddAssignChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::BLS12381_DOUBLE,
+        Rc::new(Bls12381DoubleAssignChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::BLS12381_DECOMPRESS,
+        Rc::new(Bls12381DecompressChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::SECP256R1_ADD,
+        Rc::new(WeierstrassAddAssignChip::<Secp256r1>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::SECP256R1_DOUBLE,
+        Rc::new(WeierstrassDoubleAssignChip::<Secp256r1>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::SECP256R1_DECOMPRESS,
+        Rc::new(Secp256r1DecompressChip::new()),
+    );
     syscall_map.insert(
         SyscallCode::ENTER_UNCONSTRAINED,
         Rc::new(SyscallEnterUnconstrained::new()),
@@ -226,6 +382,11 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Rc<dyn Syscall>> {
         Rc::new(SyscallExitUnconstrained::new()),
     );
     syscall_map.insert(SyscallCode::WRITE, Rc::new(SyscallWrite::new()));
+    syscall_map.insert(SyscallCode::HINT, Rc::new(SyscallHint::new()));
+    syscall_map.insert(SyscallCode::FILE_OPEN, Rc::new(SyscallFileOpen::new()));
+    syscall_map.insert(SyscallCode::FILE_LEN, Rc::new(SyscallFileLen::new()));
+    syscall_map.insert(SyscallCode::FILE_READ, Rc::new(SyscallFileRead::new()));
+    syscall_map.insert(SyscallCode::COMMIT, Rc::new(SyscallCommit::new()));
 
     syscall_map
 }
@@ -1,8 +1,12 @@
 use crate::air::MachineAir;
 pub use crate::air::SP1AirBuilder;
+use crate::bytes::trace::NUM_ROWS as BYTE_NUM_ROWS;
 use crate::memory::MemoryChipKind;
 use crate::runtime::ExecutionRecord;
+use crate::syscall::precompiles::blake3::{OPERATION_COUNT, ROUND_COUNT};
+use crate::syscall::precompiles::edwards::NUM_SCALAR_BITS;
 use p3_field::PrimeField32;
+use p3_keccak_air::NUM_ROUNDS as KECCAK_NUM_ROUNDS;
 pub use riscv_chips::*;
 
 /// A module for importing all the different RISC-V chips.
@@ -19,19 +23,28 @@ pub(crate) mod riscv_chips {
     pub use crate::cpu::CpuChip;
     pub use crate::field::FieldLtuChip;
     pub use crate::memory::MemoryGlobalChip;
+    pub use crate::memory::MemoryProgramChip;
     pub use crate::program::ProgramChip;
     pub use crate::syscall::precompiles::blake3::Blake3CompressInnerChip;
+    pub use crate::syscall::precompiles::bls12_381::Bls12381AddAssignChip;
+    pub use crate::syscall::precompiles::bls12_381::Bls12381DecompressChip;
+    pub use crate::syscall::precompiles::bls12_381::Bls12381DoubleAssignChip;
+    pub use crate::syscall::precompiles::bn254::Bn254Fp2MulChip;
     pub use crate::syscall::precompiles::edwards::EdAddAssignChip;
     pub use crate::syscall::precompiles::edwards::EdDecompressChip;
+    pub use crate::syscall::precompiles::edwards::EdScalarMulChip;
     pub use crate::syscall::precompiles::k256::K256DecompressChip;
     pub use crate::syscall::precompiles::keccak256::KeccakPermuteChip;
+    pub use crate::syscall::precompiles::secp256r1::Secp256r1DecompressChip;
     pub use crate::syscall::precompiles::sha256::ShaCompressChip;
     pub use crate::syscall::precompiles::sha256::ShaExtendChip;
     pub use crate::syscall::precompiles::weierstrass::WeierstrassAddAssignChip;
     pub use crate::syscall::precompiles::weierstrass::WeierstrassDoubleAssignChip;
     pub use crate::utils::ec::edwards::ed25519::Ed25519Parameters;
     pub use crate::utils::ec::edwards::EdwardsCurve;
+    pub use crate::utils::ec::weierstrass::bn254::Bn254Parameters;
     pub use crate::utils::ec::weierstrass::secp256k1::Secp256k1Parameters;
+    pub use crate::utils::ec::weierstrass::secp256r1::Secp256r1Parameters;
     pub use crate::utils::ec::weierstrass::SwCurve;
 }
 
@@ -42,7 +55,7 @@ pub(crate) mod riscv_chips {
 /// different AIR variants have a joint lookup argument.
 #[derive(MachineAir)]
 pub enum RiscvAir<F: PrimeField32> {
-    /// An AIR that containts a preprocessed program table and a lookup for the instructions.
+    /// An AIR that contains a preprocessed program table and a lookup for the instructions.
     Program(ProgramChip),
     /// An AIR for the RISC-V CPU. Each row represents a cpu cycle.
     Cpu(CpuChip),
@@ -70,8 +83,8 @@ pub enum RiscvAir<F: PrimeField32> {
     MemoryInit(MemoryGlobalChip),
     /// A table for finalizing the memory state.
     MemoryFinal(MemoryGlobalChip),
-    /// A table for initializing the program memory.
-    ProgramMemory(MemoryGlobalChip),
+    /// A table for initializing the program memory, with the memory image preprocessed.
+    ProgramMemory(MemoryProgramChip),
     /// A precompile for sha256 extend.
     Sha256Extend(ShaExtendChip),
     /// A precompile for sha256 compress.
@@ -80,12 +93,32 @@ pub enum RiscvAir<F: PrimeField32> {
     Ed25519Add(EdAddAssignChip<EdwardsCurve<Ed25519Parameters>>),
     /// A precompile for decompressing a point on the Edwards curve ed25519.
     Ed25519Decompress(EdDecompressChip<Ed25519Parameters>),
+    /// A precompile for scalar multiplication on the Elliptic curve ed25519.
+    Ed25519ScalarMul(EdScalarMulChip<EdwardsCurve<Ed25519Parameters>>),
     /// A precompile for decompressing a point on the K256 curve.
     K256Decompress(K256DecompressChip),
     /// A precompile for addition on the Elliptic curve secp256k1.
     Secp256k1Add(WeierstrassAddAssignChip<SwCurve<Secp256k1Parameters>>),
     /// A precompile for doubling a point on the Elliptic curve secp256k1.
     Secp256k1Double(WeierstrassDoubleAssignChip<SwCurve<Secp256k1Parameters>>),
+    /// A precompile for addition on the Elliptic curve bn254.
+    Bn254Add(WeierstrassAddAssignChip<SwCurve<Bn254Parameters>>),
+    /// A precompile for doubling a point on the Elliptic curve bn254.
+    Bn254Double(WeierstrassDoubleAssignChip<SwCurve<Bn254Parameters>>),
+    /// A precompile for multiplication in the quadratic extension of the bn254 base field.
+    Bn254Fp2Mul(Bn254Fp2MulChip),
+    /// A precompile for addition on the Elliptic curve bls12_381.
+    Bls12381Add(Bls12381AddAssignChip),
+    /// A precompile for doubling a point on the Elliptic curve bls12_381.
+    Bls12381Double(Bls12381DoubleAssignChip),
+    /// A precompile for decompressing a point on the Elliptic curve bls12_381.
+    Bls12381Decompress(Bls12381DecompressChip),
+    /// A precompile for addition on the Elliptic curve secp256r1.
+    Secp256r1Add(WeierstrassAddAssignChip<SwCurve<Secp256r1Parameters>>),
+    /// A precompile for doubling a point on the Elliptic curve secp256r1.
+    Secp256r1Double(WeierstrassDoubleAssignChip<SwCurve<Secp256r1Parameters>>),
+    /// A precompile for decompressing a point on the Elliptic curve secp256r1.
+    Secp256r1Decompress(Secp256r1DecompressChip),
     /// A precompile for the Keccak permutation.
     KeccakP(KeccakPermuteChip),
     /// A precompile for the Blake3 compression function.
@@ -110,14 +143,34 @@ impl<F: PrimeField32> RiscvAir<F> {
         chips.push(RiscvAir::Ed25519Add(ed_add_assign));
         let ed_decompress = EdDecompressChip::<Ed25519Parameters>::default();
         chips.push(RiscvAir::Ed25519Decompress(ed_decompress));
+        let ed_scalar_mul = EdScalarMulChip::<EdwardsCurve<Ed25519Parameters>>::new();
+        chips.push(RiscvAir::Ed25519ScalarMul(ed_scalar_mul));
         let k256_decompress = K256DecompressChip::default();
         chips.push(RiscvAir::K256Decompress(k256_decompress));
-        let weierstrass_add_assign =
-            WeierstrassAddAssignChip::<SwCurve<Secp256k1Parameters>>::new();
-        chips.push(RiscvAir::Secp256k1Add(weierstrass_add_assign));
-        let weierstrass_double_assign =
+        let secp256k1_add_assign = WeierstrassAddAssignChip::<SwCurve<Secp256k1Parameters>>::new();
+        chips.push(RiscvAir::Secp256k1Add(secp256k1_add_assign));
+        let secp256k1_double_assign =
             WeierstrassDoubleAssignChip::<SwCurve<Secp256k1Parameters>>::new();
-        chips.push(RiscvAir::Secp256k1Double(weierstrass_double_assign));
+        chips.push(RiscvAir::Secp256k1Double(secp256k1_double_assign));
+        let bn254_add_assign = WeierstrassAddAssignChip::<SwCurve<Bn254Parameters>>::new();
+        chips.push(RiscvAir::Bn254Add(bn254_add_assign));
+        let bn254_double_assign = WeierstrassDoubleAssignChip::<SwCurve<Bn254Parameters>>::new();
+        chips.push(RiscvAir::Bn254Double(bn254_double_assign));
+        let bn254_fp2_mul = Bn254Fp2MulChip::new();
+        chips.push(RiscvAir::Bn254Fp2Mul(bn254_fp2_mul));
+        let bls12381_add_assign = Bls12381AddAssignChip::new();
+        chips.push(RiscvAir::Bls12381Add(bls12381_add_assign));
+        let bls12381_double_assign = Bls12381DoubleAssignChip::new();
+        chips.push(RiscvAir::Bls12381Double(bls12381_double_assign));
+        let bls12381_decompress = Bls12381DecompressChip::default();
+        chips.push(RiscvAir::Bls12381Decompress(bls12381_decompress));
+        let secp256r1_add_assign = WeierstrassAddAssignChip::<SwCurve<Secp256r1Parameters>>::new();
+        chips.push(RiscvAir::Secp256r1Add(secp256r1_add_assign));
+        let secp256r1_double_assign =
+            WeierstrassDoubleAssignChip::<SwCurve<Secp256r1Parameters>>::new();
+        chips.push(RiscvAir::Secp256r1Double(secp256r1_double_assign));
+        let secp256r1_decompress = Secp256r1DecompressChip::default();
+        chips.push(RiscvAir::Secp256r1Decompress(secp256r1_decompress));
         let keccak_permute = KeccakPermuteChip::new();
         chips.push(RiscvAir::KeccakP(keccak_permute));
         let blake3_compress_inner = Blake3CompressInnerChip::new();
@@ -142,7 +195,7 @@ impl<F: PrimeField32> RiscvAir<F> {
         chips.push(RiscvAir::MemoryInit(memory_init));
         let memory_finalize = MemoryGlobalChip::new(MemoryChipKind::Finalize);
         chips.push(RiscvAir::MemoryFinal(memory_finalize));
-        let program_memory_init = MemoryGlobalChip::new(MemoryChipKind::Program);
+        let program_memory_init = MemoryProgramChip::new();
         chips.push(RiscvAir::ProgramMemory(program_memory_init));
         let field_ltu = FieldLtuChip::default();
         chips.push(RiscvAir::FieldLTU(field_ltu));
@@ -169,20 +222,75 @@ impl<F: PrimeField32> RiscvAir<F> {
             RiscvAir::FieldLTU(_) => !shard.field_events.is_empty(),
             RiscvAir::MemoryInit(_) => !shard.first_memory_record.is_empty(),
             RiscvAir::MemoryFinal(_) => !shard.last_memory_record.is_empty(),
-            RiscvAir::ProgramMemory(_) => !shard.program_memory_record.is_empty(),
+            RiscvAir::ProgramMemory(_) => !shard.program.memory_image.is_empty(),
             RiscvAir::Sha256Extend(_) => !shard.sha_extend_events.is_empty(),
             RiscvAir::Sha256Compress(_) => !shard.sha_compress_events.is_empty(),
             RiscvAir::Ed25519Add(_) => !shard.ed_add_events.is_empty(),
             RiscvAir::Ed25519Decompress(_) => !shard.ed_decompress_events.is_empty(),
+            RiscvAir::Ed25519ScalarMul(_) => !shard.ed_scalar_mul_events.is_empty(),
             RiscvAir::K256Decompress(_) => !shard.k256_decompress_events.is_empty(),
-            RiscvAir::Secp256k1Add(_) => !shard.weierstrass_add_events.is_empty(),
-            RiscvAir::Secp256k1Double(_) => !shard.weierstrass_double_events.is_empty(),
+            RiscvAir::Secp256k1Add(_) => !shard.secp256k1_add_events.is_empty(),
+            RiscvAir::Secp256k1Double(_) => !shard.secp256k1_double_events.is_empty(),
+            RiscvAir::Bn254Add(_) => !shard.bn254_add_events.is_empty(),
+            RiscvAir::Bn254Double(_) => !shard.bn254_double_events.is_empty(),
+            RiscvAir::Bn254Fp2Mul(_) => !shard.bn254_fp2_mul_events.is_empty(),
+            RiscvAir::Bls12381Add(_) => !shard.bls12381_add_events.is_empty(),
+            RiscvAir::Bls12381Double(_) => !shard.bls12381_double_events.is_empty(),
+            RiscvAir::Bls12381Decompress(_) => !shard.bls12381_decompress_events.is_empty(),
+            RiscvAir::Secp256r1Add(_) => !shard.secp256r1_add_events.is_empty(),
+            RiscvAir::Secp256r1Double(_) => !shard.secp256r1_double_events.is_empty(),
+            RiscvAir::Secp256r1Decompress(_) => !shard.secp256r1_decompress_events.is_empty(),
             RiscvAir::KeccakP(_) => !shard.keccak_permute_events.is_empty(),
             RiscvAir::Blake3Compress(_) => !shard.blake3_compress_inner_events.is_empty(),
         }
     }
 }
 
+impl<F: PrimeField32> RiscvAir<F> {
+    /// Estimate the number of rows of the trace of this AIR for the given `shard`, before padding
+    /// and without the events added while generating the traces of other AIRs.
+    pub fn estimated_rows(&self, shard: &ExecutionRecord) -> usize {
+        match self {
+            RiscvAir::Program(_) => shard.program.instructions.len(),
+            RiscvAir::Cpu(_) => shard.cpu_events.len(),
+            RiscvAir::Add(_) => shard.add_events.len(),
+            RiscvAir::Sub(_) => shard.sub_events.len(),
+            RiscvAir::Bitwise(_) => shard.bitwise_events.len(),
+            RiscvAir::Mul(_) => shard.mul_events.len(),
+            RiscvAir::DivRem(_) => shard.divrem_events.len(),
+            RiscvAir::Lt(_) => shard.lt_events.len(),
+            RiscvAir::ShiftLeft(_) => shard.shift_left_events.len(),
+            RiscvAir::ShiftRight(_) => shard.shift_right_events.len(),
+            RiscvAir::ByteLookup(_) => BYTE_NUM_ROWS,
+            RiscvAir::FieldLTU(_) => shard.field_events.len(),
+            RiscvAir::MemoryInit(_) => shard.first_memory_record.len(),
+            RiscvAir::MemoryFinal(_) => shard.last_memory_record.len(),
+            RiscvAir::ProgramMemory(_) => shard.program.memory_image.len(),
+            RiscvAir::Sha256Extend(_) => shard.sha_extend_events.len() * 48,
+            RiscvAir::Sha256Compress(_) => shard.sha_compress_events.len() * 80,
+            RiscvAir::Ed25519Add(_) => shard.ed_add_events.len(),
+            RiscvAir::Ed25519Decompress(_) => shard.ed_decompress_events.len(),
+            RiscvAir::Ed25519ScalarMul(_) => shard.ed_scalar_mul_events.len() * NUM_SCALAR_BITS,
+            RiscvAir::K256Decompress(_) => shard.k256_decompress_events.len(),
+            RiscvAir::Secp256k1Add(_) => shard.secp256k1_add_events.len(),
+            RiscvAir::Secp256k1Double(_) => shard.secp256k1_double_events.len(),
+            RiscvAir::Bn254Add(_) => shard.bn254_add_events.len(),
+            RiscvAir::Bn254Double(_) => shard.bn254_double_events.len(),
+            RiscvAir::Bn254Fp2Mul(_) => shard.bn254_fp2_mul_events.len(),
+            RiscvAir::Bls12381Add(_) => shard.bls12381_add_events.len(),
+            RiscvAir::Bls12381Double(_) => shard.bls12381_double_events.len(),
+            RiscvAir::Bls12381Decompress(_) => shard.bls12381_decompress_events.len(),
+            RiscvAir::Secp256r1Add(_) => shard.secp256r1_add_events.len(),
+            RiscvAir::Secp256r1Double(_) => shard.secp256r1_double_events.len(),
+            RiscvAir::Secp256r1Decompress(_) => shard.secp256r1_decompress_events.len(),
+            RiscvAir::KeccakP(_) => shard.keccak_permute_events.len() * KECCAK_NUM_ROUNDS,
+            RiscvAir::Blake3Compress(_) => {
+                shard.blake3_compress_inner_events.len() * ROUND_COUNT * OPERATION_COUNT
+            }
+        }
+    }
+}
+
 impl<F: PrimeField32> PartialEq for RiscvAir<F> {
     fn eq(&self, other: &Self) -> bool {
         self.name() == other.name()
@@ -50,6 +50,11 @@ impl<F: PrimeField32> Chip<F, RiscvAir<F>> {
     pub fn included(&self, shard: &ExecutionRecord) -> bool {
         self.air.included(shard)
     }
+
+    /// Estimate the number of rows of the chip's trace for the given shard, before padding.
+    pub fn estimated_rows(&self, shard: &ExecutionRecord) -> usize {
+        self.air.estimated_rows(shard)
+    }
 }
 
 /// A trait for AIRs that can be used with STARKs.
@@ -82,9 +87,9 @@ where
     /// Records the interactions and constraint degree from the air and crates a new chip.
     pub fn new(air: A) -> Self
     where
-        A: Air<InteractionBuilder<F>>,
+        A: MachineAir<F> + Air<InteractionBuilder<F>>,
     {
-        let mut builder = InteractionBuilder::new(air.width());
+        let mut builder = InteractionBuilder::new(air.preprocessed_width(), air.width());
         air.eval(&mut builder);
         let (sends, receives) = builder.interactions();
 
@@ -106,7 +111,7 @@ where
 
     pub fn generate_permutation_trace<EF: ExtensionField<F>>(
         &self,
-        preprocessed: &Option<RowMajorMatrix<F>>,
+        preprocessed: Option<&RowMajorMatrix<F>>,
         main: &RowMajorMatrix<F>,
         random_elements: &[EF],
     ) -> RowMajorMatrix<EF>
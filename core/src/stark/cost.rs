@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use p3_air::BaseAir;
+use p3_field::AbstractExtensionField;
+use serde::{Deserialize, Serialize};
+
+use super::{RiscvStark, StarkGenericConfig};
+use crate::air::MachineAir;
+use crate::runtime::{ExecutionRecord, Opcode, ShardingConfig, SyscallCode};
+
+/// The estimated size of the traces of a chip over all shards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChipCost {
+    /// The name of the chip.
+    pub name: String,
+
+    /// The number of rows of the traces, before padding.
+    pub rows: usize,
+
+    /// The number of rows of the traces, after padding each shard's trace to a power of two.
+    pub padded_rows: usize,
+
+    /// The number of columns of the preprocessed, main and permutation traces, where each
+    /// extension field element of the permutation trace counts for its degree.
+    pub width: usize,
+}
+
+impl ChipCost {
+    /// The estimated cost of proving the chip, as the number of cells of its padded traces.
+    pub fn cost(&self) -> usize {
+        self.width * self.padded_rows
+    }
+}
+
+/// A report breaking down what an execution will cost to prove, computed from its execution
+/// record before proving it.
+///
+/// The events which chips add to each other while their traces are generated, such as field and
+/// byte lookups, are generated on a copy of the record before counting the rows, so estimating
+/// the cost takes about as long as generating the traces.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostReport {
+    /// The number of cycles of the execution.
+    pub cycles: u64,
+
+    /// The number of shards the execution is split into.
+    pub shards: usize,
+
+    /// The number of cycles spent executing each opcode.
+    pub opcodes: BTreeMap<Opcode, u64>,
+
+    /// The number of times each syscall, including the precompiles, was invoked.
+    pub syscalls: BTreeMap<SyscallCode, u64>,
+
+    /// The estimated trace sizes of the chips with events, most expensive first.
+    pub chips: Vec<ChipCost>,
+}
+
+impl CostReport {
+    /// The estimated cost of proving the execution, as the number of cells of all traces.
+    pub fn total_cost(&self) -> usize {
+        self.chips.iter().map(ChipCost::cost).sum()
+    }
+}
+
+impl Display for CostReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(f, "cycles: {}, shards: {}", self.cycles, self.shards)?;
+
+        let mut opcodes = self.opcodes.iter().collect::<Vec<_>>();
+        opcodes.sort_by(|a, b| b.1.cmp(a.1));
+        writeln!(f, "\n{:<24} {:>12}", "opcode", "cycles")?;
+        for (opcode, cycles) in opcodes {
+            writeln!(f, "{:<24} {:>12}", format!("{:?}", opcode), cycles)?;
+        }
+
+        if !self.syscalls.is_empty() {
+            writeln!(f, "\n{:<24} {:>12}", "syscall", "calls")?;
+            for (syscall, calls) in self.syscalls.iter() {
+                writeln!(f, "{:<24} {:>12}", format!("{:?}", syscall), calls)?;
+            }
+        }
+
+        writeln!(
+            f,
+            "\n{:<24} {:>12} {:>12} {:>8} {:>16}",
+            "chip", "rows", "padded rows", "width", "cost"
+        )?;
+        for chip in self.chips.iter() {
+            writeln!(
+                f,
+                "{:<24} {:>12} {:>12} {:>8} {:>16}",
+                chip.name,
+                chip.rows,
+                chip.padded_rows,
+                chip.width,
+                chip.cost()
+            )?;
+        }
+        write!(f, "{:<24} {:>12}", "total cost", self.total_cost())
+    }
+}
+
+impl<SC: StarkGenericConfig> RiscvStark<SC> {
+    /// Estimate the cost of proving an execution record, sharded with the given config after
+    /// generating the dependencies of its chips.
+    pub fn estimate_cost(&self, record: &ExecutionRecord, config: &ShardingConfig) -> CostReport {
+        let mut opcodes = BTreeMap::new();
+        let mut syscalls = BTreeMap::new();
+        for event in record.cpu_events.iter() {
+            *opcodes.entry(event.instruction.opcode).or_default() += 1;
+            // The syscall number is read from t0 as the second operand of the ecall.
+            if event.instruction.opcode == Opcode::ECALL {
+                if let Some(syscall) = SyscallCode::from_u32(event.b) {
+                    *syscalls.entry(syscall).or_default() += 1;
+                }
+            }
+        }
+
+        let shards = self.shard(record.clone(), config);
+        let mut chips = self
+            .chips()
+            .iter()
+            .filter_map(|chip| {
+                let mut rows = 0;
+                let mut padded_rows = 0;
+                for shard in shards.iter().filter(|shard| chip.included(shard)) {
+                    let shard_rows = chip.estimated_rows(shard);
+                    rows += shard_rows;
+                    padded_rows += padded_height(shard_rows);
+                }
+                let permutation_width = (chip.num_interactions() + 1)
+                    * <SC::Challenge as AbstractExtensionField<SC::Val>>::D;
+                (rows > 0).then(|| ChipCost {
+                    name: chip.name(),
+                    rows,
+                    padded_rows,
+                    width: chip.preprocessed_width() + chip.width() + permutation_width,
+                })
+            })
+            .collect::<Vec<_>>();
+        chips.sort_by(|a, b| b.cost().cmp(&a.cost()).then_with(|| a.name.cmp(&b.name)));
+
+        CostReport {
+            cycles: record.cpu_events.len() as u64,
+            shards: shards.len(),
+            opcodes,
+            syscalls,
+            chips,
+        }
+    }
+}
+
+/// The height a trace with the given number of rows is padded to, following
+/// `pad_to_power_of_two`.
+fn padded_height(rows: usize) -> usize {
+    if rows <= 1 {
+        8
+    } else {
+        rows.next_power_of_two()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::tests::halting;
+    use crate::runtime::{Opcode, Program, Runtime, ShardingConfig, SyscallCode};
+    use crate::stark::RiscvStark;
+    use crate::syscall::precompiles::keccak256::permute_tests::keccak_permute_program;
+    use crate::utils::tests::FIBONACCI_ELF;
+    use crate::utils::BabyBearPoseidon2;
+
+    #[test]
+    fn test_estimate_cost() {
+        let mut runtime = Runtime::new(Program::from(FIBONACCI_ELF));
+        runtime.run().unwrap();
+        let machine = RiscvStark::new(BabyBearPoseidon2::new());
+        let report = machine.estimate_cost(&runtime.record, &ShardingConfig::default());
+
+        assert_eq!(report.cycles, runtime.state.global_clk as u64);
+        assert_eq!(report.opcodes.values().sum::<u64>(), report.cycles);
+        assert!(report.opcodes[&Opcode::ADD] > 0);
+        assert_eq!(report.syscalls[&SyscallCode::HALT], 1);
+        assert!(report.shards >= 1);
+
+        let cpu = report.chips.iter().find(|chip| chip.name == "CPU").unwrap();
+        assert_eq!(cpu.rows as u64, report.cycles);
+        assert!(cpu.padded_rows >= cpu.rows);
+        assert_eq!(
+            report.total_cost(),
+            report.chips.iter().map(|chip| chip.cost()).sum::<usize>()
+        );
+        assert!(report.to_string().contains("CPU"));
+    }
+
+    #[test]
+    fn test_estimate_cost_dependencies() {
+        let mut runtime = Runtime::new(halting(keccak_permute_program()));
+        runtime.run().unwrap();
+        let machine = RiscvStark::new(BabyBearPoseidon2::new());
+        let config = ShardingConfig::default();
+        let report = machine.estimate_cost(&runtime.record, &config);
+
+        // The field lookups of the memory accesses are only added while generating the traces of
+        // the CPU and of the precompiles.
+        assert!(runtime.record.field_events.is_empty());
+        let shards = machine.shard(runtime.record.clone(), &config);
+        let field_events = shards
+            .iter()
+            .map(|shard| shard.field_events.len())
+            .sum::<usize>();
+        let field = report
+            .chips
+            .iter()
+            .find(|chip| chip.name == "FieldLTU")
+            .unwrap();
+        assert!(field.rows > 0);
+        assert_eq!(field.rows, field_events);
+
+        let keccak = report
+            .chips
+            .iter()
+            .find(|chip| chip.name == "KeccakPermute");
+        assert!(keccak.unwrap().rows > 0);
+    }
+}
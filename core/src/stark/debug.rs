@@ -7,9 +7,10 @@ use p3_field::{AbstractField, PrimeField32};
 use p3_field::{ExtensionField, Field};
 use p3_matrix::{dense::RowMajorMatrix, Matrix, MatrixRowSlices};
 
-use crate::air::{EmptyMessageBuilder, MachineAir, MultiTableAirBuilder};
+use crate::air::{EmptyMessageBuilder, MachineAir, MultiTableAirBuilder, PublicInputBuilder, Word};
 
 use super::{RiscvChip, StarkGenericConfig};
+use crate::{PublicValues, NUM_COMMITTED_WORDS};
 
 /// Checks that the constraints of the given AIR are satisfied, including the permutation trace.
 ///
@@ -20,6 +21,8 @@ pub fn debug_constraints<SC: StarkGenericConfig>(
     main: &RowMajorMatrix<SC::Val>,
     perm: &RowMajorMatrix<SC::Challenge>,
     perm_challenges: &[SC::Challenge],
+    is_last_shard: bool,
+    public_values: PublicValues,
 ) where
     SC::Val: PrimeField32,
 {
@@ -68,6 +71,9 @@ pub fn debug_constraints<SC: StarkGenericConfig>(
             is_first_row: SC::Val::zero(),
             is_last_row: SC::Val::zero(),
             is_transition: SC::Val::one(),
+            is_last_shard: SC::Val::from_bool(is_last_shard),
+            exit_code: Word::from(public_values.exit_code),
+            committed_words: public_values.committed_words().map(Word::from),
         };
         if i == 0 {
             builder.is_first_row = SC::Val::one();
@@ -108,6 +114,9 @@ pub struct DebugConstraintBuilder<'a, F: Field, EF: ExtensionField<F>> {
     pub(crate) is_first_row: F,
     pub(crate) is_last_row: F,
     pub(crate) is_transition: F,
+    pub(crate) is_last_shard: F,
+    pub(crate) exit_code: Word<F>,
+    pub(crate) committed_words: [Word<F>; NUM_COMMITTED_WORDS],
 }
 
 impl<'a, F, EF> ExtensionBuilder for DebugConstraintBuilder<'a, F, EF>
@@ -208,3 +217,21 @@ impl<'a, F: Field, EF: ExtensionField<F>> EmptyMessageBuilder
     for DebugConstraintBuilder<'a, F, EF>
 {
 }
+
+impl<'a, F, EF> PublicInputBuilder for DebugConstraintBuilder<'a, F, EF>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    fn is_last_shard(&self) -> Self::Expr {
+        self.is_last_shard
+    }
+
+    fn exit_code(&self) -> Word<Self::Expr> {
+        self.exit_code
+    }
+
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS] {
+        self.committed_words
+    }
+}
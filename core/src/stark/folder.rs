@@ -1,8 +1,10 @@
 use super::{PackedChallenge, PackedVal, StarkGenericConfig};
-use crate::air::{EmptyMessageBuilder, MultiTableAirBuilder};
+use crate::air::{EmptyMessageBuilder, MultiTableAirBuilder, PublicInputBuilder, Word};
 use p3_air::{AirBuilder, ExtensionBuilder, PairBuilder, PermutationAirBuilder, TwoRowMatrixView};
 use p3_field::AbstractField;
 
+use crate::NUM_COMMITTED_WORDS;
+
 /// A folder for prover constraints.
 pub struct ProverConstraintFolder<'a, SC: StarkGenericConfig> {
     pub preprocessed: TwoRowMatrixView<'a, PackedVal<SC>>,
@@ -15,6 +17,9 @@ pub struct ProverConstraintFolder<'a, SC: StarkGenericConfig> {
     pub is_transition: PackedVal<SC>,
     pub alpha: SC::Challenge,
     pub accumulator: PackedChallenge<SC>,
+    pub is_last_shard: SC::Val,
+    pub exit_code: Word<SC::Val>,
+    pub committed_words: [Word<SC::Val>; NUM_COMMITTED_WORDS],
 }
 
 impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
@@ -95,6 +100,21 @@ impl<'a, SC: StarkGenericConfig> PairBuilder for ProverConstraintFolder<'a, SC>
 
 impl<'a, SC: StarkGenericConfig> EmptyMessageBuilder for ProverConstraintFolder<'a, SC> {}
 
+impl<'a, SC: StarkGenericConfig> PublicInputBuilder for ProverConstraintFolder<'a, SC> {
+    fn is_last_shard(&self) -> Self::Expr {
+        PackedVal::<SC>::from_f(self.is_last_shard)
+    }
+
+    fn exit_code(&self) -> Word<Self::Expr> {
+        self.exit_code.map(PackedVal::<SC>::from_f)
+    }
+
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS] {
+        self.committed_words
+            .map(|word| word.map(PackedVal::<SC>::from_f))
+    }
+}
+
 /// A folder for verifier constraints.
 pub struct VerifierConstraintFolder<'a, SC: StarkGenericConfig> {
     pub preprocessed: TwoRowMatrixView<'a, SC::Challenge>,
@@ -107,6 +127,9 @@ pub struct VerifierConstraintFolder<'a, SC: StarkGenericConfig> {
     pub is_transition: SC::Challenge,
     pub alpha: SC::Challenge,
     pub accumulator: SC::Challenge,
+    pub is_last_shard: SC::Challenge,
+    pub exit_code: Word<SC::Challenge>,
+    pub committed_words: [Word<SC::Challenge>; NUM_COMMITTED_WORDS],
 }
 
 impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC> {
@@ -182,3 +205,17 @@ impl<'a, SC: StarkGenericConfig> PairBuilder for VerifierConstraintFolder<'a, SC
 }
 
 impl<'a, SC: StarkGenericConfig> EmptyMessageBuilder for VerifierConstraintFolder<'a, SC> {}
+
+impl<'a, SC: StarkGenericConfig> PublicInputBuilder for VerifierConstraintFolder<'a, SC> {
+    fn is_last_shard(&self) -> Self::Expr {
+        self.is_last_shard
+    }
+
+    fn exit_code(&self) -> Word<Self::Expr> {
+        self.exit_code
+    }
+
+    fn committed_words(&self) -> [Word<Self::Expr>; NUM_COMMITTED_WORDS] {
+        self.committed_words
+    }
+}
@@ -1,14 +1,23 @@
-use std::marker::PhantomData;
+use std::collections::HashMap;
 
 use crate::air::MachineAir;
+use crate::cpu::CpuChip;
+use crate::disassembler::ElfError;
 use crate::runtime::ExecutionRecord;
 use crate::runtime::Program;
 use crate::runtime::ShardingConfig;
 use p3_challenger::CanObserve;
+use p3_commit::Pcs;
 use p3_field::AbstractField;
 use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_util::log2_strict_usize;
+use serde::{Deserialize, Serialize};
 
 use super::Chip;
+use super::Com;
+use super::PcsProverData;
 use super::Proof;
 use super::Prover;
 use super::RiscvAir;
@@ -27,16 +36,50 @@ pub struct RiscvStark<SC: StarkGenericConfig, A = RiscvAir<<SC as StarkGenericCo
     chips: Vec<Chip<SC::Val, A>>,
 }
 
-#[derive(Debug, Clone)]
+/// The proving key of a program, containing the preprocessed traces of the chips and their
+/// commitment.
 pub struct ProvingKey<SC: StarkGenericConfig> {
-    //TODO
-    marker: std::marker::PhantomData<SC>,
+    /// The commitment to the preprocessed traces.
+    pub commit: Com<SC>,
+    /// The start address of the program.
+    pub pc_start: u32,
+    /// The preprocessed traces, in the order of the chips of the machine.
+    pub traces: Vec<RowMajorMatrix<SC::Val>>,
+    /// The prover data of the commitment to the preprocessed traces.
+    pub data: PcsProverData<SC>,
+    /// A map from the name of a chip to the index of its preprocessed trace.
+    pub chip_ordering: HashMap<String, usize>,
 }
 
-#[derive(Debug, Clone)]
+/// The verifying key of a program, which binds a proof to the program it was set up with.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "Com<SC>: Serialize"))]
+#[serde(bound(deserialize = "Com<SC>: Deserialize<'de>"))]
 pub struct VerifyingKey<SC: StarkGenericConfig> {
-    // TODO:
-    marker: std::marker::PhantomData<SC>,
+    /// The commitment to the preprocessed traces.
+    pub commit: Com<SC>,
+    /// The start address of the program.
+    pub pc_start: u32,
+    /// The name, width and log degree of each preprocessed trace, in the order of the commitment.
+    pub chip_information: Vec<(String, usize, usize)>,
+    /// A map from the name of a chip to the index of its preprocessed trace.
+    pub chip_ordering: HashMap<String, usize>,
+}
+
+impl<SC: StarkGenericConfig> ProvingKey<SC> {
+    /// Observes the proving key in the challenger, so that all challenges depend on the program.
+    pub fn observe_into(&self, challenger: &mut SC::Challenger) {
+        challenger.observe(self.commit.clone());
+        challenger.observe(SC::Val::from_canonical_u32(self.pc_start));
+    }
+}
+
+impl<SC: StarkGenericConfig> VerifyingKey<SC> {
+    /// Observes the verifying key in the challenger, so that all challenges depend on the program.
+    pub fn observe_into(&self, challenger: &mut SC::Challenger) {
+        challenger.observe(self.commit.clone());
+        challenger.observe(SC::Val::from_canonical_u32(self.pc_start));
+    }
 }
 
 impl<SC: StarkGenericConfig> RiscvStark<SC> {
@@ -75,13 +118,48 @@ impl<SC: StarkGenericConfig> RiscvStark<SC> {
     ///
     /// Given a program, this function generates the proving and verifying keys. The keys correspond
     /// to the program code and other preprocessed colunms such as lookup tables.
-    pub fn setup(&self, _program: &Program) -> (ProvingKey<SC>, VerifyingKey<SC>) {
+    pub fn setup(&self, program: &Program) -> (ProvingKey<SC>, VerifyingKey<SC>) {
+        // Generate the preprocessed traces of the chips that have one, in the order of the chips.
+        let (chip_names, traces): (Vec<_>, Vec<_>) =
+            tracing::info_span!("generate preprocessed traces").in_scope(|| {
+                self.chips()
+                    .iter()
+                    .filter_map(|chip| {
+                        chip.generate_preprocessed_trace(program)
+                            .map(|trace| (chip.name(), trace))
+                    })
+                    .unzip()
+            });
+
+        // Commit to the preprocessed traces.
+        let (commit, data) = tracing::info_span!("commit to preprocessed traces")
+            .in_scope(|| self.config.pcs().commit_batches(traces.clone()));
+
+        let chip_ordering = chip_names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect::<HashMap<_, _>>();
+
+        let chip_information = chip_names
+            .into_iter()
+            .zip(traces.iter())
+            .map(|(name, trace)| (name, trace.width(), log2_strict_usize(trace.height())))
+            .collect::<Vec<_>>();
+
         (
             ProvingKey {
-                marker: PhantomData,
+                commit: commit.clone(),
+                pc_start: program.pc_start,
+                traces,
+                data,
+                chip_ordering: chip_ordering.clone(),
             },
             VerifyingKey {
-                marker: PhantomData,
+                commit,
+                pc_start: program.pc_start,
+                chip_information,
+                chip_ordering,
             },
         )
     }
@@ -129,6 +207,9 @@ impl<SC: StarkGenericConfig> RiscvStark<SC> {
         record: ExecutionRecord,
         challenger: &mut SC::Challenger,
     ) -> Proof<SC> {
+        // Observe the preprocessed commitment and the start pc, binding the proof to the program.
+        pk.observe_into(challenger);
+
         tracing::info!("Sharding the execution record.");
         let shards = self.shard(record, &ShardingConfig::default());
 
@@ -142,13 +223,16 @@ impl<SC: StarkGenericConfig> RiscvStark<SC> {
 
     pub fn verify(
         &self,
-        _vk: &VerifyingKey<SC>,
+        vk: &VerifyingKey<SC>,
         proof: &Proof<SC>,
         challenger: &mut SC::Challenger,
     ) -> Result<(), ProgramVerificationError>
     where
         SC::Challenger: Clone,
     {
+        // Observe the preprocessed commitment and the start pc, binding the proof to the program.
+        vk.observe_into(challenger);
+
         // TODO: Observe the challenges in a tree-like structure for easily verifiable reconstruction
         // in a map-reduce recursion setting.
         #[cfg(feature = "perf")]
@@ -159,15 +243,43 @@ impl<SC: StarkGenericConfig> RiscvStark<SC> {
         });
 
         // Verify the segment proofs.
+        let public_values = proof.public_values();
+        let num_shards = proof.shard_proofs.len();
+        let cpu_chip = MachineAir::<SC::Val>::name(&CpuChip);
         for (i, proof) in proof.shard_proofs.iter().enumerate() {
+            // Every shard must observe the same public values.
+            if Some(proof.public_values) != public_values {
+                return Err(ProgramVerificationError::InconsistentPublicValues);
+            }
+            // The last shard must include the CPU chip, which checks that the execution halted.
+            let is_last_shard = i == num_shards - 1;
+            if is_last_shard && !proof.chip_ids.contains(&cpu_chip) {
+                return Err(ProgramVerificationError::MissingCpuChip);
+            }
+            // Every chip with a preprocessed trace must be part of every shard, since each shard
+            // opens the whole preprocessed commitment.
+            for (name, _, _) in vk.chip_information.iter() {
+                if !proof.chip_ids.contains(name) {
+                    return Err(ProgramVerificationError::MissingPreprocessedChip(
+                        name.clone(),
+                    ));
+                }
+            }
             tracing::info_span!("verifying segment", segment = i).in_scope(|| {
                 let chips = self
                     .chips()
                     .iter()
                     .filter(|chip| proof.chip_ids.contains(&chip.name()))
                     .collect::<Vec<_>>();
-                Verifier::verify_shard(&self.config, &chips, &mut challenger.clone(), proof)
-                    .map_err(ProgramVerificationError::InvalidSegmentProof)
+                Verifier::verify_shard(
+                    &self.config,
+                    vk,
+                    &chips,
+                    &mut challenger.clone(),
+                    proof,
+                    is_last_shard,
+                )
+                .map_err(ProgramVerificationError::InvalidSegmentProof)
             })?;
         }
 
@@ -193,33 +305,54 @@ pub enum ProgramVerificationError {
     InvalidGlobalProof(VerificationError),
     NonZeroCumulativeSum,
     DebugInteractionsFailed,
+    MissingPreprocessedChip(String),
+    MissingCpuChip,
+    InconsistentPublicValues,
+    IoDigestMismatch,
+    NonZeroExitCode(u32),
+    InvalidElf(ElfError),
 }
 
 #[cfg(test)]
 #[allow(non_snake_case)]
 pub mod tests {
 
+    use crate::runtime::tests::compressed_program;
     use crate::runtime::tests::ecall_lwa_program;
     use crate::runtime::tests::fibonacci_program;
+    use crate::runtime::tests::halting;
     use crate::runtime::tests::simple_memory_program;
     use crate::runtime::tests::simple_program;
+    use crate::runtime::ExecutionRecord;
     use crate::runtime::Instruction;
     use crate::runtime::Opcode;
     use crate::runtime::Program;
+    use crate::runtime::Runtime;
+    use crate::stark::LocalProver;
+    use crate::stark::RiscvStark;
     use crate::utils;
     use crate::utils::run_test;
     use crate::utils::setup_logger;
+    use crate::utils::tests::FIBONACCI_ELF;
+    use crate::utils::BabyBearBlake3;
+    use crate::utils::StarkUtils;
+
+    #[test]
+    fn test_compressed_prove() {
+        let program = compressed_program();
+        run_test(halting(program)).unwrap();
+    }
 
     #[test]
     fn test_simple_prove() {
         let program = simple_program();
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
     }
 
     #[test]
     fn test_ecall_lwa_prove() {
         let program = ecall_lwa_program();
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
     }
 
     #[test]
@@ -240,7 +373,7 @@ pub mod tests {
                     Instruction::new(*shift_op, 31, 29, 3, false, false),
                 ];
                 let program = Program::new(instructions, 0, 0);
-                run_test(program).unwrap();
+                run_test(halting(program)).unwrap();
             }
         }
     }
@@ -253,7 +386,7 @@ pub mod tests {
             Instruction::new(Opcode::SUB, 31, 30, 29, false, false),
         ];
         let program = Program::new(instructions, 0, 0);
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
     }
 
     #[test]
@@ -265,7 +398,7 @@ pub mod tests {
             Instruction::new(Opcode::ADD, 31, 30, 29, false, false),
         ];
         let program = Program::new(instructions, 0, 0);
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
     }
 
     #[test]
@@ -287,7 +420,7 @@ pub mod tests {
                     Instruction::new(*mul_op, 31, 30, 29, false, false),
                 ];
                 let program = Program::new(instructions, 0, 0);
-                run_test(program).unwrap();
+                run_test(halting(program)).unwrap();
             }
         }
     }
@@ -302,7 +435,7 @@ pub mod tests {
                 Instruction::new(*lt_op, 31, 30, 29, false, false),
             ];
             let program = Program::new(instructions, 0, 0);
-            run_test(program).unwrap();
+            run_test(halting(program)).unwrap();
         }
     }
 
@@ -317,7 +450,7 @@ pub mod tests {
                 Instruction::new(*bitwise_op, 31, 30, 29, false, false),
             ];
             let program = Program::new(instructions, 0, 0);
-            run_test(program).unwrap();
+            run_test(halting(program)).unwrap();
         }
     }
 
@@ -339,7 +472,7 @@ pub mod tests {
                     Instruction::new(*div_rem_op, 31, 29, 30, false, false),
                 ];
                 let program = Program::new(instructions, 0, 0);
-                run_test(program).unwrap();
+                run_test(halting(program)).unwrap();
             }
         }
     }
@@ -354,6 +487,161 @@ pub mod tests {
     #[test]
     fn test_simple_memory_program_prove() {
         let program = simple_memory_program();
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
+    }
+
+    fn halt_program(exit_code: u32) -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 100, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, exit_code, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ];
+        Program::new(instructions, 4, 4)
+    }
+
+    /// A program which commits to 42 as the word at index 8 of the public values, the length of
+    /// the input, and halts.
+    fn commit_program() -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, 127, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 42, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 8, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 11, false, false),
+        ];
+        halting(Program::new(instructions, 0, 0))
+    }
+
+    #[test]
+    fn test_commit_prove() {
+        run_test(commit_program()).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(feature = "perf"))]
+    #[should_panic]
+    fn test_commit_wrong_public_values_fails() {
+        let mut runtime = Runtime::new(commit_program());
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.public_values.input_len, 42);
+
+        // Claiming another committed word must violate the CPU constraints.
+        runtime.record.public_values.input_len = 43;
+        let machine = RiscvStark::new(BabyBearBlake3::new());
+        let (pk, _) = machine.setup(runtime.program.as_ref());
+        let mut challenger = machine.config().challenger();
+        machine.prove::<LocalProver<_>>(&pk, runtime.record, &mut challenger);
+    }
+
+    #[test]
+    fn test_halt_exit_code_prove() {
+        run_test(halt_program(7)).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(feature = "perf"))]
+    #[should_panic]
+    fn test_halt_wrong_exit_code_fails() {
+        let mut runtime = Runtime::new(halt_program(7));
+        runtime.run().unwrap();
+
+        // Claiming that the execution exited successfully must violate the CPU constraints.
+        runtime.record.public_values.exit_code = 0;
+        let machine = RiscvStark::new(BabyBearBlake3::new());
+        let (pk, _) = machine.setup(runtime.program.as_ref());
+        let mut challenger = machine.config().challenger();
+        machine.prove::<LocalProver<_>>(&pk, runtime.record, &mut challenger);
+    }
+
+    /// The record of the execution of a program which stops right before its final `HALT`, as a
+    /// prover leaving out the end of the execution would produce.
+    fn truncated_record(program: Program) -> ExecutionRecord {
+        let mut runtime = Runtime::new(program);
+        runtime.initialize();
+        for _ in 0..runtime.program.instructions.len() - 1 {
+            runtime.step().unwrap();
+        }
+        assert!(!runtime.is_done());
+        runtime.finalize().unwrap();
+        runtime.record
+    }
+
+    #[test]
+    #[cfg(not(feature = "perf"))]
+    #[should_panic]
+    fn test_truncated_execution_fails() {
+        let program = halting(simple_program());
+        let record = truncated_record(program.clone());
+
+        // The last shard must end with a `HALT`.
+        let machine = RiscvStark::new(BabyBearBlake3::new());
+        let (pk, _) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        machine.prove::<LocalProver<_>>(&pk, record, &mut challenger);
+    }
+
+    #[test]
+    #[cfg(feature = "perf")]
+    fn test_truncated_execution_does_not_verify() {
+        let program = halting(simple_program());
+        let record = truncated_record(program.clone());
+
+        let machine = RiscvStark::new(BabyBearBlake3::new());
+        let (pk, vk) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        let proof = machine.prove::<LocalProver<_>>(&pk, record, &mut challenger);
+
+        // The last shard must end with a `HALT`.
+        let mut challenger = machine.config().challenger();
+        assert!(machine.verify(&vk, &proof, &mut challenger).is_err());
+    }
+
+    #[test]
+    fn test_prove_streaming() {
+        let program = Program::from(FIBONACCI_ELF);
+        let new_runtime = || {
+            let mut runtime = Runtime::new(program.clone());
+            runtime.shard_size = 256;
+            runtime
+        };
+        let (proof, runtime) =
+            utils::prove_core_streaming(BabyBearBlake3::new(), new_runtime, false).unwrap();
+        assert!(runtime.state.current_shard > 1);
+        assert!(runtime.record.cpu_events.is_empty());
+
+        let mut expected = new_runtime();
+        expected.run().unwrap();
+        assert_eq!(proof.public_values(), Some(expected.record.public_values));
+
+        let machine = RiscvStark::new(BabyBearBlake3::new());
+        let (_, vk) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "perf")]
+    fn test_proof_bound_to_program() {
+        let program = halting(simple_program());
+        let mut runtime = Runtime::new(program.clone());
+        runtime.run().unwrap();
+
+        let machine = RiscvStark::new(BabyBearBlake3::new());
+        let (pk, vk) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        let proof = machine.prove::<LocalProver<_>>(&pk, runtime.record, &mut challenger);
+
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+
+        // A proof of the execution of `simple_program` must not verify against another program.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+            Instruction::new(Opcode::SUB, 31, 30, 29, false, false),
+        ];
+        let other_program = Program::new(instructions, 0, 0);
+        let (_, other_vk) = machine.setup(&other_program);
+        let mut challenger = machine.config().challenger();
+        assert!(machine.verify(&other_vk, &proof, &mut challenger).is_err());
     }
 }
@@ -1,6 +1,7 @@
 mod air;
 mod chip;
 mod config;
+mod cost;
 mod debug;
 mod folder;
 mod machine;
@@ -15,6 +16,7 @@ mod zerofier_coset;
 pub use air::*;
 pub use chip::*;
 pub use config::*;
+pub use cost::*;
 pub use debug::*;
 pub use folder::*;
 pub use machine::*;
@@ -31,7 +31,7 @@ pub fn generate_interaction_rlc_elements<F: Field, EF: AbstractExtensionField<F>
 pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
     sends: &[Interaction<F>],
     receives: &[Interaction<F>],
-    preprocessed: &Option<RowMajorMatrix<F>>,
+    preprocessed: Option<&RowMajorMatrix<F>>,
     main: &RowMajorMatrix<F>,
     random_elements: &[EF],
 ) -> RowMajorMatrix<EF> {
@@ -41,9 +41,6 @@ pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
     // Generate the RLC elements to uniquely identify each item in the looked up tuple.
     let betas = random_elements[1].powers();
 
-    // TODO: Get the preprocessed trace and handle it properly.
-    // let preprocessed = chip.preprocessed_trace();
-
     // Iterate over the rows of the main trace to compute the permutation trace values. In
     // particular, for each row i, interaction j, and columns c_0, ..., c_{k-1} we compute the sum:
     //
@@ -57,7 +54,28 @@ pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
         // Compute the permutation trace values in parallel.
 
         let mut parallel = match preprocessed {
-            Some(_) => unimplemented!(),
+            Some(prep) => {
+                assert_eq!(prep.height(), main.height());
+                main.par_row_chunks(chunk_rate)
+                    .zip(prep.par_row_chunks(chunk_rate))
+                    .flat_map(|(main_rows_chunk, prep_rows_chunk)| {
+                        main_rows_chunk
+                            .rows()
+                            .zip(prep_rows_chunk.rows())
+                            .flat_map(|(main_row, prep_row)| {
+                                compute_permutation_row(
+                                    main_row,
+                                    prep_row,
+                                    sends,
+                                    receives,
+                                    &alphas,
+                                    betas.clone(),
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            }
             None => main
                 .par_row_chunks(chunk_rate)
                 .flat_map(|main_rows_chunk| {
@@ -81,9 +99,10 @@ pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
         // Compute the permutation trace values for the remainder.
         let remainder = main.height() % chunk_rate;
         for i in 0..remainder {
+            let row = main.height() - remainder + i;
             let perm_row = compute_permutation_row(
-                main.row_slice(main.height() - remainder + i),
-                &[],
+                main.row_slice(row),
+                preprocessed_row(preprocessed, row),
                 sends,
                 receives,
                 &alphas,
@@ -113,14 +132,15 @@ pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
         if i > 0 {
             phi[i] = phi[i - 1];
         }
+        let prep_row = preprocessed_row(preprocessed, i);
         // All all sends
         for (j, send) in sends.iter().enumerate() {
-            let mult = send.multiplicity.apply::<F, F>(&[], main_row);
+            let mult = send.multiplicity.apply::<F, F>(prep_row, main_row);
             phi[i] += EF::from_base(mult) * permutation_row[j];
         }
         // Subtract all receives
         for (j, rec) in receives.iter().enumerate() {
-            let mult = rec.multiplicity.apply::<F, F>(&[], main_row);
+            let mult = rec.multiplicity.apply::<F, F>(prep_row, main_row);
             phi[i] -= EF::from_base(mult) * permutation_row[nb_sends + j];
         }
         *permutation_row.last_mut().unwrap() = phi[i];
@@ -129,6 +149,14 @@ pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
     permutation_trace
 }
 
+/// Returns the given row of the preprocessed trace, or an empty slice if there is none.
+fn preprocessed_row<F: Field>(preprocessed: Option<&RowMajorMatrix<F>>, row: usize) -> &[F] {
+    match preprocessed {
+        Some(prep) => prep.row_slice(row),
+        None => &[],
+    }
+}
+
 /// Evaluates the permutation constraints for the given chip.
 ///
 /// In particular, the constraints checked here are:
@@ -73,7 +73,8 @@ where
 
         // Generate a proof for each segment. Note that we clone the challenger so we can observe
         // identical global challenges across the segments.
-        let chunk_size = std::cmp::max(shards.len() / num_cpus::get(), 1);
+        let num_shards = shards.len();
+        let chunk_size = std::cmp::max(num_shards / num_cpus::get(), 1);
         let config = machine.config();
         let reconstruct_commitments = env::reconstruct_commitments();
         let shard_data_chunks = chunk_vec(shard_data, chunk_size);
@@ -96,7 +97,15 @@ where
                                 .expect("failed to materialize shard main data")
                         };
                         let chips = machine.shard_chips(&shard).collect::<Vec<_>>();
-                        Self::prove_shard(config, pk, &chips, data, &mut challenger.clone())
+                        let is_last_shard = idx == num_shards - 1;
+                        Self::prove_shard(
+                            config,
+                            pk,
+                            &chips,
+                            data,
+                            is_last_shard,
+                            &mut challenger.clone(),
+                        )
                     })
                     .collect::<Vec<_>>()
             })
@@ -151,15 +160,20 @@ where
             main_data,
             chip_ids,
             index,
+            public_values: shard.public_values,
         }
     }
 
     /// Prove the program for the given shard and given a commitment to the main data.
+    ///
+    /// `is_last_shard` must be set for the last shard of the execution, which holds the global
+    /// memory tables.
     pub fn prove_shard(
         config: &SC,
-        _pk: &ProvingKey<SC>,
+        pk: &ProvingKey<SC>,
         chips: &[&RiscvChip<SC>],
         shard_data: ShardMainData<SC>,
+        is_last_shard: bool,
         challenger: &mut SC::Challenger,
     ) -> ShardProof<SC>
     where
@@ -170,6 +184,16 @@ where
         // Get the traces.
         let traces = &shard_data.traces;
 
+        // Get the preprocessed traces of the chips, if they have one.
+        let preprocessed_traces = chips
+            .iter()
+            .map(|chip| {
+                pk.chip_ordering
+                    .get(&chip.name())
+                    .map(|&index| &pk.traces[index])
+            })
+            .collect::<Vec<_>>();
+
         let log_degrees = traces
             .iter()
             .map(|trace| log2_strict_usize(trace.height()))
@@ -182,6 +206,9 @@ where
             .map(|log_deg| SC::Val::two_adic_generator(*log_deg))
             .collect::<Vec<_>>();
 
+        // Observe the public values, so that the proof is bound to the input and output.
+        challenger.observe_slice(&shard_data.public_values.to_field_elements::<SC::Val>());
+
         // Obtain the challenges used for the permutation argument.
         let mut permutation_challenges: Vec<SC::Challenge> = Vec::new();
         for _ in 0..2 {
@@ -195,9 +222,13 @@ where
             chips
                 .par_iter()
                 .zip(traces.par_iter())
-                .map(|(chip, main_trace)| {
-                    let perm_trace =
-                        chip.generate_permutation_trace(&None, main_trace, &permutation_challenges);
+                .zip(preprocessed_traces.par_iter())
+                .map(|((chip, main_trace), preprocessed_trace)| {
+                    let perm_trace = chip.generate_permutation_trace(
+                        *preprocessed_trace,
+                        main_trace,
+                        &permutation_challenges,
+                    );
                     let cumulative_sum = perm_trace
                         .row_slice(main_trace.height() - 1)
                         .last()
@@ -239,6 +270,14 @@ where
 
         // For each chip, compute the quotient polynomial.
         let log_stride_for_quotient = config.pcs().log_blowup() - log_quotient_degree;
+        let preprocessed_ldes = tracing::info_span!("get preprocessed ldes").in_scope(|| {
+            config
+                .pcs()
+                .get_ldes(&pk.data)
+                .into_iter()
+                .map(|lde| lde.vertically_strided(1 << log_stride_for_quotient, 0))
+                .collect::<Vec<_>>()
+        });
         let main_ldes = tracing::info_span!("get main ldes").in_scope(|| {
             config
                 .pcs()
@@ -262,15 +301,22 @@ where
             (0..chips.len())
                 .into_par_iter()
                 .map(|i| {
+                    let preprocessed_lde = pk
+                        .chip_ordering
+                        .get(&chips[i].name())
+                        .map(|&index| &preprocessed_ldes[index]);
                     quotient_values(
                         config,
                         chips[i],
                         cumulative_sums[i],
                         log_degrees[i],
+                        preprocessed_lde,
                         &main_ldes[i],
                         &permutation_ldes[i],
                         &permutation_challenges,
                         alpha,
+                        is_last_shard,
+                        shard_data.public_values,
                     )
                 })
                 .collect::<Vec<_>>()
@@ -326,6 +372,16 @@ where
                     .collect::<Vec<_>>()
             });
 
+        // The preprocessed traces are opened at the same points as the main trace of their chip.
+        let preprocessed_opening_points = pk
+            .traces
+            .iter()
+            .map(|trace| {
+                let g = SC::Val::two_adic_generator(log2_strict_usize(trace.height()));
+                vec![zeta, zeta * g]
+            })
+            .collect::<Vec<_>>();
+
         let zeta_quot_pow = zeta.exp_power_of_2(log_quotient_degree);
         let quotient_opening_points = (0..num_quotient_chunks)
             .map(|_| vec![zeta_quot_pow])
@@ -334,6 +390,7 @@ where
         let (openings, opening_proof) = tracing::info_span!("open multi batches").in_scope(|| {
             config.pcs().open_multi_batches(
                 &[
+                    (&pk.data, &preprocessed_opening_points),
                     (&shard_data.main_data, &trace_opening_points),
                     (&permutation_data, &trace_opening_points),
                     (&quotient_data, &quotient_opening_points),
@@ -345,7 +402,15 @@ where
         #[cfg(feature = "perf")]
         {
             // Collect the opened values for each chip.
-            let [main_values, permutation_values, quotient_values] = openings.try_into().unwrap();
+            let [preprocessed_values, main_values, permutation_values, quotient_values] =
+                openings.try_into().unwrap();
+            let preprocessed_opened_values = preprocessed_values
+                .into_iter()
+                .map(|op| {
+                    let [local, next] = op.try_into().unwrap();
+                    AirOpenedValues { local, next }
+                })
+                .collect::<Vec<_>>();
             let main_opened_values = main_values
                 .into_iter()
                 .map(|op| {
@@ -366,6 +431,7 @@ where
                 .collect::<Vec<_>>();
 
             let opened_values = izip!(
+                chips.iter(),
                 main_opened_values,
                 permutation_opened_values,
                 quotient_opened_values,
@@ -373,16 +439,22 @@ where
                 log_degrees
             )
             .map(
-                |(main, permutation, quotient, cumulative_sum, log_degree)| ChipOpenedValues {
-                    preprocessed: AirOpenedValues {
-                        local: vec![],
-                        next: vec![],
-                    },
-                    main,
-                    permutation,
-                    quotient,
-                    cumulative_sum,
-                    log_degree,
+                |(chip, main, permutation, quotient, cumulative_sum, log_degree)| {
+                    ChipOpenedValues {
+                        preprocessed: pk
+                            .chip_ordering
+                            .get(&chip.name())
+                            .map(|&index| preprocessed_opened_values[index].clone())
+                            .unwrap_or(AirOpenedValues {
+                                local: vec![],
+                                next: vec![],
+                            }),
+                        main,
+                        permutation,
+                        quotient,
+                        cumulative_sum,
+                        log_degree,
+                    }
                 },
             )
             .collect::<Vec<_>>();
@@ -399,6 +471,7 @@ where
                 },
                 opening_proof,
                 chip_ids: chips.iter().map(|chip| chip.name()).collect::<Vec<_>>(),
+                public_values: shard_data.public_values,
             }
         }
 
@@ -407,11 +480,13 @@ where
         tracing::info_span!("debug constraints").in_scope(|| {
             for i in 0..chips.len() {
                 debug_constraints::<SC>(
-                    &chips[i],
-                    None,
+                    chips[i],
+                    preprocessed_traces[i],
                     &traces[i],
                     &permutation_traces[i],
                     &permutation_challenges,
+                    is_last_shard,
+                    shard_data.public_values,
                 );
             }
         });
@@ -422,6 +497,7 @@ where
             traces: traces.to_vec(),
             permutation_traces,
             chip_ids: chips.iter().map(|chip| chip.name()).collect::<Vec<_>>(),
+            public_values: shard_data.public_values,
         };
     }
 
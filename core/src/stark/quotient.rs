@@ -14,22 +14,28 @@ use p3_matrix::MatrixGet;
 use p3_maybe_rayon::prelude::*;
 
 use super::{zerofier_coset::ZerofierOnCoset, StarkGenericConfig};
+use crate::air::Word;
+use crate::PublicValues;
 
 #[allow(clippy::too_many_arguments)]
-pub fn quotient_values<SC, A, MainLde, PermLde>(
+pub fn quotient_values<SC, A, PreprocessedLde, MainLde, PermLde>(
     config: &SC,
     chip: &Chip<SC::Val, A>,
     cumulative_sum: SC::Challenge,
     degree_bits: usize,
+    preprocessed_lde: Option<&PreprocessedLde>,
     main_lde: &MainLde,
     permutation_lde: &PermLde,
     perm_challenges: &[SC::Challenge],
     alpha: SC::Challenge,
+    is_last_shard: bool,
+    public_values: PublicValues,
 ) -> Vec<SC::Challenge>
 where
     A: StarkAir<SC>,
     SC: StarkGenericConfig,
     SC::Val: TwoAdicField,
+    PreprocessedLde: MatrixGet<SC::Val> + Sync,
     MainLde: MatrixGet<SC::Val> + Sync,
     PermLde: MatrixGet<SC::Val> + Sync,
 {
@@ -67,6 +73,24 @@ where
             let is_first_row = *PackedVal::<SC>::from_slice(&lagrange_first_evals[i_range.clone()]);
             let is_last_row = *PackedVal::<SC>::from_slice(&lagrange_last_evals[i_range]);
 
+            let preprocessed_width = preprocessed_lde.map_or(0, |lde| lde.width());
+            let preprocessed_local: Vec<_> = (0..preprocessed_width)
+                .map(|col| {
+                    PackedVal::<SC>::from_fn(|offset| {
+                        let row = wrap(i_local_start + offset);
+                        preprocessed_lde.unwrap().get(row, col)
+                    })
+                })
+                .collect();
+            let preprocessed_next: Vec<_> = (0..preprocessed_width)
+                .map(|col| {
+                    PackedVal::<SC>::from_fn(|offset| {
+                        let row = wrap(i_next_start + offset);
+                        preprocessed_lde.unwrap().get(row, col)
+                    })
+                })
+                .collect();
+
             let local: Vec<_> = (0..main_lde.width())
                 .map(|col| {
                     PackedVal::<SC>::from_fn(|offset| {
@@ -111,8 +135,8 @@ where
             let accumulator = PackedChallenge::<SC>::zero();
             let mut folder = ProverConstraintFolder {
                 preprocessed: TwoRowMatrixView {
-                    local: &[],
-                    next: &[],
+                    local: &preprocessed_local,
+                    next: &preprocessed_next,
                 },
                 main: TwoRowMatrixView {
                     local: &local,
@@ -129,6 +153,9 @@ where
                 is_transition,
                 alpha,
                 accumulator,
+                is_last_shard: SC::Val::from_bool(is_last_shard),
+                exit_code: Word::from(public_values.exit_code),
+                committed_words: public_values.committed_words().map(Word::from),
             };
             chip.eval(&mut folder);
 
@@ -15,6 +15,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tracing::trace;
 
 use super::StarkGenericConfig;
+use crate::PublicValues;
 
 pub type Val<SC> = <SC as StarkGenericConfig>::Val;
 pub type PackedVal<SC> = <<SC as StarkGenericConfig>::Val as Field>::Packing;
@@ -42,6 +43,7 @@ pub struct ShardMainData<SC: StarkGenericConfig> {
     pub main_data: PcsProverData<SC>,
     pub chip_ids: Vec<String>,
     pub index: usize,
+    pub public_values: PublicValues,
 }
 
 impl<SC: StarkGenericConfig> ShardMainData<SC> {
@@ -51,6 +53,7 @@ impl<SC: StarkGenericConfig> ShardMainData<SC> {
         main_data: PcsProverData<SC>,
         chip_ids: Vec<String>,
         index: usize,
+        public_values: PublicValues,
     ) -> Self {
         Self {
             traces,
@@ -58,6 +61,7 @@ impl<SC: StarkGenericConfig> ShardMainData<SC> {
             main_data,
             chip_ids,
             index,
+            public_values,
         }
     }
 
@@ -142,6 +146,7 @@ pub struct ShardProof<SC: StarkGenericConfig> {
     pub opened_values: ShardOpenedValues<Challenge<SC>>,
     pub opening_proof: OpeningProof<SC>,
     pub chip_ids: Vec<String>,
+    pub public_values: PublicValues,
 }
 
 #[cfg(not(feature = "perf"))]
@@ -151,6 +156,7 @@ pub struct ShardProof<SC: StarkGenericConfig> {
     pub traces: Vec<ValMat<SC>>,
     pub permutation_traces: Vec<ChallengeMat<SC>>,
     pub chip_ids: Vec<String>,
+    pub public_values: PublicValues,
 }
 
 impl<T: Serialize> ShardOpenedValues<T> {
@@ -199,3 +205,13 @@ impl<SC: StarkGenericConfig> ShardProof<SC> {
 pub struct Proof<SC: StarkGenericConfig> {
     pub shard_proofs: Vec<ShardProof<SC>>,
 }
+
+impl<SC: StarkGenericConfig> Proof<SC> {
+    /// The public values observed by the proof, if it contains any shard.
+    ///
+    /// The values are only meaningful once the proof has been verified, which checks that every
+    /// shard observes the same public values.
+    pub fn public_values(&self) -> Option<PublicValues> {
+        self.shard_proofs.first().map(|proof| proof.public_values)
+    }
+}
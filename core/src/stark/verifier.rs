@@ -19,6 +19,9 @@ use super::folder::VerifierConstraintFolder;
 use super::types::*;
 use super::RiscvChip;
 use super::StarkGenericConfig;
+use super::VerifyingKey;
+use crate::air::Word;
+use crate::PublicValues;
 
 use core::fmt::Display;
 
@@ -26,12 +29,17 @@ pub struct Verifier<SC>(PhantomData<SC>);
 
 impl<SC: StarkGenericConfig> Verifier<SC> {
     /// Verify a proof for a collection of air chips.
+    ///
+    /// `is_last_shard` must be set for the last shard of the execution, which holds the global
+    /// memory tables.
     #[cfg(feature = "perf")]
     pub fn verify_shard(
         config: &SC,
+        vk: &VerifyingKey<SC>,
         chips: &[&RiscvChip<SC>],
         challenger: &mut SC::Challenger,
         proof: &ShardProof<SC>,
+        is_last_shard: bool,
     ) -> Result<(), VerificationError> {
         use crate::air::MachineAir;
 
@@ -42,6 +50,49 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
             ..
         } = proof;
 
+        if chips.len() != opened_values.chips.len() {
+            return Err(VerificationError::ChipOpeningLengthMismatch);
+        }
+
+        // Check the shape of the preprocessed openings, and that the chips with a preprocessed
+        // trace have the degree fixed by the verifying key.
+        for (chip, values) in chips.iter().zip(opened_values.chips.iter()) {
+            let width = chip.preprocessed_width();
+            if values.preprocessed.local.len() != width || values.preprocessed.next.len() != width {
+                return Err(VerificationError::PreprocessedShapeMismatch(chip.name()));
+            }
+            if let Some(&index) = vk.chip_ordering.get(&chip.name()) {
+                let (_, _, log_degree) = vk.chip_information[index];
+                if values.log_degree != log_degree {
+                    return Err(VerificationError::PreprocessedShapeMismatch(chip.name()));
+                }
+            }
+        }
+
+        // Get the preprocessed openings, in the order of the preprocessed commitment.
+        let preprocessed_values = vk
+            .chip_information
+            .iter()
+            .map(|(name, _, _)| {
+                chips
+                    .iter()
+                    .position(|chip| chip.name() == *name)
+                    .map(|i| {
+                        let values = &opened_values.chips[i].preprocessed;
+                        vec![values.local.clone(), values.next.clone()]
+                    })
+                    .ok_or_else(|| VerificationError::PreprocessedShapeMismatch(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let preprocessed_dims = vk
+            .chip_information
+            .iter()
+            .map(|(_, width, log_degree)| Dimensions {
+                width: *width,
+                height: 1 << log_degree,
+            })
+            .collect::<Vec<_>>();
+
         let (main_dims, perm_dims, quot_dims): (Vec<_>, Vec<_>, Vec<_>) = chips
             .iter()
             .zip(opened_values.chips.iter())
@@ -63,7 +114,7 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
             })
             .multiunzip();
 
-        let dims = &[main_dims, perm_dims, quot_dims];
+        let dims = &[preprocessed_dims, main_dims, perm_dims, quot_dims];
 
         let g_subgroups = opened_values
             .chips
@@ -77,6 +128,9 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
             quotient_commit,
         } = commitment;
 
+        // Observe the public values, so that the proof is bound to the input and output.
+        challenger.observe_slice(&proof.public_values.to_field_elements::<SC::Val>());
+
         let permutation_challenges = (0..2)
             .map(|_| challenger.sample_ext_element::<SC::Challenge>())
             .collect::<Vec<_>>();
@@ -97,6 +151,15 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
             .map(|g| vec![zeta, zeta * *g])
             .collect::<Vec<_>>();
 
+        let preprocessed_opening_points = vk
+            .chip_information
+            .iter()
+            .map(|(_, _, log_degree)| {
+                let g = SC::Val::two_adic_generator(*log_degree);
+                vec![zeta, zeta * g]
+            })
+            .collect::<Vec<_>>();
+
         let quotient_opening_points = chips
             .iter()
             .map(|chip| vec![zeta.exp_power_of_2(chip.log_quotient_degree())])
@@ -106,12 +169,17 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
             .pcs()
             .verify_multi_batches(
                 &[
+                    (vk.commit.clone(), &preprocessed_opening_points),
                     (main_commit.clone(), &trace_opening_points),
                     (permutation_commit.clone(), &trace_opening_points),
                     (quotient_commit.clone(), &quotient_opening_points),
                 ],
                 dims,
-                opened_values.clone().into_values(),
+                [
+                    vec![preprocessed_values],
+                    opened_values.clone().into_values(),
+                ]
+                .concat(),
                 opening_proof,
                 challenger,
             )
@@ -128,6 +196,8 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
                 zeta,
                 alpha,
                 &permutation_challenges,
+                is_last_shard,
+                proof.public_values,
             )
             .map_err(|_| VerificationError::OodEvaluationMismatch(chip.name()))?;
         }
@@ -138,14 +208,17 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
     #[cfg(not(feature = "perf"))]
     pub fn verify_shard(
         _config: &SC,
+        _vk: &VerifyingKey<SC>,
         _chips: &[&RiscvChip<SC>],
         _challenger: &mut SC::Challenger,
         _proof: &ShardProof<SC>,
+        _is_last_shard: bool,
     ) -> Result<(), VerificationError> {
         Ok(())
     }
 
     #[cfg(feature = "perf")]
+    #[allow(clippy::too_many_arguments)]
     fn verify_constraints(
         chip: &RiscvChip<SC>,
         opening: ChipOpenedValues<SC::Challenge>,
@@ -153,6 +226,8 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
         zeta: SC::Challenge,
         alpha: SC::Challenge,
         permutation_challenges: &[SC::Challenge],
+        is_last_shard: bool,
+        public_values: PublicValues,
     ) -> Result<(), OodEvaluationMismatch> {
         let z_h = zeta.exp_power_of_2(opening.log_degree) - SC::Challenge::one();
         let is_first_row = z_h / (zeta - SC::Val::one());
@@ -211,6 +286,9 @@ impl<SC: StarkGenericConfig> Verifier<SC> {
             is_transition,
             alpha,
             accumulator: SC::Challenge::zero(),
+            is_last_shard: SC::Challenge::from_bool(is_last_shard),
+            exit_code: Word::from(public_values.exit_code),
+            committed_words: public_values.committed_words().map(Word::from),
         };
         chip.eval(&mut folder);
 
@@ -233,6 +311,10 @@ pub enum VerificationError {
     ///
     /// `constraints(zeta)` did not match `quotient(zeta) Z_H(zeta)`.
     OodEvaluationMismatch(String),
+    /// The number of chip openings does not match the number of chips in the shard.
+    ChipOpeningLengthMismatch,
+    /// The preprocessed openings of a chip do not match the verifying key.
+    PreprocessedShapeMismatch(String),
 }
 
 impl Display for VerificationError {
@@ -244,6 +326,12 @@ impl Display for VerificationError {
             VerificationError::OodEvaluationMismatch(chip) => {
                 write!(f, "Out-of-domain evaluation mismatch on chip {}", chip)
             }
+            VerificationError::ChipOpeningLengthMismatch => {
+                write!(f, "Chip opening length mismatch")
+            }
+            VerificationError::PreprocessedShapeMismatch(chip) => {
+                write!(f, "Preprocessed shape mismatch on chip {}", chip)
+            }
         }
     }
 }
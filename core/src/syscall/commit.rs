@@ -0,0 +1,30 @@
+use crate::runtime::{ExecutionErrorKind, Register, Syscall, SyscallContext};
+use crate::NUM_COMMITTED_WORDS;
+
+/// Commits to the word in `a0` as the word of the public values at the index in `a1`, leaving
+/// `a0` unchanged.
+///
+/// The CPU constrains the word to be the one at this index in the public values of the proof, so
+/// committing to two different words at the same index makes the execution unprovable. Commits
+/// made inside an unconstrained block are discarded, like the rest of its effects.
+pub struct SyscallCommit;
+
+impl SyscallCommit {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallCommit {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let word = ctx.register_unsafe(Register::X10);
+        let index = ctx.register_unsafe(Register::X11);
+        if index as usize >= NUM_COMMITTED_WORDS {
+            return Err(ExecutionErrorKind::InvalidCommitIndex(index));
+        }
+        if !ctx.rt.unconstrained {
+            ctx.rt.state.committed_words[index as usize] = word;
+        }
+        Ok(word)
+    }
+}
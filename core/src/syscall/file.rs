@@ -0,0 +1,82 @@
+use crate::runtime::{ExecutionErrorKind, Register, Syscall, SyscallContext};
+
+/// The file descriptor of the first virtual file, so that file descriptors of virtual files do
+/// not clash with the standard ones.
+pub const FD_FILES_START: u32 = 16;
+
+/// The value returned by `SyscallFileOpen` when there is no virtual file with the given name.
+pub const FILE_NOT_FOUND: u32 = u32::MAX;
+
+/// Opens the virtual file with the name passed as a pointer and length in `a0` and `a1`,
+/// returning its file descriptor or `FILE_NOT_FOUND`.
+pub struct SyscallFileOpen;
+
+impl SyscallFileOpen {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallFileOpen {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let ptr = ctx.register_unsafe(Register::X10);
+        let len = ctx.register_unsafe(Register::X11);
+        let name = ctx.bytes_unsafe(ptr, len)?;
+        let idx = ctx
+            .rt
+            .files
+            .iter()
+            .position(|(file, _)| file.as_bytes() == name);
+        Ok(idx.map_or(FILE_NOT_FOUND, |idx| FD_FILES_START + idx as u32))
+    }
+}
+
+/// Returns the length of the virtual file with the file descriptor in `a0`.
+pub struct SyscallFileLen;
+
+impl SyscallFileLen {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallFileLen {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let fd = ctx.register_unsafe(Register::X10);
+        Ok(file(ctx, fd)?.len() as u32)
+    }
+}
+
+/// Reads up to a word from the virtual file with the file descriptor in `a0`, at the offset in
+/// `a1` and with the number of bytes in `a2`, like `SyscallLWA` does for the input streams.
+pub struct SyscallFileRead;
+
+impl SyscallFileRead {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallFileRead {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let fd = ctx.register_unsafe(Register::X10);
+        let offset = ctx.register_unsafe(Register::X11) as usize;
+        let num_bytes = (ctx.register_unsafe(Register::X12) as usize).min(4);
+        let data = file(ctx, fd)?;
+        let bytes = offset
+            .checked_add(num_bytes)
+            .and_then(|end| data.get(offset..end))
+            .ok_or(ExecutionErrorKind::InputExhausted(fd))?;
+        let mut read_bytes = [0u8; 4];
+        read_bytes[..num_bytes].copy_from_slice(bytes);
+        Ok(u32::from_le_bytes(read_bytes))
+    }
+}
+
+/// The contents of the virtual file with the given file descriptor.
+fn file<'a>(ctx: &'a SyscallContext, fd: u32) -> Result<&'a [u8], ExecutionErrorKind> {
+    fd.checked_sub(FD_FILES_START)
+        .and_then(|idx| ctx.rt.files.get(idx as usize))
+        .map(|(_, data)| data.as_ref())
+        .ok_or(ExecutionErrorKind::InvalidFileDescriptor(fd))
+}
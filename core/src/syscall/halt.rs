@@ -1,4 +1,4 @@
-use crate::runtime::{Register, Syscall, SyscallContext};
+use crate::runtime::{ExecutionErrorKind, Register, Syscall, SyscallContext};
 
 pub struct SyscallHalt;
 
@@ -9,15 +9,16 @@ impl SyscallHalt {
 }
 
 impl Syscall for SyscallHalt {
-    fn execute(&self, ctx: &mut SyscallContext) -> u32 {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         let exit_code = ctx.register_unsafe(Register::X10);
-        if ctx.rt.fail_on_panic && exit_code != 0 {
-            panic!(
+        if exit_code != 0 {
+            tracing::warn!(
                 "RISC-V runtime halted during program execution with non-zero exit code {}. This likely means your program panicked during execution.",
                 exit_code
             );
         }
+        ctx.rt.state.exit_code = exit_code;
         ctx.set_next_pc(0);
-        ctx.register_unsafe(Register::X10)
+        Ok(exit_code)
     }
 }
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use crate::runtime::{ExecutionErrorKind, Register, Syscall, SyscallContext};
+
+/// A hint computed natively by the host, which takes the bytes sent by the program and returns
+/// the bytes of the answer.
+pub type Hint = Arc<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>;
+
+/// Invokes the hint registered under a name with some input, appending its answer to the hint
+/// stream, which the program reads from `FD_HINT_ANSWERS`, and returning the length of the answer.
+///
+/// The name is passed as a pointer and length in `a0` and `a1`, and the input in `a2` and `a3`.
+pub struct SyscallHint;
+
+impl SyscallHint {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallHint {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let read_bytes = |ctx: &SyscallContext, ptr: Register, len: Register| {
+            ctx.bytes_unsafe(ctx.register_unsafe(ptr), ctx.register_unsafe(len))
+        };
+        let name = read_bytes(ctx, Register::X10, Register::X11)?;
+        let input = read_bytes(ctx, Register::X12, Register::X13)?;
+        let name = String::from_utf8_lossy(&name);
+        let hint = ctx
+            .rt
+            .hints
+            .get(name.as_ref())
+            .cloned()
+            .ok_or_else(|| ExecutionErrorKind::UnknownHint(name.to_string()))?;
+        let answer = hint(&input);
+        ctx.rt.state.hint_stream.extend_from_slice(&answer);
+        Ok(answer.len() as u32)
+    }
+}
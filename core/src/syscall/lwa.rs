@@ -1,6 +1,14 @@
-use std::process::exit;
+use crate::runtime::{ExecutionErrorKind, Register, Syscall, SyscallContext};
 
-use crate::runtime::{Register, Syscall, SyscallContext};
+/// The file descriptor of the public input. The program hashes the bytes it reads from it and
+/// commits to the digest before it halts, so it cannot be read inside an unconstrained block.
+pub const FD_PUBLIC_INPUT: u32 = 3;
+
+/// The file descriptor of the private input, which is only known to the prover.
+pub const FD_PRIVATE_INPUT: u32 = 5;
+
+/// The file descriptor of the answers of the hints queried by the program.
+pub const FD_HINT_ANSWERS: u32 = 6;
 
 pub struct SyscallLWA;
 
@@ -11,23 +19,42 @@ impl SyscallLWA {
 }
 
 impl Syscall for SyscallLWA {
-    fn execute(&self, ctx: &mut SyscallContext) -> u32 {
-        // TODO: in the future this will be used for private vs. public inputs.
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         let a0 = Register::X10;
         let a1 = Register::X11;
-        let _ = ctx.register_unsafe(a0);
+        let fd = ctx.register_unsafe(a0);
         let num_bytes = ctx.register_unsafe(a1) as usize;
+        if fd != FD_PUBLIC_INPUT && fd != FD_PRIVATE_INPUT && fd != FD_HINT_ANSWERS {
+            return Err(ExecutionErrorKind::InvalidFileDescriptor(fd));
+        }
+        if fd == FD_PUBLIC_INPUT && ctx.rt.unconstrained {
+            return Err(ExecutionErrorKind::UnconstrainedPublicInput);
+        }
+        if num_bytes > 4 {
+            return Err(ExecutionErrorKind::InvalidReadLength(num_bytes as u32));
+        }
         let mut read_bytes = [0u8; 4];
         for i in 0..num_bytes {
-            if ctx.rt.state.input_stream_ptr >= ctx.rt.state.input_stream.len() {
-                tracing::error!(
-                    "Not enough input words were passed in. Use --input to pass in more words."
-                );
-                exit(1);
-            }
-            read_bytes[i] = ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr];
-            ctx.rt.state.input_stream_ptr += 1;
+            let byte = match fd {
+                FD_PUBLIC_INPUT => ctx.rt.read_input_byte()?,
+                FD_PRIVATE_INPUT => {
+                    let state = &mut ctx.rt.state;
+                    let byte = state
+                        .private_input_stream
+                        .get(state.private_input_stream_ptr)
+                        .copied();
+                    state.private_input_stream_ptr += byte.is_some() as usize;
+                    byte
+                }
+                _ => {
+                    let state = &mut ctx.rt.state;
+                    let byte = state.hint_stream.get(state.hint_stream_ptr).copied();
+                    state.hint_stream_ptr += byte.is_some() as usize;
+                    byte
+                }
+            };
+            read_bytes[i] = byte.ok_or(ExecutionErrorKind::InputExhausted(fd))?;
         }
-        u32::from_le_bytes(read_bytes)
+        Ok(u32::from_le_bytes(read_bytes))
     }
 }
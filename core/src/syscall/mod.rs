@@ -1,10 +1,16 @@
+mod commit;
+mod file;
 mod halt;
+mod hint;
 mod lwa;
 pub mod precompiles;
 mod unconstrained;
 mod write;
 
+pub use commit::*;
+pub use file::*;
 pub use halt::*;
+pub use hint::*;
 pub use lwa::*;
 pub use unconstrained::*;
 pub use write::*;
@@ -1,4 +1,5 @@
 use crate::cpu::{MemoryReadRecord, MemoryWriteRecord};
+use crate::runtime::ExecutionErrorKind;
 use crate::runtime::Register;
 use crate::runtime::Syscall;
 use crate::syscall::precompiles::blake3::{
@@ -12,7 +13,7 @@ impl Syscall for Blake3CompressInnerChip {
         (4 * ROUND_COUNT * OPERATION_COUNT) as u32
     }
 
-    fn execute(&self, rt: &mut SyscallContext) -> u32 {
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         // TODO: These pointers have to be constrained.
         let state_ptr = rt.register_unsafe(Register::X10);
         let message_ptr = rt.register_unsafe(Register::X11);
@@ -71,6 +72,6 @@ impl Syscall for Blake3CompressInnerChip {
                 message_ptr,
             });
 
-        state_ptr
+        Ok(state_ptr)
     }
 }
@@ -111,6 +111,7 @@ impl Blake3CompressInnerChip {
 
 #[cfg(test)]
 pub mod compress_tests {
+    use crate::runtime::tests::halting;
     use crate::runtime::Instruction;
     use crate::runtime::Opcode;
     use crate::runtime::Register;
@@ -168,7 +169,7 @@ pub mod compress_tests {
     fn prove_babybear() {
         setup_logger();
         let program = blake3_compress_internal_program();
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
     }
 
     #[test]
@@ -0,0 +1,454 @@
+use crate::air::BaseAirBuilder;
+use crate::air::MachineAir;
+use crate::air::SP1AirBuilder;
+use crate::air::Word;
+use crate::cpu::MemoryReadRecord;
+use crate::cpu::MemoryWriteRecord;
+use crate::memory::MemoryReadCols;
+use crate::memory::MemoryReadWriteCols;
+use crate::operations::field::field_op::FieldOpCols;
+use crate::operations::field::field_op::FieldOperation;
+use crate::operations::field::field_sqrt::FieldSqrtCols;
+use crate::operations::field::params::Limbs;
+use crate::runtime::ExecutionErrorKind;
+use crate::runtime::ExecutionRecord;
+use crate::runtime::Syscall;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::bytes_to_words_le;
+use crate::utils::ec::field::FieldParameters;
+use crate::utils::ec::weierstrass::bls12_381::bls12381_sqrt;
+use crate::utils::ec::weierstrass::bls12_381::Bls12381BaseField;
+use crate::utils::ec::weierstrass::bls12_381::Bls12381Parameters;
+use crate::utils::ec::weierstrass::bls12_381::BLS12381_NUM_LIMBS;
+use crate::utils::ec::weierstrass::bls12_381::BLS12381_NUM_WITNESS_LIMBS;
+use crate::utils::ec::weierstrass::bls12_381::BLS12381_NUM_WORDS_FIELD_ELEMENT;
+use crate::utils::ec::weierstrass::WeierstrassParameters;
+use crate::utils::limbs_from_access;
+use crate::utils::limbs_from_prev_access;
+use crate::utils::pad_rows;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use num::BigUint;
+use num::Zero;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::fmt::Debug;
+
+/// The number of bytes of an element of the BLS12-381 base field.
+const NUM_BYTES_FIELD_ELEMENT: usize = BLS12381_NUM_LIMBS;
+
+/// An event for the decompression of a BLS12-381 point. The bytes are vectors of
+/// `BLS12381_NUM_LIMBS` elements, as serde does not support arrays of more than 32 elements.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381DecompressEvent {
+    pub shard: u32,
+    pub clk: u32,
+    pub ptr: u32,
+    pub is_odd: bool,
+    pub x_bytes: Vec<u8>,
+    pub decompressed_y_bytes: Vec<u8>,
+    pub x_memory_records: [MemoryReadRecord; BLS12381_NUM_WORDS_FIELD_ELEMENT],
+    pub y_memory_records: [MemoryWriteRecord; BLS12381_NUM_WORDS_FIELD_ELEMENT],
+}
+
+pub const NUM_BLS12381_DECOMPRESS_COLS: usize = size_of::<Bls12381DecompressCols<u8>>();
+
+/// A chip that computes `Bls12381Decompress` given a pointer to a 24 word slice formatted as such:
+/// input[0] is the sign bit. The second half of the slice is the compressed X in little endian.
+///
+/// After `Bls12381Decompress`, the first 48 bytes of the slice are overwritten with the
+/// decompressed Y.
+#[derive(Default)]
+pub struct Bls12381DecompressChip;
+
+impl Bls12381DecompressChip {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for Bls12381DecompressChip {
+    fn num_extra_cycles(&self) -> u32 {
+        4
+    }
+
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let a0 = crate::runtime::Register::X10;
+
+        let start_clk = rt.clk;
+
+        // TODO: this will have to be be constrained, but can do it later.
+        let slice_ptr = rt.register_unsafe(a0);
+        if slice_ptr % 4 != 0 {
+            return Err(ExecutionErrorKind::UnalignedMemoryAccess(slice_ptr));
+        }
+
+        let (x_memory_records_vec, x_vec) = rt.mr_slice(
+            slice_ptr.wrapping_add(NUM_BYTES_FIELD_ELEMENT as u32),
+            BLS12381_NUM_WORDS_FIELD_ELEMENT,
+        )?;
+        let x_memory_records: [MemoryReadRecord; BLS12381_NUM_WORDS_FIELD_ELEMENT] =
+            x_memory_records_vec.try_into().unwrap();
+
+        // This unsafe read is okay because we do mw_slice into the first 12 words later.
+        let is_odd = rt.byte_unsafe(slice_ptr);
+
+        let x_bytes = x_vec
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        // Compute actual decompressed Y, as the square root of x^3 + b with the requested parity.
+        let modulus = Bls12381BaseField::modulus();
+        let x = BigUint::from_bytes_le(&x_bytes);
+        let x_3_plus_b = (&x * &x * &x + Bls12381Parameters::b_int()) % &modulus;
+        let mut y = bls12381_sqrt(&x_3_plus_b);
+        if (&y * &y) % &modulus != x_3_plus_b {
+            return Err(ExecutionErrorKind::InvalidCompressedPoint);
+        }
+        if y.bit(0) != (is_odd != 0) {
+            y = (&modulus - &y) % &modulus;
+        }
+
+        let mut decompressed_y_bytes = y.to_bytes_le();
+        decompressed_y_bytes.resize(NUM_BYTES_FIELD_ELEMENT, 0u8);
+        let y_words: [u32; BLS12381_NUM_WORDS_FIELD_ELEMENT] =
+            bytes_to_words_le(&decompressed_y_bytes);
+
+        let y_memory_records_vec = rt.mw_slice(slice_ptr, &y_words)?;
+        let y_memory_records: [MemoryWriteRecord; BLS12381_NUM_WORDS_FIELD_ELEMENT] =
+            y_memory_records_vec.try_into().unwrap();
+
+        let shard = rt.current_shard();
+        rt.record_mut()
+            .bls12381_decompress_events
+            .push(Bls12381DecompressEvent {
+                shard,
+                clk: start_clk,
+                ptr: slice_ptr,
+                is_odd: is_odd != 0,
+                x_bytes,
+                decompressed_y_bytes,
+                x_memory_records,
+                y_memory_records,
+            });
+
+        rt.clk += 4;
+
+        Ok(slice_ptr)
+    }
+}
+
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Bls12381DecompressCols<T> {
+    pub is_real: T,
+    pub shard: T,
+    pub clk: T,
+    pub ptr: T,
+    pub x_access: [MemoryReadCols<T>; BLS12381_NUM_WORDS_FIELD_ELEMENT],
+    pub y_access: [MemoryReadWriteCols<T>; BLS12381_NUM_WORDS_FIELD_ELEMENT],
+    pub(crate) x_2: FieldOpCols<T, BLS12381_NUM_LIMBS, BLS12381_NUM_WITNESS_LIMBS>,
+    pub(crate) x_3: FieldOpCols<T, BLS12381_NUM_LIMBS, BLS12381_NUM_WITNESS_LIMBS>,
+    pub(crate) x_3_plus_b: FieldOpCols<T, BLS12381_NUM_LIMBS, BLS12381_NUM_WITNESS_LIMBS>,
+    pub(crate) y: FieldSqrtCols<T, BLS12381_NUM_LIMBS, BLS12381_NUM_WITNESS_LIMBS>,
+    pub(crate) neg_y: FieldOpCols<T, BLS12381_NUM_LIMBS, BLS12381_NUM_WITNESS_LIMBS>,
+    pub(crate) y_least_bits: [T; 8],
+}
+
+impl<F: PrimeField32> Bls12381DecompressCols<F> {
+    pub fn populate(&mut self, event: Bls12381DecompressEvent, record: &mut ExecutionRecord) {
+        let mut new_field_events = Vec::new();
+        self.is_real = F::from_bool(true);
+        self.shard = F::from_canonical_u32(event.shard);
+        self.clk = F::from_canonical_u32(event.clk);
+        self.ptr = F::from_canonical_u32(event.ptr);
+        for i in 0..BLS12381_NUM_WORDS_FIELD_ELEMENT {
+            self.x_access[i].populate(event.x_memory_records[i], &mut new_field_events);
+            self.y_access[i].populate_write(event.y_memory_records[i], &mut new_field_events);
+        }
+
+        let x = &BigUint::from_bytes_le(&event.x_bytes);
+        self.populate_field_ops(x);
+
+        record.add_field_events(&new_field_events);
+    }
+
+    fn populate_field_ops(&mut self, x: &BigUint) {
+        // Y = sqrt(x^3 + b)
+        let x_2 = self
+            .x_2
+            .populate::<Bls12381BaseField>(x, x, FieldOperation::Mul);
+        let x_3 = self
+            .x_3
+            .populate::<Bls12381BaseField>(&x_2, x, FieldOperation::Mul);
+        let b = Bls12381Parameters::b_int();
+        let x_3_plus_b =
+            self.x_3_plus_b
+                .populate::<Bls12381BaseField>(&x_3, &b, FieldOperation::Add);
+        let y = self
+            .y
+            .populate::<Bls12381BaseField>(&x_3_plus_b, bls12381_sqrt);
+        let zero = BigUint::zero();
+        self.neg_y
+            .populate::<Bls12381BaseField>(&zero, &y, FieldOperation::Sub);
+        // Decompose bits of least significant Y byte
+        let y_bytes = y.to_bytes_le();
+        let y_lsb = if y_bytes.is_empty() { 0 } else { y_bytes[0] };
+        for i in 0..8 {
+            self.y_least_bits[i] = F::from_canonical_u32(((y_lsb >> i) & 1) as u32);
+        }
+    }
+}
+
+impl<V: Copy> Bls12381DecompressCols<V> {
+    pub fn eval<AB: SP1AirBuilder<Var = V>>(&self, builder: &mut AB)
+    where
+        V: Into<AB::Expr>,
+    {
+        // Get the 48th byte of the slice, which should be `should_be_odd`.
+        let should_be_odd: AB::Expr = self.y_access[0].prev_value[0].into();
+        builder.assert_bool(should_be_odd.clone());
+
+        let x: Limbs<_, BLS12381_NUM_LIMBS> = limbs_from_prev_access(&self.x_access);
+        self.x_2
+            .eval::<AB, Bls12381BaseField, _, _>(builder, &x, &x, FieldOperation::Mul);
+        self.x_3.eval::<AB, Bls12381BaseField, _, _>(
+            builder,
+            &self.x_2.result,
+            &x,
+            FieldOperation::Mul,
+        );
+        let b = Bls12381Parameters::b_int();
+        let b_const: Limbs<AB::F, BLS12381_NUM_LIMBS> = Bls12381BaseField::to_limbs_field(&b);
+        self.x_3_plus_b.eval::<AB, Bls12381BaseField, _, _>(
+            builder,
+            &self.x_3.result,
+            &b_const,
+            FieldOperation::Add,
+        );
+        self.y
+            .eval::<AB, Bls12381BaseField>(builder, &self.x_3_plus_b.result);
+        self.neg_y.eval::<AB, Bls12381BaseField, _, _>(
+            builder,
+            &[AB::Expr::zero()].iter(),
+            &self.y.multiplication.result,
+            FieldOperation::Sub,
+        );
+
+        // Constrain decomposition of least significant byte of Y into `y_least_bits`
+        for i in 0..8 {
+            builder.when(self.is_real).assert_bool(self.y_least_bits[i]);
+        }
+        let y_least_byte = self.y.multiplication.result.0[0];
+        let powers_of_two = [1, 2, 4, 8, 16, 32, 64, 128].map(AB::F::from_canonical_u32);
+        let recomputed_byte: AB::Expr = self
+            .y_least_bits
+            .iter()
+            .zip(powers_of_two)
+            .map(|(p, b)| (*p).into() * b)
+            .sum();
+        builder
+            .when(self.is_real)
+            .assert_eq(recomputed_byte, y_least_byte);
+
+        // Interpret the lowest bit of Y as whether it is odd or not.
+        let y_is_odd = self.y_least_bits[0];
+
+        // When y_is_odd == should_be_odd, result is y
+        // Equivalent: y_is_odd != !should_be_odd
+        let y_limbs: Limbs<_, BLS12381_NUM_LIMBS> = limbs_from_access(&self.y_access);
+        builder
+            .when(self.is_real)
+            .when_ne(y_is_odd.into(), AB::Expr::one() - should_be_odd.clone())
+            .assert_all_eq(self.y.multiplication.result, y_limbs);
+        // When y_is_odd != should_be_odd, result is -y.
+        builder
+            .when(self.is_real)
+            .when_ne(y_is_odd, should_be_odd)
+            .assert_all_eq(self.neg_y.result, y_limbs);
+
+        for i in 0..BLS12381_NUM_WORDS_FIELD_ELEMENT {
+            builder.constraint_memory_access(
+                self.shard,
+                self.clk,
+                self.ptr.into()
+                    + AB::F::from_canonical_u32((i as u32) * 4 + NUM_BYTES_FIELD_ELEMENT as u32),
+                &self.x_access[i],
+                self.is_real,
+            );
+        }
+        for i in 0..BLS12381_NUM_WORDS_FIELD_ELEMENT {
+            builder.constraint_memory_access(
+                self.shard,
+                self.clk,
+                self.ptr.into() + AB::F::from_canonical_u32((i as u32) * 4),
+                &self.y_access[i],
+                self.is_real,
+            );
+        }
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for Bls12381DecompressChip {
+    fn name(&self) -> String {
+        "Bls12381Decompress".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+
+        for i in 0..input.bls12381_decompress_events.len() {
+            let event = input.bls12381_decompress_events[i].clone();
+            let mut row = [F::zero(); NUM_BLS12381_DECOMPRESS_COLS];
+            let cols: &mut Bls12381DecompressCols<F> = row.as_mut_slice().borrow_mut();
+            cols.populate(event.clone(), output);
+
+            rows.push(row);
+        }
+
+        pad_rows(&mut rows, || {
+            let mut row = [F::zero(); NUM_BLS12381_DECOMPRESS_COLS];
+            let cols: &mut Bls12381DecompressCols<F> = row.as_mut_slice().borrow_mut();
+            // The X of the generator has a valid result -> sqrt(X^3 + 4)
+            let (dummy_value, _) = Bls12381Parameters::generator();
+            let mut dummy_bytes = dummy_value.to_bytes_le();
+            dummy_bytes.resize(NUM_BYTES_FIELD_ELEMENT, 0u8);
+            for i in 0..BLS12381_NUM_WORDS_FIELD_ELEMENT {
+                let word_bytes = dummy_bytes[i * 4..(i + 1) * 4]
+                    .iter()
+                    .map(|x| F::from_canonical_u8(*x))
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap();
+                cols.x_access[i].access.value = Word(word_bytes);
+            }
+            cols.populate_field_ops(&dummy_value);
+            row
+        });
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_BLS12381_DECOMPRESS_COLS,
+        )
+    }
+}
+
+impl<F> BaseAir<F> for Bls12381DecompressChip {
+    fn width(&self) -> usize {
+        NUM_BLS12381_DECOMPRESS_COLS
+    }
+}
+
+impl<AB> Air<AB> for Bls12381DecompressChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let row: &Bls12381DecompressCols<AB::Var> = main.row_slice(0).borrow();
+        row.eval::<AB>(builder);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        runtime::{
+            tests::halting, ExecutionErrorKind, Instruction, Opcode, Program, Runtime, SyscallCode,
+        },
+        utils::{run_test, setup_logger},
+    };
+
+    /// The coordinates of the generator of BLS12-381 and the negation of its `y`, as little-endian
+    /// bytes. The `y` of the generator is odd.
+    const G_X: [u8; 48] = [
+        187, 198, 34, 219, 10, 240, 58, 251, 239, 26, 122, 249, 63, 232, 85, 108, 88, 172, 27, 23,
+        63, 58, 78, 161, 5, 185, 116, 151, 79, 140, 104, 195, 15, 172, 169, 79, 140, 99, 149, 38,
+        148, 215, 151, 49, 167, 211, 241, 23,
+    ];
+    const G_Y: [u8; 48] = [
+        225, 231, 197, 70, 41, 35, 170, 12, 228, 138, 136, 162, 68, 199, 60, 208, 237, 179, 4, 44,
+        203, 24, 219, 0, 246, 10, 208, 213, 149, 224, 245, 252, 228, 138, 29, 116, 237, 48, 158,
+        160, 241, 160, 170, 227, 129, 244, 179, 8,
+    ];
+    const NEG_G_Y: [u8; 48] = [
+        202, 194, 57, 185, 214, 220, 84, 173, 27, 117, 203, 14, 186, 56, 111, 78, 54, 66, 172, 202,
+        213, 185, 85, 102, 201, 7, 181, 29, 239, 106, 129, 103, 242, 33, 46, 207, 200, 118, 125,
+        170, 168, 69, 213, 85, 104, 29, 77, 17,
+    ];
+
+    const PTR: u32 = 100;
+
+    /// A program which writes `x` to the second half of a slice and the sign bit `is_odd` to its
+    /// first byte, and decompresses it with `BLS12381_DECOMPRESS`.
+    fn bls12381_decompress_program(x: &[u8; 48], is_odd: bool) -> Program {
+        let mut words = vec![is_odd as u32];
+        words.extend([0; 11]);
+        words.extend(
+            x.chunks_exact(4)
+                .map(|word| u32::from_le_bytes(word.try_into().unwrap())),
+        );
+        let mut instructions = Vec::new();
+        for (i, word) in words.into_iter().enumerate() {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, PTR + i as u32 * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::BLS12381_DECOMPRESS as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, PTR, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_bls12381_decompress_execute() {
+        for (is_odd, y) in [(true, G_Y), (false, NEG_G_Y)] {
+            let mut runtime = Runtime::new(bls12381_decompress_program(&G_X, is_odd));
+            runtime.run().unwrap();
+            let result = (0..12)
+                .flat_map(|i| runtime.word(PTR + i * 4).to_le_bytes())
+                .collect::<Vec<_>>();
+            assert_eq!(result, y);
+            assert_eq!(runtime.record.bls12381_decompress_events.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_bls12381_decompress_invalid_point() {
+        // There is no point on the curve with `x = 1`.
+        let mut x = [0u8; 48];
+        x[0] = 1;
+        let mut runtime = Runtime::new(bls12381_decompress_program(&x, false));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InvalidCompressedPoint);
+    }
+
+    #[test]
+    fn test_bls12381_decompress_prove() {
+        setup_logger();
+        let program = bls12381_decompress_program(&G_X, false);
+        run_test(halting(program)).unwrap();
+    }
+}
@@ -0,0 +1,26 @@
+mod decompress;
+
+pub use decompress::*;
+
+use crate::syscall::precompiles::weierstrass::{
+    WeierstrassAddAssignChip, WeierstrassDoubleAssignChip,
+};
+use crate::utils::ec::weierstrass::bls12_381::{
+    Bls12381, BLS12381_NUM_LIMBS, BLS12381_NUM_WITNESS_LIMBS, BLS12381_NUM_WORDS_EC_POINT,
+};
+
+/// The chip adding points on the BLS12-381 curve, whose base field elements have 48 limbs.
+pub type Bls12381AddAssignChip = WeierstrassAddAssignChip<
+    Bls12381,
+    BLS12381_NUM_LIMBS,
+    BLS12381_NUM_WITNESS_LIMBS,
+    BLS12381_NUM_WORDS_EC_POINT,
+>;
+
+/// The chip doubling points on the BLS12-381 curve, whose base field elements have 48 limbs.
+pub type Bls12381DoubleAssignChip = WeierstrassDoubleAssignChip<
+    Bls12381,
+    BLS12381_NUM_LIMBS,
+    BLS12381_NUM_WITNESS_LIMBS,
+    BLS12381_NUM_WORDS_EC_POINT,
+>;
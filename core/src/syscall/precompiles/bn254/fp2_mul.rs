@@ -0,0 +1,398 @@
+use crate::air::MachineAir;
+use crate::air::SP1AirBuilder;
+use crate::cpu::MemoryReadRecord;
+use crate::cpu::MemoryWriteRecord;
+use crate::field::event::FieldEvent;
+use crate::memory::MemoryCols;
+use crate::memory::MemoryReadCols;
+use crate::memory::MemoryWriteCols;
+use crate::operations::field::field_inner_product::FieldInnerProductCols;
+use crate::operations::field::field_op::FieldOpCols;
+use crate::operations::field::field_op::FieldOperation;
+use crate::operations::field::params::Limbs;
+use crate::operations::field::params::NUM_LIMBS;
+use crate::runtime::ExecutionErrorKind;
+use crate::runtime::ExecutionRecord;
+use crate::runtime::Register;
+use crate::runtime::Syscall;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::field::FieldParameters;
+use crate::utils::ec::weierstrass::bn254::Bn254BaseField;
+use crate::utils::ec::NUM_WORDS_FIELD_ELEMENT;
+use crate::utils::limbs_from_prev_access;
+use crate::utils::pad_rows;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use num::BigUint;
+use num::Zero;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_maybe_rayon::prelude::IntoParallelRefIterator;
+use p3_maybe_rayon::prelude::ParallelIterator;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::fmt::Debug;
+use tracing::instrument;
+
+/// The number of words of an element of the quadratic extension of the BN254 base field.
+pub const NUM_WORDS_FP2_ELEMENT: usize = 2 * NUM_WORDS_FIELD_ELEMENT;
+
+/// An event for a multiplication in the quadratic extension `Fp2 = Fp[u] / (u^2 + 1)` of the BN254
+/// base field, which sets `x` to `x * y`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bn254Fp2MulEvent {
+    pub shard: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub x: [u32; NUM_WORDS_FP2_ELEMENT],
+    pub y_ptr: u32,
+    pub y: [u32; NUM_WORDS_FP2_ELEMENT],
+    pub y_ptr_record: MemoryReadRecord,
+    pub x_memory_records: [MemoryWriteRecord; NUM_WORDS_FP2_ELEMENT],
+    pub y_memory_records: [MemoryReadRecord; NUM_WORDS_FP2_ELEMENT],
+}
+
+pub const NUM_BN254_FP2_MUL_COLS: usize = size_of::<Bn254Fp2MulCols<u8>>();
+
+/// A set of columns to compute `x * y` for `x = x_0 + x_1 * u` and `y = y_0 + y_1 * u` in `Fp2`,
+/// as `(x_0 * y_0 - x_1 * y_1) + (x_0 * y_1 + x_1 * y_0) * u`.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Bn254Fp2MulCols<T> {
+    pub is_real: T,
+    pub shard: T,
+    pub clk: T,
+    pub x_ptr: T,
+    pub y_ptr: T,
+    pub y_ptr_access: MemoryReadCols<T>,
+    pub x_access: [MemoryWriteCols<T>; NUM_WORDS_FP2_ELEMENT],
+    pub y_access: [MemoryReadCols<T>; NUM_WORDS_FP2_ELEMENT],
+    pub(crate) x0_mul_y0: FieldOpCols<T>,
+    pub(crate) x1_mul_y1: FieldOpCols<T>,
+    pub(crate) z0: FieldOpCols<T>,
+    pub(crate) z1: FieldInnerProductCols<T>,
+}
+
+/// A chip for `BN254_FP2_MUL`. It is the only chip of the BN254 pairing: the line evaluations of
+/// the Miller loop, the multiplications in `Fp12` and the final exponentiation have no chips yet.
+#[derive(Default)]
+pub struct Bn254Fp2MulChip;
+
+impl Bn254Fp2MulChip {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn populate_field_ops<F: PrimeField32>(
+        cols: &mut Bn254Fp2MulCols<F>,
+        x0: BigUint,
+        x1: BigUint,
+        y0: BigUint,
+        y1: BigUint,
+    ) {
+        let x0_mul_y0 = cols
+            .x0_mul_y0
+            .populate::<Bn254BaseField>(&x0, &y0, FieldOperation::Mul);
+        let x1_mul_y1 = cols
+            .x1_mul_y1
+            .populate::<Bn254BaseField>(&x1, &y1, FieldOperation::Mul);
+        cols.z0
+            .populate::<Bn254BaseField>(&x0_mul_y0, &x1_mul_y1, FieldOperation::Sub);
+        cols.z1.populate::<Bn254BaseField>(&[x0, x1], &[y1, y0]);
+    }
+}
+
+/// Decode the two coordinates of an element of `Fp2` from its little-endian words.
+fn fp2_from_words_le(words: &[u32; NUM_WORDS_FP2_ELEMENT]) -> (BigUint, BigUint) {
+    (
+        BigUint::from_slice(&words[..NUM_WORDS_FIELD_ELEMENT]),
+        BigUint::from_slice(&words[NUM_WORDS_FIELD_ELEMENT..]),
+    )
+}
+
+impl Syscall for Bn254Fp2MulChip {
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let start_clk = rt.clk;
+
+        let x_ptr = rt.register_unsafe(Register::X10);
+        if x_ptr % 4 != 0 {
+            return Err(ExecutionErrorKind::UnalignedMemoryAccess(x_ptr));
+        }
+
+        let (y_ptr_record, y_ptr) = rt.mr(Register::X11 as u32);
+        if y_ptr % 4 != 0 {
+            return Err(ExecutionErrorKind::UnalignedMemoryAccess(y_ptr));
+        }
+
+        let x: [u32; NUM_WORDS_FP2_ELEMENT] = rt
+            .slice_unsafe(x_ptr, NUM_WORDS_FP2_ELEMENT)
+            .try_into()
+            .unwrap();
+        let (y_memory_records, y) = rt.mr_slice(y_ptr, NUM_WORDS_FP2_ELEMENT)?;
+        let y: [u32; NUM_WORDS_FP2_ELEMENT] = y.try_into().unwrap();
+
+        // When we write to x, we want the clk to be incremented.
+        rt.clk += 4;
+
+        let modulus = Bn254BaseField::modulus();
+        let (x0, x1) = fp2_from_words_le(&x);
+        let (y0, y1) = fp2_from_words_le(&y);
+        let z0 = (&modulus + (&x0 * &y0) % &modulus - (&x1 * &y1) % &modulus) % &modulus;
+        let z1 = (&x0 * &y1 + &x1 * &y0) % &modulus;
+
+        let mut result = [0u32; NUM_WORDS_FP2_ELEMENT];
+        for (words, z) in result
+            .chunks_exact_mut(NUM_WORDS_FIELD_ELEMENT)
+            .zip([z0, z1])
+        {
+            for (word, digit) in words.iter_mut().zip(z.to_u32_digits()) {
+                *word = digit;
+            }
+        }
+        let x_memory_records = rt.mw_slice(x_ptr, &result)?.try_into().unwrap();
+
+        rt.clk += 4;
+
+        let event = Bn254Fp2MulEvent {
+            shard: rt.current_shard(),
+            clk: start_clk,
+            x_ptr,
+            x,
+            y_ptr,
+            y,
+            y_ptr_record,
+            x_memory_records,
+            y_memory_records: y_memory_records.try_into().unwrap(),
+        };
+        rt.record_mut().bn254_fp2_mul_events.push(event);
+
+        Ok(x_ptr + 1)
+    }
+
+    fn num_extra_cycles(&self) -> u32 {
+        8
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for Bn254Fp2MulChip {
+    fn name(&self) -> String {
+        "Bn254Fp2Mul".to_string()
+    }
+
+    #[instrument(name = "generate Bn254Fp2Mul trace", skip_all)]
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let (mut rows, new_field_events_list): (
+            Vec<[F; NUM_BN254_FP2_MUL_COLS]>,
+            Vec<Vec<FieldEvent>>,
+        ) = input
+            .bn254_fp2_mul_events
+            .par_iter()
+            .map(|event| {
+                let mut row = [F::zero(); NUM_BN254_FP2_MUL_COLS];
+                let cols: &mut Bn254Fp2MulCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Decode the field elements.
+                let (x0, x1) = fp2_from_words_le(&event.x);
+                let (y0, y1) = fp2_from_words_le(&event.y);
+
+                // Populate basic columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.y_ptr = F::from_canonical_u32(event.y_ptr);
+
+                Self::populate_field_ops(cols, x0, x1, y0, y1);
+
+                // Populate the memory access columns.
+                let mut new_field_events = Vec::new();
+                for i in 0..NUM_WORDS_FP2_ELEMENT {
+                    cols.y_access[i].populate(event.y_memory_records[i], &mut new_field_events);
+                }
+                for i in 0..NUM_WORDS_FP2_ELEMENT {
+                    cols.x_access[i].populate(event.x_memory_records[i], &mut new_field_events);
+                }
+                cols.y_ptr_access
+                    .populate(event.y_ptr_record, &mut new_field_events);
+
+                (row, new_field_events)
+            })
+            .unzip();
+
+        for new_field_events in new_field_events_list {
+            output.add_field_events(&new_field_events);
+        }
+
+        pad_rows(&mut rows, || {
+            let mut row = [F::zero(); NUM_BN254_FP2_MUL_COLS];
+            let cols: &mut Bn254Fp2MulCols<F> = row.as_mut_slice().borrow_mut();
+            let zero = BigUint::zero();
+            Self::populate_field_ops(cols, zero.clone(), zero.clone(), zero.clone(), zero);
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_BN254_FP2_MUL_COLS,
+        )
+    }
+}
+
+impl<F> BaseAir<F> for Bn254Fp2MulChip {
+    fn width(&self) -> usize {
+        NUM_BN254_FP2_MUL_COLS
+    }
+}
+
+impl<AB> Air<AB> for Bn254Fp2MulChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let row: &Bn254Fp2MulCols<AB::Var> = main.row_slice(0).borrow();
+
+        let x0: Limbs<_> = limbs_from_prev_access(&row.x_access[0..8]);
+        let x1: Limbs<_> = limbs_from_prev_access(&row.x_access[8..16]);
+        let y0: Limbs<_> = limbs_from_prev_access(&row.y_access[0..8]);
+        let y1: Limbs<_> = limbs_from_prev_access(&row.y_access[8..16]);
+
+        // z0 = x0 * y0 - x1 * y1.
+        row.x0_mul_y0
+            .eval::<AB, Bn254BaseField, _, _>(builder, &x0, &y0, FieldOperation::Mul);
+        row.x1_mul_y1
+            .eval::<AB, Bn254BaseField, _, _>(builder, &x1, &y1, FieldOperation::Mul);
+        row.z0.eval::<AB, Bn254BaseField, _, _>(
+            builder,
+            &row.x0_mul_y0.result,
+            &row.x1_mul_y1.result,
+            FieldOperation::Sub,
+        );
+
+        // z1 = x0 * y1 + x1 * y0.
+        row.z1
+            .eval::<AB, Bn254BaseField>(builder, &[x0, x1], &[y1, y0]);
+
+        // Constraint self.x_access.value = [self.z0.result, self.z1.result]
+        // This is to ensure that x_access is updated with the new value.
+        for i in 0..NUM_LIMBS {
+            builder
+                .when(row.is_real)
+                .assert_eq(row.z0.result[i], row.x_access[i / 4].value()[i % 4]);
+            builder
+                .when(row.is_real)
+                .assert_eq(row.z1.result[i], row.x_access[8 + i / 4].value()[i % 4]);
+        }
+
+        builder.constraint_memory_access(
+            row.shard,
+            row.clk, // clk + 0 -> C
+            AB::F::from_canonical_u32(11),
+            &row.y_ptr_access,
+            row.is_real,
+        );
+        for i in 0..NUM_WORDS_FP2_ELEMENT as u32 {
+            builder.constraint_memory_access(
+                row.shard,
+                row.clk, // clk + 0 -> Memory
+                row.y_ptr + AB::F::from_canonical_u32(i * 4),
+                &row.y_access[i as usize],
+                row.is_real,
+            );
+        }
+        for i in 0..NUM_WORDS_FP2_ELEMENT as u32 {
+            builder.constraint_memory_access(
+                row.shard,
+                row.clk + AB::F::from_canonical_u32(4), // clk + 4 -> Memory
+                row.x_ptr + AB::F::from_canonical_u32(i * 4),
+                &row.x_access[i as usize],
+                row.is_real,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        runtime::{tests::halting, Instruction, Opcode, Program, Runtime, SyscallCode},
+        utils::{run_test, setup_logger},
+    };
+
+    /// Two elements `x` and `y` of the quadratic extension, and their product, as little-endian
+    /// words of the real part followed by the imaginary part.
+    const X: [u32; 16] = [
+        0x24a138e5, 0x3267e6dc, 0x59dbefa3, 0xb5b4c5e5, 0x1be06ac3, 0x81be1899, 0xceb8aaae,
+        0x2b149d40, 0x6e0c2c4b, 0x24c6b8ee, 0x678e2ac0, 0xb080cb99, 0xc7729f7d, 0xa27fb246,
+        0x76fd0675, 0x12acf2ca,
+    ];
+    const Y: [u32; 16] = [
+        0xdcc9e470, 0xd60b35da, 0x292f2176, 0x5c521e08, 0x76e68b60, 0xe8b99fdd, 0x2865a7df,
+        0x1284b71c, 0x80f362ac, 0xca5cf05f, 0x8eeec7e5, 0x74799277, 0x12150b8e, 0xa6327cfe,
+        0xb4fae7e6, 0x246996f3,
+    ];
+    const X_MUL_Y: [u32; 16] = [
+        0x67043215, 0x8344d647, 0x4913caba, 0x1709bf5f, 0x2b89ccc4, 0x5dfe4b16, 0x02a45730,
+        0x2aabbd9b, 0xc7133bea, 0xa0077ea2, 0xf7229bc4, 0xbc806da3, 0xf70f0cec, 0xf78fb746,
+        0x3be59576, 0x16660a0b,
+    ];
+
+    const X_PTR: u32 = 100;
+    const Y_PTR: u32 = 200;
+
+    /// A program which writes `x` and `y` to memory and multiplies `x` by `y` with
+    /// `BN254_FP2_MUL`.
+    fn bn254_fp2_mul_program(x: &[u32; 16], y: &[u32; 16]) -> Program {
+        let mut instructions = Vec::new();
+        for (ptr, element) in [(X_PTR, x), (Y_PTR, y)] {
+            for (i, &word) in element.iter().enumerate() {
+                instructions.extend(vec![
+                    Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                    Instruction::new(Opcode::ADD, 30, 0, ptr + i as u32 * 4, false, true),
+                    Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                ]);
+            }
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::BN254_FP2_MUL as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, X_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, Y_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_bn254_fp2_mul_execute() {
+        let mut runtime = Runtime::new(bn254_fp2_mul_program(&X, &Y));
+        runtime.run().unwrap();
+        let result = (0..16)
+            .map(|i| runtime.word(X_PTR + i * 4))
+            .collect::<Vec<_>>();
+        assert_eq!(result, X_MUL_Y);
+        assert_eq!(runtime.record.bn254_fp2_mul_events.len(), 1);
+    }
+
+    #[test]
+    fn test_bn254_fp2_mul_prove() {
+        setup_logger();
+        let program = bn254_fp2_mul_program(&X, &Y);
+        run_test(halting(program)).unwrap();
+    }
+}
@@ -0,0 +1,3 @@
+mod fp2_mul;
+
+pub use fp2_mul::*;
@@ -10,6 +10,7 @@ use crate::operations::field::field_op::FieldOpCols;
 use crate::operations::field::field_op::FieldOperation;
 use crate::operations::field::params::Limbs;
 use crate::operations::field::params::NUM_LIMBS;
+use crate::runtime::ExecutionErrorKind;
 use crate::runtime::ExecutionRecord;
 use crate::runtime::Syscall;
 use crate::syscall::precompiles::create_ec_add_event;
@@ -114,10 +115,10 @@ impl<E: EllipticCurve + EdwardsParameters> Syscall for EdAddAssignChip<E> {
         8
     }
 
-    fn execute(&self, rt: &mut SyscallContext) -> u32 {
-        let event = create_ec_add_event::<E>(rt);
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let event = create_ec_add_event::<E>(rt)?;
         rt.record_mut().ed_add_events.push(event.clone());
-        event.p_ptr + 1
+        Ok(event.p_ptr + 1)
     }
 }
 
@@ -206,10 +207,10 @@ where
         let main = builder.main();
         let row: &EdAddAssignCols<AB::Var> = main.row_slice(0).borrow();
 
-        let x1 = limbs_from_prev_access(&row.p_access[0..8]);
-        let x2 = limbs_from_prev_access(&row.q_access[0..8]);
-        let y1 = limbs_from_prev_access(&row.p_access[8..16]);
-        let y2 = limbs_from_prev_access(&row.q_access[8..16]);
+        let x1: Limbs<_> = limbs_from_prev_access(&row.p_access[0..8]);
+        let x2: Limbs<_> = limbs_from_prev_access(&row.q_access[0..8]);
+        let y1: Limbs<_> = limbs_from_prev_access(&row.p_access[8..16]);
+        let y2: Limbs<_> = limbs_from_prev_access(&row.q_access[8..16]);
 
         // x3_numerator = x1 * y2 + x2 * y1.
         row.x3_numerator
@@ -233,7 +234,7 @@ where
         // d * f.
         let f = row.f.result;
         let d_biguint = E::d_biguint();
-        let d_const = E::BaseField::to_limbs_field::<AB::F>(&d_biguint);
+        let d_const: Limbs<AB::F> = E::BaseField::to_limbs_field(&d_biguint);
         let d_const_expr = Limbs::<AB::Expr>(d_const.0.map(|x| x.into()));
         row.d_mul_f
             .eval::<AB, E::BaseField, _, _>(builder, &f, &d_const_expr, FieldOperation::Mul);
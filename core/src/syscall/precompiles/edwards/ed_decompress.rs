@@ -9,6 +9,8 @@ use crate::memory::MemoryWriteCols;
 use crate::operations::field::field_op::FieldOpCols;
 use crate::operations::field::field_op::FieldOperation;
 use crate::operations::field::field_sqrt::FieldSqrtCols;
+use crate::operations::field::params::Limbs;
+use crate::runtime::ExecutionErrorKind;
 use crate::runtime::ExecutionRecord;
 use crate::runtime::Syscall;
 use crate::syscall::precompiles::SyscallContext;
@@ -128,7 +130,7 @@ impl<V: Copy> EdDecompressCols<V> {
             self.x_access[NUM_WORDS_FIELD_ELEMENT - 1].prev_value[WORD_SIZE - 1].into();
         builder.assert_bool(sign.clone());
 
-        let y = limbs_from_prev_access(&self.y_access);
+        let y: Limbs<_> = limbs_from_prev_access(&self.y_access);
         self.yy
             .eval::<AB, P, _, _>(builder, &y, &y, FieldOperation::Mul);
         self.u.eval::<AB, P, _, _>(
@@ -138,7 +140,7 @@ impl<V: Copy> EdDecompressCols<V> {
             FieldOperation::Sub,
         );
         let d_biguint = E::d_biguint();
-        let d_const = E::BaseField::to_limbs_field::<AB::F>(&d_biguint);
+        let d_const: Limbs<AB::F> = E::BaseField::to_limbs_field(&d_biguint);
         self.dyy
             .eval::<AB, P, _, _>(builder, &d_const, &self.yy.result, FieldOperation::Mul);
         self.v.eval::<AB, P, _, _>(
@@ -180,7 +182,7 @@ impl<V: Copy> EdDecompressCols<V> {
             );
         }
 
-        let x_limbs = limbs_from_access(&self.x_access);
+        let x_limbs: Limbs<_> = limbs_from_access(&self.x_access);
         builder
             .when(self.is_real)
             .when(sign.clone())
@@ -198,7 +200,7 @@ pub struct EdDecompressChip<E> {
 }
 
 impl<E: EdwardsParameters> Syscall for EdDecompressChip<E> {
-    fn execute(&self, rt: &mut SyscallContext) -> u32 {
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         let a0 = crate::runtime::Register::X10;
 
         let start_clk = rt.clk;
@@ -206,13 +208,13 @@ impl<E: EdwardsParameters> Syscall for EdDecompressChip<E> {
         // TODO: this will have to be be constrained, but can do it later.
         let slice_ptr = rt.register_unsafe(a0);
         if slice_ptr % 4 != 0 {
-            panic!();
+            return Err(ExecutionErrorKind::UnalignedMemoryAccess(slice_ptr));
         }
 
         let (y_memory_records_vec, y_vec) = rt.mr_slice(
-            slice_ptr + (COMPRESSED_POINT_BYTES as u32),
+            slice_ptr.wrapping_add(COMPRESSED_POINT_BYTES as u32),
             NUM_WORDS_FIELD_ELEMENT,
-        );
+        )?;
         let y_memory_records: [MemoryReadRecord; 8] = y_memory_records_vec.try_into().unwrap();
 
         // This unsafe read is okay because we do mw_slice into the first 8 words later.
@@ -238,7 +240,7 @@ impl<E: EdwardsParameters> Syscall for EdDecompressChip<E> {
             bytes_to_words_le(&decompressed_x_bytes);
 
         // Write decompressed X into slice
-        let x_memory_records_vec = rt.mw_slice(slice_ptr, &decompressed_x_words);
+        let x_memory_records_vec = rt.mw_slice(slice_ptr, &decompressed_x_words)?;
         let x_memory_records: [MemoryWriteRecord; 8] = x_memory_records_vec.try_into().unwrap();
 
         let shard = rt.current_shard();
@@ -257,7 +259,7 @@ impl<E: EdwardsParameters> Syscall for EdDecompressChip<E> {
 
         rt.clk += 4;
 
-        slice_ptr
+        Ok(slice_ptr)
     }
 
     fn num_extra_cycles(&self) -> u32 {
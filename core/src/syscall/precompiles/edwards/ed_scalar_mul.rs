@@ -0,0 +1,909 @@
+use crate::air::BaseAirBuilder;
+use crate::air::MachineAir;
+use crate::air::SP1AirBuilder;
+use crate::cpu::MemoryReadRecord;
+use crate::cpu::MemoryWriteRecord;
+use crate::field::event::FieldEvent;
+use crate::memory::MemoryCols;
+use crate::memory::MemoryReadCols;
+use crate::memory::MemoryWriteCols;
+use crate::operations::field::field_den::FieldDenCols;
+use crate::operations::field::field_inner_product::FieldInnerProductCols;
+use crate::operations::field::field_op::FieldOpCols;
+use crate::operations::field::field_op::FieldOperation;
+use crate::operations::field::params::Limbs;
+use crate::operations::field::params::NUM_LIMBS;
+use crate::runtime::ExecutionErrorKind;
+use crate::runtime::ExecutionRecord;
+use crate::runtime::Syscall;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::edwards::EdwardsParameters;
+use crate::utils::ec::field::FieldParameters;
+use crate::utils::ec::AffinePoint;
+use crate::utils::ec::EllipticCurve;
+use crate::utils::ec::NUM_WORDS_EC_POINT;
+use crate::utils::ec::NUM_WORDS_FIELD_ELEMENT;
+use crate::utils::limbs_from_access;
+use crate::utils::limbs_from_prev_access;
+use crate::utils::words_to_bytes_le;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use num::BigUint;
+use num::Zero;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_maybe_rayon::prelude::IntoParallelRefIterator;
+use p3_maybe_rayon::prelude::ParallelIterator;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::marker::PhantomData;
+use tracing::instrument;
+
+/// The number of bits of a scalar, each of which is handled by a row of the trace.
+pub const NUM_SCALAR_BITS: usize = NUM_WORDS_FIELD_ELEMENT * 32;
+
+pub const NUM_ED_SCALAR_MUL_COLS: usize = size_of::<EdScalarMulCols<u8>>();
+
+/// The number of words of the arguments of `ED_SCALAR_MUL`: the scalars `a` and `b`, and the point
+/// `q`.
+pub const NUM_ARGS_WORDS: usize = 2 * NUM_WORDS_FIELD_ELEMENT + NUM_WORDS_EC_POINT;
+
+/// Edwards curve double scalar multiplication event, which computes `a * p + b * q`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdScalarMulEvent {
+    pub shard: u32,
+    pub clk: u32,
+    pub p_ptr: u32,
+    pub p: [u32; NUM_WORDS_EC_POINT],
+    pub args_ptr: u32,
+    pub a: [u32; NUM_WORDS_FIELD_ELEMENT],
+    pub b: [u32; NUM_WORDS_FIELD_ELEMENT],
+    pub q: [u32; NUM_WORDS_EC_POINT],
+    pub args_ptr_record: MemoryReadRecord,
+    pub p_memory_records: [MemoryWriteRecord; NUM_WORDS_EC_POINT],
+    pub args_memory_records: [MemoryReadRecord; NUM_ARGS_WORDS],
+}
+
+/// A set of columns to compute the sum of two points `(x1, y1)` and `(x2, y2)` on an Edwards curve,
+/// with the same operations as `EdAddAssignCols`.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct EdAddCols<T> {
+    pub(crate) x3_numerator: FieldInnerProductCols<T>,
+    pub(crate) y3_numerator: FieldInnerProductCols<T>,
+    pub(crate) x1_mul_y1: FieldOpCols<T>,
+    pub(crate) x2_mul_y2: FieldOpCols<T>,
+    pub(crate) f: FieldOpCols<T>,
+    pub(crate) d_mul_f: FieldOpCols<T>,
+    pub(crate) x3_ins: FieldDenCols<T>,
+    pub(crate) y3_ins: FieldDenCols<T>,
+}
+
+impl<F: PrimeField32> EdAddCols<F> {
+    pub fn populate<E: EdwardsParameters>(
+        &mut self,
+        p_x: &BigUint,
+        p_y: &BigUint,
+        q_x: &BigUint,
+        q_y: &BigUint,
+    ) -> (BigUint, BigUint) {
+        let x3_numerator = self
+            .x3_numerator
+            .populate::<E::BaseField>(&[p_x.clone(), q_x.clone()], &[q_y.clone(), p_y.clone()]);
+        let y3_numerator = self
+            .y3_numerator
+            .populate::<E::BaseField>(&[p_y.clone(), p_x.clone()], &[q_y.clone(), q_x.clone()]);
+        let x1_mul_y1 = self
+            .x1_mul_y1
+            .populate::<E::BaseField>(p_x, p_y, FieldOperation::Mul);
+        let x2_mul_y2 = self
+            .x2_mul_y2
+            .populate::<E::BaseField>(q_x, q_y, FieldOperation::Mul);
+        let f = self
+            .f
+            .populate::<E::BaseField>(&x1_mul_y1, &x2_mul_y2, FieldOperation::Mul);
+
+        let d = E::d_biguint();
+        let d_mul_f = self
+            .d_mul_f
+            .populate::<E::BaseField>(&f, &d, FieldOperation::Mul);
+
+        let x3 = self
+            .x3_ins
+            .populate::<E::BaseField>(&x3_numerator, &d_mul_f, true);
+        let y3 = self
+            .y3_ins
+            .populate::<E::BaseField>(&y3_numerator, &d_mul_f, false);
+        (x3, y3)
+    }
+}
+
+impl<V: Copy> EdAddCols<V> {
+    pub fn eval<AB: SP1AirBuilder<Var = V>, E: EdwardsParameters>(
+        &self,
+        builder: &mut AB,
+        x1: Limbs<V>,
+        y1: Limbs<V>,
+        x2: Limbs<V>,
+        y2: Limbs<V>,
+    ) where
+        V: Into<AB::Expr>,
+    {
+        // x3_numerator = x1 * y2 + x2 * y1.
+        self.x3_numerator
+            .eval::<AB, E::BaseField>(builder, &[x1, x2], &[y2, y1]);
+
+        // y3_numerator = y1 * y2 + x1 * x2.
+        self.y3_numerator
+            .eval::<AB, E::BaseField>(builder, &[y1, x1], &[y2, x2]);
+
+        // f = x1 * x2 * y1 * y2.
+        self.x1_mul_y1
+            .eval::<AB, E::BaseField, _, _>(builder, &x1, &y1, FieldOperation::Mul);
+        self.x2_mul_y2
+            .eval::<AB, E::BaseField, _, _>(builder, &x2, &y2, FieldOperation::Mul);
+        self.f.eval::<AB, E::BaseField, _, _>(
+            builder,
+            &self.x1_mul_y1.result,
+            &self.x2_mul_y2.result,
+            FieldOperation::Mul,
+        );
+
+        // d * f.
+        let d_biguint = E::d_biguint();
+        let d_const: Limbs<AB::F> = E::BaseField::to_limbs_field(&d_biguint);
+        let d_const_expr = Limbs::<AB::Expr>(d_const.0.map(|x| x.into()));
+        self.d_mul_f.eval::<AB, E::BaseField, _, _>(
+            builder,
+            &self.f.result,
+            &d_const_expr,
+            FieldOperation::Mul,
+        );
+
+        // x3 = x3_numerator / (1 + d * f).
+        self.x3_ins.eval::<AB, E::BaseField>(
+            builder,
+            &self.x3_numerator.result,
+            &self.d_mul_f.result,
+            true,
+        );
+
+        // y3 = y3_numerator / (1 - d * f).
+        self.y3_ins.eval::<AB, E::BaseField>(
+            builder,
+            &self.y3_numerator.result,
+            &self.d_mul_f.result,
+            false,
+        );
+    }
+}
+/// A set of columns to compute `EdScalarMul`, which is the double scalar multiplication
+/// `a * p + b * q` with Straus' method. Each event spans `NUM_SCALAR_BITS` rows, and the row `i` of
+/// an event handles the bit `NUM_SCALAR_BITS - 1 - i` of both scalars, starting from the most
+/// significant one: the accumulator is doubled, and `p`, `q`, `p + q` or nothing is added to it.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct EdScalarMulCols<T> {
+    pub is_real: T,
+    pub shard: T,
+    pub clk: T,
+    pub p_ptr: T,
+    pub args_ptr: T,
+
+    /// The one-hot encodings of the index of the current byte of the scalars, counted from the most
+    /// significant one, and of the index of the current bit within that byte, counted the same way.
+    pub byte_index: [T; NUM_WORDS_FIELD_ELEMENT * 4],
+    pub bit_index: [T; 8],
+
+    /// Whether the row is the first row of an event, and whether it is the last one.
+    pub is_start: T,
+    pub is_end: T,
+
+    /// Whether the row is the first row of a real event, on which the memory is accessed.
+    pub is_real_start: T,
+
+    /// The memory accesses, which are copied over all the rows of an event. The arguments are the
+    /// scalars `a` and `b` followed by the point `q`.
+    pub args_ptr_access: MemoryReadCols<T>,
+    pub args_access: [MemoryReadCols<T>; NUM_ARGS_WORDS],
+    pub p_access: [MemoryWriteCols<T>; NUM_WORDS_EC_POINT],
+
+    /// The bits of the current bytes of the scalars, the current bits, and their product.
+    pub a_byte_bits: [T; 8],
+    pub b_byte_bits: [T; 8],
+    pub a_bit: T,
+    pub b_bit: T,
+    pub ab_bit: T,
+
+    /// The points added by `lhs_plus_rhs`, which are both the accumulator, except on the first row
+    /// where they are `p` and `q`.
+    pub lhs_x: Limbs<T>,
+    pub lhs_y: Limbs<T>,
+    pub rhs_x: Limbs<T>,
+    pub rhs_y: Limbs<T>,
+
+    /// `p + q`, which is computed on the first row and copied over the other rows.
+    pub p_plus_q_x: Limbs<T>,
+    pub p_plus_q_y: Limbs<T>,
+
+    /// The point selected by the current bits: the neutral element, `p`, `q` or `p + q`.
+    pub selected_x: Limbs<T>,
+    pub selected_y: Limbs<T>,
+
+    pub(crate) lhs_plus_rhs: EdAddCols<T>,
+    pub(crate) sum_plus_selected: EdAddCols<T>,
+
+    /// The accumulator of the next row, `2 * acc + selected`.
+    pub new_acc_x: Limbs<T>,
+    pub new_acc_y: Limbs<T>,
+}
+
+impl<F: PrimeField32> EdScalarMulCols<F> {
+    fn populate_flags(&mut self, row: usize) {
+        let i = row % NUM_SCALAR_BITS;
+        self.byte_index[i / 8] = F::one();
+        self.bit_index[i % 8] = F::one();
+        self.is_start = F::from_bool(i == 0);
+        self.is_end = F::from_bool(i == NUM_SCALAR_BITS - 1);
+        self.is_real_start = self.is_real * self.is_start;
+    }
+}
+
+#[derive(Default)]
+pub struct EdScalarMulChip<E> {
+    _marker: PhantomData<E>,
+}
+
+impl<E: EllipticCurve + EdwardsParameters> EdScalarMulChip<E> {
+    pub fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    fn neutral() -> AffinePoint<E> {
+        let (x, y) = E::neutral();
+        AffinePoint::new(x, y)
+    }
+
+    /// Generates the `NUM_SCALAR_BITS` rows computing `a * p + b * q`, starting from `row`, which
+    /// holds the columns that are the same on all the rows of the event.
+    fn populate_rows<F: PrimeField32>(
+        row: [F; NUM_ED_SCALAR_MUL_COLS],
+        p: &AffinePoint<E>,
+        q: &AffinePoint<E>,
+        a: &[u32],
+        b: &[u32],
+    ) -> Vec<[F; NUM_ED_SCALAR_MUL_COLS]> {
+        let a_bytes: [u8; NUM_WORDS_FIELD_ELEMENT * 4] = words_to_bytes_le(a);
+        let b_bytes: [u8; NUM_WORDS_FIELD_ELEMENT * 4] = words_to_bytes_le(b);
+        let nb_bytes = a_bytes.len();
+
+        let mut acc = Self::neutral();
+        let mut p_plus_q = Self::neutral();
+
+        let mut rows = Vec::with_capacity(NUM_SCALAR_BITS);
+        for i in 0..NUM_SCALAR_BITS {
+            let mut row = row;
+            let cols: &mut EdScalarMulCols<F> = row.as_mut_slice().borrow_mut();
+            cols.populate_flags(i);
+
+            let a_byte = a_bytes[nb_bytes - 1 - i / 8];
+            let b_byte = b_bytes[nb_bytes - 1 - i / 8];
+            for j in 0..8 {
+                cols.a_byte_bits[j] = F::from_canonical_u8((a_byte >> j) & 1);
+                cols.b_byte_bits[j] = F::from_canonical_u8((b_byte >> j) & 1);
+            }
+            let a_bit = (a_byte >> (7 - i % 8)) & 1 == 1;
+            let b_bit = (b_byte >> (7 - i % 8)) & 1 == 1;
+            cols.a_bit = F::from_bool(a_bit);
+            cols.b_bit = F::from_bool(b_bit);
+            cols.ab_bit = F::from_bool(a_bit && b_bit);
+
+            // On the first row, the accumulator is the neutral element so it doesn't need to be
+            // doubled, and the addition computes `p + q` instead.
+            let (lhs, rhs) = if i == 0 { (p, q) } else { (&acc, &acc) };
+            cols.lhs_x = E::BaseField::to_limbs_field(&lhs.x);
+            cols.lhs_y = E::BaseField::to_limbs_field(&lhs.y);
+            cols.rhs_x = E::BaseField::to_limbs_field(&rhs.x);
+            cols.rhs_y = E::BaseField::to_limbs_field(&rhs.y);
+            let (sum_x, sum_y) = cols
+                .lhs_plus_rhs
+                .populate::<E>(&lhs.x, &lhs.y, &rhs.x, &rhs.y);
+            let sum = AffinePoint::new(sum_x, sum_y);
+            if i == 0 {
+                p_plus_q = sum.clone();
+            }
+            cols.p_plus_q_x = E::BaseField::to_limbs_field(&p_plus_q.x);
+            cols.p_plus_q_y = E::BaseField::to_limbs_field(&p_plus_q.y);
+
+            let selected = match (a_bit, b_bit) {
+                (false, false) => Self::neutral(),
+                (true, false) => p.clone(),
+                (false, true) => q.clone(),
+                (true, true) => p_plus_q.clone(),
+            };
+            cols.selected_x = E::BaseField::to_limbs_field(&selected.x);
+            cols.selected_y = E::BaseField::to_limbs_field(&selected.y);
+
+            let (new_acc_x, new_acc_y) =
+                cols.sum_plus_selected
+                    .populate::<E>(&sum.x, &sum.y, &selected.x, &selected.y);
+            acc = if i == 0 {
+                selected
+            } else {
+                AffinePoint::new(new_acc_x, new_acc_y)
+            };
+            cols.new_acc_x = E::BaseField::to_limbs_field(&acc.x);
+            cols.new_acc_y = E::BaseField::to_limbs_field(&acc.y);
+
+            rows.push(row);
+        }
+        rows
+    }
+
+    /// Generates the `NUM_SCALAR_BITS` rows of an event.
+    fn event_rows<F: PrimeField32>(
+        event: &EdScalarMulEvent,
+        new_field_events: &mut Vec<FieldEvent>,
+    ) -> Vec<[F; NUM_ED_SCALAR_MUL_COLS]> {
+        // Populate the columns which are the same on all the rows.
+        let mut row = [F::zero(); NUM_ED_SCALAR_MUL_COLS];
+        let cols: &mut EdScalarMulCols<F> = row.as_mut_slice().borrow_mut();
+        cols.is_real = F::one();
+        cols.shard = F::from_canonical_u32(event.shard);
+        cols.clk = F::from_canonical_u32(event.clk);
+        cols.p_ptr = F::from_canonical_u32(event.p_ptr);
+        cols.args_ptr = F::from_canonical_u32(event.args_ptr);
+        cols.args_ptr_access
+            .populate(event.args_ptr_record, new_field_events);
+        for i in 0..NUM_ARGS_WORDS {
+            cols.args_access[i].populate(event.args_memory_records[i], new_field_events);
+        }
+        for i in 0..NUM_WORDS_EC_POINT {
+            cols.p_access[i].populate(event.p_memory_records[i], new_field_events);
+        }
+
+        let p = AffinePoint::<E>::from_words_le(&event.p);
+        let q = AffinePoint::<E>::from_words_le(&event.q);
+        Self::populate_rows(row, &p, &q, &event.a, &event.b)
+    }
+}
+
+impl<E: EllipticCurve + EdwardsParameters> Syscall for EdScalarMulChip<E> {
+    fn num_extra_cycles(&self) -> u32 {
+        8
+    }
+
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let a0 = crate::runtime::Register::X10;
+        let a1 = crate::runtime::Register::X11;
+
+        let start_clk = rt.clk;
+
+        // TODO: these will have to be constrained, but can do it later.
+        let p_ptr = rt.register_unsafe(a0);
+        if p_ptr % 4 != 0 {
+            return Err(ExecutionErrorKind::UnalignedMemoryAccess(p_ptr));
+        }
+
+        let (args_ptr_record, args_ptr) = rt.mr(a1 as u32);
+        if args_ptr % 4 != 0 {
+            return Err(ExecutionErrorKind::UnalignedMemoryAccess(args_ptr));
+        }
+
+        let p: [u32; NUM_WORDS_EC_POINT] = rt
+            .slice_unsafe(p_ptr, NUM_WORDS_EC_POINT)
+            .try_into()
+            .unwrap();
+        let (args_memory_records, args) = rt.mr_slice(args_ptr, NUM_ARGS_WORDS)?;
+        let (a, rest) = args.split_at(NUM_WORDS_FIELD_ELEMENT);
+        let (b, q) = rest.split_at(NUM_WORDS_FIELD_ELEMENT);
+        // When we write to p, we want the clk to be incremented.
+        rt.clk += 4;
+
+        let p_affine = AffinePoint::<E>::from_words_le(&p);
+        let q_affine = AffinePoint::<E>::from_words_le(q);
+        let result_affine = p_affine.scalar_mul(&BigUint::from_slice(a))
+            + q_affine.scalar_mul(&BigUint::from_slice(b));
+        let result_words = result_affine.to_words_le();
+
+        let p_memory_records = rt.mw_slice(p_ptr, &result_words)?;
+
+        rt.clk += 4;
+
+        let shard = rt.current_shard();
+        rt.record_mut().ed_scalar_mul_events.push(EdScalarMulEvent {
+            shard,
+            clk: start_clk,
+            p_ptr,
+            p,
+            args_ptr,
+            a: a.try_into().unwrap(),
+            b: b.try_into().unwrap(),
+            q: q.try_into().unwrap(),
+            args_ptr_record,
+            p_memory_records: p_memory_records.try_into().unwrap(),
+            args_memory_records: args_memory_records.try_into().unwrap(),
+        });
+
+        Ok(p_ptr)
+    }
+}
+
+impl<F: PrimeField32, E: EllipticCurve + EdwardsParameters> MachineAir<F> for EdScalarMulChip<E> {
+    fn name(&self) -> String {
+        "EdScalarMul".to_string()
+    }
+
+    #[instrument(name = "generate Ed scalar mul trace", skip_all)]
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let (rows_list, new_field_events_list): (
+            Vec<Vec<[F; NUM_ED_SCALAR_MUL_COLS]>>,
+            Vec<Vec<FieldEvent>>,
+        ) = input
+            .ed_scalar_mul_events
+            .par_iter()
+            .map(|event| {
+                let mut new_field_events = Vec::new();
+                let rows = Self::event_rows(event, &mut new_field_events);
+                (rows, new_field_events)
+            })
+            .unzip();
+
+        for new_field_events in new_field_events_list {
+            output.add_field_events(&new_field_events);
+        }
+
+        let mut rows = rows_list.into_iter().flatten().collect::<Vec<_>>();
+
+        // The padding rows compute `0 * (0, 0) + 0 * (0, 0)`, which is not a point of the curve but
+        // goes through the same constraints. They are not all the same as the flags keep track of
+        // the position in the current event, so `pad_rows` can't be used.
+        let nb_rows = rows.len();
+        let mut padded_nb_rows = nb_rows.next_power_of_two();
+        if padded_nb_rows < 4 {
+            padded_nb_rows = 4;
+        }
+        if padded_nb_rows > nb_rows {
+            let zero = AffinePoint::new(BigUint::zero(), BigUint::zero());
+            let zero_scalar = [0u32; NUM_WORDS_FIELD_ELEMENT];
+            let padding_rows = Self::populate_rows(
+                [F::zero(); NUM_ED_SCALAR_MUL_COLS],
+                &zero,
+                &zero,
+                &zero_scalar,
+                &zero_scalar,
+            );
+            for i in nb_rows..padded_nb_rows {
+                rows.push(padding_rows[i % NUM_SCALAR_BITS]);
+            }
+        }
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_ED_SCALAR_MUL_COLS,
+        )
+    }
+}
+
+impl<F, E: EllipticCurve + EdwardsParameters> BaseAir<F> for EdScalarMulChip<E> {
+    fn width(&self) -> usize {
+        NUM_ED_SCALAR_MUL_COLS
+    }
+}
+
+impl<AB, E: EllipticCurve + EdwardsParameters> Air<AB> for EdScalarMulChip<E>
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: &EdScalarMulCols<AB::Var> = main.row_slice(0).borrow();
+        let next: &EdScalarMulCols<AB::Var> = main.row_slice(1).borrow();
+
+        // The flags start at the first byte and bit of the scalars.
+        builder.when_first_row().assert_one(local.byte_index[0]);
+        builder.when_first_row().assert_one(local.bit_index[0]);
+        for i in 1..local.byte_index.len() {
+            builder.when_first_row().assert_zero(local.byte_index[i]);
+        }
+        for i in 1..local.bit_index.len() {
+            builder.when_first_row().assert_zero(local.bit_index[i]);
+        }
+
+        // Move to the next bit on every row, and to the next byte after the last bit of a byte.
+        for i in 0..local.bit_index.len() {
+            builder
+                .when_transition()
+                .assert_eq(local.bit_index[i], next.bit_index[(i + 1) % 8]);
+        }
+        let nb_bytes = local.byte_index.len();
+        for i in 0..nb_bytes {
+            builder
+                .when_transition()
+                .when(local.bit_index[7])
+                .assert_eq(local.byte_index[i], next.byte_index[(i + 1) % nb_bytes]);
+            builder
+                .when_transition()
+                .when_not(local.bit_index[7])
+                .assert_eq(local.byte_index[i], next.byte_index[i]);
+        }
+        builder.assert_eq(local.is_start, local.byte_index[0] * local.bit_index[0]);
+        builder.assert_eq(
+            local.is_end,
+            local.byte_index[nb_bytes - 1] * local.bit_index[7],
+        );
+        builder.assert_bool(local.is_real);
+        builder.assert_eq(local.is_real_start, local.is_real * local.is_start);
+
+        // Copy over the inputs until the last row of the event.
+        builder
+            .when_transition()
+            .when_not(local.is_end)
+            .assert_eq(local.is_real, next.is_real);
+        builder
+            .when_transition()
+            .when_not(local.is_end)
+            .assert_eq(local.shard, next.shard);
+        builder
+            .when_transition()
+            .when_not(local.is_end)
+            .assert_eq(local.clk, next.clk);
+        builder
+            .when_transition()
+            .when_not(local.is_end)
+            .assert_eq(local.p_ptr, next.p_ptr);
+        builder
+            .when_transition()
+            .when_not(local.is_end)
+            .assert_eq(local.args_ptr, next.args_ptr);
+        for i in 0..NUM_ARGS_WORDS {
+            builder
+                .when_transition()
+                .when_not(local.is_end)
+                .assert_word_eq(*local.args_access[i].value(), *next.args_access[i].value());
+        }
+        for i in 0..NUM_WORDS_EC_POINT {
+            builder
+                .when_transition()
+                .when_not(local.is_end)
+                .assert_word_eq(
+                    *local.p_access[i].prev_value(),
+                    *next.p_access[i].prev_value(),
+                );
+            builder
+                .when_transition()
+                .when_not(local.is_end)
+                .assert_word_eq(*local.p_access[i].value(), *next.p_access[i].value());
+        }
+
+        // Select the current bits of the scalars, starting from the most significant byte and bit.
+        let scalar_bytes = local
+            .args_access
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+        let (a_bytes, rest) = scalar_bytes.split_at(nb_bytes);
+        let b_bytes = &rest[..nb_bytes];
+        for (scalar_bytes, byte_bits, scalar_bit) in [
+            (a_bytes, local.a_byte_bits, local.a_bit),
+            (b_bytes, local.b_byte_bits, local.b_bit),
+        ] {
+            let byte: AB::Expr = local
+                .byte_index
+                .iter()
+                .zip(scalar_bytes.iter().rev())
+                .map(|(selector, byte)| *selector * *byte)
+                .sum();
+            for bit in byte_bits {
+                builder.assert_bool(bit);
+            }
+            let recomputed_byte: AB::Expr = byte_bits
+                .iter()
+                .enumerate()
+                .map(|(i, bit)| *bit * AB::F::from_canonical_u32(1 << i))
+                .sum();
+            builder.assert_eq(recomputed_byte, byte);
+            let bit: AB::Expr = local
+                .bit_index
+                .iter()
+                .zip(byte_bits.iter().rev())
+                .map(|(selector, bit)| *selector * *bit)
+                .sum();
+            builder.assert_eq(scalar_bit, bit);
+        }
+        builder.assert_eq(local.ab_bit, local.a_bit * local.b_bit);
+
+        // The inputs of the first addition are `p` and `q` on the first row, and the accumulator
+        // passed by the previous row otherwise.
+        let p_x: Limbs<_> = limbs_from_prev_access(&local.p_access[0..8]);
+        let p_y: Limbs<_> = limbs_from_prev_access(&local.p_access[8..16]);
+        let q_x: Limbs<_> = limbs_from_access(&local.args_access[16..24]);
+        let q_y: Limbs<_> = limbs_from_access(&local.args_access[24..32]);
+        builder
+            .when(local.is_real_start)
+            .assert_all_eq(local.lhs_x, p_x);
+        builder
+            .when(local.is_real_start)
+            .assert_all_eq(local.lhs_y, p_y);
+        builder
+            .when(local.is_real_start)
+            .assert_all_eq(local.rhs_x, q_x);
+        builder
+            .when(local.is_real_start)
+            .assert_all_eq(local.rhs_y, q_y);
+        for (next_point, new_acc) in [
+            (next.lhs_x, local.new_acc_x),
+            (next.lhs_y, local.new_acc_y),
+            (next.rhs_x, local.new_acc_x),
+            (next.rhs_y, local.new_acc_y),
+        ] {
+            builder
+                .when_transition()
+                .when_not(local.is_end)
+                .assert_all_eq(next_point, new_acc);
+        }
+
+        // sum = lhs + rhs, which is `p + q` on the first row and `2 * acc` otherwise.
+        local.lhs_plus_rhs.eval::<AB, E>(
+            builder,
+            local.lhs_x,
+            local.lhs_y,
+            local.rhs_x,
+            local.rhs_y,
+        );
+        let sum_x = local.lhs_plus_rhs.x3_ins.result;
+        let sum_y = local.lhs_plus_rhs.y3_ins.result;
+        builder
+            .when(local.is_start)
+            .assert_all_eq(local.p_plus_q_x, sum_x);
+        builder
+            .when(local.is_start)
+            .assert_all_eq(local.p_plus_q_y, sum_y);
+        builder
+            .when_transition()
+            .when_not(local.is_end)
+            .assert_all_eq(local.p_plus_q_x, next.p_plus_q_x);
+        builder
+            .when_transition()
+            .when_not(local.is_end)
+            .assert_all_eq(local.p_plus_q_y, next.p_plus_q_y);
+
+        // selected = O + a * (p - O) + b * (q - O) + ab * (p + q - p - q + O), where O = (0, 1) is
+        // the neutral element.
+        for i in 0..NUM_LIMBS {
+            for (selected, p, q, p_plus_q, neutral) in [
+                (local.selected_x, p_x, q_x, local.p_plus_q_x, 0),
+                (
+                    local.selected_y,
+                    p_y,
+                    q_y,
+                    local.p_plus_q_y,
+                    (i == 0) as u32,
+                ),
+            ] {
+                let neutral = AB::F::from_canonical_u32(neutral);
+                builder.assert_eq(
+                    selected[i],
+                    local.a_bit * (p[i] - neutral)
+                        + local.b_bit * (q[i] - neutral)
+                        + local.ab_bit * (p_plus_q[i] - p[i] - q[i] + neutral)
+                        + neutral,
+                );
+            }
+        }
+
+        // new_acc = sum + selected, except on the first row where the accumulator was the neutral
+        // element and new_acc = selected.
+        local.sum_plus_selected.eval::<AB, E>(
+            builder,
+            sum_x,
+            sum_y,
+            local.selected_x,
+            local.selected_y,
+        );
+        let new_acc_x = local.sum_plus_selected.x3_ins.result;
+        let new_acc_y = local.sum_plus_selected.y3_ins.result;
+        for i in 0..NUM_LIMBS {
+            builder.assert_eq(
+                local.new_acc_x[i],
+                new_acc_x[i] + local.is_start * (local.selected_x[i] - new_acc_x[i]),
+            );
+            builder.assert_eq(
+                local.new_acc_y[i],
+                new_acc_y[i] + local.is_start * (local.selected_y[i] - new_acc_y[i]),
+            );
+        }
+
+        // On the last row, the accumulator is the result written to memory.
+        let result_x: Limbs<_> = limbs_from_access(&local.p_access[0..8]);
+        let result_y: Limbs<_> = limbs_from_access(&local.p_access[8..16]);
+        builder
+            .when(local.is_real)
+            .when(local.is_end)
+            .assert_all_eq(local.new_acc_x, result_x);
+        builder
+            .when(local.is_real)
+            .when(local.is_end)
+            .assert_all_eq(local.new_acc_y, result_y);
+
+        // The memory is accessed once per event, on its first row.
+        builder
+            .when(local.is_real_start)
+            .assert_eq(local.args_ptr, local.args_ptr_access.value().reduce::<AB>());
+        builder.constraint_memory_access(
+            local.shard,
+            local.clk, // clk + 0 -> C
+            AB::F::from_canonical_u32(11),
+            &local.args_ptr_access,
+            local.is_real_start,
+        );
+        for i in 0..NUM_ARGS_WORDS {
+            builder.constraint_memory_access(
+                local.shard,
+                local.clk, // clk + 0 -> Memory
+                local.args_ptr + AB::F::from_canonical_u32((i as u32) * 4),
+                &local.args_access[i],
+                local.is_real_start,
+            );
+        }
+        for i in 0..NUM_WORDS_EC_POINT {
+            builder.constraint_memory_access(
+                local.shard,
+                local.clk + AB::F::from_canonical_u32(4), // clk + 4 -> Memory
+                local.p_ptr + AB::F::from_canonical_u32((i as u32) * 4),
+                &local.p_access[i],
+                local.is_real_start,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+    use num::BigUint;
+
+    use crate::{
+        runtime::{tests::halting, Instruction, Opcode, Program, Runtime, SyscallCode},
+        utils::{
+            ec::{
+                edwards::ed25519::{decompress, Ed25519},
+                AffinePoint, EllipticCurve,
+            },
+            run_test, setup_logger,
+        },
+    };
+
+    const P_PTR: u32 = 100;
+    const ARGS_PTR: u32 = 200;
+
+    /// A program which writes `p`, `a`, `b` and `q` to memory and computes `a * p + b * q` with
+    /// `ED_SCALAR_MUL`.
+    fn scalar_mul_program(p: &[u32], a: &[u32], b: &[u32], q: &[u32]) -> Program {
+        let args = [a, b, q].concat();
+        let mut instructions = Vec::new();
+        for (ptr, words) in [(P_PTR, p), (ARGS_PTR, &args)] {
+            for (i, word) in words.iter().enumerate() {
+                instructions.extend(vec![
+                    Instruction::new(Opcode::ADD, 29, 0, *word, false, true),
+                    Instruction::new(Opcode::ADD, 30, 0, ptr + i as u32 * 4, false, true),
+                    Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                ]);
+            }
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::ED_SCALAR_MUL as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, P_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, ARGS_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// The little endian words of two scalars with both low and high bits set, and some bits set in
+    /// both of them.
+    fn scalars() -> (Vec<u32>, Vec<u32>) {
+        let a = vec![
+            0x1234_5678,
+            0x9abc_def0,
+            0,
+            0xffff_ffff,
+            0x0000_0001,
+            0x8000_0000,
+            0x0f0f_0f0f,
+            0x1000_0000,
+        ];
+        let b = vec![
+            0x8765_4321,
+            0,
+            0xffff_ffff,
+            0x0f0f_0f0f,
+            0x8000_0001,
+            0x0000_ffff,
+            0x1234_5678,
+            0x8000_0000,
+        ];
+        (a, b)
+    }
+
+    /// Runs a program made by `scalar_mul_program` and returns the point written to `P_PTR`.
+    fn run_scalar_mul(program: Program) -> AffinePoint<Ed25519> {
+        let mut runtime = Runtime::new(program);
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.ed_scalar_mul_events.len(), 1);
+        let result = (0..16)
+            .map(|i| runtime.word(P_PTR + i * 4))
+            .collect::<Vec<_>>();
+        AffinePoint::from_words_le(&result)
+    }
+
+    #[test]
+    fn test_ed_scalar_mul_execute() {
+        let generator = Ed25519::ec_generator();
+        let q = generator.scalar_mul(&BigUint::from(7u32));
+        let (a, b) = scalars();
+        let program = scalar_mul_program(&generator.to_words_le(), &a, &b, &q.to_words_le());
+        let expected =
+            generator.scalar_mul(&BigUint::from_slice(&a)) + q.scalar_mul(&BigUint::from_slice(&b));
+        assert_eq!(run_scalar_mul(program), expected);
+    }
+
+    #[test]
+    fn test_ed_scalar_mul_verify_signature() {
+        // The first test vector of RFC 8032, which signs the empty message. The signature is valid
+        // if `s * B - k * A = R`, where `k` is `SHA512(R || A || M)` reduced modulo the group order.
+        let a = hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+            .unwrap();
+        let r = hex::decode("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155")
+            .unwrap();
+        let s = hex::decode("5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b")
+            .unwrap();
+        let k = hex::decode("86eabc8e4c96193d290504e7c600df6cf8d8256131ec2c138a3e7e162e525404")
+            .unwrap();
+
+        let a = decompress(&CompressedEdwardsY(a.try_into().unwrap()));
+        let r = decompress(&CompressedEdwardsY(r.try_into().unwrap()));
+        let scalar_words = |bytes: &[u8]| {
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+                .collect::<Vec<_>>()
+        };
+
+        let program = scalar_mul_program(
+            &Ed25519::ec_generator().to_words_le(),
+            &scalar_words(&s),
+            &scalar_words(&k),
+            &(-a).to_words_le(),
+        );
+        assert_eq!(run_scalar_mul(program), r);
+    }
+
+    #[test]
+    fn test_ed_scalar_mul_prove() {
+        setup_logger();
+        let generator = Ed25519::ec_generator();
+        let q = generator.scalar_mul(&BigUint::from(7u32));
+        let (a, b) = scalars();
+        let program = scalar_mul_program(&generator.to_words_le(), &a, &b, &q.to_words_le());
+        run_test(halting(program)).unwrap();
+    }
+}
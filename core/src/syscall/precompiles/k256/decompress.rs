@@ -9,6 +9,8 @@ use crate::memory::MemoryReadWriteCols;
 use crate::operations::field::field_op::FieldOpCols;
 use crate::operations::field::field_op::FieldOperation;
 use crate::operations::field::field_sqrt::FieldSqrtCols;
+use crate::operations::field::params::Limbs;
+use crate::runtime::ExecutionErrorKind;
 use crate::runtime::ExecutionRecord;
 use crate::runtime::Syscall;
 use crate::syscall::precompiles::SyscallContext;
@@ -76,7 +78,7 @@ impl Syscall for K256DecompressChip {
         4
     }
 
-    fn execute(&self, rt: &mut SyscallContext) -> u32 {
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         let a0 = crate::runtime::Register::X10;
 
         let start_clk = rt.clk;
@@ -84,13 +86,13 @@ impl Syscall for K256DecompressChip {
         // TODO: this will have to be be constrained, but can do it later.
         let slice_ptr = rt.register_unsafe(a0);
         if slice_ptr % 4 != 0 {
-            panic!();
+            return Err(ExecutionErrorKind::UnalignedMemoryAccess(slice_ptr));
         }
 
         let (x_memory_records_vec, x_vec) = rt.mr_slice(
-            slice_ptr + (COMPRESSED_POINT_BYTES as u32),
+            slice_ptr.wrapping_add(COMPRESSED_POINT_BYTES as u32),
             NUM_WORDS_FIELD_ELEMENT,
-        );
+        )?;
         let x_memory_records: [MemoryReadRecord; 8] = x_memory_records_vec.try_into().unwrap();
 
         // This unsafe read is okay because we do mw_slice into the first 8 words later.
@@ -101,9 +103,9 @@ impl Syscall for K256DecompressChip {
         x_bytes_be.reverse();
 
         // Compute actual decompressed Y
-        let computed_point =
-            k256::AffinePoint::decompress((&x_bytes_be).into(), Choice::from(is_odd as u8))
-                .unwrap();
+        let computed_point: Option<k256::AffinePoint> =
+            k256::AffinePoint::decompress((&x_bytes_be).into(), Choice::from(is_odd as u8)).into();
+        let computed_point = computed_point.ok_or(ExecutionErrorKind::InvalidCompressedPoint)?;
 
         let decompressed_point = computed_point.to_encoded_point(false);
         let decompressed_point_bytes = decompressed_point.as_bytes();
@@ -113,7 +115,7 @@ impl Syscall for K256DecompressChip {
         decompressed_y_bytes.reverse();
         let y_words: [u32; NUM_WORDS_FIELD_ELEMENT] = bytes_to_words_le(&decompressed_y_bytes);
 
-        let y_memory_records_vec = rt.mw_slice(slice_ptr, &y_words);
+        let y_memory_records_vec = rt.mw_slice(slice_ptr, &y_words)?;
         let y_memory_records: [MemoryWriteRecord; 8] = y_memory_records_vec.try_into().unwrap();
 
         let shard = rt.current_shard();
@@ -132,7 +134,7 @@ impl Syscall for K256DecompressChip {
 
         rt.clk += 4;
 
-        slice_ptr
+        Ok(slice_ptr)
     }
 }
 
@@ -207,7 +209,7 @@ impl<V: Copy> K256DecompressCols<V> {
         let should_be_odd: AB::Expr = self.y_access[0].prev_value[0].into();
         builder.assert_bool(should_be_odd.clone());
 
-        let x = limbs_from_prev_access(&self.x_access);
+        let x: Limbs<_> = limbs_from_prev_access(&self.x_access);
         self.x_2
             .eval::<AB, Secp256k1BaseField, _, _>(builder, &x, &x, FieldOperation::Mul);
         self.x_3.eval::<AB, Secp256k1BaseField, _, _>(
@@ -217,7 +219,7 @@ impl<V: Copy> K256DecompressCols<V> {
             FieldOperation::Mul,
         );
         let b = Secp256k1Parameters::b_int();
-        let b_const = Secp256k1BaseField::to_limbs_field::<AB::F>(&b);
+        let b_const: Limbs<AB::F> = Secp256k1BaseField::to_limbs_field(&b);
         self.x_3_plus_b.eval::<AB, Secp256k1BaseField, _, _>(
             builder,
             &self.x_3.result,
@@ -254,7 +256,7 @@ impl<V: Copy> K256DecompressCols<V> {
 
         // When y_is_odd == should_be_odd, result is y
         // Equivalent: y_is_odd != !should_be_odd
-        let y_limbs = limbs_from_access(&self.y_access);
+        let y_limbs: Limbs<_> = limbs_from_access(&self.y_access);
         builder
             .when(self.is_real)
             .when_ne(y_is_odd.into(), AB::Expr::one() - should_be_odd.clone())
@@ -1,5 +1,5 @@
 use crate::{
-    runtime::{Register, Syscall},
+    runtime::{ExecutionErrorKind, Register, Syscall},
     syscall::precompiles::{keccak256::KeccakPermuteEvent, SyscallContext},
 };
 
@@ -20,7 +20,7 @@ impl Syscall for KeccakPermuteChip {
         NUM_ROUNDS as u32 * 4
     }
 
-    fn execute(&self, rt: &mut SyscallContext) -> u32 {
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         // Read `state_ptr` from register a0.
         let state_ptr = rt.register_unsafe(Register::X10);
 
@@ -30,7 +30,7 @@ impl Syscall for KeccakPermuteChip {
 
         let mut state = Vec::new();
 
-        let (state_records, state_values) = rt.mr_slice(state_ptr, STATE_NUM_WORDS);
+        let (state_records, state_values) = rt.mr_slice(state_ptr, STATE_NUM_WORDS)?;
         state_read_records.extend_from_slice(&state_records);
 
         for values in state_values.chunks_exact(2) {
@@ -91,7 +91,7 @@ impl Syscall for KeccakPermuteChip {
             values_to_write.push(most_sig);
         }
 
-        let write_records = rt.mw_slice(state_ptr, values_to_write.as_slice());
+        let write_records = rt.mw_slice(state_ptr, values_to_write.as_slice())?;
         state_write_records.extend_from_slice(&write_records);
 
         rt.clk += 4;
@@ -110,6 +110,6 @@ impl Syscall for KeccakPermuteChip {
                 state_addr: state_ptr,
             });
 
-        state_ptr
+        Ok(state_ptr)
     }
 }
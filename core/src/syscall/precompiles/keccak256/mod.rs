@@ -40,7 +40,7 @@ impl KeccakPermuteChip {
 pub mod permute_tests {
     use crate::utils::run_test;
     use crate::{
-        runtime::{Instruction, Opcode, Program, Runtime},
+        runtime::{tests::halting, Instruction, Opcode, Program, Runtime},
         utils::{self, tests::KECCAK_PERMUTE_ELF},
     };
 
@@ -66,7 +66,7 @@ pub mod permute_tests {
     pub fn test_keccak_permute_program_execute() {
         let program = keccak_permute_program();
         let mut runtime = Runtime::new(program);
-        runtime.run()
+        runtime.run().unwrap();
     }
 
     #[test]
@@ -74,7 +74,7 @@ pub mod permute_tests {
         utils::setup_logger();
 
         let program = keccak_permute_program();
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
     }
 
     #[test]
@@ -1,7 +1,10 @@
 pub mod blake3;
+pub mod bls12_381;
+pub mod bn254;
 pub mod edwards;
 pub mod k256;
 pub mod keccak256;
+pub mod secp256r1;
 pub mod sha256;
 pub mod weierstrass;
 
@@ -10,26 +13,29 @@ use serde::{Deserialize, Serialize};
 
 use crate::air::SP1AirBuilder;
 use crate::operations::field::params::Limbs;
-use crate::runtime::SyscallContext;
+use crate::runtime::{ExecutionErrorKind, SyscallContext};
 use crate::utils::ec::field::FieldParameters;
-use crate::utils::ec::{AffinePoint, EllipticCurve};
+use crate::utils::ec::{num_words_ec_point, AffinePoint, EllipticCurve};
 use crate::{cpu::MemoryReadRecord, cpu::MemoryWriteRecord};
 
-/// Elliptic curve add event.
+/// Elliptic curve add event. The points have as many words as two elements of the base field of
+/// the curve.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ECAddEvent {
     pub shard: u32,
     pub clk: u32,
     pub p_ptr: u32,
-    pub p: [u32; 16],
+    pub p: Vec<u32>,
     pub q_ptr: u32,
-    pub q: [u32; 16],
+    pub q: Vec<u32>,
     pub q_ptr_record: MemoryReadRecord,
-    pub p_memory_records: [MemoryWriteRecord; 16],
-    pub q_memory_records: [MemoryReadRecord; 16],
+    pub p_memory_records: Vec<MemoryWriteRecord>,
+    pub q_memory_records: Vec<MemoryReadRecord>,
 }
 
-pub fn create_ec_add_event<E: EllipticCurve>(rt: &mut SyscallContext) -> ECAddEvent {
+pub fn create_ec_add_event<E: EllipticCurve>(
+    rt: &mut SyscallContext,
+) -> Result<ECAddEvent, ExecutionErrorKind> {
     let a0 = crate::runtime::Register::X10;
     let a1 = crate::runtime::Register::X11;
 
@@ -38,18 +44,17 @@ pub fn create_ec_add_event<E: EllipticCurve>(rt: &mut SyscallContext) -> ECAddEv
     // TODO: these will have to be be constrained, but can do it later.
     let p_ptr = rt.register_unsafe(a0);
     if p_ptr % 4 != 0 {
-        panic!();
+        return Err(ExecutionErrorKind::UnalignedMemoryAccess(p_ptr));
     }
 
     let (q_ptr_record, q_ptr) = rt.mr(a1 as u32);
     if q_ptr % 4 != 0 {
-        panic!();
+        return Err(ExecutionErrorKind::UnalignedMemoryAccess(q_ptr));
     }
 
-    let p: [u32; 16] = rt.slice_unsafe(p_ptr, 16).try_into().unwrap();
-    let (q_memory_records_vec, q_vec) = rt.mr_slice(q_ptr, 16);
-    let q_memory_records = q_memory_records_vec.try_into().unwrap();
-    let q: [u32; 16] = q_vec.try_into().unwrap();
+    let num_words = num_words_ec_point::<E>();
+    let p = rt.slice_unsafe(p_ptr, num_words);
+    let (q_memory_records, q) = rt.mr_slice(q_ptr, num_words)?;
     // When we write to p, we want the clk to be incremented.
     rt.clk += 4;
 
@@ -58,11 +63,11 @@ pub fn create_ec_add_event<E: EllipticCurve>(rt: &mut SyscallContext) -> ECAddEv
     let result_affine = p_affine + q_affine;
     let result_words = result_affine.to_words_le();
 
-    let p_memory_records = rt.mw_slice(p_ptr, &result_words).try_into().unwrap();
+    let p_memory_records = rt.mw_slice(p_ptr, &result_words)?;
 
     rt.clk += 4;
 
-    ECAddEvent {
+    Ok(ECAddEvent {
         shard: rt.current_shard(),
         clk: start_clk,
         p_ptr,
@@ -72,7 +77,7 @@ pub fn create_ec_add_event<E: EllipticCurve>(rt: &mut SyscallContext) -> ECAddEv
         q_ptr_record,
         p_memory_records,
         q_memory_records,
-    }
+    })
 }
 
 /// Elliptic curve double event.
@@ -81,11 +86,13 @@ pub struct ECDoubleEvent {
     pub shard: u32,
     pub clk: u32,
     pub p_ptr: u32,
-    pub p: [u32; 16],
-    pub p_memory_records: [MemoryWriteRecord; 16],
+    pub p: Vec<u32>,
+    pub p_memory_records: Vec<MemoryWriteRecord>,
 }
 
-pub fn create_ec_double_event<E: EllipticCurve>(rt: &mut SyscallContext) -> ECDoubleEvent {
+pub fn create_ec_double_event<E: EllipticCurve>(
+    rt: &mut SyscallContext,
+) -> Result<ECDoubleEvent, ExecutionErrorKind> {
     let a0 = crate::runtime::Register::X10;
 
     let start_clk = rt.clk;
@@ -93,10 +100,10 @@ pub fn create_ec_double_event<E: EllipticCurve>(rt: &mut SyscallContext) -> ECDo
     // TODO: these will have to be be constrained, but can do it later.
     let p_ptr = rt.register_unsafe(a0);
     if p_ptr % 4 != 0 {
-        panic!();
+        return Err(ExecutionErrorKind::UnalignedMemoryAccess(p_ptr));
     }
 
-    let p: [u32; 16] = rt.slice_unsafe(p_ptr, 16).try_into().unwrap();
+    let p = rt.slice_unsafe(p_ptr, num_words_ec_point::<E>());
 
     // When we write to p, we want the clk to be incremented.
     rt.clk += 4;
@@ -105,23 +112,25 @@ pub fn create_ec_double_event<E: EllipticCurve>(rt: &mut SyscallContext) -> ECDo
     let result_affine = E::ec_double(&p_affine);
     let result_words = result_affine.to_words_le();
 
-    let p_memory_records = rt.mw_slice(p_ptr, &result_words).try_into().unwrap();
+    let p_memory_records = rt.mw_slice(p_ptr, &result_words)?;
 
     rt.clk += 4;
 
-    ECDoubleEvent {
+    Ok(ECDoubleEvent {
         shard: rt.current_shard(),
         clk: start_clk,
         p_ptr,
         p,
         p_memory_records,
-    }
+    })
 }
 
-pub fn limbs_from_biguint<AB, F: FieldParameters>(value: &BigUint) -> Limbs<AB::Expr>
+pub fn limbs_from_biguint<AB, F: FieldParameters, const N: usize>(
+    value: &BigUint,
+) -> Limbs<AB::Expr, N>
 where
     AB: SP1AirBuilder,
 {
-    let a_const = F::to_limbs_field::<AB::F>(value);
-    Limbs::<AB::Expr>(a_const.0.map(|x| x.into()))
+    let a_const = F::to_limbs_field::<AB::F, N>(value);
+    Limbs::<AB::Expr, N>(a_const.0.map(|x| x.into()))
 }
@@ -0,0 +1,467 @@
+use crate::air::BaseAirBuilder;
+use crate::air::MachineAir;
+use crate::air::SP1AirBuilder;
+use crate::air::Word;
+use crate::cpu::MemoryReadRecord;
+use crate::cpu::MemoryWriteRecord;
+use crate::memory::MemoryReadCols;
+use crate::memory::MemoryReadWriteCols;
+use crate::operations::field::field_op::FieldOpCols;
+use crate::operations::field::field_op::FieldOperation;
+use crate::operations::field::field_sqrt::FieldSqrtCols;
+use crate::operations::field::params::Limbs;
+use crate::runtime::ExecutionErrorKind;
+use crate::runtime::ExecutionRecord;
+use crate::runtime::Syscall;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::bytes_to_words_le;
+use crate::utils::ec::field::FieldParameters;
+use crate::utils::ec::weierstrass::secp256r1::secp256r1_sqrt;
+use crate::utils::ec::weierstrass::secp256r1::Secp256r1BaseField;
+use crate::utils::ec::weierstrass::secp256r1::Secp256r1Parameters;
+use crate::utils::ec::weierstrass::WeierstrassParameters;
+use crate::utils::ec::COMPRESSED_POINT_BYTES;
+use crate::utils::ec::NUM_BYTES_FIELD_ELEMENT;
+use crate::utils::ec::NUM_WORDS_FIELD_ELEMENT;
+use crate::utils::limbs_from_access;
+use crate::utils::limbs_from_prev_access;
+use crate::utils::pad_rows;
+use crate::utils::words_to_bytes_le;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use num::BigUint;
+use num::Zero;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::fmt::Debug;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256r1DecompressEvent {
+    pub shard: u32,
+    pub clk: u32,
+    pub ptr: u32,
+    pub is_odd: bool,
+    pub x_bytes: [u8; COMPRESSED_POINT_BYTES],
+    pub decompressed_y_bytes: [u8; NUM_BYTES_FIELD_ELEMENT],
+    pub x_memory_records: [MemoryReadRecord; NUM_WORDS_FIELD_ELEMENT],
+    pub y_memory_records: [MemoryWriteRecord; NUM_WORDS_FIELD_ELEMENT],
+}
+
+pub const NUM_SECP256R1_DECOMPRESS_COLS: usize = size_of::<Secp256r1DecompressCols<u8>>();
+
+/// A chip that computes `Secp256r1Decompress` given a pointer to a 16 word slice formatted as such:
+/// input[0] is the sign bit. The second half of the slice is the compressed X in little endian.
+///
+/// After `Secp256r1Decompress`, the first 32 bytes of the slice are overwritten with the
+/// decompressed Y.
+#[derive(Default)]
+pub struct Secp256r1DecompressChip;
+
+impl Secp256r1DecompressChip {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for Secp256r1DecompressChip {
+    fn num_extra_cycles(&self) -> u32 {
+        4
+    }
+
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let a0 = crate::runtime::Register::X10;
+
+        let start_clk = rt.clk;
+
+        // TODO: this will have to be be constrained, but can do it later.
+        let slice_ptr = rt.register_unsafe(a0);
+        if slice_ptr % 4 != 0 {
+            return Err(ExecutionErrorKind::UnalignedMemoryAccess(slice_ptr));
+        }
+
+        let (x_memory_records_vec, x_vec) = rt.mr_slice(
+            slice_ptr.wrapping_add(COMPRESSED_POINT_BYTES as u32),
+            NUM_WORDS_FIELD_ELEMENT,
+        )?;
+        let x_memory_records: [MemoryReadRecord; NUM_WORDS_FIELD_ELEMENT] =
+            x_memory_records_vec.try_into().unwrap();
+
+        // This unsafe read is okay because we do mw_slice into the first 8 words later.
+        let is_odd = rt.byte_unsafe(slice_ptr);
+
+        let x_bytes: [u8; COMPRESSED_POINT_BYTES] = words_to_bytes_le(&x_vec);
+
+        // Compute actual decompressed Y, as the square root of x^3 + ax + b with the requested
+        // parity.
+        let modulus = Secp256r1BaseField::modulus();
+        let x = BigUint::from_bytes_le(&x_bytes);
+        let y_2 = (&x * &x * &x + Secp256r1Parameters::a_int() * &x + Secp256r1Parameters::b_int())
+            % &modulus;
+        let mut y = secp256r1_sqrt(&y_2);
+        if (&y * &y) % &modulus != y_2 {
+            return Err(ExecutionErrorKind::InvalidCompressedPoint);
+        }
+        if y.bit(0) != (is_odd != 0) {
+            y = (&modulus - &y) % &modulus;
+        }
+
+        let mut decompressed_y_bytes = [0_u8; NUM_BYTES_FIELD_ELEMENT];
+        let y_bytes = y.to_bytes_le();
+        decompressed_y_bytes[..y_bytes.len()].copy_from_slice(&y_bytes);
+        let y_words: [u32; NUM_WORDS_FIELD_ELEMENT] = bytes_to_words_le(&decompressed_y_bytes);
+
+        let y_memory_records_vec = rt.mw_slice(slice_ptr, &y_words)?;
+        let y_memory_records: [MemoryWriteRecord; NUM_WORDS_FIELD_ELEMENT] =
+            y_memory_records_vec.try_into().unwrap();
+
+        let shard = rt.current_shard();
+        rt.record_mut()
+            .secp256r1_decompress_events
+            .push(Secp256r1DecompressEvent {
+                shard,
+                clk: start_clk,
+                ptr: slice_ptr,
+                is_odd: is_odd != 0,
+                x_bytes,
+                decompressed_y_bytes,
+                x_memory_records,
+                y_memory_records,
+            });
+
+        rt.clk += 4;
+
+        Ok(slice_ptr)
+    }
+}
+
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Secp256r1DecompressCols<T> {
+    pub is_real: T,
+    pub shard: T,
+    pub clk: T,
+    pub ptr: T,
+    pub x_access: [MemoryReadCols<T>; NUM_WORDS_FIELD_ELEMENT],
+    pub y_access: [MemoryReadWriteCols<T>; NUM_WORDS_FIELD_ELEMENT],
+    pub(crate) x_2: FieldOpCols<T>,
+    pub(crate) x_3: FieldOpCols<T>,
+    pub(crate) ax: FieldOpCols<T>,
+    pub(crate) x_3_plus_ax: FieldOpCols<T>,
+    pub(crate) x_3_plus_ax_plus_b: FieldOpCols<T>,
+    pub(crate) y: FieldSqrtCols<T>,
+    pub(crate) neg_y: FieldOpCols<T>,
+    pub(crate) y_least_bits: [T; 8],
+}
+
+impl<F: PrimeField32> Secp256r1DecompressCols<F> {
+    pub fn populate(&mut self, event: Secp256r1DecompressEvent, record: &mut ExecutionRecord) {
+        let mut new_field_events = Vec::new();
+        self.is_real = F::from_bool(true);
+        self.shard = F::from_canonical_u32(event.shard);
+        self.clk = F::from_canonical_u32(event.clk);
+        self.ptr = F::from_canonical_u32(event.ptr);
+        for i in 0..NUM_WORDS_FIELD_ELEMENT {
+            self.x_access[i].populate(event.x_memory_records[i], &mut new_field_events);
+            self.y_access[i].populate_write(event.y_memory_records[i], &mut new_field_events);
+        }
+
+        let x = &BigUint::from_bytes_le(&event.x_bytes);
+        self.populate_field_ops(x);
+
+        record.add_field_events(&new_field_events);
+    }
+
+    fn populate_field_ops(&mut self, x: &BigUint) {
+        // Y = sqrt(x^3 + ax + b)
+        let x_2 = self
+            .x_2
+            .populate::<Secp256r1BaseField>(x, x, FieldOperation::Mul);
+        let x_3 = self
+            .x_3
+            .populate::<Secp256r1BaseField>(&x_2, x, FieldOperation::Mul);
+        let a = Secp256r1Parameters::a_int();
+        let ax = self
+            .ax
+            .populate::<Secp256r1BaseField>(&a, x, FieldOperation::Mul);
+        let x_3_plus_ax =
+            self.x_3_plus_ax
+                .populate::<Secp256r1BaseField>(&x_3, &ax, FieldOperation::Add);
+        let b = Secp256r1Parameters::b_int();
+        let x_3_plus_ax_plus_b = self.x_3_plus_ax_plus_b.populate::<Secp256r1BaseField>(
+            &x_3_plus_ax,
+            &b,
+            FieldOperation::Add,
+        );
+        let y = self
+            .y
+            .populate::<Secp256r1BaseField>(&x_3_plus_ax_plus_b, secp256r1_sqrt);
+        let zero = BigUint::zero();
+        self.neg_y
+            .populate::<Secp256r1BaseField>(&zero, &y, FieldOperation::Sub);
+        // Decompose bits of least significant Y byte
+        let y_bytes = y.to_bytes_le();
+        let y_lsb = if y_bytes.is_empty() { 0 } else { y_bytes[0] };
+        for i in 0..8 {
+            self.y_least_bits[i] = F::from_canonical_u32(((y_lsb >> i) & 1) as u32);
+        }
+    }
+}
+
+impl<V: Copy> Secp256r1DecompressCols<V> {
+    pub fn eval<AB: SP1AirBuilder<Var = V>>(&self, builder: &mut AB)
+    where
+        V: Into<AB::Expr>,
+    {
+        // Get the 32nd byte of the slice, which should be `should_be_odd`.
+        let should_be_odd: AB::Expr = self.y_access[0].prev_value[0].into();
+        builder.assert_bool(should_be_odd.clone());
+
+        let x: Limbs<_> = limbs_from_prev_access(&self.x_access);
+        self.x_2
+            .eval::<AB, Secp256r1BaseField, _, _>(builder, &x, &x, FieldOperation::Mul);
+        self.x_3.eval::<AB, Secp256r1BaseField, _, _>(
+            builder,
+            &self.x_2.result,
+            &x,
+            FieldOperation::Mul,
+        );
+        let a = Secp256r1Parameters::a_int();
+        let a_const: Limbs<AB::F> = Secp256r1BaseField::to_limbs_field(&a);
+        self.ax
+            .eval::<AB, Secp256r1BaseField, _, _>(builder, &a_const, &x, FieldOperation::Mul);
+        self.x_3_plus_ax.eval::<AB, Secp256r1BaseField, _, _>(
+            builder,
+            &self.x_3.result,
+            &self.ax.result,
+            FieldOperation::Add,
+        );
+        let b = Secp256r1Parameters::b_int();
+        let b_const: Limbs<AB::F> = Secp256r1BaseField::to_limbs_field(&b);
+        self.x_3_plus_ax_plus_b
+            .eval::<AB, Secp256r1BaseField, _, _>(
+                builder,
+                &self.x_3_plus_ax.result,
+                &b_const,
+                FieldOperation::Add,
+            );
+        self.y
+            .eval::<AB, Secp256r1BaseField>(builder, &self.x_3_plus_ax_plus_b.result);
+        self.neg_y.eval::<AB, Secp256r1BaseField, _, _>(
+            builder,
+            &[AB::Expr::zero()].iter(),
+            &self.y.multiplication.result,
+            FieldOperation::Sub,
+        );
+
+        // Constrain decomposition of least significant byte of Y into `y_least_bits`
+        for i in 0..8 {
+            builder.when(self.is_real).assert_bool(self.y_least_bits[i]);
+        }
+        let y_least_byte = self.y.multiplication.result.0[0];
+        let powers_of_two = [1, 2, 4, 8, 16, 32, 64, 128].map(AB::F::from_canonical_u32);
+        let recomputed_byte: AB::Expr = self
+            .y_least_bits
+            .iter()
+            .zip(powers_of_two)
+            .map(|(p, b)| (*p).into() * b)
+            .sum();
+        builder
+            .when(self.is_real)
+            .assert_eq(recomputed_byte, y_least_byte);
+
+        // Interpret the lowest bit of Y as whether it is odd or not.
+        let y_is_odd = self.y_least_bits[0];
+
+        // When y_is_odd == should_be_odd, result is y
+        // Equivalent: y_is_odd != !should_be_odd
+        let y_limbs: Limbs<_> = limbs_from_access(&self.y_access);
+        builder
+            .when(self.is_real)
+            .when_ne(y_is_odd.into(), AB::Expr::one() - should_be_odd.clone())
+            .assert_all_eq(self.y.multiplication.result, y_limbs);
+        // When y_is_odd != should_be_odd, result is -y.
+        builder
+            .when(self.is_real)
+            .when_ne(y_is_odd, should_be_odd)
+            .assert_all_eq(self.neg_y.result, y_limbs);
+
+        for i in 0..NUM_WORDS_FIELD_ELEMENT {
+            builder.constraint_memory_access(
+                self.shard,
+                self.clk,
+                self.ptr.into() + AB::F::from_canonical_u32((i as u32) * 4 + 32),
+                &self.x_access[i],
+                self.is_real,
+            );
+        }
+        for i in 0..NUM_WORDS_FIELD_ELEMENT {
+            builder.constraint_memory_access(
+                self.shard,
+                self.clk,
+                self.ptr.into() + AB::F::from_canonical_u32((i as u32) * 4),
+                &self.y_access[i],
+                self.is_real,
+            );
+        }
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for Secp256r1DecompressChip {
+    fn name(&self) -> String {
+        "Secp256r1Decompress".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+
+        for i in 0..input.secp256r1_decompress_events.len() {
+            let event = input.secp256r1_decompress_events[i].clone();
+            let mut row = [F::zero(); NUM_SECP256R1_DECOMPRESS_COLS];
+            let cols: &mut Secp256r1DecompressCols<F> = row.as_mut_slice().borrow_mut();
+            cols.populate(event.clone(), output);
+
+            rows.push(row);
+        }
+
+        pad_rows(&mut rows, || {
+            let mut row = [F::zero(); NUM_SECP256R1_DECOMPRESS_COLS];
+            let cols: &mut Secp256r1DecompressCols<F> = row.as_mut_slice().borrow_mut();
+            // The X of the generator has a valid result -> sqrt(X^3 + aX + b)
+            let (dummy_value, _) = Secp256r1Parameters::generator();
+            let mut dummy_bytes = dummy_value.to_bytes_le();
+            dummy_bytes.resize(NUM_BYTES_FIELD_ELEMENT, 0u8);
+            for i in 0..NUM_WORDS_FIELD_ELEMENT {
+                let word_bytes = dummy_bytes[i * 4..(i + 1) * 4]
+                    .iter()
+                    .map(|x| F::from_canonical_u8(*x))
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap();
+                cols.x_access[i].access.value = Word(word_bytes);
+            }
+            cols.populate_field_ops(&dummy_value);
+            row
+        });
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_SECP256R1_DECOMPRESS_COLS,
+        )
+    }
+}
+
+impl<F> BaseAir<F> for Secp256r1DecompressChip {
+    fn width(&self) -> usize {
+        NUM_SECP256R1_DECOMPRESS_COLS
+    }
+}
+
+impl<AB> Air<AB> for Secp256r1DecompressChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let row: &Secp256r1DecompressCols<AB::Var> = main.row_slice(0).borrow();
+        row.eval::<AB>(builder);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        runtime::{
+            tests::halting, ExecutionErrorKind, Instruction, Opcode, Program, Runtime, SyscallCode,
+        },
+        utils::{run_test, setup_logger},
+    };
+
+    /// The coordinates of the generator of Secp256r1 and the negation of its `y`, as little-endian
+    /// bytes. The `y` of the generator is odd.
+    const G_X: [u8; 32] = [
+        150, 194, 152, 216, 69, 57, 161, 244, 160, 51, 235, 45, 129, 125, 3, 119, 242, 64, 164, 99,
+        229, 230, 188, 248, 71, 66, 44, 225, 242, 209, 23, 107,
+    ];
+    const G_Y: [u8; 32] = [
+        245, 81, 191, 55, 104, 64, 182, 203, 206, 94, 49, 107, 87, 51, 206, 43, 22, 158, 15, 124,
+        74, 235, 231, 142, 155, 127, 26, 254, 226, 66, 227, 79,
+    ];
+    const NEG_G_Y: [u8; 32] = [
+        10, 174, 64, 200, 151, 191, 73, 52, 49, 161, 206, 148, 169, 204, 49, 212, 233, 97, 240,
+        131, 181, 20, 24, 113, 101, 128, 229, 1, 28, 189, 28, 176,
+    ];
+
+    const PTR: u32 = 100;
+
+    /// A program which writes `x` to the second half of a slice and the sign bit `is_odd` to its
+    /// first byte, and decompresses it with `SECP256R1_DECOMPRESS`.
+    fn secp256r1_decompress_program(x: &[u8; 32], is_odd: bool) -> Program {
+        let mut words = vec![is_odd as u32];
+        words.extend([0; 7]);
+        words.extend(
+            x.chunks_exact(4)
+                .map(|word| u32::from_le_bytes(word.try_into().unwrap())),
+        );
+        let mut instructions = Vec::new();
+        for (i, word) in words.into_iter().enumerate() {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, PTR + i as u32 * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::SECP256R1_DECOMPRESS as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, PTR, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_secp256r1_decompress_execute() {
+        for (is_odd, y) in [(true, G_Y), (false, NEG_G_Y)] {
+            let mut runtime = Runtime::new(secp256r1_decompress_program(&G_X, is_odd));
+            runtime.run().unwrap();
+            let result = (0..8)
+                .flat_map(|i| runtime.word(PTR + i * 4).to_le_bytes())
+                .collect::<Vec<_>>();
+            assert_eq!(result, y);
+            assert_eq!(runtime.record.secp256r1_decompress_events.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_secp256r1_decompress_invalid_point() {
+        // There is no point on the curve with `x = 1`.
+        let mut x = [0u8; 32];
+        x[0] = 1;
+        let mut runtime = Runtime::new(secp256r1_decompress_program(&x, false));
+        let err = runtime.run().unwrap_err();
+        assert_eq!(err.kind, ExecutionErrorKind::InvalidCompressedPoint);
+    }
+
+    #[test]
+    fn test_secp256r1_decompress_prove() {
+        setup_logger();
+        let program = secp256r1_decompress_program(&G_X, false);
+        run_test(halting(program)).unwrap();
+    }
+}
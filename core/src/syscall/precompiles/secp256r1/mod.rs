@@ -0,0 +1,3 @@
+mod decompress;
+
+pub use decompress::*;
@@ -42,7 +42,7 @@ impl ShaCompressChip {
 pub mod compress_tests {
 
     use crate::{
-        runtime::{Instruction, Opcode, Program},
+        runtime::{tests::halting, Instruction, Opcode, Program},
         utils::{run_test, setup_logger},
     };
 
@@ -67,6 +67,6 @@ pub mod compress_tests {
     fn prove_babybear() {
         setup_logger();
         let program = sha_compress_program();
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    runtime::{Register, Syscall},
+    runtime::{ExecutionErrorKind, Register, Syscall},
     syscall::precompiles::{sha256::ShaExtendEvent, SyscallContext},
 };
 
@@ -10,7 +10,7 @@ impl Syscall for ShaExtendChip {
         48 * 20
     }
 
-    fn execute(&self, rt: &mut SyscallContext) -> u32 {
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         // Initialize the registers.
         let a0 = Register::X10;
 
@@ -78,6 +78,6 @@ impl Syscall for ShaExtendChip {
             w_i_writes,
         });
 
-        w_ptr
+        Ok(w_ptr)
     }
 }
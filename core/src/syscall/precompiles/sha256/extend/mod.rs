@@ -48,7 +48,7 @@ pub mod extend_tests {
     use crate::{
         air::MachineAir,
         alu::AluEvent,
-        runtime::{ExecutionRecord, Instruction, Opcode, Program},
+        runtime::{tests::halting, ExecutionRecord, Instruction, Opcode, Program},
         utils::run_test,
     };
 
@@ -84,6 +84,6 @@ pub mod extend_tests {
     #[test]
     fn test_sha_prove() {
         let program = sha_extend_program();
-        run_test(program).unwrap();
+        run_test(halting(program)).unwrap();
     }
 }
@@ -5,7 +5,10 @@ use crate::memory::MemoryReadCols;
 use crate::memory::MemoryWriteCols;
 use crate::operations::field::field_op::FieldOpCols;
 use crate::operations::field::field_op::FieldOperation;
+use crate::operations::field::params::Limbs;
 use crate::operations::field::params::NUM_LIMBS;
+use crate::operations::field::params::NUM_WITNESS_LIMBS;
+use crate::runtime::ExecutionErrorKind;
 use crate::runtime::ExecutionRecord;
 use crate::runtime::Register;
 use crate::runtime::Syscall;
@@ -13,9 +16,9 @@ use crate::syscall::precompiles::create_ec_add_event;
 use crate::syscall::precompiles::SyscallContext;
 use crate::utils::ec::weierstrass::WeierstrassParameters;
 use crate::utils::ec::AffinePoint;
+use crate::utils::ec::CurveType;
 use crate::utils::ec::EllipticCurve;
 use crate::utils::ec::NUM_WORDS_EC_POINT;
-use crate::utils::ec::NUM_WORDS_FIELD_ELEMENT;
 use crate::utils::limbs_from_prev_access;
 use crate::utils::pad_rows;
 use core::borrow::{Borrow, BorrowMut};
@@ -34,42 +37,66 @@ use std::marker::PhantomData;
 
 pub const NUM_WEIERSTRASS_ADD_COLS: usize = size_of::<WeierstrassAddAssignCols<u8>>();
 
+/// The number of columns of `WeierstrassAddAssignCols` for `N` limbs, `W` witness limbs and points
+/// of `M` words.
+pub const fn num_weierstrass_add_cols<const N: usize, const W: usize, const M: usize>() -> usize {
+    size_of::<WeierstrassAddAssignCols<u8, N, W, M>>()
+}
+
 /// A set of columns to compute `WeierstrassAdd` that add two points on a Weierstrass curve.
 ///
-/// Right now the number of limbs is assumed to be a constant, although this could be macro-ed or
-/// made generic in the future.
+/// The base field elements have `N` limbs and `W` witness limbs, and the points have `M` words,
+/// which default to the sizes of a 256-bit field.
 #[derive(Debug, Clone, AlignedBorrow)]
 #[repr(C)]
-pub struct WeierstrassAddAssignCols<T> {
+pub struct WeierstrassAddAssignCols<
+    T,
+    const N: usize = NUM_LIMBS,
+    const W: usize = NUM_WITNESS_LIMBS,
+    const M: usize = NUM_WORDS_EC_POINT,
+> {
     pub is_real: T,
     pub shard: T,
     pub clk: T,
     pub p_ptr: T,
     pub q_ptr: T,
     pub q_ptr_access: MemoryReadCols<T>,
-    pub p_access: [MemoryWriteCols<T>; NUM_WORDS_EC_POINT],
-    pub q_access: [MemoryReadCols<T>; NUM_WORDS_EC_POINT],
-    pub(crate) slope_denominator: FieldOpCols<T>,
-    pub(crate) slope_numerator: FieldOpCols<T>,
-    pub(crate) slope: FieldOpCols<T>,
-    pub(crate) slope_squared: FieldOpCols<T>,
-    pub(crate) p_x_plus_q_x: FieldOpCols<T>,
-    pub(crate) x3_ins: FieldOpCols<T>,
-    pub(crate) p_x_minus_x: FieldOpCols<T>,
-    pub(crate) y3_ins: FieldOpCols<T>,
-    pub(crate) slope_times_p_x_minus_x: FieldOpCols<T>,
+    pub p_access: [MemoryWriteCols<T>; M],
+    pub q_access: [MemoryReadCols<T>; M],
+    pub(crate) slope_denominator: FieldOpCols<T, N, W>,
+    pub(crate) slope_numerator: FieldOpCols<T, N, W>,
+    pub(crate) slope: FieldOpCols<T, N, W>,
+    pub(crate) slope_squared: FieldOpCols<T, N, W>,
+    pub(crate) p_x_plus_q_x: FieldOpCols<T, N, W>,
+    pub(crate) x3_ins: FieldOpCols<T, N, W>,
+    pub(crate) p_x_minus_x: FieldOpCols<T, N, W>,
+    pub(crate) y3_ins: FieldOpCols<T, N, W>,
+    pub(crate) slope_times_p_x_minus_x: FieldOpCols<T, N, W>,
 }
 
 #[derive(Default)]
-pub struct WeierstrassAddAssignChip<E> {
+pub struct WeierstrassAddAssignChip<
+    E,
+    const N: usize = NUM_LIMBS,
+    const W: usize = NUM_WITNESS_LIMBS,
+    const M: usize = NUM_WORDS_EC_POINT,
+> {
     _marker: PhantomData<E>,
 }
 
-impl<E: EllipticCurve> Syscall for WeierstrassAddAssignChip<E> {
-    fn execute(&self, rt: &mut SyscallContext) -> u32 {
-        let event = create_ec_add_event::<E>(rt);
-        rt.record_mut().weierstrass_add_events.push(event.clone());
-        event.p_ptr + 1
+impl<E: EllipticCurve, const N: usize, const W: usize, const M: usize> Syscall
+    for WeierstrassAddAssignChip<E, N, W, M>
+{
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let event = create_ec_add_event::<E>(rt)?;
+        match E::CURVE_TYPE {
+            CurveType::Secp256k1 => rt.record_mut().secp256k1_add_events.push(event.clone()),
+            CurveType::Bn254 => rt.record_mut().bn254_add_events.push(event.clone()),
+            CurveType::Bls12381 => rt.record_mut().bls12381_add_events.push(event.clone()),
+            CurveType::Secp256r1 => rt.record_mut().secp256r1_add_events.push(event.clone()),
+            _ => panic!("Unsupported curve"),
+        }
+        Ok(event.p_ptr + 1)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -77,7 +104,9 @@ impl<E: EllipticCurve> Syscall for WeierstrassAddAssignChip<E> {
     }
 }
 
-impl<E: EllipticCurve> WeierstrassAddAssignChip<E> {
+impl<E: EllipticCurve, const N: usize, const W: usize, const M: usize>
+    WeierstrassAddAssignChip<E, N, W, M>
+{
     pub fn new() -> Self {
         Self {
             _marker: PhantomData,
@@ -85,7 +114,7 @@ impl<E: EllipticCurve> WeierstrassAddAssignChip<E> {
     }
 
     fn populate_field_ops<F: PrimeField32>(
-        cols: &mut WeierstrassAddAssignCols<F>,
+        cols: &mut WeierstrassAddAssignCols<F, N, W, M>,
         p_x: BigUint,
         p_y: BigUint,
         q_x: BigUint,
@@ -142,11 +171,22 @@ impl<E: EllipticCurve> WeierstrassAddAssignChip<E> {
     }
 }
 
-impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
-    for WeierstrassAddAssignChip<E>
+impl<
+        F: PrimeField32,
+        E: EllipticCurve + WeierstrassParameters,
+        const N: usize,
+        const W: usize,
+        const M: usize,
+    > MachineAir<F> for WeierstrassAddAssignChip<E, N, W, M>
 {
     fn name(&self) -> String {
-        "WeierstrassAddAssign".to_string()
+        match E::CURVE_TYPE {
+            CurveType::Secp256k1 => "Secp256k1AddAssign".to_string(),
+            CurveType::Bn254 => "Bn254AddAssign".to_string(),
+            CurveType::Bls12381 => "Bls12381AddAssign".to_string(),
+            CurveType::Secp256r1 => "Secp256r1AddAssign".to_string(),
+            _ => panic!("Unsupported curve"),
+        }
     }
 
     fn generate_trace(
@@ -154,14 +194,22 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
     ) -> RowMajorMatrix<F> {
+        let events = match E::CURVE_TYPE {
+            CurveType::Secp256k1 => &input.secp256k1_add_events,
+            CurveType::Bn254 => &input.bn254_add_events,
+            CurveType::Bls12381 => &input.bls12381_add_events,
+            CurveType::Secp256r1 => &input.secp256r1_add_events,
+            _ => panic!("Unsupported curve"),
+        };
+
+        let num_cols = num_weierstrass_add_cols::<N, W, M>();
         let mut rows = Vec::new();
 
         let mut new_field_events = Vec::new();
 
-        for i in 0..input.weierstrass_add_events.len() {
-            let event = input.weierstrass_add_events[i].clone();
-            let mut row = [F::zero(); NUM_WEIERSTRASS_ADD_COLS];
-            let cols: &mut WeierstrassAddAssignCols<F> = row.as_mut_slice().borrow_mut();
+        for event in events.iter() {
+            let mut row = vec![F::zero(); num_cols];
+            let cols: &mut WeierstrassAddAssignCols<F, N, W, M> = row.as_mut_slice().borrow_mut();
 
             // Decode affine points.
             let p = &event.p;
@@ -181,10 +229,10 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
             Self::populate_field_ops(cols, p_x, p_y, q_x, q_y);
 
             // Populate the memory access columns.
-            for i in 0..NUM_WORDS_EC_POINT {
+            for i in 0..M {
                 cols.q_access[i].populate(event.q_memory_records[i], &mut new_field_events);
             }
-            for i in 0..NUM_WORDS_EC_POINT {
+            for i in 0..M {
                 cols.p_access[i].populate(event.p_memory_records[i], &mut new_field_events);
             }
             cols.q_ptr_access
@@ -195,40 +243,40 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
         output.add_field_events(&new_field_events);
 
         pad_rows(&mut rows, || {
-            let mut row = [F::zero(); NUM_WEIERSTRASS_ADD_COLS];
-            let cols: &mut WeierstrassAddAssignCols<F> = row.as_mut_slice().borrow_mut();
+            let mut row = vec![F::zero(); num_cols];
+            let cols: &mut WeierstrassAddAssignCols<F, N, W, M> = row.as_mut_slice().borrow_mut();
             let zero = BigUint::zero();
             Self::populate_field_ops(cols, zero.clone(), zero.clone(), zero.clone(), zero);
             row
         });
 
         // Convert the trace to a row major matrix.
-        RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_WEIERSTRASS_ADD_COLS,
-        )
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), num_cols)
     }
 }
 
-impl<F, E: EllipticCurve> BaseAir<F> for WeierstrassAddAssignChip<E> {
+impl<F, E: EllipticCurve, const N: usize, const W: usize, const M: usize> BaseAir<F>
+    for WeierstrassAddAssignChip<E, N, W, M>
+{
     fn width(&self) -> usize {
-        NUM_WEIERSTRASS_ADD_COLS
+        num_weierstrass_add_cols::<N, W, M>()
     }
 }
 
-impl<AB, E: EllipticCurve> Air<AB> for WeierstrassAddAssignChip<E>
+impl<AB, E: EllipticCurve, const N: usize, const W: usize, const M: usize> Air<AB>
+    for WeierstrassAddAssignChip<E, N, W, M>
 where
     AB: SP1AirBuilder,
 {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
-        let row: &WeierstrassAddAssignCols<AB::Var> = main.row_slice(0).borrow();
+        let row: &WeierstrassAddAssignCols<AB::Var, N, W, M> = main.row_slice(0).borrow();
 
-        let p_x = limbs_from_prev_access(&row.p_access[0..NUM_WORDS_FIELD_ELEMENT]);
-        let p_y = limbs_from_prev_access(&row.p_access[NUM_WORDS_FIELD_ELEMENT..]);
+        let p_x: Limbs<_, N> = limbs_from_prev_access(&row.p_access[0..M / 2]);
+        let p_y: Limbs<_, N> = limbs_from_prev_access(&row.p_access[M / 2..]);
 
-        let q_x = limbs_from_prev_access(&row.q_access[0..NUM_WORDS_FIELD_ELEMENT]);
-        let q_y = limbs_from_prev_access(&row.q_access[NUM_WORDS_FIELD_ELEMENT..]);
+        let q_x: Limbs<_, N> = limbs_from_prev_access(&row.q_access[0..M / 2]);
+        let q_y: Limbs<_, N> = limbs_from_prev_access(&row.q_access[M / 2..]);
 
         // slope = (q.y - p.y) / (q.x - p.x).
         let slope = {
@@ -304,13 +352,14 @@ where
 
         // Constraint self.p_access.value = [self.x3_ins.result, self.y3_ins.result]. This is to
         // ensure that p_access is updated with the new value.
-        for i in 0..NUM_LIMBS {
+        for i in 0..N {
             builder
                 .when(row.is_real)
                 .assert_eq(row.x3_ins.result[i], row.p_access[i / 4].value()[i % 4]);
-            builder
-                .when(row.is_real)
-                .assert_eq(row.y3_ins.result[i], row.p_access[8 + i / 4].value()[i % 4]);
+            builder.when(row.is_real).assert_eq(
+                row.y3_ins.result[i],
+                row.p_access[M / 2 + i / 4].value()[i % 4],
+            );
         }
 
         builder.constraint_memory_access(
@@ -340,14 +389,159 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{
-        runtime::Program,
+        runtime::{tests::halting, Instruction, Opcode, Program, Runtime, SyscallCode},
         utils::{run_test, setup_logger, tests::SECP256K1_ADD_ELF},
     };
 
+    /// The generator of bn254, and the points `2 * generator` and `3 * generator`.
+    const BN254_G: [u8; 64] = [
+        1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0,
+    ];
+    const BN254_2G: [u8; 64] = [
+        211, 207, 135, 109, 193, 8, 194, 211, 168, 28, 135, 22, 169, 22, 120, 217, 133, 21, 24,
+        104, 91, 4, 133, 155, 2, 26, 19, 46, 231, 68, 6, 3, 196, 162, 24, 90, 122, 191, 62, 255,
+        199, 143, 83, 227, 73, 164, 166, 104, 10, 156, 174, 178, 150, 95, 132, 231, 146, 124, 10,
+        14, 140, 115, 237, 21,
+    ];
+    const BN254_3G: [u8; 64] = [
+        240, 171, 21, 25, 150, 85, 211, 242, 121, 230, 184, 21, 71, 216, 21, 147, 21, 189, 182,
+        177, 188, 50, 2, 244, 63, 234, 107, 197, 154, 191, 105, 7, 97, 34, 254, 217, 61, 255, 241,
+        205, 87, 91, 156, 11, 180, 99, 158, 49, 117, 100, 8, 141, 124, 219, 79, 85, 41, 148, 72,
+        224, 190, 153, 183, 42,
+    ];
+
+    /// The generator of bls12_381, and the points `2 * generator` and `3 * generator`.
+    const BLS12381_G: [u8; 96] = [
+        187, 198, 34, 219, 10, 240, 58, 251, 239, 26, 122, 249, 63, 232, 85, 108, 88, 172, 27, 23,
+        63, 58, 78, 161, 5, 185, 116, 151, 79, 140, 104, 195, 15, 172, 169, 79, 140, 99, 149, 38,
+        148, 215, 151, 49, 167, 211, 241, 23, 225, 231, 197, 70, 41, 35, 170, 12, 228, 138, 136,
+        162, 68, 199, 60, 208, 237, 179, 4, 44, 203, 24, 219, 0, 246, 10, 208, 213, 149, 224, 245,
+        252, 228, 138, 29, 116, 237, 48, 158, 160, 241, 160, 170, 227, 129, 244, 179, 8,
+    ];
+    const BLS12381_2G: [u8; 96] = [
+        78, 15, 191, 41, 85, 140, 154, 195, 66, 124, 28, 143, 187, 117, 143, 226, 42, 166, 88, 195,
+        10, 45, 144, 67, 37, 1, 40, 145, 48, 219, 33, 151, 12, 69, 169, 80, 235, 200, 8, 136, 70,
+        103, 77, 144, 234, 203, 114, 5, 40, 157, 116, 121, 25, 136, 134, 186, 27, 189, 22, 205,
+        212, 217, 86, 76, 106, 215, 95, 29, 2, 185, 59, 247, 97, 228, 112, 134, 203, 62, 186, 34,
+        56, 142, 157, 119, 115, 166, 253, 34, 163, 115, 198, 171, 140, 157, 106, 22,
+    ];
+    const BLS12381_3G: [u8; 96] = [
+        36, 82, 78, 2, 201, 192, 210, 150, 155, 23, 162, 44, 11, 122, 116, 129, 249, 63, 91, 51,
+        81, 10, 120, 243, 241, 165, 233, 155, 31, 214, 18, 177, 151, 150, 169, 236, 45, 33, 101,
+        23, 19, 240, 209, 249, 8, 227, 236, 9, 209, 48, 174, 144, 5, 59, 71, 163, 92, 244, 74, 99,
+        108, 37, 69, 231, 230, 59, 212, 15, 49, 39, 156, 157, 127, 9, 195, 171, 221, 12, 154, 166,
+        12, 248, 197, 137, 51, 98, 132, 138, 159, 176, 245, 166, 211, 128, 43, 3,
+    ];
+
+    /// The generator of secp256r1, and the points `2 * generator` and `3 * generator`.
+    const SECP256R1_G: [u8; 64] = [
+        150, 194, 152, 216, 69, 57, 161, 244, 160, 51, 235, 45, 129, 125, 3, 119, 242, 64, 164, 99,
+        229, 230, 188, 248, 71, 66, 44, 225, 242, 209, 23, 107, 245, 81, 191, 55, 104, 64, 182,
+        203, 206, 94, 49, 107, 87, 51, 206, 43, 22, 158, 15, 124, 74, 235, 231, 142, 155, 127, 26,
+        254, 226, 66, 227, 79,
+    ];
+    const SECP256R1_2G: [u8; 64] = [
+        120, 153, 102, 71, 252, 72, 11, 166, 53, 27, 242, 119, 226, 105, 137, 192, 195, 26, 181, 4,
+        3, 56, 82, 138, 126, 79, 3, 141, 24, 123, 242, 124, 209, 115, 120, 34, 157, 183, 4, 158,
+        41, 130, 233, 60, 230, 173, 125, 186, 219, 48, 116, 159, 198, 154, 61, 41, 64, 208, 142,
+        219, 16, 85, 119, 7,
+    ];
+    const SECP256R1_3G: [u8; 64] = [
+        108, 253, 231, 198, 27, 102, 65, 251, 133, 169, 173, 239, 33, 183, 198, 230, 101, 241, 75,
+        29, 149, 239, 247, 200, 68, 10, 51, 166, 209, 228, 203, 94, 50, 80, 125, 162, 39, 177, 121,
+        154, 61, 184, 79, 56, 54, 176, 42, 216, 236, 162, 100, 26, 206, 6, 75, 55, 126, 255, 152,
+        73, 12, 100, 52, 135,
+    ];
+
+    const P_PTR: u32 = 100;
+    const Q_PTR: u32 = 200;
+
+    /// A program which writes `p` and `q` to memory and adds `q` to `p` with the syscall `code`.
+    fn add_program(code: SyscallCode, p: &[u8], q: &[u8]) -> Program {
+        let mut instructions = Vec::new();
+        for (ptr, point) in [(P_PTR, p), (Q_PTR, q)] {
+            for (i, word) in point.chunks_exact(4).enumerate() {
+                let word = u32::from_le_bytes(word.try_into().unwrap());
+                instructions.extend(vec![
+                    Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                    Instruction::new(Opcode::ADD, 30, 0, ptr + i as u32 * 4, false, true),
+                    Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                ]);
+            }
+        }
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, P_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, Q_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
     #[test]
     fn test_secp256k1_add_simple() {
         setup_logger();
         let program = Program::from(SECP256K1_ADD_ELF);
         run_test(program).unwrap();
     }
+
+    #[test]
+    fn test_bn254_add_execute() {
+        let mut runtime = Runtime::new(add_program(SyscallCode::BN254_ADD, &BN254_G, &BN254_2G));
+        runtime.run().unwrap();
+        let result = (0..16)
+            .flat_map(|i| runtime.word(P_PTR + i * 4).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(result, BN254_3G);
+        assert_eq!(runtime.record.bn254_add_events.len(), 1);
+        assert!(runtime.record.secp256k1_add_events.is_empty());
+    }
+
+    #[test]
+    fn test_bn254_add_prove() {
+        setup_logger();
+        let program = add_program(SyscallCode::BN254_ADD, &BN254_G, &BN254_2G);
+        run_test(halting(program)).unwrap();
+    }
+
+    #[test]
+    fn test_bls12381_add_execute() {
+        let program = add_program(SyscallCode::BLS12381_ADD, &BLS12381_G, &BLS12381_2G);
+        let mut runtime = Runtime::new(program);
+        runtime.run().unwrap();
+        let result = (0..24)
+            .flat_map(|i| runtime.word(P_PTR + i * 4).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(result, BLS12381_3G);
+        assert_eq!(runtime.record.bls12381_add_events.len(), 1);
+    }
+
+    #[test]
+    fn test_bls12381_add_prove() {
+        setup_logger();
+        let program = add_program(SyscallCode::BLS12381_ADD, &BLS12381_G, &BLS12381_2G);
+        run_test(halting(program)).unwrap();
+    }
+
+    #[test]
+    fn test_secp256r1_add_execute() {
+        let program = add_program(SyscallCode::SECP256R1_ADD, &SECP256R1_G, &SECP256R1_2G);
+        let mut runtime = Runtime::new(program);
+        runtime.run().unwrap();
+        let result = (0..16)
+            .flat_map(|i| runtime.word(P_PTR + i * 4).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(result, SECP256R1_3G);
+        assert_eq!(runtime.record.secp256r1_add_events.len(), 1);
+        assert!(runtime.record.secp256k1_add_events.is_empty());
+    }
+
+    #[test]
+    fn test_secp256r1_add_prove() {
+        setup_logger();
+        let program = add_program(SyscallCode::SECP256R1_ADD, &SECP256R1_G, &SECP256R1_2G);
+        run_test(halting(program)).unwrap();
+    }
 }
@@ -4,7 +4,10 @@ use crate::memory::MemoryCols;
 use crate::memory::MemoryWriteCols;
 use crate::operations::field::field_op::FieldOpCols;
 use crate::operations::field::field_op::FieldOperation;
+use crate::operations::field::params::Limbs;
 use crate::operations::field::params::NUM_LIMBS;
+use crate::operations::field::params::NUM_WITNESS_LIMBS;
+use crate::runtime::ExecutionErrorKind;
 use crate::runtime::ExecutionRecord;
 use crate::runtime::Syscall;
 use crate::syscall::precompiles::create_ec_double_event;
@@ -12,9 +15,9 @@ use crate::syscall::precompiles::limbs_from_biguint;
 use crate::syscall::precompiles::SyscallContext;
 use crate::utils::ec::weierstrass::WeierstrassParameters;
 use crate::utils::ec::AffinePoint;
+use crate::utils::ec::CurveType;
 use crate::utils::ec::EllipticCurve;
 use crate::utils::ec::NUM_WORDS_EC_POINT;
-use crate::utils::ec::NUM_WORDS_FIELD_ELEMENT;
 use crate::utils::limbs_from_prev_access;
 use crate::utils::pad_rows;
 use core::borrow::{Borrow, BorrowMut};
@@ -36,43 +39,66 @@ use tracing::instrument;
 
 pub const NUM_WEIERSTRASS_DOUBLE_COLS: usize = size_of::<WeierstrassDoubleAssignCols<u8>>();
 
+/// The number of columns of `WeierstrassDoubleAssignCols` for `N` limbs, `W` witness limbs and
+/// points of `M` words.
+pub const fn num_weierstrass_double_cols<const N: usize, const W: usize, const M: usize>() -> usize
+{
+    size_of::<WeierstrassDoubleAssignCols<u8, N, W, M>>()
+}
+
 /// A set of columns to double a point on a Weierstrass curve.
 ///
-/// Right now the number of limbs is assumed to be a constant, although this could be macro-ed or
-/// made generic in the future.
+/// The base field elements have `N` limbs and `W` witness limbs, and the points have `M` words,
+/// which default to the sizes of a 256-bit field.
 #[derive(Debug, Clone, AlignedBorrow)]
 #[repr(C)]
-pub struct WeierstrassDoubleAssignCols<T> {
+pub struct WeierstrassDoubleAssignCols<
+    T,
+    const N: usize = NUM_LIMBS,
+    const W: usize = NUM_WITNESS_LIMBS,
+    const M: usize = NUM_WORDS_EC_POINT,
+> {
     pub is_real: T,
     pub shard: T,
     pub clk: T,
     pub p_ptr: T,
-    pub p_access: [MemoryWriteCols<T>; NUM_WORDS_EC_POINT],
-    pub(crate) slope_denominator: FieldOpCols<T>,
-    pub(crate) slope_numerator: FieldOpCols<T>,
-    pub(crate) slope: FieldOpCols<T>,
-    pub(crate) p_x_squared: FieldOpCols<T>,
-    pub(crate) p_x_squared_times_3: FieldOpCols<T>,
-    pub(crate) slope_squared: FieldOpCols<T>,
-    pub(crate) p_x_plus_p_x: FieldOpCols<T>,
-    pub(crate) x3_ins: FieldOpCols<T>,
-    pub(crate) p_x_minus_x: FieldOpCols<T>,
-    pub(crate) y3_ins: FieldOpCols<T>,
-    pub(crate) slope_times_p_x_minus_x: FieldOpCols<T>,
+    pub p_access: [MemoryWriteCols<T>; M],
+    pub(crate) slope_denominator: FieldOpCols<T, N, W>,
+    pub(crate) slope_numerator: FieldOpCols<T, N, W>,
+    pub(crate) slope: FieldOpCols<T, N, W>,
+    pub(crate) p_x_squared: FieldOpCols<T, N, W>,
+    pub(crate) p_x_squared_times_3: FieldOpCols<T, N, W>,
+    pub(crate) slope_squared: FieldOpCols<T, N, W>,
+    pub(crate) p_x_plus_p_x: FieldOpCols<T, N, W>,
+    pub(crate) x3_ins: FieldOpCols<T, N, W>,
+    pub(crate) p_x_minus_x: FieldOpCols<T, N, W>,
+    pub(crate) y3_ins: FieldOpCols<T, N, W>,
+    pub(crate) slope_times_p_x_minus_x: FieldOpCols<T, N, W>,
 }
 
 #[derive(Default)]
-pub struct WeierstrassDoubleAssignChip<E> {
+pub struct WeierstrassDoubleAssignChip<
+    E,
+    const N: usize = NUM_LIMBS,
+    const W: usize = NUM_WITNESS_LIMBS,
+    const M: usize = NUM_WORDS_EC_POINT,
+> {
     _marker: PhantomData<E>,
 }
 
-impl<E: EllipticCurve + WeierstrassParameters> Syscall for WeierstrassDoubleAssignChip<E> {
-    fn execute(&self, rt: &mut SyscallContext) -> u32 {
-        let event = create_ec_double_event::<E>(rt);
-        rt.record_mut()
-            .weierstrass_double_events
-            .push(event.clone());
-        event.p_ptr + 1
+impl<E: EllipticCurve + WeierstrassParameters, const N: usize, const W: usize, const M: usize>
+    Syscall for WeierstrassDoubleAssignChip<E, N, W, M>
+{
+    fn execute(&self, rt: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
+        let event = create_ec_double_event::<E>(rt)?;
+        match E::CURVE_TYPE {
+            CurveType::Secp256k1 => rt.record_mut().secp256k1_double_events.push(event.clone()),
+            CurveType::Bn254 => rt.record_mut().bn254_double_events.push(event.clone()),
+            CurveType::Bls12381 => rt.record_mut().bls12381_double_events.push(event.clone()),
+            CurveType::Secp256r1 => rt.record_mut().secp256r1_double_events.push(event.clone()),
+            _ => panic!("Unsupported curve"),
+        }
+        Ok(event.p_ptr + 1)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -80,7 +106,9 @@ impl<E: EllipticCurve + WeierstrassParameters> Syscall for WeierstrassDoubleAssi
     }
 }
 
-impl<E: EllipticCurve + WeierstrassParameters> WeierstrassDoubleAssignChip<E> {
+impl<E: EllipticCurve + WeierstrassParameters, const N: usize, const W: usize, const M: usize>
+    WeierstrassDoubleAssignChip<E, N, W, M>
+{
     pub fn new() -> Self {
         Self {
             _marker: PhantomData,
@@ -88,7 +116,7 @@ impl<E: EllipticCurve + WeierstrassParameters> WeierstrassDoubleAssignChip<E> {
     }
 
     fn populate_field_ops<F: PrimeField32>(
-        cols: &mut WeierstrassDoubleAssignCols<F>,
+        cols: &mut WeierstrassDoubleAssignCols<F, N, W, M>,
         p_x: BigUint,
         p_y: BigUint,
     ) {
@@ -160,11 +188,22 @@ impl<E: EllipticCurve + WeierstrassParameters> WeierstrassDoubleAssignChip<E> {
     }
 }
 
-impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
-    for WeierstrassDoubleAssignChip<E>
+impl<
+        F: PrimeField32,
+        E: EllipticCurve + WeierstrassParameters,
+        const N: usize,
+        const W: usize,
+        const M: usize,
+    > MachineAir<F> for WeierstrassDoubleAssignChip<E, N, W, M>
 {
     fn name(&self) -> String {
-        "WeierstrassDoubleAssign".to_string()
+        match E::CURVE_TYPE {
+            CurveType::Secp256k1 => "Secp256k1DoubleAssign".to_string(),
+            CurveType::Bn254 => "Bn254DoubleAssign".to_string(),
+            CurveType::Bls12381 => "Bls12381DoubleAssign".to_string(),
+            CurveType::Secp256r1 => "Secp256r1DoubleAssign".to_string(),
+            _ => panic!("Unsupported curve"),
+        }
     }
 
     #[instrument(name = "generate WeierstrassDoubleAssign trace", skip_all)]
@@ -173,11 +212,19 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
     ) -> RowMajorMatrix<F> {
-        let chunk_size = std::cmp::max(input.weierstrass_double_events.len() / num_cpus::get(), 1);
+        let events = match E::CURVE_TYPE {
+            CurveType::Secp256k1 => &input.secp256k1_double_events,
+            CurveType::Bn254 => &input.bn254_double_events,
+            CurveType::Bls12381 => &input.bls12381_double_events,
+            CurveType::Secp256r1 => &input.secp256r1_double_events,
+            _ => panic!("Unsupported curve"),
+        };
+
+        let num_cols = num_weierstrass_double_cols::<N, W, M>();
+        let chunk_size = std::cmp::max(events.len() / num_cpus::get(), 1);
 
         // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let rows_and_records = input
-            .weierstrass_double_events
+        let rows_and_records = events
             .par_chunks(chunk_size)
             .map(|events| {
                 let mut record = ExecutionRecord::default();
@@ -186,8 +233,8 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
                 let rows = events
                     .iter()
                     .map(|event| {
-                        let mut row = [F::zero(); NUM_WEIERSTRASS_DOUBLE_COLS];
-                        let cols: &mut WeierstrassDoubleAssignCols<F> =
+                        let mut row = vec![F::zero(); num_cols];
+                        let cols: &mut WeierstrassDoubleAssignCols<F, N, W, M> =
                             row.as_mut_slice().borrow_mut();
 
                         // Decode affine points.
@@ -204,7 +251,7 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
                         Self::populate_field_ops(cols, p_x, p_y);
 
                         // Populate the memory access columns.
-                        for i in 0..NUM_WORDS_EC_POINT {
+                        for i in 0..M {
                             cols.p_access[i]
                                 .populate(event.p_memory_records[i], &mut new_field_events);
                         }
@@ -224,40 +271,51 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
         }
 
         pad_rows(&mut rows, || {
-            let mut row = [F::zero(); NUM_WEIERSTRASS_DOUBLE_COLS];
-            let cols: &mut WeierstrassDoubleAssignCols<F> = row.as_mut_slice().borrow_mut();
+            let mut row = vec![F::zero(); num_cols];
+            let cols: &mut WeierstrassDoubleAssignCols<F, N, W, M> =
+                row.as_mut_slice().borrow_mut();
             let zero = BigUint::zero();
             Self::populate_field_ops(cols, zero.clone(), zero.clone());
             row
         });
 
         // Convert the trace to a row major matrix.
-        RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_WEIERSTRASS_DOUBLE_COLS,
-        )
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), num_cols)
     }
 }
 
-impl<F, E: EllipticCurve + WeierstrassParameters> BaseAir<F> for WeierstrassDoubleAssignChip<E> {
+impl<
+        F,
+        E: EllipticCurve + WeierstrassParameters,
+        const N: usize,
+        const W: usize,
+        const M: usize,
+    > BaseAir<F> for WeierstrassDoubleAssignChip<E, N, W, M>
+{
     fn width(&self) -> usize {
-        NUM_WEIERSTRASS_DOUBLE_COLS
+        num_weierstrass_double_cols::<N, W, M>()
     }
 }
 
-impl<AB, E: EllipticCurve + WeierstrassParameters> Air<AB> for WeierstrassDoubleAssignChip<E>
+impl<
+        AB,
+        E: EllipticCurve + WeierstrassParameters,
+        const N: usize,
+        const W: usize,
+        const M: usize,
+    > Air<AB> for WeierstrassDoubleAssignChip<E, N, W, M>
 where
     AB: SP1AirBuilder,
 {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
-        let row: &WeierstrassDoubleAssignCols<AB::Var> = main.row_slice(0).borrow();
+        let row: &WeierstrassDoubleAssignCols<AB::Var, N, W, M> = main.row_slice(0).borrow();
 
-        let p_x = limbs_from_prev_access(&row.p_access[0..NUM_WORDS_FIELD_ELEMENT]);
-        let p_y = limbs_from_prev_access(&row.p_access[NUM_WORDS_FIELD_ELEMENT..]);
+        let p_x: Limbs<_, N> = limbs_from_prev_access(&row.p_access[0..M / 2]);
+        let p_y: Limbs<_, N> = limbs_from_prev_access(&row.p_access[M / 2..]);
 
         // a in the Weierstrass form: y^2 = x^3 + a * x + b.
-        let a = limbs_from_biguint::<AB, E::BaseField>(&E::a_int());
+        let a = limbs_from_biguint::<AB, E::BaseField, N>(&E::a_int());
 
         // slope = slope_numerator / slope_denominator.
         let slope = {
@@ -273,7 +331,7 @@ where
                 row.p_x_squared_times_3.eval::<AB, E::BaseField, _, _>(
                     builder,
                     &row.p_x_squared.result,
-                    &limbs_from_biguint::<AB, E::BaseField>(&BigUint::from(3u32)),
+                    &limbs_from_biguint::<AB, E::BaseField, N>(&BigUint::from(3u32)),
                     FieldOperation::Mul,
                 );
 
@@ -288,7 +346,7 @@ where
             // slope_denominator = 2 * y.
             row.slope_denominator.eval::<AB, E::BaseField, _, _>(
                 builder,
-                &limbs_from_biguint::<AB, E::BaseField>(&BigUint::from(2u32)),
+                &limbs_from_biguint::<AB, E::BaseField, N>(&BigUint::from(2u32)),
                 &p_y,
                 FieldOperation::Mul,
             );
@@ -346,13 +404,13 @@ where
 
         // Constraint self.p_access.value = [self.x3_ins.result, self.y3_ins.result]. This is to
         // ensure that p_access is updated with the new value.
-        for i in 0..NUM_LIMBS {
+        for i in 0..N {
             builder
                 .when(row.is_real)
                 .assert_eq(row.x3_ins.result[i], row.p_access[i / 4].value()[i % 4]);
             builder.when(row.is_real).assert_eq(
                 row.y3_ins.result[i],
-                row.p_access[NUM_WORDS_FIELD_ELEMENT + i / 4].value()[i % 4],
+                row.p_access[M / 2 + i / 4].value()[i % 4],
             );
         }
 
@@ -370,14 +428,137 @@ where
 pub mod tests {
 
     use crate::{
-        runtime::Program,
+        runtime::{tests::halting, Instruction, Opcode, Program, Runtime, SyscallCode},
         utils::{run_test, setup_logger, tests::SECP256K1_DOUBLE_ELF},
     };
 
+    /// The generator of bn254, and the point `2 * generator`.
+    const BN254_G: [u8; 64] = [
+        1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0,
+    ];
+    const BN254_2G: [u8; 64] = [
+        211, 207, 135, 109, 193, 8, 194, 211, 168, 28, 135, 22, 169, 22, 120, 217, 133, 21, 24,
+        104, 91, 4, 133, 155, 2, 26, 19, 46, 231, 68, 6, 3, 196, 162, 24, 90, 122, 191, 62, 255,
+        199, 143, 83, 227, 73, 164, 166, 104, 10, 156, 174, 178, 150, 95, 132, 231, 146, 124, 10,
+        14, 140, 115, 237, 21,
+    ];
+
+    /// The generator of bls12_381, and the point `2 * generator`.
+    const BLS12381_G: [u8; 96] = [
+        187, 198, 34, 219, 10, 240, 58, 251, 239, 26, 122, 249, 63, 232, 85, 108, 88, 172, 27, 23,
+        63, 58, 78, 161, 5, 185, 116, 151, 79, 140, 104, 195, 15, 172, 169, 79, 140, 99, 149, 38,
+        148, 215, 151, 49, 167, 211, 241, 23, 225, 231, 197, 70, 41, 35, 170, 12, 228, 138, 136,
+        162, 68, 199, 60, 208, 237, 179, 4, 44, 203, 24, 219, 0, 246, 10, 208, 213, 149, 224, 245,
+        252, 228, 138, 29, 116, 237, 48, 158, 160, 241, 160, 170, 227, 129, 244, 179, 8,
+    ];
+    const BLS12381_2G: [u8; 96] = [
+        78, 15, 191, 41, 85, 140, 154, 195, 66, 124, 28, 143, 187, 117, 143, 226, 42, 166, 88, 195,
+        10, 45, 144, 67, 37, 1, 40, 145, 48, 219, 33, 151, 12, 69, 169, 80, 235, 200, 8, 136, 70,
+        103, 77, 144, 234, 203, 114, 5, 40, 157, 116, 121, 25, 136, 134, 186, 27, 189, 22, 205,
+        212, 217, 86, 76, 106, 215, 95, 29, 2, 185, 59, 247, 97, 228, 112, 134, 203, 62, 186, 34,
+        56, 142, 157, 119, 115, 166, 253, 34, 163, 115, 198, 171, 140, 157, 106, 22,
+    ];
+
+    /// The generator of secp256r1, and the point `2 * generator`. Doubling exercises the nonzero
+    /// coefficient `a` of the curve.
+    const SECP256R1_G: [u8; 64] = [
+        150, 194, 152, 216, 69, 57, 161, 244, 160, 51, 235, 45, 129, 125, 3, 119, 242, 64, 164, 99,
+        229, 230, 188, 248, 71, 66, 44, 225, 242, 209, 23, 107, 245, 81, 191, 55, 104, 64, 182,
+        203, 206, 94, 49, 107, 87, 51, 206, 43, 22, 158, 15, 124, 74, 235, 231, 142, 155, 127, 26,
+        254, 226, 66, 227, 79,
+    ];
+    const SECP256R1_2G: [u8; 64] = [
+        120, 153, 102, 71, 252, 72, 11, 166, 53, 27, 242, 119, 226, 105, 137, 192, 195, 26, 181, 4,
+        3, 56, 82, 138, 126, 79, 3, 141, 24, 123, 242, 124, 209, 115, 120, 34, 157, 183, 4, 158,
+        41, 130, 233, 60, 230, 173, 125, 186, 219, 48, 116, 159, 198, 154, 61, 41, 64, 208, 142,
+        219, 16, 85, 119, 7,
+    ];
+
+    const P_PTR: u32 = 100;
+
+    /// A program which writes `p` to memory and doubles it with the syscall `code`.
+    fn double_program(code: SyscallCode, p: &[u8]) -> Program {
+        let mut instructions = Vec::new();
+        for (i, word) in p.chunks_exact(4).enumerate() {
+            let word = u32::from_le_bytes(word.try_into().unwrap());
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, P_PTR + i as u32 * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, P_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 10, 5, 0, false, true),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
     #[test]
     fn test_secp256k1_double_simple() {
         setup_logger();
         let program = Program::from(SECP256K1_DOUBLE_ELF);
         run_test(program).unwrap();
     }
+
+    #[test]
+    fn test_bn254_double_execute() {
+        let mut runtime = Runtime::new(double_program(SyscallCode::BN254_DOUBLE, &BN254_G));
+        runtime.run().unwrap();
+        let result = (0..16)
+            .flat_map(|i| runtime.word(P_PTR + i * 4).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(result, BN254_2G);
+        assert_eq!(runtime.record.bn254_double_events.len(), 1);
+        assert!(runtime.record.secp256k1_double_events.is_empty());
+    }
+
+    #[test]
+    fn test_bn254_double_prove() {
+        setup_logger();
+        let program = double_program(SyscallCode::BN254_DOUBLE, &BN254_G);
+        run_test(halting(program)).unwrap();
+    }
+
+    #[test]
+    fn test_bls12381_double_execute() {
+        let program = double_program(SyscallCode::BLS12381_DOUBLE, &BLS12381_G);
+        let mut runtime = Runtime::new(program);
+        runtime.run().unwrap();
+        let result = (0..24)
+            .flat_map(|i| runtime.word(P_PTR + i * 4).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(result, BLS12381_2G);
+        assert_eq!(runtime.record.bls12381_double_events.len(), 1);
+    }
+
+    #[test]
+    fn test_bls12381_double_prove() {
+        setup_logger();
+        let program = double_program(SyscallCode::BLS12381_DOUBLE, &BLS12381_G);
+        run_test(halting(program)).unwrap();
+    }
+
+    #[test]
+    fn test_secp256r1_double_execute() {
+        let program = double_program(SyscallCode::SECP256R1_DOUBLE, &SECP256R1_G);
+        let mut runtime = Runtime::new(program);
+        runtime.run().unwrap();
+        let result = (0..16)
+            .flat_map(|i| runtime.word(P_PTR + i * 4).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(result, SECP256R1_2G);
+        assert_eq!(runtime.record.secp256r1_double_events.len(), 1);
+        assert!(runtime.record.secp256k1_double_events.is_empty());
+    }
+
+    #[test]
+    fn test_secp256r1_double_prove() {
+        setup_logger();
+        let program = double_program(SyscallCode::SECP256R1_DOUBLE, &SECP256R1_G);
+        run_test(halting(program)).unwrap();
+    }
 }
@@ -1,4 +1,4 @@
-use crate::runtime::{ForkState, Syscall, SyscallContext};
+use crate::runtime::{ExecutionErrorKind, ForkState, Syscall, SyscallContext};
 use hashbrown::HashMap;
 
 pub struct SyscallEnterUnconstrained;
@@ -10,9 +10,9 @@ impl SyscallEnterUnconstrained {
 }
 
 impl Syscall for SyscallEnterUnconstrained {
-    fn execute(&self, ctx: &mut SyscallContext) -> u32 {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         if ctx.rt.unconstrained {
-            panic!("Unconstrained block is already active.");
+            return Err(ExecutionErrorKind::NestedUnconstrained);
         }
         ctx.rt.unconstrained = true;
         ctx.rt.unconstrained_state = ForkState {
@@ -23,7 +23,7 @@ impl Syscall for SyscallEnterUnconstrained {
             record: std::mem::take(&mut ctx.rt.record),
             op_record: std::mem::take(&mut ctx.rt.cpu_record),
         };
-        1
+        Ok(1)
     }
 }
 
@@ -36,7 +36,7 @@ impl SyscallExitUnconstrained {
 }
 
 impl Syscall for SyscallExitUnconstrained {
-    fn execute(&self, ctx: &mut SyscallContext) -> u32 {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         // Reset the state of the runtime.
         if ctx.rt.unconstrained {
             ctx.rt.state.global_clk = ctx.rt.unconstrained_state.global_clk;
@@ -58,6 +58,6 @@ impl Syscall for SyscallExitUnconstrained {
             ctx.rt.unconstrained = false;
         }
         ctx.rt.unconstrained_state = ForkState::default();
-        0
+        Ok(0)
     }
 }
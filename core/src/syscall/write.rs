@@ -1,5 +1,5 @@
 use crate::{
-    runtime::{Register, Syscall, SyscallContext},
+    runtime::{ExecutionErrorKind, Register, Syscall, SyscallContext},
     utils::u32_to_comma_separated,
 };
 
@@ -12,7 +12,7 @@ impl SyscallWrite {
 }
 
 impl Syscall for SyscallWrite {
-    fn execute(&self, ctx: &mut SyscallContext) -> u32 {
+    fn execute(&self, ctx: &mut SyscallContext) -> Result<u32, ExecutionErrorKind> {
         let a0 = Register::X10;
         let a1 = Register::X11;
         let a2 = Register::X12;
@@ -27,7 +27,8 @@ impl Syscall for SyscallWrite {
                 .collect::<Vec<u8>>();
             let slice = bytes.as_slice();
             if fd == 1 {
-                let s = core::str::from_utf8(slice).unwrap();
+                let s =
+                    core::str::from_utf8(slice).map_err(|_| ExecutionErrorKind::InvalidUtf8(fd))?;
                 if s.contains("cycle-tracker-start:") {
                     let fn_name = s
                         .split("cycle-tracker-start:")
@@ -35,9 +36,9 @@ impl Syscall for SyscallWrite {
                         .unwrap()
                         .trim_end()
                         .trim_start();
-                    let depth = rt.cycle_tracker.len() as u32;
-                    rt.cycle_tracker
-                        .insert(fn_name.to_string(), (rt.state.global_clk, depth));
+                    let depth = rt.cycle_tracker.depth();
+                    let events = rt.record.event_counts();
+                    rt.cycle_tracker.start(fn_name, rt.state.global_clk, events);
                     let padding = (0..depth).map(|_| "│ ").collect::<String>();
                     log::info!("{}┌╴{}", padding, fn_name);
                 } else if s.contains("cycle-tracker-end:") {
@@ -47,14 +48,15 @@ impl Syscall for SyscallWrite {
                         .unwrap()
                         .trim_end()
                         .trim_start();
-                    let (start, depth) = rt.cycle_tracker.remove(fn_name).unwrap_or((0, 0));
-                    // Leftpad by 2 spaces for each depth.
-                    let padding = (0..depth).map(|_| "│ ").collect::<String>();
-                    log::info!(
-                        "{}└╴{} cycles",
-                        padding,
-                        u32_to_comma_separated(rt.state.global_clk - start)
-                    );
+                    let events = rt.record.event_counts();
+                    match rt.cycle_tracker.end(fn_name, rt.state.global_clk, &events) {
+                        Some((depth, cycles)) => {
+                            // Leftpad by 2 spaces for each depth.
+                            let padding = (0..depth).map(|_| "│ ").collect::<String>();
+                            log::info!("{}└╴{} cycles", padding, u32_to_comma_separated(cycles));
+                        }
+                        None => log::warn!("cycle-tracker-end: {} was never started", fn_name),
+                    }
                 } else {
                     let flush_s = update_io_buf(ctx, fd, s);
                     if !flush_s.is_empty() {
@@ -64,7 +66,8 @@ impl Syscall for SyscallWrite {
                     }
                 }
             } else if fd == 2 {
-                let s = core::str::from_utf8(slice).unwrap();
+                let s =
+                    core::str::from_utf8(slice).map_err(|_| ExecutionErrorKind::InvalidUtf8(fd))?;
                 let flush_s = update_io_buf(ctx, fd, s);
                 if !flush_s.is_empty() {
                     flush_s
@@ -72,14 +75,19 @@ impl Syscall for SyscallWrite {
                         .for_each(|line| println!("[stderr] {}", line));
                 }
             } else if fd == 3 {
-                rt.state.output_stream.extend_from_slice(slice);
+                // The program only hashes the output it writes outside of unconstrained blocks.
+                if !rt.unconstrained {
+                    rt.state.output_stream.extend_from_slice(slice);
+                }
             } else if fd == 4 {
-                rt.state.input_stream.extend_from_slice(slice);
+                rt.state.hint_stream.extend_from_slice(slice);
             } else {
                 unreachable!()
             }
+        } else {
+            return Err(ExecutionErrorKind::InvalidFileDescriptor(fd));
         }
-        0
+        Ok(0)
     }
 }
 
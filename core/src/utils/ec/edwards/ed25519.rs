@@ -6,7 +6,7 @@ use std::str::FromStr;
 use crate::operations::field::params::{NB_BITS_PER_LIMB, NUM_LIMBS};
 use crate::utils::ec::edwards::{EdwardsCurve, EdwardsParameters};
 use crate::utils::ec::field::{FieldParameters, MAX_NB_LIMBS};
-use crate::utils::ec::{AffinePoint, EllipticCurveParameters};
+use crate::utils::ec::{AffinePoint, CurveType, EllipticCurveParameters};
 
 pub type Ed25519 = EdwardsCurve<Ed25519Parameters>;
 
@@ -20,9 +20,10 @@ impl FieldParameters for Ed25519BaseField {
     const NB_BITS_PER_LIMB: usize = NB_BITS_PER_LIMB;
     const NB_LIMBS: usize = NUM_LIMBS;
     const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
-    const MODULUS: [u8; NUM_LIMBS] = [
+    const MODULUS: [u8; MAX_NB_LIMBS] = [
         237, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
-        255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 127,
+        255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 127, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
     const WITNESS_OFFSET: usize = 1usize << 13;
 
@@ -33,12 +34,15 @@ impl FieldParameters for Ed25519BaseField {
 
 impl EllipticCurveParameters for Ed25519Parameters {
     type BaseField = Ed25519BaseField;
+
+    const CURVE_TYPE: CurveType = CurveType::Ed25519;
 }
 
 impl EdwardsParameters for Ed25519Parameters {
     const D: [u16; MAX_NB_LIMBS] = [
         30883, 4953, 19914, 30187, 55467, 16705, 2637, 112, 59544, 30585, 16505, 36039, 65139,
-        11119, 27886, 20995, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        11119, 27886, 20995, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
 
     fn prime_group_order() -> BigUint {
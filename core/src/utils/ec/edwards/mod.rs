@@ -4,7 +4,7 @@ use num::{BigUint, Zero};
 use serde::{Deserialize, Serialize};
 
 use crate::utils::ec::field::{FieldParameters, MAX_NB_LIMBS};
-use crate::utils::ec::{AffinePoint, EllipticCurve, EllipticCurveParameters};
+use crate::utils::ec::{AffinePoint, CurveType, EllipticCurve, EllipticCurveParameters};
 
 pub trait EdwardsParameters: EllipticCurveParameters {
     const D: [u16; MAX_NB_LIMBS];
@@ -52,6 +52,8 @@ impl<E: EdwardsParameters> EdwardsParameters for EdwardsCurve<E> {
 
 impl<E: EdwardsParameters> EllipticCurveParameters for EdwardsCurve<E> {
     type BaseField = E::BaseField;
+
+    const CURVE_TYPE: CurveType = E::CURVE_TYPE;
 }
 
 impl<E: EdwardsParameters> EdwardsCurve<E> {
@@ -7,7 +7,8 @@ use p3_field::Field;
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
 
-pub const MAX_NB_LIMBS: usize = 32;
+/// The largest number of limbs of a field, which is the one of the 381-bit BLS12-381 base field.
+pub const MAX_NB_LIMBS: usize = 48;
 
 pub trait FieldParameters:
     Send + Sync + Copy + 'static + Debug + Serialize + DeserializeOwned
@@ -16,7 +17,9 @@ pub trait FieldParameters:
     const NB_LIMBS: usize = NUM_LIMBS;
     const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
     const WITNESS_OFFSET: usize = 1usize << 13;
-    const MODULUS: [u8; NUM_LIMBS];
+
+    /// The limbs of the modulus, padded with zeros to `MAX_NB_LIMBS` limbs.
+    const MODULUS: [u8; MAX_NB_LIMBS];
 
     fn modulus() -> BigUint {
         biguint_from_limbs(&Self::MODULUS)
@@ -33,18 +36,17 @@ pub trait FieldParameters:
             .take(Self::NB_LIMBS)
     }
 
-    fn to_limbs(x: &BigUint) -> Limbs<u8> {
+    fn to_limbs(x: &BigUint) -> Vec<u8> {
         let mut bytes = x.to_bytes_le();
-        bytes.resize(NUM_LIMBS, 0u8);
-        let mut limbs = [0u8; NUM_LIMBS];
-        limbs.copy_from_slice(&bytes);
-        Limbs(limbs)
+        bytes.resize(Self::NB_LIMBS, 0u8);
+        bytes
     }
 
-    fn to_limbs_field<F: Field>(x: &BigUint) -> Limbs<F> {
+    /// Converts `x` to `N` limbs, where `N` must be the number of limbs of the field.
+    fn to_limbs_field<F: Field, const N: usize>(x: &BigUint) -> Limbs<F, N> {
+        debug_assert_eq!(N, Self::NB_LIMBS);
         Limbs(
             Self::to_limbs(x)
-                .0
                 .into_iter()
                 .map(|x| F::from_canonical_u8(x))
                 .collect::<Vec<F>>()
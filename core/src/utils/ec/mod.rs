@@ -6,12 +6,11 @@ pub mod weierstrass;
 
 use field::FieldParameters;
 use num::BigUint;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::fmt::Debug;
 use std::ops::{Add, Neg};
 
 use crate::air::WORD_SIZE;
-use crate::operations::field::params::NUM_LIMBS;
 
 pub const NUM_WORDS_FIELD_ELEMENT: usize = 8;
 pub const NUM_BYTES_FIELD_ELEMENT: usize = NUM_WORDS_FIELD_ELEMENT * WORD_SIZE;
@@ -21,6 +20,12 @@ pub const COMPRESSED_POINT_BYTES: usize = 32;
 /// words needed to represent a field element as a point consists of the x and y coordinates.
 pub const NUM_WORDS_EC_POINT: usize = 2 * NUM_WORDS_FIELD_ELEMENT;
 
+/// The number of words needed to represent a point on the curve `E`, which is `NUM_WORDS_EC_POINT`
+/// unless the base field of the curve is larger than 256 bits.
+pub fn num_words_ec_point<E: EllipticCurveParameters>() -> usize {
+    2 * E::BaseField::NB_LIMBS / WORD_SIZE
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AffinePoint<E> {
     pub x: BigUint,
@@ -55,36 +60,36 @@ impl<E> AffinePoint<E> {
             _marker: std::marker::PhantomData,
         }
     }
+}
 
-    pub fn to_words_le(&self) -> [u32; 16] {
-        let mut x_bytes = self.x.to_bytes_le();
-        x_bytes.resize(NUM_LIMBS, 0u8);
-        let mut y_bytes = self.y.to_bytes_le();
-        y_bytes.resize(NUM_LIMBS, 0u8);
-
-        let mut words = [0u32; 16];
-        for i in 0..8 {
-            words[i] = u32::from_le_bytes([
-                x_bytes[i * 4],
-                x_bytes[i * 4 + 1],
-                x_bytes[i * 4 + 2],
-                x_bytes[i * 4 + 3],
-            ]);
-            words[i + 8] = u32::from_le_bytes([
-                y_bytes[i * 4],
-                y_bytes[i * 4 + 1],
-                y_bytes[i * 4 + 2],
-                y_bytes[i * 4 + 3],
-            ]);
-        }
-        words
+impl<E: EllipticCurveParameters> AffinePoint<E> {
+    pub fn to_words_le(&self) -> Vec<u32> {
+        let mut bytes = E::BaseField::to_limbs(&self.x);
+        bytes.extend(E::BaseField::to_limbs(&self.y));
+        bytes
+            .chunks_exact(WORD_SIZE)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect()
     }
 }
 
+/// The curves with precompiles, which tells apart the events and chips of curves sharing the
+/// same chip implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CurveType {
+    Secp256k1,
+    Bn254,
+    Ed25519,
+    Bls12381,
+    Secp256r1,
+}
+
 pub trait EllipticCurveParameters:
     Debug + Send + Sync + Copy + Serialize + DeserializeOwned + 'static
 {
     type BaseField: FieldParameters;
+
+    const CURVE_TYPE: CurveType;
 }
 
 /// An interface for elliptic curve groups.
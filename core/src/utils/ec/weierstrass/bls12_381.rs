@@ -0,0 +1,144 @@
+//! Modulo defining the BLS12-381 curve and its base field. The constants are all taken from
+//! https://github.com/zkcrypto/bls12_381.
+
+use std::str::FromStr;
+
+use num::{BigUint, Num, Zero};
+use serde::{Deserialize, Serialize};
+
+use super::{SwCurve, WeierstrassParameters};
+use crate::air::WORD_SIZE;
+use crate::operations::field::params::NB_BITS_PER_LIMB;
+use crate::utils::ec::field::{FieldParameters, MAX_NB_LIMBS};
+use crate::utils::ec::{CurveType, EllipticCurveParameters};
+
+/// The number of limbs of an element of the BLS12-381 base field.
+pub const BLS12381_NUM_LIMBS: usize = 48;
+
+/// The number of witness limbs of a field operation in the BLS12-381 base field.
+pub const BLS12381_NUM_WITNESS_LIMBS: usize = 2 * BLS12381_NUM_LIMBS - 2;
+
+/// The number of words needed to represent an element of the BLS12-381 base field.
+pub const BLS12381_NUM_WORDS_FIELD_ELEMENT: usize = BLS12381_NUM_LIMBS / WORD_SIZE;
+
+/// The number of words needed to represent a point on the BLS12-381 curve.
+pub const BLS12381_NUM_WORDS_EC_POINT: usize = 2 * BLS12381_NUM_WORDS_FIELD_ELEMENT;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Bls12381 curve parameter
+pub struct Bls12381Parameters;
+
+pub type Bls12381 = SwCurve<Bls12381Parameters>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Bls12381 base field parameter
+pub struct Bls12381BaseField;
+
+impl FieldParameters for Bls12381BaseField {
+    const NB_BITS_PER_LIMB: usize = NB_BITS_PER_LIMB;
+
+    const NB_LIMBS: usize = BLS12381_NUM_LIMBS;
+
+    const NB_WITNESS_LIMBS: usize = BLS12381_NUM_WITNESS_LIMBS;
+
+    const MODULUS: [u8; MAX_NB_LIMBS] = [
+        171, 170, 255, 255, 255, 255, 254, 185, 255, 255, 83, 177, 254, 255, 171, 30, 36, 246, 176,
+        246, 160, 210, 48, 103, 191, 18, 133, 243, 132, 75, 119, 100, 215, 172, 75, 67, 182, 167,
+        27, 75, 154, 230, 127, 57, 234, 17, 1, 26,
+    ];
+
+    /// A rough witness-offset estimate given the size of the limbs and the size of the field.
+    const WITNESS_OFFSET: usize = 1usize << 15;
+
+    fn modulus() -> BigUint {
+        BigUint::from_str_radix(
+            "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787",
+            10,
+        )
+        .unwrap()
+    }
+}
+
+impl EllipticCurveParameters for Bls12381Parameters {
+    type BaseField = Bls12381BaseField;
+
+    const CURVE_TYPE: CurveType = CurveType::Bls12381;
+}
+
+impl WeierstrassParameters for Bls12381Parameters {
+    const A: [u16; MAX_NB_LIMBS] = [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    const B: [u16; MAX_NB_LIMBS] = [
+        4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    fn generator() -> (BigUint, BigUint) {
+        let x = BigUint::from_str(
+            "3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507",
+        )
+        .unwrap();
+        let y = BigUint::from_str(
+            "1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569",
+        )
+        .unwrap();
+        (x, y)
+    }
+
+    fn prime_group_order() -> num::BigUint {
+        BigUint::from_str(
+            "52435875175126190479447740508185965837690552500527637822603658699938581184513",
+        )
+        .unwrap()
+    }
+
+    fn a_int() -> BigUint {
+        BigUint::zero()
+    }
+
+    fn b_int() -> BigUint {
+        BigUint::from(4u32)
+    }
+}
+
+/// Computes a square root of `n` in the base field, assuming that `n` is a square.
+///
+/// As the modulus is `3 mod 4`, the square root is `n^((p + 1) / 4)`.
+pub fn bls12381_sqrt(n: &BigUint) -> BigUint {
+    let modulus = Bls12381BaseField::modulus();
+    let exponent = (&modulus + 1u32) >> 2;
+    n.modpow(&exponent, &modulus)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::utils::ec::utils::biguint_from_limbs;
+    use num::bigint::RandBigInt;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_weierstrass_biguint_scalar_mul() {
+        assert_eq!(
+            biguint_from_limbs(&Bls12381BaseField::MODULUS),
+            Bls12381BaseField::modulus()
+        );
+    }
+
+    #[test]
+    fn test_bls12381_sqrt() {
+        let mut rng = thread_rng();
+        let modulus = Bls12381BaseField::modulus();
+        for _ in 0..10 {
+            // Check that sqrt(x^2)^2 == x^2, as not all field elements have a square root.
+            let x = rng.gen_biguint(381) % &modulus;
+            let x_2 = (&x * &x) % &modulus;
+            let sqrt = bls12381_sqrt(&x_2);
+            assert_eq!((&sqrt * &sqrt) % &modulus, x_2);
+        }
+    }
+}
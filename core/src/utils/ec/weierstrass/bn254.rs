@@ -2,8 +2,9 @@ use num::{BigUint, Num, Zero};
 use serde::{Deserialize, Serialize};
 
 use super::{SwCurve, WeierstrassParameters};
+use crate::operations::field::params::{NB_BITS_PER_LIMB, NUM_LIMBS};
 use crate::utils::ec::field::{FieldParameters, MAX_NB_LIMBS};
-use crate::utils::ec::EllipticCurveParameters;
+use crate::utils::ec::{CurveType, EllipticCurveParameters};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 /// Bn254 curve parameter
@@ -16,18 +17,20 @@ pub type Bn254 = SwCurve<Bn254Parameters>;
 pub struct Bn254BaseField;
 
 impl FieldParameters for Bn254BaseField {
-    const NB_BITS_PER_LIMB: usize = 16;
+    const NB_BITS_PER_LIMB: usize = NB_BITS_PER_LIMB;
 
-    const NB_LIMBS: usize = 16;
+    const NB_LIMBS: usize = NUM_LIMBS;
 
     const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
 
     const MODULUS: [u8; MAX_NB_LIMBS] = [
         71, 253, 124, 216, 22, 140, 32, 60, 141, 202, 113, 104, 145, 106, 129, 151, 93, 88, 129,
-        129, 182, 69, 80, 184, 41, 160, 49, 225, 114, 78, 100, 48,
+        129, 182, 69, 80, 184, 41, 160, 49, 225, 114, 78, 100, 48, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0,
     ];
 
-    const WITNESS_OFFSET: usize = 1usize << 20;
+    /// A rough witness-offset estimate given the size of the limbs and the size of the field.
+    const WITNESS_OFFSET: usize = 1usize << 14;
 
     fn modulus() -> BigUint {
         BigUint::from_str_radix(
@@ -40,17 +43,19 @@ impl FieldParameters for Bn254BaseField {
 
 impl EllipticCurveParameters for Bn254Parameters {
     type BaseField = Bn254BaseField;
+
+    const CURVE_TYPE: CurveType = CurveType::Bn254;
 }
 
 impl WeierstrassParameters for Bn254Parameters {
     const A: [u16; MAX_NB_LIMBS] = [
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
 
     const B: [u16; MAX_NB_LIMBS] = [
         3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
     fn generator() -> (BigUint, BigUint) {
         let x = BigUint::from(1u32);
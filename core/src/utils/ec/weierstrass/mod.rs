@@ -3,10 +3,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::utils::ec::field::{FieldParameters, MAX_NB_LIMBS};
 use crate::utils::ec::utils::biguint_to_bits_le;
-use crate::utils::ec::{AffinePoint, EllipticCurve, EllipticCurveParameters};
+use crate::utils::ec::{AffinePoint, CurveType, EllipticCurve, EllipticCurveParameters};
 
+pub mod bls12_381;
 pub mod bn254;
 pub mod secp256k1;
+pub mod secp256r1;
 
 /// Parameters that specify a short Weierstrass curve : y^2 = x^3 + ax + b.
 pub trait WeierstrassParameters: EllipticCurveParameters {
@@ -68,6 +70,8 @@ impl<E: WeierstrassParameters> WeierstrassParameters for SwCurve<E> {
 
 impl<E: WeierstrassParameters> EllipticCurveParameters for SwCurve<E> {
     type BaseField = E::BaseField;
+
+    const CURVE_TYPE: CurveType = E::CURVE_TYPE;
 }
 
 impl<E: WeierstrassParameters> EllipticCurve for SwCurve<E> {
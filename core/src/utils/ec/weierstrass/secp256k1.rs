@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use super::{SwCurve, WeierstrassParameters};
 use crate::operations::field::params::{NB_BITS_PER_LIMB, NUM_LIMBS};
 use crate::utils::ec::field::{FieldParameters, MAX_NB_LIMBS};
-use crate::utils::ec::EllipticCurveParameters;
+use crate::utils::ec::{CurveType, EllipticCurveParameters};
 use k256::FieldElement;
 use num::traits::FromBytes;
 use num::traits::ToBytes;
@@ -34,7 +34,7 @@ impl FieldParameters for Secp256k1BaseField {
     const MODULUS: [u8; MAX_NB_LIMBS] = [
         0x2f, 0xfc, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
         0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
-        0xff, 0xff,
+        0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
 
     /// A rough witness-offset estimate given the size of the limbs and the size of the field.
@@ -47,17 +47,19 @@ impl FieldParameters for Secp256k1BaseField {
 
 impl EllipticCurveParameters for Secp256k1Parameters {
     type BaseField = Secp256k1BaseField;
+
+    const CURVE_TYPE: CurveType = CurveType::Secp256k1;
 }
 
 impl WeierstrassParameters for Secp256k1Parameters {
     const A: [u16; MAX_NB_LIMBS] = [
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
 
     const B: [u16; MAX_NB_LIMBS] = [
         7, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
     fn generator() -> (BigUint, BigUint) {
         let x = BigUint::from_str(
@@ -0,0 +1,127 @@
+//! Modulo defining the Secp256r1 (P-256) curve and its base field. The constants are all taken
+//! from https://neuromancer.sk/std/nist/P-256.
+
+use std::str::FromStr;
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use super::{SwCurve, WeierstrassParameters};
+use crate::operations::field::params::{NB_BITS_PER_LIMB, NUM_LIMBS};
+use crate::utils::ec::field::{FieldParameters, MAX_NB_LIMBS};
+use crate::utils::ec::{CurveType, EllipticCurveParameters};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Secp256r1 curve parameter
+pub struct Secp256r1Parameters;
+
+pub type Secp256r1 = SwCurve<Secp256r1Parameters>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Secp256r1 base field parameter
+pub struct Secp256r1BaseField;
+
+impl FieldParameters for Secp256r1BaseField {
+    const NB_BITS_PER_LIMB: usize = NB_BITS_PER_LIMB;
+
+    const NB_LIMBS: usize = NUM_LIMBS;
+
+    const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+
+    const MODULUS: [u8; MAX_NB_LIMBS] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0xff, 0xff,
+        0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    /// A rough witness-offset estimate given the size of the limbs and the size of the field.
+    const WITNESS_OFFSET: usize = 1usize << 14;
+
+    fn modulus() -> BigUint {
+        BigUint::from_bytes_le(&Self::MODULUS)
+    }
+}
+
+impl EllipticCurveParameters for Secp256r1Parameters {
+    type BaseField = Secp256r1BaseField;
+
+    const CURVE_TYPE: CurveType = CurveType::Secp256r1;
+}
+
+impl WeierstrassParameters for Secp256r1Parameters {
+    /// The coefficient `a = -3` of the curve, reduced modulo the base field modulus.
+    const A: [u16; MAX_NB_LIMBS] = [
+        65532, 65535, 65535, 65535, 65535, 65535, 0, 0, 0, 0, 0, 0, 1, 0, 65535, 65535, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    const B: [u16; MAX_NB_LIMBS] = [
+        24651, 10194, 15422, 15310, 45302, 52307, 1712, 25885, 34492, 30360, 48469, 46059, 37863,
+        43578, 13784, 23238, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    fn generator() -> (BigUint, BigUint) {
+        let x = BigUint::from_str(
+            "48439561293906451759052585252797914202762949526041747995844080717082404635286",
+        )
+        .unwrap();
+        let y = BigUint::from_str(
+            "36134250956749795798585127919587881956611106672985015071877198253568414405109",
+        )
+        .unwrap();
+        (x, y)
+    }
+
+    fn prime_group_order() -> num::BigUint {
+        BigUint::from_slice(&[
+            0xFC632551, 0xF3B9CAC2, 0xA7179E84, 0xBCE6FAAD, 0xFFFFFFFF, 0xFFFFFFFF, 0x00000000,
+            0xFFFFFFFF,
+        ])
+    }
+}
+
+/// Computes a square root of `n` in the base field, assuming that `n` is a square.
+///
+/// As the modulus is `3 mod 4`, the square root is `n^((p + 1) / 4)`.
+pub fn secp256r1_sqrt(n: &BigUint) -> BigUint {
+    let modulus = Secp256r1BaseField::modulus();
+    let exponent = (&modulus + 1u32) >> 2;
+    n.modpow(&exponent, &modulus)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::utils::ec::utils::biguint_from_limbs;
+    use num::bigint::RandBigInt;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_weierstrass_biguint_scalar_mul() {
+        assert_eq!(
+            biguint_from_limbs(&Secp256r1BaseField::MODULUS),
+            Secp256r1BaseField::modulus()
+        );
+    }
+
+    #[test]
+    fn test_secp256r1_a() {
+        let modulus = Secp256r1BaseField::modulus();
+        assert_eq!(Secp256r1Parameters::a_int(), &modulus - BigUint::from(3u32));
+    }
+
+    #[test]
+    fn test_secp256r1_sqrt() {
+        let mut rng = thread_rng();
+        let modulus = Secp256r1BaseField::modulus();
+        for _ in 0..10 {
+            // Check that sqrt(x^2)^2 == x^2, as not all field elements have a square root.
+            let x = rng.gen_biguint(256) % &modulus;
+            let x_2 = (&x * &x) % &modulus;
+            let sqrt = secp256r1_sqrt(&x_2);
+            assert_eq!((&sqrt * &sqrt) % &modulus, x_2);
+        }
+    }
+}
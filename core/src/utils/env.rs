@@ -1,3 +1,7 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+
 /// Gets the number of rows which by default should be used for each chip to maximize padding.
 ///
 /// Some chips, such as FieldLTU, may use a constant multiple of this value to optimize performance.
@@ -25,3 +29,27 @@ pub fn reconstruct_commitments() -> bool {
         Err(_) => true,
     }
 }
+
+/// Gets the maximum number of cycles a program may execute for, if any.
+pub fn max_cycles() -> anyhow::Result<Option<u64>> {
+    parse_optional("MAX_CYCLES")
+}
+
+/// Gets the maximum number of memory words a program may use, if any.
+pub fn max_memory() -> anyhow::Result<Option<usize>> {
+    parse_optional("MAX_MEMORY")
+}
+
+/// Parses the value of the environment variable `name`, if it is set.
+fn parse_optional<T: FromStr>(name: &str) -> anyhow::Result<Option<T>>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match std::env::var(name) {
+        Ok(val) => val
+            .parse()
+            .map(Some)
+            .with_context(|| format!("invalid value {:?} for {}", val, name)),
+        Err(_) => Ok(None),
+    }
+}
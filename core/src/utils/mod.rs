@@ -36,7 +36,9 @@ pub fn pad_to_power_of_two<const N: usize, T: Clone + Default>(values: &mut Vec<
     values.resize(n_real_rows.next_power_of_two() * N, T::default());
 }
 
-pub fn limbs_from_prev_access<T: Copy, M: MemoryCols<T>>(cols: &[M]) -> Limbs<T> {
+pub fn limbs_from_prev_access<T: Copy, M: MemoryCols<T>, const N: usize>(
+    cols: &[M],
+) -> Limbs<T, N> {
     let vec = cols
         .iter()
         .flat_map(|access| access.prev_value().0)
@@ -48,7 +50,7 @@ pub fn limbs_from_prev_access<T: Copy, M: MemoryCols<T>>(cols: &[M]) -> Limbs<T>
     Limbs(sized)
 }
 
-pub fn limbs_from_access<T: Copy, M: MemoryCols<T>>(cols: &[M]) -> Limbs<T> {
+pub fn limbs_from_access<T: Copy, M: MemoryCols<T>, const N: usize>(cols: &[M]) -> Limbs<T, N> {
     let vec = cols
         .iter()
         .flat_map(|access| access.value().0)
@@ -60,7 +62,7 @@ pub fn limbs_from_access<T: Copy, M: MemoryCols<T>>(cols: &[M]) -> Limbs<T> {
     Limbs(sized)
 }
 
-pub fn pad_rows<T: Clone, const N: usize>(rows: &mut Vec<[T; N]>, row_fn: impl Fn() -> [T; N]) {
+pub fn pad_rows<R: Clone>(rows: &mut Vec<R>, row_fn: impl Fn() -> R) {
     let nb_rows = rows.len();
     let mut padded_nb_rows = nb_rows.next_power_of_two();
     if padded_nb_rows == 2 || padded_nb_rows == 1 {
@@ -2,11 +2,12 @@ use std::time::Instant;
 
 use crate::utils::poseidon2_instance::RC_16_30;
 use crate::{
-    runtime::{Program, Runtime},
+    runtime::{ExecutionCheckpoint, Program, Runtime, ShardingConfig},
     stark::{LocalProver, OpeningProof, ShardMainData},
     stark::{RiscvStark, StarkGenericConfig},
 };
 pub use baby_bear_blake3::BabyBearBlake3;
+use p3_challenger::CanObserve;
 use p3_commit::Pcs;
 use p3_field::PrimeField32;
 use serde::de::DeserializeOwned;
@@ -27,14 +28,14 @@ pub trait StarkUtils: StarkGenericConfig {
 
 pub fn get_cycles(program: Program) -> u64 {
     let mut runtime = Runtime::new(program);
-    runtime.run();
+    runtime.run().unwrap();
     runtime.state.global_clk as u64
 }
 
 pub fn prove(program: Program) -> crate::stark::Proof<BabyBearBlake3> {
     let runtime = tracing::info_span!("runtime.run(...)").in_scope(|| {
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         runtime
     });
     let config = BabyBearBlake3::new();
@@ -48,7 +49,7 @@ pub fn run_test(program: Program) -> Result<(), crate::stark::ProgramVerificatio
 
     let runtime = tracing::info_span!("runtime.run(...)").in_scope(|| {
         let mut runtime = Runtime::new(program);
-        runtime.run();
+        runtime.run().unwrap();
         runtime
     });
     let config = BabyBearBlake3::new();
@@ -126,6 +127,149 @@ where
     proof
 }
 
+/// Prove the execution of a program one shard at a time, so that the memory used grows with the
+/// size of a shard rather than with the number of cycles of the execution.
+///
+/// `new_runtime` must return a runtime for the program with its input written, which has not been
+/// initialized. The program is executed twice: the first time, the record of each shard of the
+/// runtime is committed to and dropped as soon as the shard ends, and a checkpoint is saved to a
+/// temporary directory at the start of each shard. Once all commitments have been observed, each
+/// shard is executed again from its checkpoint to prove it, and fails if its commitment differs
+/// from the one observed in the first execution. The finished runtime of the first execution is
+/// returned along with the proof, with an empty record.
+///
+/// Unless `allow_nonzero_exit` is set, fails after the first execution, before proving anything,
+/// if the program exits with a non-zero exit code.
+pub fn prove_core_streaming<SC, F>(
+    config: SC,
+    new_runtime: F,
+    allow_nonzero_exit: bool,
+) -> anyhow::Result<(crate::stark::Proof<SC>, Runtime)>
+where
+    SC: StarkGenericConfig + StarkUtils + Send + Sync + Serialize,
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    <SC::Pcs as Pcs<SC::Val, RowMajorMatrix<SC::Val>>>::Commitment: Send + Sync,
+    <SC::Pcs as Pcs<SC::Val, RowMajorMatrix<SC::Val>>>::ProverData: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+    F: Fn() -> Runtime,
+{
+    let mut challenger = config.challenger();
+
+    let start = Instant::now();
+
+    let machine = RiscvStark::new(config);
+    let config = machine.config();
+    let sharding_config = ShardingConfig::default();
+    let checkpoint_dir = tempfile::tempdir()?;
+
+    let mut runtime = new_runtime();
+    let (pk, _) = machine.setup(runtime.program.as_ref());
+    pk.observe_into(&mut challenger);
+
+    // Execute the program, committing to the main traces of each shard as soon as it ends.
+    let mut checkpoints = Vec::new();
+    let mut main_commits = Vec::new();
+    runtime.initialize();
+    let public_values = loop {
+        let path = checkpoint_dir
+            .path()
+            .join(format!("checkpoint-{}", checkpoints.len()));
+        runtime.checkpoint()?.save(&path)?;
+        checkpoints.push(path);
+
+        runtime.run_shard()?;
+        let done = runtime.is_done();
+        if done {
+            runtime.finalize()?;
+        }
+        let record = runtime.take_record();
+        let public_values = record.public_values;
+        tracing::info_span!("commit shard", shard = checkpoints.len()).in_scope(|| {
+            for shard in machine.shard(record, &sharding_config) {
+                let data = LocalProver::commit_main(config, &machine, &shard, main_commits.len());
+                main_commits.push(bincode::serialize(&data.main_commit)?);
+                challenger.observe(data.main_commit);
+            }
+            Ok::<_, bincode::Error>(())
+        })?;
+        if done {
+            break public_values;
+        }
+    };
+    let num_shards = main_commits.len();
+    tracing::info!("num_shards={}", num_shards);
+    if !allow_nonzero_exit && runtime.state.exit_code != 0 {
+        anyhow::bail!(
+            "program exited with non-zero exit code {}",
+            runtime.state.exit_code
+        );
+    }
+
+    // Execute each shard again from its checkpoint to prove it, with the public values of the
+    // whole execution. Note that we clone the challenger so we can observe identical global
+    // challenges across the shards.
+    let mut shard_proofs = Vec::with_capacity(num_shards);
+    for path in checkpoints.iter() {
+        let mut shard_runtime = new_runtime();
+        shard_runtime.resume(ExecutionCheckpoint::load(path)?)?;
+        shard_runtime.run_shard()?;
+        if shard_runtime.is_done() {
+            shard_runtime.finalize()?;
+        }
+        let mut record = shard_runtime.take_record();
+        record.public_values = public_values;
+        for shard in machine.shard(record, &sharding_config) {
+            let index = shard_proofs.len();
+            let data = LocalProver::commit_main(config, &machine, &shard, index);
+            if main_commits.get(index) != Some(&bincode::serialize(&data.main_commit)?) {
+                anyhow::bail!(
+                    "the execution diverged when it was resumed from its checkpoints: the \
+                     commitment to shard {} differs from the first execution",
+                    index + 1
+                );
+            }
+            let chips = machine.shard_chips(&shard).collect::<Vec<_>>();
+            let is_last_shard = index == num_shards - 1;
+            let proof = tracing::info_span!("prove shard", shard = index).in_scope(|| {
+                LocalProver::prove_shard(
+                    config,
+                    &pk,
+                    &chips,
+                    data,
+                    is_last_shard,
+                    &mut challenger.clone(),
+                )
+            });
+            shard_proofs.push(proof);
+        }
+    }
+    if shard_proofs.len() != num_shards {
+        anyhow::bail!(
+            "the execution diverged when it was resumed from its checkpoints: it produced {} \
+             shards instead of {}",
+            shard_proofs.len(),
+            num_shards
+        );
+    }
+    let proof = crate::stark::Proof { shard_proofs };
+
+    let cycles = runtime.state.global_clk;
+    let time = start.elapsed().as_millis();
+    let nb_bytes = bincode::serialize(&proof).unwrap().len();
+
+    tracing::info!(
+        "cycles={}, e2e={}, khz={:.2}, proofSize={}",
+        cycles,
+        time,
+        (cycles as f64 / time as f64),
+        Size::from_bytes(nb_bytes),
+    );
+
+    Ok((proof, runtime))
+}
+
 pub fn uni_stark_prove<SC, A>(
     config: &SC,
     air: &A,
@@ -36,21 +36,41 @@ pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
 
     // Get struct name from ast
     let name = &ast.ident;
+
+    // The const generics of the struct, such as its number of limbs, which follow the type of the
+    // columns.
+    let const_params = ast
+        .generics
+        .const_params()
+        .map(|param| {
+            let ident = &param.ident;
+            let ty = &param.ty;
+            quote! { const #ident: #ty }
+        })
+        .collect::<Vec<_>>();
+    let const_args = ast
+        .generics
+        .const_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
     let methods = quote! {
-        impl<T: Copy> core::borrow::Borrow<#name<T>> for [T] {
-            fn borrow(&self) -> &#name<T> {
-                debug_assert_eq!(self.len(), size_of::<#name<u8>>());
-                let (prefix, shorts, _suffix) = unsafe { self.align_to::<#name<T>>() };
+        impl<T: Copy, #(#const_params),*> core::borrow::Borrow<#name<T, #(#const_args),*>> for [T] {
+            fn borrow(&self) -> &#name<T, #(#const_args),*> {
+                debug_assert_eq!(self.len(), size_of::<#name<u8, #(#const_args),*>>());
+                let (prefix, shorts, _suffix) =
+                    unsafe { self.align_to::<#name<T, #(#const_args),*>>() };
                 debug_assert!(prefix.is_empty(), "Alignment should match");
                 debug_assert_eq!(shorts.len(), 1);
                 &shorts[0]
             }
         }
 
-        impl<T: Copy> core::borrow::BorrowMut<#name<T>> for [T] {
-            fn borrow_mut(&mut self) -> &mut #name<T> {
-                debug_assert_eq!(self.len(), size_of::<#name<u8>>());
-                let (prefix, shorts, _suffix) = unsafe { self.align_to_mut::<#name<T>>() };
+        impl<T: Copy, #(#const_params),*> core::borrow::BorrowMut<#name<T, #(#const_args),*>> for [T] {
+            fn borrow_mut(&mut self) -> &mut #name<T, #(#const_args),*> {
+                debug_assert_eq!(self.len(), size_of::<#name<u8, #(#const_args),*>>());
+                let (prefix, shorts, _suffix) =
+                    unsafe { self.align_to_mut::<#name<T, #(#const_args),*>>() };
                 debug_assert!(prefix.is_empty(), "Alignment should match");
                 debug_assert_eq!(shorts.len(), 1);
                 &mut shorts[0]
@@ -193,12 +213,16 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
-            // Attach an extra generic AB : crate::air::SP1AirBuilder to the generics of the enum
+            // Attach an extra generic AB : crate::air::SP1AirBuilder + p3_air::PairBuilder +
+            // crate::air::PublicInputBuilder to the generics of the enum, as some of the variants
+            // read from a preprocessed trace or from the public inputs of the shard.
             let generics = &ast.generics;
             let mut new_generics = generics.clone();
-            new_generics
-                .params
-                .push(syn::parse_quote! { AB: crate::air::SP1AirBuilder<F = F> });
+            new_generics.params.push(syn::parse_quote! {
+                AB: crate::air::SP1AirBuilder<F = F>
+                    + p3_air::PairBuilder
+                    + crate::air::PublicInputBuilder
+            });
 
             let (air_impl_generics, _, _) = new_generics.split_for_impl();
 
@@ -132,16 +132,19 @@ fn run_evaluation(hashfn: &HashFnId, program: &Program, elf: &[u8]) -> (f64, f64
         HashFnId::Blake3 => {
             let mut runtime = Runtime::new(program.clone());
             let execution_start = Instant::now();
-            runtime.run();
+            runtime.run().unwrap();
             let execution_duration = execution_start.elapsed().as_secs_f64();
 
             let config = BabyBearBlake3::new();
+            let stdout = SP1Stdout::from(&runtime.state.output_stream);
+            let exit_code = runtime.state.exit_code;
             let prove_start = Instant::now();
             let proof = prove_core(config.clone(), runtime);
             let prove_duration = prove_start.elapsed().as_secs_f64();
             let proof = SP1ProofWithIO {
                 stdin: SP1Stdin::new(),
-                stdout: SP1Stdout::new(),
+                stdout,
+                exit_code,
                 proof,
             };
 
@@ -154,16 +157,19 @@ fn run_evaluation(hashfn: &HashFnId, program: &Program, elf: &[u8]) -> (f64, f64
         HashFnId::Poseidon => {
             let mut runtime = Runtime::new(program.clone());
             let execution_start = Instant::now();
-            runtime.run();
+            runtime.run().unwrap();
             let execution_duration = execution_start.elapsed().as_secs_f64();
 
             let config = BabyBearPoseidon2::new();
+            let stdout = SP1Stdout::from(&runtime.state.output_stream);
+            let exit_code = runtime.state.exit_code;
             let prove_start = Instant::now();
             let proof = prove_core(config.clone(), runtime);
             let prove_duration = prove_start.elapsed().as_secs_f64();
             let proof = SP1ProofWithIO {
                 stdin: SP1Stdin::new(),
-                stdout: SP1Stdout::new(),
+                stdout,
+                exit_code,
                 proof,
             };
 
@@ -176,16 +182,19 @@ fn run_evaluation(hashfn: &HashFnId, program: &Program, elf: &[u8]) -> (f64, f64
         HashFnId::Keccak256 => {
             let mut runtime = Runtime::new(program.clone());
             let execution_start = Instant::now();
-            runtime.run();
+            runtime.run().unwrap();
             let execution_duration = execution_start.elapsed().as_secs_f64();
 
             let config = BabyBearKeccak::new();
+            let stdout = SP1Stdout::from(&runtime.state.output_stream);
+            let exit_code = runtime.state.exit_code;
             let prove_start = Instant::now();
             let proof = prove_core(config.clone(), runtime);
             let prove_duration = prove_start.elapsed().as_secs_f64();
             let proof = SP1ProofWithIO {
                 stdin: SP1Stdin::new(),
-                stdout: SP1Stdout::new(),
+                stdout,
+                exit_code,
                 proof,
             };
 
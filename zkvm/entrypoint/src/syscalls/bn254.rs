@@ -0,0 +1,63 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Adds two Bn254 points.
+///
+/// The result is stored in the first point.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_bn254_add(p: *mut u32, q: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::BN254_ADD,
+            in("a0") p,
+            in("a1") q
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Double a Bn254 point.
+///
+/// The result is stored in the first point.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_bn254_double(p: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::BN254_DOUBLE,
+            in("a0") p,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Multiplies two elements of the quadratic extension `Fp2 = Fp[u] / (u^2 + 1)` of the Bn254 base
+/// field.
+///
+/// Each element is given as its two coordinates in little-endian format, which must be reduced
+/// modulo the field modulus. The result is stored in the first element.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_bn254_fp2_mul(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::BN254_FP2_MUL,
+            in("a0") x,
+            in("a1") y
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
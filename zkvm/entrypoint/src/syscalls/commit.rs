@@ -0,0 +1,83 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+#[cfg(target_os = "zkvm")]
+use sha2::{Digest, Sha256};
+
+/// The hashers of the public input read by the program and of the output it wrote, whose digests
+/// the program commits to when it halts.
+#[cfg(target_os = "zkvm")]
+struct IoHashers {
+    input: Sha256,
+    input_len: u32,
+    output: Sha256,
+}
+
+#[cfg(target_os = "zkvm")]
+static mut IO_HASHERS: Option<IoHashers> = None;
+
+#[cfg(target_os = "zkvm")]
+fn io_hashers() -> &'static mut IoHashers {
+    // SAFETY: the program is single threaded, and the reference is only used by the caller.
+    unsafe {
+        (*core::ptr::addr_of_mut!(IO_HASHERS)).get_or_insert_with(|| IoHashers {
+            input: Sha256::new(),
+            input_len: 0,
+            output: Sha256::new(),
+        })
+    }
+}
+
+/// Hashes bytes of public input read by the program.
+#[cfg(target_os = "zkvm")]
+pub(crate) fn hash_input(bytes: &[u8]) {
+    let hashers = io_hashers();
+    hashers.input.update(bytes);
+    hashers.input_len += bytes.len() as u32;
+}
+
+/// Hashes bytes of output written by the program.
+#[cfg(target_os = "zkvm")]
+pub(crate) fn hash_output(bytes: &[u8]) {
+    io_hashers().output.update(bytes);
+}
+
+/// Commits to the digest of the public input read by the program, its length and the digest of
+/// the output it wrote, which are the words of the public values of the proof.
+#[cfg(target_os = "zkvm")]
+pub(crate) fn commit_io_digests() {
+    let hashers = io_hashers();
+    let input_digest = hashers.input.finalize_reset();
+    let output_digest = hashers.output.finalize_reset();
+    let digest_words = |digest: &[u8]| {
+        digest
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>()
+    };
+    let words = digest_words(&input_digest)
+        .into_iter()
+        .chain(core::iter::once(hashers.input_len))
+        .chain(digest_words(&output_digest));
+    for (index, word) in words.enumerate() {
+        syscall_commit(index as u32, word);
+    }
+}
+
+/// Commits to the word at the given index of the public values of the proof.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_commit(index: u32, word: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::COMMIT,
+            inlateout("a0") word => _,
+            in("a1") index,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -47,3 +47,24 @@ pub extern "C" fn syscall_ed_decompress(point: &mut [u8; 64]) {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Computes `a * p + b * q` for two Edwards points `p` and `q`.
+///
+/// The arguments are the scalars `a` and `b` as 8 little endian words each, followed by the point
+/// `q`, and the result is stored in `p`.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_ed_scalar_mul(p: *mut u32, args: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::ED_SCALAR_MUL,
+            in("a0") p,
+            in("a1") args
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -1,11 +1,12 @@
 #[cfg(target_os = "zkvm")]
 use core::arch::asm;
 
-/// Halts the program.
+/// Halts the program, after committing to the digests of its public input and output.
 #[allow(unused_variables)]
 pub extern "C" fn syscall_halt(exit_code: u8) -> ! {
     #[cfg(target_os = "zkvm")]
     unsafe {
+        crate::syscalls::commit_io_digests();
         asm!(
             "ecall",
             in("a0") exit_code,
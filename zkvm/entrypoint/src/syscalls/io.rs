@@ -1,6 +1,10 @@
 #[cfg(target_os = "zkvm")]
 use core::arch::asm;
 
+/// The file descriptor of the public input and of the output, which the program commits to.
+#[cfg(target_os = "zkvm")]
+const FD_IO: u32 = 3;
+
 /// Reads data from the prover.
 #[allow(unused_variables)]
 #[no_mangle]
@@ -51,6 +55,11 @@ pub extern "C" fn syscall_read(fd: u32, read_buf: *mut u8, nbytes: usize) {
         }
     }
 
+    #[cfg(target_os = "zkvm")]
+    if fd == FD_IO {
+        crate::syscalls::hash_input(unsafe { core::slice::from_raw_parts(read_buf, nbytes) });
+    }
+
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
@@ -61,6 +70,9 @@ pub extern "C" fn syscall_read(fd: u32, read_buf: *mut u8, nbytes: usize) {
 pub extern "C" fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize) {
     #[cfg(target_os = "zkvm")]
     unsafe {
+        if fd == FD_IO {
+            crate::syscalls::hash_output(core::slice::from_raw_parts(write_buf, nbytes));
+        }
         asm!(
             "ecall",
             in("t0") crate::syscalls::WRITE,
@@ -73,3 +85,101 @@ pub extern "C" fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize) {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Invokes the hint the host registered under `name` with the given input, returning the length
+/// of the answer. The answer is appended to the stream of hint answers, which the program reads
+/// from file descriptor 6, and is not part of the public values.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_hint(
+    name: *const u8,
+    name_len: usize,
+    input: *const u8,
+    input_len: usize,
+) -> usize {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let len;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::HINT,
+            inlateout("a0") name => len,
+            in("a1") name_len,
+            in("a2") input,
+            in("a3") input_len,
+        );
+        len
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Opens the virtual file the host supplied under the given name, returning its file descriptor,
+/// or `u32::MAX` if there is no such file.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_file_open(name: *const u8, name_len: usize) -> u32 {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let fd;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::FILE_OPEN,
+            inlateout("a0") name => fd,
+            in("a1") name_len,
+        );
+        fd
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Returns the length of the virtual file with the given file descriptor.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_file_len(fd: u32) -> usize {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let len;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::FILE_LEN,
+            inlateout("a0") fd => len,
+        );
+        len
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Reads `nbytes` from the virtual file with the given file descriptor, starting at `offset`.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_file_read(fd: u32, offset: usize, read_buf: *mut u8, nbytes: usize) {
+    #[cfg(target_os = "zkvm")]
+    for i in (0..nbytes).step_by(4) {
+        let len = (nbytes - i).min(4);
+        unsafe {
+            let word: u32;
+            asm!(
+                "ecall",
+                in("t0") crate::syscalls::FILE_READ,
+                inlateout("a0") fd => word,
+                in("a1") offset + i,
+                in("a2") len,
+            );
+
+            // Copy the bytes of the word into the read buffer
+            let bytes = word.to_le_bytes();
+            for j in 0..len {
+                *read_buf.add(i + j) = bytes[j];
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
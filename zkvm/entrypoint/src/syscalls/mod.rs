@@ -1,25 +1,35 @@
 mod blake3_compress;
+mod bls12381;
+mod bn254;
+mod commit;
 mod ed25519;
 mod halt;
 mod io;
 mod keccak_permute;
 mod memory;
 mod secp256k1;
+mod secp256r1;
 mod sha_compress;
 mod sha_extend;
 mod sys;
 mod unconstrained;
+mod user;
 
+pub use bls12381::*;
+pub use bn254::*;
+pub use commit::*;
 pub use ed25519::*;
 pub use halt::*;
 pub use io::*;
 pub use keccak_permute::*;
 pub use memory::*;
 pub use secp256k1::*;
+pub use secp256r1::*;
 pub use sha_compress::*;
 pub use sha_extend::*;
 pub use sys::*;
 pub use unconstrained::*;
+pub use user::*;
 
 /// Halts the program.
 pub const HALT: u32 = 100;
@@ -60,5 +70,56 @@ pub const EXIT_UNCONSTRAINED: u32 = 111;
 /// Executes `BLAKE3_COMPRESS_INNER`.
 pub const BLAKE3_COMPRESS_INNER: u32 = 112;
 
+/// Invokes a hint registered by the host.
+pub const HINT: u32 = 113;
+
+/// Opens a virtual file supplied by the host.
+pub const FILE_OPEN: u32 = 114;
+
+/// Returns the length of a virtual file.
+pub const FILE_LEN: u32 = 115;
+
+/// Reads a word from a virtual file.
+pub const FILE_READ: u32 = 116;
+
+/// Executes `BN254_ADD`.
+pub const BN254_ADD: u32 = 117;
+
+/// Executes `BN254_DOUBLE`.
+pub const BN254_DOUBLE: u32 = 118;
+
+/// Executes `BN254_FP2_MUL`.
+pub const BN254_FP2_MUL: u32 = 119;
+
+/// Executes `BLS12381_ADD`.
+pub const BLS12381_ADD: u32 = 120;
+
+/// Executes `BLS12381_DOUBLE`.
+pub const BLS12381_DOUBLE: u32 = 121;
+
+/// Executes `BLS12381_DECOMPRESS`.
+pub const BLS12381_DECOMPRESS: u32 = 122;
+
+/// Executes `SECP256R1_ADD`.
+pub const SECP256R1_ADD: u32 = 123;
+
+/// Executes `SECP256R1_DOUBLE`.
+pub const SECP256R1_DOUBLE: u32 = 124;
+
+/// Executes `SECP256R1_DECOMPRESS`.
+pub const SECP256R1_DECOMPRESS: u32 = 125;
+
+/// Executes `ED_SCALAR_MUL`.
+pub const ED_SCALAR_MUL: u32 = 126;
+
+/// Commits to a word of the public values.
+pub const COMMIT: u32 = 127;
+
 /// Writes to a file descriptor. Currently only used for `STDOUT/STDERR`.
 pub const WRITE: u32 = 999;
+
+/// The first syscall number reserved for syscalls registered by the host.
+pub const USER_SYSCALL_START: u32 = 0x10000;
+
+/// The end (exclusive) of the syscall numbers reserved for syscalls registered by the host.
+pub const USER_SYSCALL_END: u32 = 0x20000;
@@ -0,0 +1,26 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Invokes the syscall the host registered with `Runtime::register_syscall` under `code`, which
+/// must be in the range `USER_SYSCALL_START..USER_SYSCALL_END`.
+///
+/// The arguments are passed in `a0` and `a1`, and the value the syscall returns in `a0` is
+/// returned.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_user(code: u32, arg0: u32, arg1: u32) -> u32 {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let result;
+        asm!(
+            "ecall",
+            in("t0") code,
+            inlateout("a0") arg0 => result,
+            in("a1") arg1,
+        );
+        result
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
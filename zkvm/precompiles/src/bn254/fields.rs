@@ -0,0 +1,762 @@
+//! The tower of extensions of the BN254 base field used by the pairing.
+//!
+//! `Fp2 = Fp[u] / (u^2 + 1)`, `Fp6 = Fp2[v] / (v^3 - (9 + u))` and `Fp12 = Fp6[w] / (w^2 - v)`.
+//! Multiplications in `Fp2`, and so in the whole tower, are done with the `BN254_FP2_MUL`
+//! precompile inside the zkVM.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// The modulus of the BN254 base field, as little-endian words.
+const MODULUS: [u32; 8] = [
+    0xd87cfd47, 0x3c208c16, 0x6871ca8d, 0x97816a91, 0x8181585d, 0xb85045b6, 0xe131a029, 0x30644e72,
+];
+
+/// The modulus minus two, the exponent of the inverse of an element.
+const MODULUS_MINUS_TWO: [u32; 8] = [
+    0xd87cfd45, 0x3c208c16, 0x6871ca8d, 0x97816a91, 0x8181585d, 0xb85045b6, 0xe131a029, 0x30644e72,
+];
+
+/// `-MODULUS^{-1} mod 2^32`, for Montgomery reduction outside of the zkVM.
+#[cfg(not(all(target_os = "zkvm", target_vendor = "succinct")))]
+const INV: u32 = 0xe4866389;
+
+/// `2^512 mod MODULUS`, to convert products out of the Montgomery form outside of the zkVM.
+#[cfg(not(all(target_os = "zkvm", target_vendor = "succinct")))]
+const R2: [u32; 8] = [
+    0x538afa89, 0xf32cfc5b, 0xd44501fb, 0xb5e71911, 0x0a417ff6, 0x47ab1eff, 0xcab8351f, 0x06d89f71,
+];
+
+/// Adds two 256-bit integers, returning the carry.
+fn add_words(a: &[u32; 8], b: &[u32; 8]) -> ([u32; 8], bool) {
+    let mut result = [0; 8];
+    let mut carry = 0u64;
+    for i in 0..8 {
+        let sum = a[i] as u64 + b[i] as u64 + carry;
+        result[i] = sum as u32;
+        carry = sum >> 32;
+    }
+    (result, carry != 0)
+}
+
+/// Subtracts two 256-bit integers, returning the borrow.
+fn sub_words(a: &[u32; 8], b: &[u32; 8]) -> ([u32; 8], bool) {
+    let mut result = [0; 8];
+    let mut borrow = false;
+    for i in 0..8 {
+        let (diff, borrow0) = a[i].overflowing_sub(b[i]);
+        let (diff, borrow1) = diff.overflowing_sub(borrow as u32);
+        result[i] = diff;
+        borrow = borrow0 || borrow1;
+    }
+    (result, borrow)
+}
+
+/// Montgomery multiplication `a * b * 2^-256 mod MODULUS`, used outside of the zkVM.
+#[cfg(not(all(target_os = "zkvm", target_vendor = "succinct")))]
+fn mont_mul(a: &[u32; 8], b: &[u32; 8]) -> [u32; 8] {
+    let mut t = [0u32; 10];
+    for i in 0..8 {
+        let mut carry = 0u64;
+        for j in 0..8 {
+            let sum = t[j] as u64 + a[j] as u64 * b[i] as u64 + carry;
+            t[j] = sum as u32;
+            carry = sum >> 32;
+        }
+        let sum = t[8] as u64 + carry;
+        t[8] = sum as u32;
+        t[9] = (sum >> 32) as u32;
+
+        let m = t[0].wrapping_mul(INV) as u64;
+        let mut carry = (t[0] as u64 + m * MODULUS[0] as u64) >> 32;
+        for j in 1..8 {
+            let sum = t[j] as u64 + m * MODULUS[j] as u64 + carry;
+            t[j - 1] = sum as u32;
+            carry = sum >> 32;
+        }
+        let sum = t[8] as u64 + carry;
+        t[7] = sum as u32;
+        t[8] = t[9] + (sum >> 32) as u32;
+    }
+    let mut result = [0; 8];
+    result.copy_from_slice(&t[..8]);
+    Fp::reduce(result).0
+}
+
+/// An element of the BN254 base field, as little-endian words reduced modulo the field modulus.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub(crate) struct Fp(pub(crate) [u32; 8]);
+
+impl Fp {
+    pub(crate) const ZERO: Fp = Fp([0; 8]);
+    pub(crate) const ONE: Fp = Fp([1, 0, 0, 0, 0, 0, 0, 0]);
+
+    /// Parses a field element from its big-endian bytes, or returns `None` if it is not reduced.
+    pub(crate) fn from_be_bytes(bytes: &[u8]) -> Option<Fp> {
+        let mut words = [0; 8];
+        for (word, chunk) in words.iter_mut().rev().zip(bytes.chunks_exact(4)) {
+            *word = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+        let (_, borrow) = sub_words(&words, &MODULUS);
+        borrow.then_some(Fp(words))
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        *self == Fp::ZERO
+    }
+
+    /// Subtracts the modulus if `words` is not reduced, given that it is less than twice the
+    /// modulus.
+    fn reduce(words: [u32; 8]) -> Fp {
+        let (reduced, borrow) = sub_words(&words, &MODULUS);
+        if borrow {
+            Fp(words)
+        } else {
+            Fp(reduced)
+        }
+    }
+
+    pub(crate) fn square(&self) -> Fp {
+        *self * *self
+    }
+
+    /// The inverse of the element, or zero if the element is zero.
+    pub(crate) fn inverse(&self) -> Fp {
+        let mut result = Fp::ONE;
+        for word in MODULUS_MINUS_TWO.iter().rev() {
+            for i in (0..32).rev() {
+                result = result.square();
+                if (word >> i) & 1 == 1 {
+                    result = result * *self;
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Add for Fp {
+    type Output = Fp;
+
+    fn add(self, rhs: Fp) -> Fp {
+        // The modulus is less than 2^255, so the sum does not overflow.
+        let (sum, _) = add_words(&self.0, &rhs.0);
+        Fp::reduce(sum)
+    }
+}
+
+impl Sub for Fp {
+    type Output = Fp;
+
+    fn sub(self, rhs: Fp) -> Fp {
+        let (diff, borrow) = sub_words(&self.0, &rhs.0);
+        if borrow {
+            Fp(add_words(&diff, &MODULUS).0)
+        } else {
+            Fp(diff)
+        }
+    }
+}
+
+impl Neg for Fp {
+    type Output = Fp;
+
+    fn neg(self) -> Fp {
+        Fp::ZERO - self
+    }
+}
+
+impl Mul for Fp {
+    type Output = Fp;
+
+    fn mul(self, rhs: Fp) -> Fp {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+                (Fp2::from(self) * Fp2::from(rhs)).c0
+            } else {
+                Fp(mont_mul(&mont_mul(&self.0, &rhs.0), &R2))
+            }
+        }
+    }
+}
+
+/// An element `c0 + c1 * u` of `Fp2`, laid out as expected by the `BN254_FP2_MUL` precompile.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub(crate) struct Fp2 {
+    pub(crate) c0: Fp,
+    pub(crate) c1: Fp,
+}
+
+impl Fp2 {
+    pub(crate) const ZERO: Fp2 = Fp2::new([0; 8], [0; 8]);
+    pub(crate) const ONE: Fp2 = Fp2::new([1, 0, 0, 0, 0, 0, 0, 0], [0; 8]);
+
+    /// The non-residue `9 + u` defining `Fp6`.
+    const NON_RESIDUE: Fp2 = Fp2::new([9, 0, 0, 0, 0, 0, 0, 0], [1, 0, 0, 0, 0, 0, 0, 0]);
+
+    pub(crate) const fn new(c0: [u32; 8], c1: [u32; 8]) -> Fp2 {
+        Fp2 {
+            c0: Fp(c0),
+            c1: Fp(c1),
+        }
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        *self == Fp2::ZERO
+    }
+
+    pub(crate) fn double(&self) -> Fp2 {
+        *self + *self
+    }
+
+    pub(crate) fn square(&self) -> Fp2 {
+        *self * *self
+    }
+
+    /// The image `c0 - c1 * u` of the element under the Frobenius map.
+    pub(crate) fn conjugate(&self) -> Fp2 {
+        Fp2 {
+            c0: self.c0,
+            c1: -self.c1,
+        }
+    }
+
+    pub(crate) fn mul_by_fp(&self, rhs: Fp) -> Fp2 {
+        *self * Fp2::from(rhs)
+    }
+
+    fn mul_by_non_residue(&self) -> Fp2 {
+        *self * Fp2::NON_RESIDUE
+    }
+
+    /// The inverse of the element, or zero if the element is zero.
+    pub(crate) fn inverse(&self) -> Fp2 {
+        let norm = self.c0.square() + self.c1.square();
+        self.conjugate().mul_by_fp(norm.inverse())
+    }
+}
+
+impl From<Fp> for Fp2 {
+    fn from(c0: Fp) -> Fp2 {
+        Fp2 { c0, c1: Fp::ZERO }
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Fp2;
+
+    fn add(self, rhs: Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+        }
+    }
+}
+
+impl Sub for Fp2 {
+    type Output = Fp2;
+
+    fn sub(self, rhs: Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+        }
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Fp2;
+
+    fn neg(self) -> Fp2 {
+        Fp2 {
+            c0: -self.c0,
+            c1: -self.c1,
+        }
+    }
+}
+
+impl Mul for Fp2 {
+    type Output = Fp2;
+
+    fn mul(self, rhs: Fp2) -> Fp2 {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+                let mut result = self;
+                unsafe {
+                    crate::syscall_bn254_fp2_mul(
+                        &mut result as *mut Fp2 as *mut u32,
+                        &rhs as *const Fp2 as *const u32,
+                    );
+                }
+                result
+            } else {
+                Fp2 {
+                    c0: self.c0 * rhs.c0 - self.c1 * rhs.c1,
+                    c1: self.c0 * rhs.c1 + self.c1 * rhs.c0,
+                }
+            }
+        }
+    }
+}
+
+/// An element `c0 + c1 * v + c2 * v^2` of `Fp6`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Fp6 {
+    pub(crate) c0: Fp2,
+    pub(crate) c1: Fp2,
+    pub(crate) c2: Fp2,
+}
+
+impl Fp6 {
+    pub(crate) const ZERO: Fp6 = Fp6 {
+        c0: Fp2::ZERO,
+        c1: Fp2::ZERO,
+        c2: Fp2::ZERO,
+    };
+    pub(crate) const ONE: Fp6 = Fp6 {
+        c0: Fp2::ONE,
+        c1: Fp2::ZERO,
+        c2: Fp2::ZERO,
+    };
+
+    /// Multiplies the element by `v`, the non-residue defining `Fp12`.
+    fn mul_by_non_residue(&self) -> Fp6 {
+        Fp6 {
+            c0: self.c2.mul_by_non_residue(),
+            c1: self.c0,
+            c2: self.c1,
+        }
+    }
+
+    fn inverse(&self) -> Fp6 {
+        let t0 = self.c0.square() - (self.c1 * self.c2).mul_by_non_residue();
+        let t1 = self.c2.square().mul_by_non_residue() - self.c0 * self.c1;
+        let t2 = self.c1.square() - self.c0 * self.c2;
+        let norm = self.c0 * t0 + (self.c2 * t1 + self.c1 * t2).mul_by_non_residue();
+        let norm_inverse = norm.inverse();
+        Fp6 {
+            c0: t0 * norm_inverse,
+            c1: t1 * norm_inverse,
+            c2: t2 * norm_inverse,
+        }
+    }
+}
+
+impl Add for Fp6 {
+    type Output = Fp6;
+
+    fn add(self, rhs: Fp6) -> Fp6 {
+        Fp6 {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+            c2: self.c2 + rhs.c2,
+        }
+    }
+}
+
+impl Sub for Fp6 {
+    type Output = Fp6;
+
+    fn sub(self, rhs: Fp6) -> Fp6 {
+        Fp6 {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+            c2: self.c2 - rhs.c2,
+        }
+    }
+}
+
+impl Neg for Fp6 {
+    type Output = Fp6;
+
+    fn neg(self) -> Fp6 {
+        Fp6 {
+            c0: -self.c0,
+            c1: -self.c1,
+            c2: -self.c2,
+        }
+    }
+}
+
+impl Mul for Fp6 {
+    type Output = Fp6;
+
+    fn mul(self, rhs: Fp6) -> Fp6 {
+        // Karatsuba multiplication, with 6 multiplications in `Fp2`.
+        let v0 = self.c0 * rhs.c0;
+        let v1 = self.c1 * rhs.c1;
+        let v2 = self.c2 * rhs.c2;
+        Fp6 {
+            c0: v0 + ((self.c1 + self.c2) * (rhs.c1 + rhs.c2) - v1 - v2).mul_by_non_residue(),
+            c1: (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - v0 - v1 + v2.mul_by_non_residue(),
+            c2: (self.c0 + self.c2) * (rhs.c0 + rhs.c2) - v0 - v2 + v1,
+        }
+    }
+}
+
+/// The coefficients of the Frobenius maps on `Fp12`: `FROBENIUS_COEFFS[k - 1][e - 1]` is
+/// `(9 + u)^(e * (p^k - 1) / 6)`, by which the image of the coefficient of `w^e` is multiplied.
+pub(crate) const FROBENIUS_COEFFS: [[Fp2; 5]; 3] = [
+    [
+        Fp2::new(
+            [
+                0xdcc9e470, 0xd60b35da, 0x292f2176, 0x5c521e08, 0x76e68b60, 0xe8b99fdd, 0x2865a7df,
+                0x1284b71c,
+            ],
+            [
+                0x80f362ac, 0xca5cf05f, 0x8eeec7e5, 0x74799277, 0x12150b8e, 0xa6327cfe, 0xb4fae7e6,
+                0x246996f3,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x176f553d, 0x99e39557, 0xc2c3330c, 0xb78cc310, 0xf559b143, 0x4c0bec3c, 0x4f7911f7,
+                0x2fb34798,
+            ],
+            [
+                0x640fcba2, 0x1665d51c, 0x0b7c9dce, 0x32ae2a1d, 0xd75a0794, 0x4ba4cc8b, 0x61ebae20,
+                0x16c9e550,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x71a0135a, 0xdc540146, 0xa9c95998, 0xdbaae0ed, 0xb6e2f9b9, 0xdc5ec698, 0x489af5dc,
+                0x063cf305,
+            ],
+            [
+                0x2623b0e3, 0x82d37f63, 0x8fa25bd2, 0x21807dc9, 0xec796f2b, 0x0704b5a7, 0xac41049a,
+                0x07c03cbc,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x921ea762, 0x848a1f55, 0xbe94ec72, 0xd33365f7, 0x5a181e84, 0x80f3c0b7, 0x64eea801,
+                0x05b54f5e,
+            ],
+            [
+                0xcd2b8126, 0xc13b4711, 0x1bdec763, 0x3685d2ea, 0x3b0b1c92, 0x9f3a80b0, 0xe7fd8aee,
+                0x2c145edb,
+            ],
+        ),
+        Fp2::new(
+            [
+                0xeab7692f, 0x2ea2c810, 0x55aa1bd3, 0x425c459b, 0xa4353ff4, 0xe93a3661, 0x4f798649,
+                0x0183c1e7,
+            ],
+            [
+                0x6e0c2c4b, 0x24c6b8ee, 0x678e2ac0, 0xb080cb99, 0xc7729f7d, 0xa27fb246, 0x76fd0675,
+                0x12acf2ca,
+            ],
+        ),
+    ],
+    [
+        Fp2::new(
+            [
+                0x607cfd49, 0xe4bd44e5, 0xbb966e3d, 0xc28f069f, 0xe0acccb0, 0x5e6dd9e7, 0xe131a029,
+                0x30644e72,
+            ],
+            [
+                0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000,
+                0x00000000,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x607cfd48, 0xe4bd44e5, 0xbb966e3d, 0xc28f069f, 0xe0acccb0, 0x5e6dd9e7, 0xe131a029,
+                0x30644e72,
+            ],
+            [
+                0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000,
+                0x00000000,
+            ],
+        ),
+        Fp2::new(
+            [
+                0xd87cfd46, 0x3c208c16, 0x6871ca8d, 0x97816a91, 0x8181585d, 0xb85045b6, 0xe131a029,
+                0x30644e72,
+            ],
+            [
+                0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000,
+                0x00000000,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x77fffffe, 0x57634731, 0xacdb5c4f, 0xd4f263f1, 0xa0d48bac, 0x59e26bce, 0x00000000,
+                0x00000000,
+            ],
+            [
+                0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000,
+                0x00000000,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x77ffffff, 0x57634731, 0xacdb5c4f, 0xd4f263f1, 0xa0d48bac, 0x59e26bce, 0x00000000,
+                0x00000000,
+            ],
+            [
+                0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000,
+                0x00000000,
+            ],
+        ),
+    ],
+    [
+        Fp2::new(
+            [
+                0x1ed4a67f, 0xe86f7d39, 0xbe55d24a, 0x894cb38d, 0xd0acaa90, 0xefe9608c, 0xcc82e4bb,
+                0x19dc81cf,
+            ],
+            [
+                0xf4c0c101, 0x7694aa2b, 0x97d439ec, 0x7f03a5e3, 0x3576139d, 0x06cbeee3, 0x0be77d73,
+                0x00abf8b6,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x7bdcfb6d, 0x7b746ee8, 0x5d6942d3, 0x805ffd3d, 0x959f25ac, 0xbaff1c77, 0xb755ef0a,
+                0x0856e078,
+            ],
+            [
+                0xaaa586de, 0x380cab2b, 0x98ff2631, 0x0fdf31bf, 0xec26094f, 0xa9f30e6d, 0xb3d1766f,
+                0x04f1de41,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x66dce9ed, 0x5fcc8ad0, 0xbea870f4, 0xbbd689a3, 0xca9e5ea3, 0xdbf17f1d, 0x9896aa4c,
+                0x2a275b6d,
+            ],
+            [
+                0xb2594c64, 0xb94d0cb3, 0xd8cf6eba, 0x7600ecc7, 0x9507e932, 0xb14b900e, 0x34f09b8f,
+                0x28a411b6,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x3ccbf066, 0x0e1a92bc, 0x75b06bcb, 0xe6330945, 0xb5b2444e, 0x19bee0f7, 0x11c08dab,
+                0x0bc58c66,
+            ],
+            [
+                0x730c239f, 0x5fe3ed9d, 0x737f96e5, 0xa44a9e08, 0x0cd21d04, 0xfeb0f6ef, 0xe1910a12,
+                0x23d5e999,
+            ],
+        ),
+        Fp2::new(
+            [
+                0x76261b43, 0xebde8470, 0x967c84a5, 0x2ed68098, 0x3b4d3f69, 0x711699fa, 0x952c0905,
+                0x13c49044,
+            ],
+            [
+                0x84282499, 0x1f250413, 0x20028021, 0x3e2ddaea, 0x2a48633d, 0x9fb1b228, 0x59b1dd0b,
+                0x16db366a,
+            ],
+        ),
+    ],
+];
+
+/// An element `c0 + c1 * w` of `Fp12`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Fp12 {
+    pub(crate) c0: Fp6,
+    pub(crate) c1: Fp6,
+}
+
+impl Fp12 {
+    pub(crate) const ONE: Fp12 = Fp12 {
+        c0: Fp6::ONE,
+        c1: Fp6::ZERO,
+    };
+
+    pub(crate) fn square(&self) -> Fp12 {
+        // Complex squaring, with 2 multiplications in `Fp6`.
+        let t = self.c0 * self.c1;
+        Fp12 {
+            c0: (self.c0 + self.c1) * (self.c0 + self.c1.mul_by_non_residue())
+                - t
+                - t.mul_by_non_residue(),
+            c1: t + t,
+        }
+    }
+
+    /// The conjugate `c0 - c1 * w`, which is the inverse of elements of the cyclotomic subgroup.
+    pub(crate) fn conjugate(&self) -> Fp12 {
+        Fp12 {
+            c0: self.c0,
+            c1: -self.c1,
+        }
+    }
+
+    pub(crate) fn inverse(&self) -> Fp12 {
+        let norm_inverse = (self.c0 * self.c0 - (self.c1 * self.c1).mul_by_non_residue()).inverse();
+        Fp12 {
+            c0: self.c0 * norm_inverse,
+            c1: -(self.c1 * norm_inverse),
+        }
+    }
+
+    /// Raises the element to the power `p^k`, for `k` in `1..=3`.
+    pub(crate) fn frobenius_map(&self, k: usize) -> Fp12 {
+        let coeffs = &FROBENIUS_COEFFS[k - 1];
+        let map = |c: Fp2, e: usize| {
+            let c = if k % 2 == 1 { c.conjugate() } else { c };
+            if e == 0 {
+                c
+            } else {
+                c * coeffs[e - 1]
+            }
+        };
+        // The coefficient of `v^i * w^j` is the coefficient of `w^(2 * i + j)`.
+        Fp12 {
+            c0: Fp6 {
+                c0: map(self.c0.c0, 0),
+                c1: map(self.c0.c1, 2),
+                c2: map(self.c0.c2, 4),
+            },
+            c1: Fp6 {
+                c0: map(self.c1.c0, 1),
+                c1: map(self.c1.c1, 3),
+                c2: map(self.c1.c2, 5),
+            },
+        }
+    }
+
+    /// Raises the element to the power `exp`.
+    pub(crate) fn pow(&self, exp: u64) -> Fp12 {
+        let mut result = Fp12::ONE;
+        for i in (0..64 - exp.leading_zeros()).rev() {
+            result = result.square();
+            if (exp >> i) & 1 == 1 {
+                result = result * *self;
+            }
+        }
+        result
+    }
+}
+
+impl Mul for Fp12 {
+    type Output = Fp12;
+
+    fn mul(self, rhs: Fp12) -> Fp12 {
+        // Karatsuba multiplication, with 3 multiplications in `Fp6`.
+        let t0 = self.c0 * rhs.c0;
+        let t1 = self.c1 * rhs.c1;
+        Fp12 {
+            c0: t0 + t1.mul_by_non_residue(),
+            c1: (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - t0 - t1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Fp = Fp([
+        0x24c43f59, 0xdd31c160, 0x76ada664, 0x81ffa7aa, 0xbba11933, 0x4541d57e, 0x4def4bb9,
+        0x1c76476f,
+    ]);
+    const B: Fp = Fp([
+        0xa76aef41, 0xe45c9b03, 0xd3e9d294, 0xfc819b55, 0x1c678745, 0x04fee281, 0x20f673e2,
+        0x3034dd29,
+    ]);
+
+    fn fp6(a: Fp2, b: Fp2) -> Fp6 {
+        Fp6 {
+            c0: a,
+            c1: b,
+            c2: a * b,
+        }
+    }
+
+    fn fp12() -> Fp12 {
+        let x = Fp2 { c0: A, c1: B };
+        let y = Fp2 { c0: B, c1: -A };
+        Fp12 {
+            c0: fp6(x, y),
+            c1: fp6(y.square(), x + y),
+        }
+    }
+
+    #[test]
+    fn test_fp_arithmetic() {
+        let product = Fp([
+            0x3d09f36a, 0x6fc46dc6, 0xf5326d31, 0x2d899e47, 0x2de94969, 0x46d23bf0, 0xf38b1d6e,
+            0x02b87a63,
+        ]);
+        assert_eq!(A * B, product);
+        assert_eq!(A * A.inverse(), Fp::ONE);
+        assert_eq!(A - B + B, A);
+        assert_eq!(A + -A, Fp::ZERO);
+    }
+
+    #[test]
+    fn test_fp_from_be_bytes() {
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(MODULUS.iter().rev()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        assert_eq!(Fp::from_be_bytes(&bytes), None);
+        bytes[31] -= 1;
+        assert_eq!(Fp::from_be_bytes(&bytes), Some(-Fp::ONE));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let x = Fp2 { c0: A, c1: B };
+        assert_eq!(x * x.inverse(), Fp2::ONE);
+        let y = fp6(x, x.conjugate());
+        assert_eq!(y * y.inverse(), Fp6::ONE);
+        let z = fp12();
+        assert_eq!(z * z.inverse(), Fp12::ONE);
+    }
+
+    #[test]
+    fn test_fp12_square() {
+        let z = fp12();
+        assert_eq!(z.square(), z * z);
+        assert_eq!(z.pow(5), z.square().square() * z);
+    }
+
+    /// Raises `z` to the power of the modulus.
+    fn pow_modulus(z: Fp12) -> Fp12 {
+        let mut result = Fp12::ONE;
+        for word in MODULUS.iter().rev() {
+            for i in (0..32).rev() {
+                result = result.square();
+                if (word >> i) & 1 == 1 {
+                    result = result * z;
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_frobenius_map() {
+        let z = fp12();
+        assert_eq!(z.frobenius_map(1), pow_modulus(z));
+        assert_eq!(z.frobenius_map(1).frobenius_map(1), z.frobenius_map(2));
+        assert_eq!(z.frobenius_map(2).frobenius_map(1), z.frobenius_map(3));
+        assert_eq!(
+            z.frobenius_map(3).frobenius_map(3),
+            z.frobenius_map(2).frobenius_map(2).frobenius_map(2)
+        );
+        // The map is a field automorphism.
+        let w = z.square() * z;
+        assert_eq!(
+            (z * w).frobenius_map(1),
+            z.frobenius_map(1) * w.frobenius_map(1)
+        );
+        // Elements of `Fp` are fixed by the map.
+        let a = Fp12 {
+            c0: Fp6 {
+                c0: Fp2::from(A),
+                ..Fp6::ZERO
+            },
+            c1: Fp6::ZERO,
+        };
+        assert_eq!(a.frobenius_map(1), a);
+    }
+}
@@ -0,0 +1,60 @@
+mod fields;
+mod pairing;
+
+pub use pairing::*;
+
+use crate::{syscall_bn254_add, syscall_bn254_double};
+
+/// An affine point on the BN254 curve, which cannot be the point at infinity.
+///
+/// The point is represented internally by little-endian words in order to ensure a contiguous
+/// memory layout, with the X coordinate followed by the Y coordinate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AffinePoint {
+    limbs: [u32; 16],
+}
+
+impl AffinePoint {
+    /// The generator of the BN254 G1 group.
+    pub const GENERATOR: AffinePoint =
+        AffinePoint::from_limbs([1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+
+    pub const fn from_limbs(limbs: [u32; 16]) -> Self {
+        Self { limbs }
+    }
+
+    /// Creates a point from the little-endian bytes of its X coordinate followed by the
+    /// little-endian bytes of its Y coordinate.
+    pub fn from_le_bytes(bytes: &[u8; 64]) -> Self {
+        let mut limbs = [0; 16];
+        for (limb, word) in limbs.iter_mut().zip(bytes.chunks_exact(4)) {
+            *limb = u32::from_le_bytes(word.try_into().unwrap());
+        }
+        Self { limbs }
+    }
+
+    /// The little-endian bytes of the X coordinate followed by the little-endian bytes of the Y
+    /// coordinate.
+    pub fn to_le_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0; 64];
+        for (word, limb) in bytes.chunks_exact_mut(4).zip(self.limbs.iter()) {
+            word.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Adds `other` to this point. The two points must be distinct and must not be the negation
+    /// of each other, use `double` to add a point to itself.
+    pub fn add_assign(&mut self, other: &AffinePoint) {
+        unsafe {
+            syscall_bn254_add(self.limbs.as_mut_ptr(), other.limbs.as_ptr());
+        }
+    }
+
+    /// Doubles this point.
+    pub fn double(&mut self) {
+        unsafe {
+            syscall_bn254_double(self.limbs.as_mut_ptr());
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! The optimal ate pairing on BN254, as used by the `ecPairing` precompile of EIP-197.
+//!
+//! Only the multiplications in `Fp2` are done by a precompile inside the zkVM. The Miller loop and
+//! the final exponentiation are ordinary guest code built on top of them, so a pairing check still
+//! costs far more cycles than it would with chips for them.
+//!
+//! TODO: add chips for the line evaluations of the Miller loop, the multiplications in `Fp12` and
+//! the final exponentiation, and use them here.
+
+use anyhow::{anyhow, Result};
+
+use super::fields::{Fp, Fp12, Fp2, Fp6, FROBENIUS_COEFFS};
+
+/// The size of the encoding of a pair of a G1 point and a G2 point.
+const PAIR_LEN: usize = 192;
+
+/// The coefficient `b` of the curve `y^2 = x^3 + b` over `Fp`.
+const B: Fp = Fp([3, 0, 0, 0, 0, 0, 0, 0]);
+
+/// The coefficient `b / (9 + u)` of the twist `y^2 = x^3 + b / (9 + u)` over `Fp2`.
+const TWIST_B: Fp2 = Fp2::new(
+    [
+        0x24a138e5, 0x3267e6dc, 0x59dbefa3, 0xb5b4c5e5, 0x1be06ac3, 0x81be1899, 0xceb8aaae,
+        0x2b149d40,
+    ],
+    [
+        0x85c315d2, 0xe4a2bd06, 0xe52d1852, 0xa74fa084, 0xeed8fdf4, 0xcd2cafad, 0x3af0fed4,
+        0x009713b0,
+    ],
+);
+
+/// The inverse of two in `Fp`.
+const TWO_INV: Fp = Fp([
+    0x6c3e7ea4, 0x9e10460b, 0xb438e546, 0xcbc0b548, 0x40c0ac2e, 0xdc2822db, 0x7098d014, 0x18322739,
+]);
+
+/// The order of the G2 subgroup minus one, as little-endian words.
+const SUBGROUP_ORDER_MINUS_ONE: [u32; 8] = [
+    0xf0000000, 0x43e1f593, 0x79b97091, 0x2833e848, 0x8181585d, 0xb85045b6, 0xe131a029, 0x30644e72,
+];
+
+/// The parameter `x` of the curve.
+const X: u64 = 0x44e992b44a6909f1;
+
+/// The number of iterations `6 * x + 2` of the Miller loop.
+const ATE_LOOP_COUNT: u128 = 6 * X as u128 + 2;
+
+/// A point of the curve over `Fp` in affine coordinates, which is not the point at infinity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct G1Affine {
+    x: Fp,
+    y: Fp,
+}
+
+impl G1Affine {
+    /// Parses a point from the big-endian bytes of its coordinates, or returns `None` for the
+    /// point at infinity, encoded as zero coordinates.
+    fn from_be_bytes(bytes: &[u8]) -> Result<Option<G1Affine>> {
+        let x = Fp::from_be_bytes(&bytes[..32]).ok_or_else(|| anyhow!("invalid G1 coordinate"))?;
+        let y = Fp::from_be_bytes(&bytes[32..]).ok_or_else(|| anyhow!("invalid G1 coordinate"))?;
+        if x.is_zero() && y.is_zero() {
+            return Ok(None);
+        }
+        if y.square() != x.square() * x + B {
+            return Err(anyhow!("G1 point is not on the curve"));
+        }
+        Ok(Some(G1Affine { x, y }))
+    }
+}
+
+/// A point of the twist over `Fp2` in affine coordinates, which is not the point at infinity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct G2Affine {
+    x: Fp2,
+    y: Fp2,
+}
+
+impl G2Affine {
+    /// Parses a point from the big-endian bytes of its coordinates, with the imaginary part of
+    /// each coordinate first, or returns `None` for the point at infinity, encoded as zero
+    /// coordinates.
+    fn from_be_bytes(bytes: &[u8]) -> Result<Option<G2Affine>> {
+        let fp =
+            |bytes: &[u8]| Fp::from_be_bytes(bytes).ok_or_else(|| anyhow!("invalid G2 coordinate"));
+        let x = Fp2 {
+            c0: fp(&bytes[32..64])?,
+            c1: fp(&bytes[..32])?,
+        };
+        let y = Fp2 {
+            c0: fp(&bytes[96..])?,
+            c1: fp(&bytes[64..96])?,
+        };
+        if x.is_zero() && y.is_zero() {
+            return Ok(None);
+        }
+        let point = G2Affine { x, y };
+        if y.square() != x.square() * x + TWIST_B {
+            return Err(anyhow!("G2 point is not on the curve"));
+        }
+        if !point.is_in_subgroup() {
+            return Err(anyhow!("G2 point is not in the subgroup"));
+        }
+        Ok(Some(point))
+    }
+
+    /// Checks that the point is in the subgroup of prime order, by checking that multiplying it
+    /// by the order minus one gives its negation.
+    fn is_in_subgroup(&self) -> bool {
+        // The intermediate multiples are neither the point at infinity nor equal to the point or
+        // its negation if the point is in the subgroup, so the incomplete formulas can be used.
+        let mut t = G2Projective::from(*self);
+        let mut started = false;
+        for word in SUBGROUP_ORDER_MINUS_ONE.iter().rev() {
+            for i in (0..32).rev() {
+                let bit = (word >> i) & 1 == 1;
+                if started {
+                    t.double_step();
+                    if bit {
+                        t.add_step(self);
+                    }
+                }
+                started |= bit;
+            }
+        }
+        !t.z.is_zero() && t.x == self.x * t.z && t.y == -self.y * t.z
+    }
+
+    /// The image of the point under the endomorphism of the twist induced by the Frobenius map.
+    fn mul_by_char(&self) -> G2Affine {
+        G2Affine {
+            x: self.x.conjugate() * FROBENIUS_COEFFS[0][1],
+            y: self.y.conjugate() * FROBENIUS_COEFFS[0][2],
+        }
+    }
+}
+
+/// A point of the twist over `Fp2` in homogeneous projective coordinates.
+#[derive(Copy, Clone, Debug)]
+struct G2Projective {
+    x: Fp2,
+    y: Fp2,
+    z: Fp2,
+}
+
+/// The coefficients of a line through points of the twist, evaluated at a G1 point by `ell`.
+type LineCoeffs = (Fp2, Fp2, Fp2);
+
+impl From<G2Affine> for G2Projective {
+    fn from(point: G2Affine) -> G2Projective {
+        G2Projective {
+            x: point.x,
+            y: point.y,
+            z: Fp2::ONE,
+        }
+    }
+}
+
+impl G2Projective {
+    /// Doubles the point, returning the coefficients of the tangent line.
+    fn double_step(&mut self) -> LineCoeffs {
+        let a = (self.x * self.y).mul_by_fp(TWO_INV);
+        let b = self.y.square();
+        let c = self.z.square();
+        let e = TWIST_B * (c.double() + c);
+        let f = e.double() + e;
+        let g = (b + f).mul_by_fp(TWO_INV);
+        let h = (self.y + self.z).square() - (b + c);
+        let i = e - b;
+        let j = self.x.square();
+        let e_square = e.square();
+        self.x = a * (b - f);
+        self.y = g.square() - (e_square.double() + e_square);
+        self.z = b * h;
+        (-h, j.double() + j, i)
+    }
+
+    /// Adds an affine point to the point, returning the coefficients of the line through them.
+    fn add_step(&mut self, q: &G2Affine) -> LineCoeffs {
+        let theta = self.y - q.y * self.z;
+        let lambda = self.x - q.x * self.z;
+        let c = theta.square();
+        let d = lambda.square();
+        let e = lambda * d;
+        let f = self.z * c;
+        let g = self.x * d;
+        let h = e + f - g.double();
+        self.x = lambda * h;
+        self.y = theta * (g - h) - e * self.y;
+        self.z = self.z * e;
+        let j = theta * q.x - lambda * q.y;
+        (lambda, -theta, j)
+    }
+}
+
+/// Multiplies `f` by the line with the given coefficients evaluated at `p`.
+fn ell(f: Fp12, coeffs: LineCoeffs, p: &G1Affine) -> Fp12 {
+    let line = Fp12 {
+        c0: Fp6 {
+            c0: coeffs.0.mul_by_fp(p.y),
+            c1: Fp2::ZERO,
+            c2: Fp2::ZERO,
+        },
+        c1: Fp6 {
+            c0: coeffs.1.mul_by_fp(p.x),
+            c1: coeffs.2,
+            c2: Fp2::ZERO,
+        },
+    };
+    f * line
+}
+
+/// The product of the Miller loops of the pairs of points.
+fn miller_loop(pairs: &[(G1Affine, G2Affine)]) -> Fp12 {
+    let mut f = Fp12::ONE;
+    let mut ts = pairs
+        .iter()
+        .map(|(_, q)| G2Projective::from(*q))
+        .collect::<Vec<_>>();
+    for i in (0..127 - ATE_LOOP_COUNT.leading_zeros()).rev() {
+        f = f.square();
+        for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+            f = ell(f, t.double_step(), p);
+            if (ATE_LOOP_COUNT >> i) & 1 == 1 {
+                f = ell(f, t.add_step(q), p);
+            }
+        }
+    }
+    for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+        let q1 = q.mul_by_char();
+        let mut q2 = q1.mul_by_char();
+        q2.y = -q2.y;
+        f = ell(f, t.add_step(&q1), p);
+        f = ell(f, t.add_step(&q2), p);
+    }
+    f
+}
+
+/// Raises an element of the cyclotomic subgroup to the power `-x`.
+fn exp_by_neg_x(f: Fp12) -> Fp12 {
+    f.pow(X).conjugate()
+}
+
+/// Raises the output of the Miller loop to the power `(p^12 - 1) / r`, up to a fixed power
+/// coprime to `r`, following Fuentes-Castañeda et al., "Faster hashing to G2".
+fn final_exponentiation(f: Fp12) -> Fp12 {
+    // The easy part, `f^((p^6 - 1) * (p^2 + 1))`.
+    let r = f.conjugate() * f.inverse();
+    let r = r.frobenius_map(2) * r;
+
+    // The hard part.
+    let y0 = exp_by_neg_x(r);
+    let y1 = y0.square();
+    let y2 = y1.square();
+    let y3 = y2 * y1;
+    let y4 = exp_by_neg_x(y3);
+    let y5 = y4.square();
+    let y6 = exp_by_neg_x(y5);
+    let y3 = y3.conjugate();
+    let y6 = y6.conjugate();
+    let y7 = y6 * y4;
+    let y8 = y7 * y3;
+    let y9 = y8 * y1;
+    let y10 = y8 * y4;
+    let y11 = y10 * r;
+    let y12 = y9.frobenius_map(1);
+    let y13 = y12 * y11;
+    let y14 = y8.frobenius_map(2) * y13;
+    let y15 = (r.conjugate() * y9).frobenius_map(3);
+    y15 * y14
+}
+
+/// Checks that the product of the pairings of pairs of G1 and G2 points is one, as the
+/// `ecPairing` precompile of EIP-197.
+///
+/// The input is a sequence of pairs of 192 bytes, each made of a G1 point given as the big-endian
+/// bytes of its coordinates `x` and `y`, followed by a G2 point given as the big-endian bytes of
+/// the imaginary and real parts of `x`, then of `y`. The point at infinity is encoded as zero
+/// coordinates. Fails if the input is not a sequence of pairs, or if a point is not in its group.
+pub fn pairing_check(input: &[u8]) -> Result<bool> {
+    if input.len() % PAIR_LEN != 0 {
+        return Err(anyhow!("invalid pairing input length"));
+    }
+    let mut pairs = Vec::with_capacity(input.len() / PAIR_LEN);
+    for pair in input.chunks_exact(PAIR_LEN) {
+        let p = G1Affine::from_be_bytes(&pair[..64])?;
+        let q = G2Affine::from_be_bytes(&pair[64..])?;
+        // The pairing is one if either point is the point at infinity.
+        if let (Some(p), Some(q)) = (p, q) {
+            pairs.push((p, q));
+        }
+    }
+    if pairs.is_empty() {
+        return Ok(true);
+    }
+    Ok(final_exponentiation(miller_loop(&pairs)) == Fp12::ONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The generator of G1.
+    const G1: &str = "\
+        0000000000000000000000000000000000000000000000000000000000000001\
+        0000000000000000000000000000000000000000000000000000000000000002";
+
+    /// The negation of the generator of G1.
+    const NEG_G1: &str = "\
+        0000000000000000000000000000000000000000000000000000000000000001\
+        30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45";
+
+    /// Twice the generator of G1.
+    const TWO_G1: &str = "\
+        030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+        15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+
+    /// The generator of G2.
+    const G2: &str = "\
+        198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+        1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+        090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+        12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+
+    /// Decodes the concatenation of the given hex strings.
+    fn input(parts: &[&str]) -> Vec<u8> {
+        let hex = parts.concat();
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_pairing_check_empty() {
+        assert!(pairing_check(&[]).unwrap());
+    }
+
+    #[test]
+    fn test_pairing_check_generators() {
+        // The pairing is not degenerate.
+        assert!(!pairing_check(&input(&[G1, G2])).unwrap());
+        assert!(pairing_check(&input(&[G1, G2, NEG_G1, G2])).unwrap());
+        // The pairing is bilinear.
+        assert!(pairing_check(&input(&[TWO_G1, G2, NEG_G1, G2, NEG_G1, G2])).unwrap());
+        assert!(!pairing_check(&input(&[TWO_G1, G2, NEG_G1, G2])).unwrap());
+    }
+
+    #[test]
+    fn test_pairing_check_infinity() {
+        let infinity_g1 = "0".repeat(128);
+        let infinity_g2 = "0".repeat(256);
+        assert!(pairing_check(&input(&[&infinity_g1, G2])).unwrap());
+        assert!(pairing_check(&input(&[G1, &infinity_g2])).unwrap());
+        assert!(!pairing_check(&input(&[G1, G2, &infinity_g1, G2])).unwrap());
+    }
+
+    #[test]
+    fn test_pairing_check_eip197() {
+        // The `jeff1` test vector of the `ecPairing` precompile.
+        let jeff1 = input(&[
+            "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f59",
+            "3034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41",
+            "209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf7",
+            "04bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a41678",
+            "2bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d",
+            "120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550",
+            "111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c",
+            "2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411",
+            G2,
+        ]);
+        assert!(pairing_check(&jeff1).unwrap());
+
+        // Dropping the second pair breaks the relation.
+        assert!(!pairing_check(&jeff1[..PAIR_LEN]).unwrap());
+    }
+
+    #[test]
+    fn test_pairing_check_invalid_input() {
+        // The input must be a sequence of pairs.
+        assert!(pairing_check(&input(&[G1, G2])[..PAIR_LEN - 1]).is_err());
+
+        // The points must be on the curve.
+        let ones = "1".repeat(2 * PAIR_LEN);
+        assert!(pairing_check(&input(&[&ones])).is_err());
+
+        // The coordinates must be reduced.
+        let unreduced = "\
+            30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd48\
+            0000000000000000000000000000000000000000000000000000000000000002";
+        assert!(pairing_check(&input(&[unreduced, G2])).is_err());
+    }
+}
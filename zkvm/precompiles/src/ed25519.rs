@@ -0,0 +1,158 @@
+#![allow(unused)]
+
+use crate::{syscall_ed_decompress, syscall_ed_scalar_mul};
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use sha2::{Digest, Sha512};
+
+/// Verifies an ed25519 signature of `message` by the compressed public key `public_key`.
+///
+/// The signature is the compressed point `R` followed by the scalar `s`, which must be reduced
+/// modulo the group order. It is valid if `s * B - k * A = R`, where `k` is `SHA512(R || A || M)`
+/// reduced modulo the group order, which is the same check as `ed25519-dalek`'s `verify`.
+///
+/// Inside the zkVM, both scalar multiplications are done by a single `ED_SCALAR_MUL` call.
+/// Warning: the execution fails if the public key can't be decompressed, instead of returning
+/// false.
+pub fn verify_signature(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let r_bytes: [u8; 32] = signature[..32].try_into().unwrap();
+    let s: Option<Scalar> =
+        Scalar::from_canonical_bytes(signature[32..].try_into().unwrap()).into();
+    let Some(s) = s else {
+        return false;
+    };
+
+    let mut hasher = Sha512::new();
+    hasher.update(r_bytes);
+    hasher.update(public_key);
+    hasher.update(message);
+    let hash: [u8; 64] = hasher.finalize().as_slice().try_into().unwrap();
+    let k = Scalar::from_bytes_mod_order_wide(&hash);
+
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            let mut decompressed_key = [0u8; 64];
+            decompressed_key[32..].copy_from_slice(public_key);
+            unsafe {
+                syscall_ed_decompress(&mut decompressed_key);
+            }
+
+            // The arguments of `ED_SCALAR_MUL` are the scalars followed by the point `-A`.
+            let mut args = [0u32; 32];
+            words_from_le_bytes(&mut args[..8], &s.to_bytes());
+            words_from_le_bytes(&mut args[8..16], &k.to_bytes());
+            words_from_le_bytes(&mut args[16..32], &decompressed_key);
+            negate_coordinate(&mut args[16..24]);
+
+            let mut result = BASEPOINT;
+            unsafe {
+                syscall_ed_scalar_mul(result.as_mut_ptr(), args.as_ptr());
+            }
+
+            // Compress the result, which stores the sign of x in the last bit of y.
+            let mut compressed_result = [0u8; 32];
+            for (bytes, word) in compressed_result.chunks_exact_mut(4).zip(&result[8..]) {
+                bytes.copy_from_slice(&word.to_le_bytes());
+            }
+            compressed_result[31] |= ((result[0] & 1) as u8) << 7;
+            compressed_result == r_bytes
+        } else {
+            let Some(public_key) = CompressedEdwardsY(*public_key).decompress() else {
+                return false;
+            };
+            let r = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-public_key, &s);
+            r.compress().to_bytes() == r_bytes
+        }
+    }
+}
+
+/// Reads little endian bytes into words.
+fn words_from_le_bytes(words: &mut [u32], bytes: &[u8]) {
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+}
+
+/// Replaces the little endian words of a coordinate `x` by `-x` modulo the base field modulus.
+fn negate_coordinate(x: &mut [u32]) {
+    if x.iter().all(|word| *word == 0) {
+        return;
+    }
+    let mut borrow = 0;
+    for (word, modulus_word) in x.iter_mut().zip(MODULUS) {
+        let (diff, borrow1) = modulus_word.overflowing_sub(*word);
+        let (diff, borrow2) = diff.overflowing_sub(borrow);
+        *word = diff;
+        borrow = (borrow1 || borrow2) as u32;
+    }
+}
+
+/// The little endian words of the base field modulus `2^255 - 19`.
+const MODULUS: [u32; 8] = [
+    0xffff_ffed,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0x7fff_ffff,
+];
+
+/// The little endian words of the coordinates of the ed25519 base point.
+const BASEPOINT: [u32; 16] = [
+    2401621274, 3377868128, 2502272946, 1764542304, 4258716764, 3232031281, 3446559742, 560543443,
+    1717986904, 1717986918, 1717986918, 1717986918, 1717986918, 1717986918, 1717986918, 1717986918,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The first test vector of RFC 8032, which signs the empty message.
+    const PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+    fn decode<const N: usize>(hex: &str) -> [u8; N] {
+        (0..N)
+            .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let public_key = decode(PUBLIC_KEY);
+        let signature = decode(SIGNATURE);
+        assert!(verify_signature(&public_key, b"", &signature));
+        assert!(!verify_signature(&public_key, b"a", &signature));
+
+        let mut tampered_signature = signature;
+        tampered_signature[40] ^= 1;
+        assert!(!verify_signature(&public_key, b"", &tampered_signature));
+    }
+
+    #[test]
+    fn test_negate_coordinate() {
+        let mut x = [5, 0, 0, 0, 0, 0, 0, 0];
+        negate_coordinate(&mut x);
+        assert_eq!(
+            x,
+            [
+                0xffff_ffe8,
+                0xffff_ffff,
+                0xffff_ffff,
+                0xffff_ffff,
+                0xffff_ffff,
+                0xffff_ffff,
+                0xffff_ffff,
+                0x7fff_ffff
+            ]
+        );
+
+        let mut zero = [0; 8];
+        negate_coordinate(&mut zero);
+        assert_eq!(zero, [0; 8]);
+    }
+}
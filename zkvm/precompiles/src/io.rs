@@ -1,13 +1,19 @@
 #![allow(unused_unsafe)]
-use crate::{syscall_read, syscall_write};
+use crate::{
+    syscall_file_len, syscall_file_open, syscall_file_read, syscall_hint, syscall_read,
+    syscall_write,
+};
 use bincode;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::io::Read;
 use std::io::Write;
+use std::io::{Seek, SeekFrom};
 
 const FD_IO: u32 = 3;
 const FD_HINT: u32 = 4;
+const FD_PRIVATE: u32 = 5;
+const FD_HINT_ANSWERS: u32 = 6;
 pub struct SyscallReader {
     fd: u32,
 }
@@ -41,17 +47,44 @@ impl std::io::Write for SyscallWriter {
     }
 }
 
+/// Reads a public input value. Equivalent to `read_public`.
 pub fn read<T: DeserializeOwned>() -> T {
+    read_public()
+}
+
+/// Reads a slice of public input bytes. Equivalent to `read_public_slice`.
+pub fn read_slice(buf: &mut [u8]) {
+    read_public_slice(buf);
+}
+
+/// Reads a public input value. The bytes read are hashed into the input digest the program
+/// commits to when it halts. It cannot be called inside an `unconstrained!` block.
+pub fn read_public<T: DeserializeOwned>() -> T {
     let my_reader = SyscallReader { fd: FD_IO };
     let result = bincode::deserialize_from::<_, T>(my_reader);
     result.unwrap()
 }
 
-pub fn read_slice(buf: &mut [u8]) {
+/// Reads a slice of public input bytes. The bytes read are hashed into the input digest the
+/// program commits to when it halts. It cannot be called inside an `unconstrained!` block.
+pub fn read_public_slice(buf: &mut [u8]) {
     let mut my_reader = SyscallReader { fd: FD_IO };
     my_reader.read_exact(buf).unwrap();
 }
 
+/// Reads a private input value, which is only known to the prover.
+pub fn read_private<T: DeserializeOwned>() -> T {
+    let my_reader = SyscallReader { fd: FD_PRIVATE };
+    let result = bincode::deserialize_from::<_, T>(my_reader);
+    result.unwrap()
+}
+
+/// Reads a slice of private input bytes, which are only known to the prover.
+pub fn read_private_slice(buf: &mut [u8]) {
+    let mut my_reader = SyscallReader { fd: FD_PRIVATE };
+    my_reader.read_exact(buf).unwrap();
+}
+
 pub fn write<T: Serialize>(value: &T) {
     let writer = SyscallWriter { fd: FD_IO };
     bincode::serialize_into(writer, value).expect("serialization failed");
@@ -62,12 +95,126 @@ pub fn write_slice(buf: &[u8]) {
     my_reader.write_all(buf).unwrap();
 }
 
+/// Provides a value to the rest of the program from inside an `unconstrained!` block. It is
+/// appended to the stream of hint answers, which the program reads with `read_hint`.
 pub fn hint<T: Serialize>(value: &T) {
     let writer = SyscallWriter { fd: FD_HINT };
     bincode::serialize_into(writer, value).expect("serialization failed");
 }
 
+/// Provides bytes to the rest of the program from inside an `unconstrained!` block. They are
+/// appended to the stream of hint answers, which the program reads with `read_hint_slice`.
 pub fn hint_slice(buf: &[u8]) {
     let mut my_reader = SyscallWriter { fd: FD_HINT };
     my_reader.write_all(buf).unwrap();
 }
+
+/// Reads a value from the stream of hint answers. It is not part of the public values of the
+/// proof, so the program must check it.
+pub fn read_hint<T: DeserializeOwned>() -> T {
+    let my_reader = SyscallReader {
+        fd: FD_HINT_ANSWERS,
+    };
+    let result = bincode::deserialize_from::<_, T>(my_reader);
+    result.unwrap()
+}
+
+/// Reads a slice of bytes from the stream of hint answers. They are not part of the public values
+/// of the proof, so the program must check them.
+pub fn read_hint_slice(buf: &mut [u8]) {
+    let mut my_reader = SyscallReader {
+        fd: FD_HINT_ANSWERS,
+    };
+    my_reader.read_exact(buf).unwrap();
+}
+
+/// Asks the host for the answer of the hint registered under `name` with `SP1Stdin::register_hint`
+/// on the given input, which is computed natively rather than in the VM.
+///
+/// The answer is read from a stream of its own, so hints can be queried at any point, whatever
+/// input is left to read. It is not part of the public values of the proof, so the program must
+/// check it.
+pub fn hint_query(name: &str, input: &[u8]) -> Vec<u8> {
+    let len = unsafe { syscall_hint(name.as_ptr(), name.len(), input.as_ptr(), input.len()) };
+    let mut answer = vec![0u8; len];
+    let mut reader = SyscallReader {
+        fd: FD_HINT_ANSWERS,
+    };
+    reader.read_exact(&mut answer).unwrap();
+    answer
+}
+
+/// A virtual file supplied by the host with `SP1Stdin::add_file`, which can be read and seeked
+/// like a `std::fs::File`.
+pub struct File {
+    fd: u32,
+    len: u64,
+    pos: u64,
+}
+
+impl File {
+    /// Opens the virtual file with the given name.
+    pub fn open(name: &str) -> std::io::Result<File> {
+        let fd = unsafe { syscall_file_open(name.as_ptr(), name.len()) };
+        if fd == u32::MAX {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no virtual file named {}", name),
+            ));
+        }
+        let len = unsafe { syscall_file_len(fd) } as u64;
+        Ok(File { fd, len, pos: 0 })
+    }
+
+    /// The length of the file in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = (self.len.saturating_sub(self.pos) as usize).min(buf.len());
+        unsafe {
+            syscall_file_read(self.fd, self.pos as usize, buf.as_mut_ptr(), n);
+        }
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+        self.pos = pos.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "seek to a negative position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
+
+/// Reads the whole contents of a virtual file, like `std::fs::read`.
+pub fn read_file(name: &str) -> std::io::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    File::open(name)?.read_to_end(&mut contents)?;
+    Ok(contents)
+}
+
+/// Reads the whole contents of a virtual file into a string, like `std::fs::read_to_string`.
+pub fn read_file_to_string(name: &str) -> std::io::Result<String> {
+    let mut contents = String::new();
+    File::open(name)?.read_to_string(&mut contents)?;
+    Ok(contents)
+}
@@ -1,4 +1,7 @@
+pub mod bn254;
+pub mod ed25519;
 pub mod io;
+pub mod p256;
 pub mod secp256k1;
 pub mod unconstrained;
 
@@ -6,16 +9,36 @@ extern "C" {
     pub fn syscall_halt(exit_code: u8) -> !;
     pub fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize);
     pub fn syscall_read(fd: u32, read_buf: *mut u8, nbytes: usize);
+    pub fn syscall_hint(
+        name: *const u8,
+        name_len: usize,
+        input: *const u8,
+        input_len: usize,
+    ) -> usize;
+    pub fn syscall_file_open(name: *const u8, name_len: usize) -> u32;
+    pub fn syscall_file_len(fd: u32) -> usize;
+    pub fn syscall_file_read(fd: u32, offset: usize, read_buf: *mut u8, nbytes: usize);
     pub fn syscall_sha256_extend(w: *mut u32);
     pub fn syscall_sha256_compress(w: *mut u32, state: *mut u32);
     pub fn syscall_ed_add(p: *mut u32, q: *mut u32);
     pub fn syscall_ed_decompress(point: &mut [u8; 64]);
+    pub fn syscall_ed_scalar_mul(p: *mut u32, args: *const u32);
     pub fn syscall_secp256k1_add(p: *mut u32, q: *const u32);
     pub fn syscall_secp256k1_double(p: *mut u32);
     pub fn syscall_secp256k1_decompress(point: &mut [u8; 64], is_odd: bool);
+    pub fn syscall_bn254_add(p: *mut u32, q: *const u32);
+    pub fn syscall_bn254_double(p: *mut u32);
+    pub fn syscall_bn254_fp2_mul(x: *mut u32, y: *const u32);
+    pub fn syscall_bls12381_add(p: *mut u32, q: *const u32);
+    pub fn syscall_bls12381_double(p: *mut u32);
+    pub fn syscall_bls12381_decompress(point: &mut [u8; 96], is_odd: bool);
+    pub fn syscall_secp256r1_add(p: *mut u32, q: *const u32);
+    pub fn syscall_secp256r1_double(p: *mut u32);
+    pub fn syscall_secp256r1_decompress(point: &mut [u8; 64], is_odd: bool);
     pub fn syscall_keccak_permute(state: *mut u64);
     pub fn syscall_blake3_compress_inner(p: *mut u32, q: *const u32);
     pub fn syscall_enter_unconstrained() -> bool;
     pub fn syscall_exit_unconstrained();
+    pub fn syscall_user(code: u32, arg0: u32, arg1: u32) -> u32;
     pub fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8;
 }
@@ -0,0 +1,179 @@
+#![allow(unused)]
+
+use crate::{syscall_secp256r1_add, syscall_secp256r1_decompress, syscall_secp256r1_double};
+use ::p256::ecdsa::signature::hazmat::PrehashVerifier;
+use ::p256::ecdsa::{Signature, VerifyingKey};
+use ::p256::elliptic_curve::ff::PrimeFieldBits;
+use ::p256::elliptic_curve::ops::{Invert, Reduce};
+use ::p256::elliptic_curve::sec1::ToEncodedPoint;
+use ::p256::elliptic_curve::Field;
+use ::p256::{FieldBytes, PublicKey, Scalar, U256};
+use anyhow::Context;
+use anyhow::{anyhow, Result};
+use core::convert::TryInto;
+
+/// Decompresses a compressed public key using secp256r1_decompress precompile.
+pub fn decompress_pubkey(compressed_key: &[u8; 33]) -> Result<[u8; 65]> {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            let mut decompressed_key: [u8; 64] = [0; 64];
+            decompressed_key[..32].copy_from_slice(&compressed_key[1..]);
+            let is_odd = match compressed_key[0] {
+                2 => false,
+                3 => true,
+                _ => return Err(anyhow!("Invalid compressed key")),
+            };
+            unsafe {
+                syscall_secp256r1_decompress(&mut decompressed_key, is_odd);
+            }
+
+            let mut result: [u8; 65] = [0; 65];
+            result[0] = 4;
+            result[1..].copy_from_slice(&decompressed_key);
+            Ok(result)
+        } else {
+            let public_key = PublicKey::from_sec1_bytes(compressed_key).context("invalid pubkey")?;
+            let bytes = public_key.to_encoded_point(false).to_bytes();
+            let mut result: [u8; 65] = [0; 65];
+            result.copy_from_slice(&bytes);
+            Ok(result)
+        }
+    }
+}
+
+/// Verifies a P-256 signature using the public key and the message hash. If the s_inverse is
+/// provided, it will be validated and used to verify the signature. Otherwise, the inverse of s
+/// will be computed and used.
+///
+/// Warning: this function does not check if the key is actually on the curve.
+pub fn verify_signature(
+    pubkey: &[u8; 65],
+    msg_hash: &[u8; 32],
+    signature: &Signature,
+    s_inverse: Option<&Scalar>,
+) -> bool {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            // The coordinates of the public key are elements of the base field, which is larger
+            // than the scalar field, so they are read directly as limbs.
+            let affine = AffinePoint::from_be_bytes(&pubkey[1..33], &pubkey[33..]);
+
+            let z = <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(*msg_hash));
+            let (r, s) = signature.split_scalars();
+            let s_inv = match s_inverse {
+                Some(s_inv) => {
+                    assert_eq!(*s_inv * s.as_ref(), Scalar::ONE);
+                    *s_inv
+                }
+                None => *s.invert(),
+            };
+
+            let u1 = z * s_inv;
+            let u2 = *r * s_inv;
+
+            let res = double_and_add_base(&u1, &GENERATOR, &u2, &affine).unwrap();
+            let mut x_bytes_be = [0u8; 32];
+            for i in 0..8 {
+                x_bytes_be[i * 4..(i * 4) + 4].copy_from_slice(&res.limbs[i].to_le_bytes());
+            }
+            x_bytes_be.reverse();
+
+            *r == <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(x_bytes_be))
+        } else {
+            let verify_key = VerifyingKey::from_sec1_bytes(pubkey);
+            if verify_key.is_err() {
+                return false;
+            }
+            let verify_key = verify_key.unwrap();
+
+            let res = verify_key
+                .verify_prehash(msg_hash, signature)
+                .context("invalid signature");
+
+            res.is_ok()
+        }
+    }
+}
+
+/// An affine point on the P-256 curve.
+///
+/// The point is represented internally by limbs in order to ensure a contiguous memory layout.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AffinePoint {
+    limbs: [u32; 16],
+}
+
+impl AffinePoint {
+    /// Creates a point from the big-endian bytes of its coordinates.
+    pub fn from_be_bytes(x: &[u8], y: &[u8]) -> Self {
+        let mut x_bytes: [u8; 32] = x.try_into().unwrap();
+        let mut y_bytes: [u8; 32] = y.try_into().unwrap();
+        // convert to LE
+        x_bytes.reverse();
+        y_bytes.reverse();
+        let mut limbs = [0; 16];
+        for i in 0..8 {
+            let x_byte = u32::from_le_bytes(x_bytes[i * 4..(i + 1) * 4].try_into().unwrap());
+            let y_byte = u32::from_le_bytes(y_bytes[i * 4..(i + 1) * 4].try_into().unwrap());
+            limbs[i] = x_byte;
+            limbs[i + 8] = y_byte;
+        }
+        Self { limbs }
+    }
+
+    pub const fn from_limbs(limbs: [u32; 16]) -> Self {
+        Self { limbs }
+    }
+
+    pub fn add_assign(&mut self, other: &mut AffinePoint) {
+        unsafe {
+            syscall_secp256r1_add(self.limbs.as_mut_ptr(), other.limbs.as_mut_ptr());
+        }
+    }
+
+    pub fn double(&mut self) {
+        unsafe {
+            syscall_secp256r1_double(self.limbs.as_mut_ptr());
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+fn double_and_add_base(
+    a: &Scalar,
+    A: &AffinePoint,
+    b: &Scalar,
+    B: &AffinePoint,
+) -> Option<AffinePoint> {
+    let mut res: Option<AffinePoint> = None;
+    let mut temp_A = *A;
+    let mut temp_B = *B;
+
+    let a_bits = a.to_le_bits();
+    let b_bits = b.to_le_bits();
+    for (a_bit, b_bit) in a_bits.iter().zip(b_bits) {
+        if *a_bit {
+            match res.as_mut() {
+                Some(res) => res.add_assign(&mut temp_A),
+                None => res = Some(temp_A),
+            };
+        }
+
+        if b_bit {
+            match res.as_mut() {
+                Some(res) => res.add_assign(&mut temp_B),
+                None => res = Some(temp_B),
+            };
+        }
+
+        temp_A.double();
+        temp_B.double();
+    }
+
+    res
+}
+
+const GENERATOR: AffinePoint = AffinePoint::from_limbs([
+    3633889942, 4104206661, 770388896, 1996717441, 1671708914, 4173129445, 3777774151, 1796723186,
+    935285237, 3417718888, 1798397646, 734933847, 2081398294, 2397563722, 4263149467, 1340293858,
+]);
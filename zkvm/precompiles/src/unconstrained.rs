@@ -5,7 +5,8 @@
 /// cycles.
 ///
 /// Any changes to the VM state will be reset at the end of the block. To provide data to the VM,
-/// use `io::hint` or `io::hint_slice`, and read it using `io::read` or `io::read_slice`.
+/// use `io::hint` or `io::hint_slice`, and read it using `io::read_hint` or `io::read_hint_slice`.
+/// Output written inside the block is discarded, and reading public input inside it fails.
 #[macro_export]
 macro_rules! unconstrained {
     (  $($block:tt)* ) => {